@@ -12,6 +12,12 @@ import logging
 
 logger = logging.getLogger(__name__)
 
+# Precursors scoring below this are dropped by analyze_precursors
+DEFAULT_MIN_CONFIDENCE = 0.5
+
+# Weights of the confidence components; they sum to 1
+CONFIDENCE_WEIGHTS = {'magnitude': 0.4, 'density': 0.3, 'corroboration': 0.3}
+
 
 @dataclass
 class PrecursorEvent:
@@ -22,11 +28,15 @@ class PrecursorEvent:
     severity: str  # 'minor', 'moderate', 'major'
     hours_before_peak: float
     description: str
+    # Span of readings the detection rests on
+    evidence_start: Optional[pd.Timestamp] = None
+    evidence_end: Optional[pd.Timestamp] = None
+    confidence: float = 0.0  # 0-1, see score_confidence
     
     def __str__(self) -> str:
         return (
             f"{self.precursor_type} ({self.severity}) at {self.detected_at}\n"
-            f"  Value: {self.value:.2f}\n"
+            f"  Value: {self.value:.2f} (confidence {self.confidence:.2f})\n"
             f"  {self.hours_before_peak:.1f} hours before peak\n"
             f"  {self.description}"
         )
@@ -107,7 +117,9 @@ def detect_rapid_rise(
                     value=max_rate,
                     severity=severity,
                     hours_before_peak=0,  # Updated by caller
-                    description=f"Rapid rise of {max_rate:.2f} ft/day for {duration:.1f} hours"
+                    description=f"Rapid rise of {max_rate:.2f} ft/day for {duration:.1f} hours",
+                    evidence_start=event_start - pd.Timedelta(hours=6),
+                    evidence_end=timestamp
                 ))
             in_event = False
     
@@ -164,19 +176,116 @@ def detect_sustained_rise(
             value=rise_value,
             severity=severity,
             hours_before_peak=0,
-            description=f"Sustained rise of {rise_value:.2f} ft over {window_days} days"
+            description=f"Sustained rise of {rise_value:.2f} ft over {window_days} days",
+            evidence_start=timestamp - pd.Timedelta(days=window_days - 1),
+            evidence_end=timestamp + pd.Timedelta(days=1)
         ))
     
     logger.info(f"Detected {len(events)} sustained rise events")
     return events
 
 
+def data_density(
+    series: pd.Series,
+    start: pd.Timestamp,
+    end: pd.Timestamp
+) -> float:
+    """Fraction of hours in [start, end) with at least one reading.
+    
+    Gaps are interpolated over before rates are computed, so a rise seen
+    across a mostly empty window is weaker evidence than one seen hour by
+    hour.
+    
+    Args:
+        series: Raw (not resampled) time series
+        start: Start of the span
+        end: End of the span
+        
+    Returns:
+        Density between 0 and 1 (0 for an empty span)
+    """
+    hours = pd.date_range(start.floor('1H'), end, freq='1H', inclusive='left')
+    if len(hours) == 0:
+        return 0.0
+    
+    observed = series[start:end].resample('1H').count()
+    return float((observed > 0).sum()) / len(hours)
+
+
+def discharge_corroboration(
+    discharge: Optional[pd.Series],
+    start: pd.Timestamp,
+    end: pd.Timestamp
+) -> float:
+    """Whether discharge rose along with stage over [start, end].
+    
+    A stage rise with flat discharge points at backwater, ice, or a sensor
+    problem rather than more water arriving.
+    
+    Args:
+        discharge: Discharge time series, or None if the site has none
+        start: Start of the span
+        end: End of the span
+        
+    Returns:
+        1.0 if discharge rose, 0.0 if it did not, 0.5 when there is no
+        discharge to judge by
+    """
+    if discharge is None:
+        return 0.5
+    
+    window = discharge[start:end].dropna()
+    if len(window) < 2:
+        return 0.5
+    
+    return 1.0 if window.iloc[-1] > window.iloc[0] else 0.0
+
+
+def score_confidence(
+    event: PrecursorEvent,
+    threshold: float,
+    stage: pd.Series,
+    discharge: Optional[pd.Series] = None
+) -> float:
+    """Confidence (0-1) that a detected precursor reflects a real rise.
+    
+    Weighted (CONFIDENCE_WEIGHTS) from:
+    - magnitude: how far the value clears its detection threshold, from 0
+      at the threshold toward 1 well above it
+    - density: share of hours in the evidence span with stage readings
+    - corroboration: whether discharge rose over the same span
+    
+    Args:
+        event: Detected precursor with its evidence span set
+        threshold: Threshold the event's value was detected against
+        stage: Raw stage series the event was detected in
+        discharge: Raw discharge series, if available
+        
+    Returns:
+        Confidence between 0 and 1
+    """
+    magnitude = max(0.0, 1.0 - threshold / event.value) if event.value > 0 else 0.0
+    
+    start = event.evidence_start if event.evidence_start is not None else event.detected_at
+    end = event.evidence_end if event.evidence_end is not None else event.detected_at
+    density = data_density(stage, start, end)
+    corroboration = discharge_corroboration(discharge, start, end)
+    
+    return (
+        CONFIDENCE_WEIGHTS['magnitude'] * magnitude
+        + CONFIDENCE_WEIGHTS['density'] * density
+        + CONFIDENCE_WEIGHTS['corroboration'] * corroboration
+    )
+
+
 def analyze_precursors(
     stage: pd.Series,
     peak_time: pd.Timestamp,
     lookback_days: int = 14,
     rapid_rise_threshold: float = 0.5,
-    sustained_rise_threshold: float = 2.0
+    sustained_rise_threshold: float = 2.0,
+    discharge: Optional[pd.Series] = None,
+    min_confidence: float = DEFAULT_MIN_CONFIDENCE
 ) -> List[PrecursorEvent]:
     """Comprehensive precursor analysis for a flood event.
     
@@ -186,9 +295,12 @@ def analyze_precursors(
         lookback_days: Days before peak to analyze
         rapid_rise_threshold: Threshold for rapid rise (ft/day)
         sustained_rise_threshold: Threshold for sustained rise (ft)
+        discharge: Discharge at the same site, used to corroborate rises
+        min_confidence: Precursors scoring below this are dropped
         
     Returns:
-        List of all detected precursor events with timing relative to peak
+        List of detected precursor events at or above min_confidence, with
+        timing relative to peak and a confidence score
     """
     # Extract window before peak
     window_start = peak_time - timedelta(days=lookback_days)
@@ -200,8 +312,19 @@ def analyze_precursors(
     rapid_events = detect_rapid_rise(stage_window, threshold_ft_per_day=rapid_rise_threshold)
     sustained_events = detect_sustained_rise(stage_window, threshold_ft=sustained_rise_threshold)
     
-    # Combine and update hours_before_peak
-    all_events = rapid_events + sustained_events
+    for event in rapid_events:
+        event.confidence = score_confidence(event, rapid_rise_threshold, stage_window, discharge)
+    for event in sustained_events:
+        event.confidence = score_confidence(event, sustained_rise_threshold, stage_window, discharge)
+    
+    # Combine, drop low-confidence noise, and update hours_before_peak
+    detected = rapid_events + sustained_events
+    all_events = [e for e in detected if e.confidence >= min_confidence]
+    if len(all_events) < len(detected):
+        logger.info(
+            f"Dropped {len(detected) - len(all_events)} precursors below "
+            f"confidence {min_confidence:.2f}"
+        )
     
     for event in all_events:
         event.hours_before_peak = (peak_time - event.detected_at).total_seconds() / 3600
@@ -213,6 +336,110 @@ def analyze_precursors(
     return all_events
 
 
+PHASE_BOUNDARY_MODES = ('rate', 'window')
+
+
+def find_rise_onset(
+    stage: pd.Series,
+    start: pd.Timestamp,
+    peak_time: pd.Timestamp,
+    threshold_ft_per_day: float = 0.5,
+    min_duration_hours: int = 3
+) -> Optional[pd.Timestamp]:
+    """Find when the rise toward a crest first becomes sustained.
+    
+    Args:
+        stage: Time series of stage values
+        start: Earliest time to consider (e.g. precursor window start)
+        peak_time: Timestamp of flood peak
+        threshold_ft_per_day: Rise rate that counts as rising
+        min_duration_hours: Hours the rate must stay above threshold
+        
+    Returns:
+        First timestamp of the earliest qualifying run, or None if the rate
+        never stays above threshold long enough before the peak
+    """
+    rise_rate = calculate_rise_rate(stage, window_hours=6)[start:peak_time]
+    rising = rise_rate > threshold_ft_per_day
+    
+    # Length of the current run of rising hours at each timestamp
+    run_id = (~rising).cumsum()
+    run_length = rising.groupby(run_id).cumsum()
+    
+    sustained = run_length[run_length >= min_duration_hours]
+    if sustained.empty:
+        return None
+    
+    return sustained.index[0] - pd.Timedelta(hours=min_duration_hours - 1)
+
+
+def classify_phases(
+    stage: pd.Series,
+    peak_time: pd.Timestamp,
+    precursor_window_start: pd.Timestamp,
+    boundary_mode: str = 'rate',
+    rise_rate_threshold: float = 0.5,
+    min_duration_hours: int = 3,
+    peak_tolerance_hours: int = 6,
+    post_peak_window_days: int = 7
+) -> pd.Series:
+    """Label each observation with its event phase.
+    
+    Labels match flood_analysis.event_observations.phase: 'precursor',
+    'rising', 'peak', 'falling', 'post'.
+    
+    The precursor → rising boundary depends on boundary_mode:
+    - 'rate': when the rise rate first stays above rise_rate_threshold for
+      min_duration_hours, falling back to the window edge if it never does
+    - 'window': the fixed precursor-window edges (window start to crest), as
+      in earlier analyses, so events analyzed that way remain comparable;
+      no observation is labeled 'rising'
+    
+    Args:
+        stage: Time series of stage values
+        peak_time: Timestamp of flood peak
+        precursor_window_start: Start of the precursor window
+        boundary_mode: 'rate' or 'window'
+        rise_rate_threshold: Rise rate (ft/day) marking the rising phase
+        min_duration_hours: Hours the rate must be sustained
+        peak_tolerance_hours: Half-width of the 'peak' phase around the crest
+        post_peak_window_days: Days after the crest labeled 'falling'
+        
+    Returns:
+        Series of phase labels indexed like stage (None before the window)
+    """
+    if boundary_mode not in PHASE_BOUNDARY_MODES:
+        raise ValueError(f"boundary_mode must be one of {PHASE_BOUNDARY_MODES}, got {boundary_mode!r}")
+    
+    rising_start = peak_time
+    if boundary_mode == 'rate':
+        onset = find_rise_onset(
+            stage, precursor_window_start, peak_time,
+            threshold_ft_per_day=rise_rate_threshold,
+            min_duration_hours=min_duration_hours
+        )
+        if onset is not None:
+            rising_start = onset
+    
+    peak_tolerance = pd.Timedelta(hours=peak_tolerance_hours)
+    falling_end = peak_time + pd.Timedelta(days=post_peak_window_days)
+    
+    def phase(t: pd.Timestamp) -> Optional[str]:
+        if t < precursor_window_start:
+            return None
+        if abs(t - peak_time) <= peak_tolerance:
+            return 'peak'
+        if t < rising_start:
+            return 'precursor'
+        if t < peak_time:
+            return 'rising'
+        if t <= falling_end:
+            return 'falling'
+        return 'post'
+    
+    return pd.Series([phase(t) for t in stage.index], index=stage.index, dtype=object)
+
+
 def compute_precursor_metrics(events: List[PrecursorEvent]) -> Dict[str, float]:
     """Compute summary metrics from precursor events.
     
@@ -225,6 +452,7 @@ def compute_precursor_metrics(events: List[PrecursorEvent]) -> Dict[str, float]:
     if not events:
         return {
             'total_events': 0,
+            'mean_confidence': 0,
             'earliest_warning_hours': 0,
             'max_rise_rate': 0,
             'major_events': 0
@@ -235,6 +463,7 @@ def compute_precursor_metrics(events: List[PrecursorEvent]) -> Dict[str, float]:
     
     return {
         'total_events': len(events),
+        'mean_confidence': float(np.mean([e.confidence for e in events])),
         'earliest_warning_hours': max(e.hours_before_peak for e in events),
         'max_rise_rate': max((e.value for e in rapid_events), default=0),
         'rapid_rise_events': len(rapid_events),
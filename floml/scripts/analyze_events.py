@@ -17,7 +17,9 @@ import pandas as pd
 import numpy as np
 
 from floml.db import get_engine, verify_schemas
-from floml.precursors import analyze_precursors, compute_precursor_metrics
+from floml.precursors import (
+    analyze_precursors, classify_phases, compute_precursor_metrics, DEFAULT_MIN_CONFIDENCE
+)
 from floml.regression import fit_stage_discharge
 from floml.correlation import correlate_stations
 
@@ -74,7 +76,30 @@ def load_stage_data(engine, site_code, start_time, end_time):
     return data
 
 
-def analyze_event(engine, event_row):
+def load_discharge_data(engine, site_code, start_time, end_time):
+    """Load discharge data for a site and time window (empty if none)."""
+    query = """
+        SELECT reading_time as timestamp, value as discharge_cfs
+        FROM usgs_raw.gauge_readings
+        WHERE site_code = %(site_code)s
+          AND parameter_code = '00060'
+          AND reading_time BETWEEN %(start)s AND %(end)s
+        ORDER BY reading_time
+    """
+    
+    data = pd.read_sql(
+        query, 
+        engine,
+        params={'site_code': site_code, 'start': start_time, 'end': end_time}
+    )
+    
+    if len(data) > 0:
+        data.set_index('timestamp', inplace=True)
+    
+    return data
+
+
+def analyze_event(engine, event_row, phase_boundaries='rate', min_confidence=DEFAULT_MIN_CONFIDENCE):
     """Analyze a single flood event."""
     site_code = event_row['site_code']
     crest_time = pd.Timestamp(event_row['crest_time'])
@@ -96,27 +121,47 @@ def analyze_event(engine, event_row):
         logger.warning("No data available for analysis")
         return None
     
+    # Discharge corroborates stage rises; sites without it score neutral
+    discharge_data = load_discharge_data(engine, site_code, window_start, window_end)
+    discharge = discharge_data['discharge_cfs'] if not discharge_data.empty else None
+    
     # Analyze precursors
     logger.info("🔍 Detecting precursor patterns...")
     precursors = analyze_precursors(
         stage_data['stage_ft'],
         peak_time=crest_time,
-        lookback_days=lookback_days
+        lookback_days=lookback_days,
+        discharge=discharge,
+        min_confidence=min_confidence
     )
     
     if precursors:
-        print(f"\n📋 Found {len(precursors)} precursor events:")
+        print(f"\n📋 Found {len(precursors)} precursor events (confidence >= {min_confidence:.2f}):")
         for p in precursors:
-            print(f"  • {p.precursor_type:15s} {p.hours_before_peak:6.1f}h before peak - {p.description}")
+            print(f"  • {p.precursor_type:15s} {p.hours_before_peak:6.1f}h before peak "
+                  f"[{p.confidence:.2f}] - {p.description}")
         
         metrics = compute_precursor_metrics(precursors)
         print(f"\n📊 Precursor Metrics:")
         print(f"  Earliest warning: {metrics['earliest_warning_hours']:.1f} hours")
         print(f"  Max rise rate: {metrics['max_rise_rate']:.2f} ft/day")
         print(f"  Major events: {metrics['major_events']}")
+        print(f"  Mean confidence: {metrics['mean_confidence']:.2f}")
     else:
         print("  No significant precursors detected")
     
+    # Phase labels (precursor → rising boundary per --phase-boundaries)
+    phases = classify_phases(
+        stage_data['stage_ft'],
+        peak_time=crest_time,
+        precursor_window_start=window_start,
+        boundary_mode=phase_boundaries
+    )
+    phase_counts = phases.value_counts()
+    print(f"\n🗂️  Observation phases ({phase_boundaries} boundaries):")
+    for label in ('precursor', 'rising', 'peak', 'falling', 'post'):
+        print(f"  {label:10s} {phase_counts.get(label, 0)}")
+    
     return {
         'event_id': event_id,
         'site_code': site_code,
@@ -177,6 +222,12 @@ def main():
     parser = argparse.ArgumentParser(description='Analyze flood events')
     parser.add_argument('--site-code', help='Analyze specific site only')
     parser.add_argument('--regression', action='store_true', help='Include stage-discharge regression')
+    parser.add_argument('--phase-boundaries', choices=['rate', 'window'], default='rate',
+                        help="Precursor/rising boundary: sustained rise rate, or the fixed "
+                             "precursor-window edge used by earlier analyses")
+    parser.add_argument('--min-confidence', type=float, default=DEFAULT_MIN_CONFIDENCE,
+                        help="Drop precursors scoring below this confidence (0-1; "
+                             f"default {DEFAULT_MIN_CONFIDENCE})")
     args = parser.parse_args()
     
     try:
@@ -198,7 +249,7 @@ def main():
         # Analyze each event
         results = []
         for idx, event in events.iterrows():
-            result = analyze_event(engine, event)
+            result = analyze_event(engine, event, args.phase_boundaries, args.min_confidence)
             if result:
                 results.append(result)
         
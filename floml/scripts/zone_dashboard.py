@@ -152,9 +152,9 @@ def init_colors() -> Dict[str, int]:
 
 def alert_attr(level: str, P: Dict[str, int]) -> int:
     lvl = (level or "NORMAL").upper()
-    if lvl in ("CRITICAL", "WARNING", "FLOOD_WARNING"):
+    if lvl in ("MAJOR", "MODERATE", "FLOOD", "ACTION"):
         return P["red"] | curses.A_BOLD
-    if lvl in ("WATCH", "ELEVATED", "FLOOD_WATCH"):
+    if lvl == "WATCH":
         return P["yellow"] | curses.A_BOLD
     return P["green"]
 
@@ -0,0 +1,34 @@
+-- Migration 008: NWS Official Flood Alerts
+--
+-- Purpose: Store official NWS flood warning/watch/advisory products for the
+-- counties along the monitored reach, so computed basin status can be
+-- cross-checked against the authoritative determination.
+--
+-- Source: https://api.weather.gov/alerts/active?zone=ILC143,...
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/008_nws_alerts.sql
+
+-- ============================================================================
+-- NWS Alerts
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS nws.alerts (
+    alert_id TEXT PRIMARY KEY,              -- NWS CAP identifier (urn:oid:...)
+    event TEXT NOT NULL,                    -- e.g., 'Flood Warning', 'Flood Watch'
+    severity TEXT NOT NULL,                 -- CAP severity: Extreme, Severe, Moderate, Minor, Unknown
+    headline TEXT,
+    effective TIMESTAMPTZ,
+    expires TIMESTAMPTZ,
+    areas TEXT[] NOT NULL DEFAULT '{}',     -- Affected areas from areaDesc
+    first_seen TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_nws_alerts_expires
+    ON nws.alerts(expires DESC);
+
+COMMENT ON TABLE nws.alerts IS
+    'Official NWS flood-related alerts (warnings, watches, advisories) for monitored counties';
+COMMENT ON COLUMN nws.alerts.last_seen IS
+    'Last poll in which the alert was still listed as active by api.weather.gov';
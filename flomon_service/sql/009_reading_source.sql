@@ -0,0 +1,24 @@
+-- Migration 009: Reading Source Provenance
+--
+-- Purpose: Record which upstream feed each gauge reading came from
+-- (USGS IV vs DV) so high-resolution data can be preferred during
+-- DV/IV reconciliation and analysis can filter by data quality.
+--
+-- Rows stored before this migration are marked 'unknown'.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/009_reading_source.sql
+
+-- ============================================================================
+-- Gauge Reading Source
+-- ============================================================================
+
+ALTER TABLE usgs_raw.gauge_readings
+    ADD COLUMN IF NOT EXISTS source TEXT NOT NULL DEFAULT 'unknown'
+    CHECK (source IN ('usgs_iv', 'usgs_dv', 'cwms', 'asos', 'unknown'));
+
+CREATE INDEX IF NOT EXISTS idx_gauge_readings_source
+    ON usgs_raw.gauge_readings(site_code, source);
+
+COMMENT ON COLUMN usgs_raw.gauge_readings.source IS
+    'Upstream feed: usgs_iv (instantaneous), usgs_dv (daily mean), cwms, asos, or unknown (pre-009 rows)';
@@ -0,0 +1,30 @@
+-- Migration 010: Basin Status History
+--
+-- Purpose: Persist the computed /status assessment on every poll cycle so
+-- the evolution of basin conditions during a past event can be replayed
+-- for after-action review. /status itself is always computed live.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/010_basin_status_history.sql
+
+-- ============================================================================
+-- Basin Status Snapshots
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS public.basin_status_history (
+    id BIGSERIAL PRIMARY KEY,
+    recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- AlertLevel::as_str(): UNKNOWN, DEGRADED, NORMAL, WATCH, ACTION, FLOOD,
+    -- MODERATE, MAJOR. Older rows use NORMAL, ELEVATED, FLOOD_WATCH,
+    -- FLOOD_WARNING; read through AlertLevel::parse, which maps both.
+    overall_status TEXT NOT NULL,
+    compound_event_risk TEXT NOT NULL,      -- LOW, MODERATE, HIGH
+    active_zones JSONB NOT NULL,            -- ActiveZoneStatus[] as served by /status
+    backwater_risk JSONB NOT NULL           -- BackwaterRiskResponse as served by /status
+);
+
+CREATE INDEX IF NOT EXISTS idx_basin_status_history_recorded
+    ON public.basin_status_history(recorded_at DESC);
+
+COMMENT ON TABLE public.basin_status_history IS
+    'Snapshot of the computed basin status (/status) taken each daemon poll cycle';
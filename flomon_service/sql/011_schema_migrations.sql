@@ -0,0 +1,50 @@
+-- Migration 011: Schema Migration Tracking
+--
+-- Purpose: Record which bundled migrations have been applied so the service
+-- can report its schema version and `--migrate` can apply only what is
+-- missing.
+--
+-- Databases set up before this migration are seeded by probing for an
+-- object each earlier migration creates. Safe to run repeatedly.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/011_schema_migrations.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Migration Tracking
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS public.schema_migrations (
+    version INTEGER PRIMARY KEY,            -- Numeric prefix of the sql/ file
+    name TEXT NOT NULL,                     -- File name without extension
+    applied_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+COMMENT ON TABLE public.schema_migrations IS
+    'Bundled SQL migrations applied to this database (see sql/)';
+
+-- ============================================================================
+-- Seed previously applied migrations
+-- ============================================================================
+
+INSERT INTO public.schema_migrations (version, name)
+SELECT v.version, v.name
+FROM (VALUES
+    (1,  '001_initial_schema',        to_regclass('usgs_raw.gauge_readings') IS NOT NULL),
+    (2,  '002_monitoring_metadata',   to_regclass('usgs_raw.monitoring_state') IS NOT NULL),
+    (3,  '003_flood_metadata',        to_regclass('nws.flood_thresholds') IS NOT NULL),
+    (4,  '004_usace_cwms',            to_regclass('usace.cwms_timeseries') IS NOT NULL),
+    (5,  '005_flood_analysis',        to_regclass('flood_analysis.events') IS NOT NULL),
+    (6,  '006_iem_asos',              to_regclass('public.asos_observations') IS NOT NULL),
+    (7,  '007_backfill_tracking',     to_regclass('public.backfill_queue') IS NOT NULL),
+    (8,  '008_nws_alerts',            to_regclass('nws.alerts') IS NOT NULL),
+    (9,  '009_reading_source',        EXISTS (
+        SELECT 1 FROM information_schema.columns
+        WHERE table_schema = 'usgs_raw' AND table_name = 'gauge_readings' AND column_name = 'source'
+    )),
+    (10, '010_basin_status_history',  to_regclass('public.basin_status_history') IS NOT NULL),
+    (11, '011_schema_migrations',     true)
+) AS v(version, name, applied)
+WHERE v.applied
+ON CONFLICT (version) DO NOTHING;
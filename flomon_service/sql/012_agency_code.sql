@@ -0,0 +1,28 @@
+-- Migration 012: Gauge Reading Agency
+--
+-- Purpose: Record the agency that operates each gauge (NWIS `agencyCode`)
+-- so identically-numbered sites run by different agencies (e.g. a USACE
+-- gauge published through NWIS) don't collide on the same rows.
+--
+-- Existing rows are all USGS sites and default to 'USGS'.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/012_agency_code.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Gauge Reading Agency
+-- ============================================================================
+
+ALTER TABLE usgs_raw.gauge_readings
+    ADD COLUMN IF NOT EXISTS agency_code VARCHAR(10) NOT NULL DEFAULT 'USGS';
+
+-- Widen the duplicate-reading key to include the agency
+ALTER TABLE usgs_raw.gauge_readings
+    DROP CONSTRAINT IF EXISTS unique_reading;
+
+ALTER TABLE usgs_raw.gauge_readings
+    ADD CONSTRAINT unique_reading UNIQUE (agency_code, site_code, parameter_code, reading_time);
+
+COMMENT ON COLUMN usgs_raw.gauge_readings.agency_code IS
+    'Operating agency from NWIS siteCode.agencyCode (USGS unless stated)';
@@ -0,0 +1,49 @@
+-- Migration 013: Flood Event Crest Key
+--
+-- Purpose: Enforce one nws.flood_events row per (site_code, crest_time) so
+-- peak-flow ingest can upsert with ON CONFLICT. USGS revises historical
+-- peaks; re-running the ingest then updates the stored crest instead of
+-- duplicating or skipping it.
+--
+-- Existing duplicates are collapsed onto the most recently inserted row,
+-- with flood_analysis.events repointed to it first. Rows with no crest_time
+-- (ongoing live events) are unaffected, since NULLs never conflict.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/013_flood_event_crest_key.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Collapse duplicates
+-- ============================================================================
+
+WITH ranked AS (
+    SELECT id,
+           FIRST_VALUE(id) OVER (PARTITION BY site_code, crest_time ORDER BY id DESC) AS keep_id
+    FROM nws.flood_events
+    WHERE crest_time IS NOT NULL
+)
+UPDATE flood_analysis.events e
+SET source_event_id = r.keep_id
+FROM ranked r
+WHERE e.source_event_id = r.id
+  AND r.id <> r.keep_id;
+
+DELETE FROM nws.flood_events f
+USING nws.flood_events newer
+WHERE f.site_code = newer.site_code
+  AND f.crest_time = newer.crest_time
+  AND f.id < newer.id;
+
+-- ============================================================================
+-- Crest key
+-- ============================================================================
+
+ALTER TABLE nws.flood_events
+    DROP CONSTRAINT IF EXISTS unique_flood_event_crest;
+
+ALTER TABLE nws.flood_events
+    ADD CONSTRAINT unique_flood_event_crest UNIQUE (site_code, crest_time);
+
+COMMENT ON CONSTRAINT unique_flood_event_crest ON nws.flood_events IS
+    'One event per crest; peak-flow ingest upserts on this key';
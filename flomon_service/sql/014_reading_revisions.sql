@@ -0,0 +1,42 @@
+-- Migration 014: Reading Revisions
+--
+-- Purpose: Audit trail for USGS provisional-to-approved revisions. With
+-- APPLY_APPROVED_REVISIONS=true, an approved ('A') reading replaces a
+-- stored provisional ('P') one for the same instant instead of being
+-- skipped; each replacement is recorded here with the before and after
+-- value, so it's possible to see how much of the record USGS changed.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/014_reading_revisions.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Reading Revisions
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS usgs_raw.reading_revisions (
+    id BIGSERIAL PRIMARY KEY,
+    
+    -- The revised reading (gauge_readings unique_reading key)
+    agency_code VARCHAR(10) NOT NULL,
+    site_code VARCHAR(8) NOT NULL,
+    parameter_code VARCHAR(5) NOT NULL,
+    reading_time TIMESTAMPTZ NOT NULL,
+    
+    -- Before and after
+    old_value NUMERIC(12, 4) NOT NULL,
+    new_value NUMERIC(12, 4) NOT NULL,
+    old_qualifier VARCHAR(1) NOT NULL,
+    new_qualifier VARCHAR(1) NOT NULL,
+    
+    revised_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_reading_revisions_site_time
+    ON usgs_raw.reading_revisions(site_code, parameter_code, reading_time);
+
+CREATE INDEX IF NOT EXISTS idx_reading_revisions_revised_at
+    ON usgs_raw.reading_revisions(revised_at DESC);
+
+COMMENT ON TABLE usgs_raw.reading_revisions IS
+    'Provisional gauge readings replaced by USGS-approved values (APPLY_APPROVED_REVISIONS)';
@@ -0,0 +1,33 @@
+-- Migration 015: NWS Stage Forecasts
+--
+-- Purpose: Official short-term stage (and flow) forecasts issued by NWS for
+-- AHPS forecast points on the monitored reach (stations with an `nws_id` in
+-- usgs_stations.toml). Each issuance is kept as issued, so later ones can be
+-- compared with earlier ones and with what the river actually did.
+--
+-- Source: https://api.water.noaa.gov/nwps/v1/gauges/{nws_id}/stageflow/forecast
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/015_nws_stage_forecasts.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Stage Forecasts
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS nws.stage_forecasts (
+    nws_id VARCHAR(10) NOT NULL,            -- AHPS location ID, e.g. 'KINI2'
+    issued_time TIMESTAMPTZ NOT NULL,
+    valid_time TIMESTAMPTZ NOT NULL,
+    stage_ft NUMERIC(10, 2) NOT NULL,
+    flow_cfs NUMERIC(12, 0),                -- NULL where NWS gives no flow
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    
+    PRIMARY KEY (nws_id, issued_time, valid_time)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stage_forecasts_latest
+    ON nws.stage_forecasts(nws_id, issued_time DESC);
+
+COMMENT ON TABLE nws.stage_forecasts IS
+    'Official NWS stage forecasts for AHPS forecast points, one row per issuance and valid time';
@@ -0,0 +1,23 @@
+-- Migration 016: NWS Alert Polls
+--
+-- Purpose: Record when api.weather.gov was last polled for active alerts.
+-- An alert NWS cancels simply drops off the active list, so its row in
+-- nws.alerts stops being refreshed; only alerts whose last_seen matches the
+-- latest poll are still active. A poll that finds no alerts still needs a
+-- row here, or a cancelled alert would look current until it expires.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/016_nws_alert_polls.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Alert Polls
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS nws.alert_polls (
+    polled_at TIMESTAMPTZ PRIMARY KEY,
+    active_alerts INTEGER NOT NULL          -- Alerts listed as active in this poll
+);
+
+COMMENT ON TABLE nws.alert_polls IS
+    'Successful polls of api.weather.gov active alerts; nws.alerts rows last seen before the latest are no longer active';
@@ -0,0 +1,40 @@
+-- Migration 017: DV Central Dates
+--
+-- Purpose: Re-stamp USGS daily values stored before DV dates were read as
+-- Central days. Those rows have reading_time at 00:00 UTC on the DV date,
+-- which is 18:00 or 19:00 the previous evening in Peoria; new rows are
+-- stored at local midnight (05:00 or 06:00 UTC). Left alone, each old day
+-- sits beside its re-ingested twin several hours apart.
+--
+-- Rows whose Central-midnight twin already exists are deleted; the rest
+-- are moved to local midnight. Re-stamped rows are never at 00:00 UTC, so
+-- re-running this is a no-op. Pre-009 rows (source 'unknown') can't be told
+-- apart from instantaneous readings and are left as they are.
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/017_dv_central_dates.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Drop rows already re-ingested at local midnight
+-- ============================================================================
+
+DELETE FROM usgs_raw.gauge_readings old
+USING usgs_raw.gauge_readings restamped
+WHERE old.source = 'usgs_dv'
+  AND (old.reading_time AT TIME ZONE 'UTC')::time = '00:00'
+  AND restamped.agency_code = old.agency_code
+  AND restamped.site_code = old.site_code
+  AND restamped.parameter_code = old.parameter_code
+  AND restamped.reading_time =
+      ((old.reading_time AT TIME ZONE 'UTC')::date::timestamp AT TIME ZONE 'America/Chicago');
+
+-- ============================================================================
+-- Re-stamp the rest
+-- ============================================================================
+
+UPDATE usgs_raw.gauge_readings
+SET reading_time =
+    ((reading_time AT TIME ZONE 'UTC')::date::timestamp AT TIME ZONE 'America/Chicago')
+WHERE source = 'usgs_dv'
+  AND (reading_time AT TIME ZONE 'UTC')::time = '00:00';
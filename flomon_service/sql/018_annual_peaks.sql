@@ -0,0 +1,30 @@
+-- Migration 018: Annual Peaks
+--
+-- Purpose: USGS annual peak stages (the Peak Streamflow database), one row
+-- per published peak. Flood-frequency curves (return periods on /zone) are
+-- fit to these rather than to the maxima of stored daily/instantaneous
+-- readings, whose record is shorter and misses crests between samples.
+-- Loaded at daemon startup; USGS revisions replace the stored stage.
+--
+-- Source: https://nwis.waterdata.usgs.gov/nwis/peak?site_no={site}&agency_cd=USGS&format=rdb
+--
+-- Usage:
+--   psql -U flopro_admin -d flopro_db -f sql/018_annual_peaks.sql
+--   (or start the service with --migrate)
+
+-- ============================================================================
+-- Annual Peaks
+-- ============================================================================
+
+CREATE TABLE IF NOT EXISTS usgs_raw.annual_peaks (
+    site_code VARCHAR(8) NOT NULL,
+    peak_date DATE NOT NULL,
+    peak_stage_ft NUMERIC(8, 2) NOT NULL,   -- gage_ht, or ag_gage_ht where higher
+    peak_discharge_cfs NUMERIC(12, 0),      -- NULL where USGS gives no flow
+    fetched_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    
+    PRIMARY KEY (site_code, peak_date)
+);
+
+COMMENT ON TABLE usgs_raw.annual_peaks IS
+    'USGS Peak Streamflow database annual peak stages; input to return-period curves';
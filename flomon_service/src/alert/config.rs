@@ -20,6 +20,18 @@ pub struct AlertingSection {
     pub daily_digest_hour_utc: i32,
     pub intervals_minutes: IntervalsConfig,
     pub recipients: RecipientsConfig,
+    /// Optional overnight window during which non-critical alerts are held.
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHoursConfig>,
+    /// Optional alert on rapidly building Mississippi backwater at LaGrange.
+    #[serde(default)]
+    pub backwater_onset: Option<BackwaterOnsetConfig>,
+    /// Optional alert on stage held above a threshold for a long stretch.
+    #[serde(default)]
+    pub sustained_high_water: Option<SustainedHighWaterConfig>,
+    /// Optional heads-up while a rising stage nears flood stage.
+    #[serde(default)]
+    pub approaching_flood_stage: Option<ApproachingFloodStageConfig>,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -35,12 +47,136 @@ pub struct RecipientsConfig {
     pub numbers: Vec<String>,
 }
 
+/// Quiet-hours window in station-local time ("HH:MM", IANA timezone).
+#[derive(Debug, Clone, Deserialize)]
+pub struct QuietHoursConfig {
+    pub start: String,
+    pub end: String,
+    #[serde(default = "default_quiet_hours_timezone")]
+    pub timezone: String,
+}
+
+fn default_quiet_hours_timezone() -> String {
+    "America/Chicago".to_string()
+}
+
+/// Rapid backwater onset thresholds (LaGrange tailwater minus pool).
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackwaterOnsetConfig {
+    /// Trailing window for the rate of change, in hours
+    #[serde(default = "default_onset_window_hours")]
+    pub window_hours: i32,
+    /// Alert only once tailwater is within this many feet of pool
+    /// (negative = below pool)
+    #[serde(default = "default_onset_min_differential_ft")]
+    pub min_differential_ft: f64,
+    /// ...and rising at least this fast
+    #[serde(default = "default_onset_min_rate_ft_per_hour")]
+    pub min_rate_ft_per_hour: f64,
+    /// Pair tailwater and pool readings logged at most this many minutes
+    /// apart (the two gauges don't report on the same minute)
+    #[serde(default = "default_onset_align_tolerance_minutes")]
+    pub align_tolerance_minutes: i64,
+}
+
+/// Sustained high water ("nuisance flooding") alert settings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SustainedHighWaterConfig {
+    /// Hours stage must stay continuously above the threshold
+    #[serde(default = "default_sustained_min_hours")]
+    pub min_hours: i32,
+    /// Fixed threshold in feet for every station; when absent each
+    /// station's action stage is used
+    #[serde(default)]
+    pub threshold_ft: Option<f64>,
+}
+
+/// "Approaching flood stage" alert settings.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApproachingFloodStageConfig {
+    /// Alert once stage is within this many feet below flood stage
+    #[serde(default = "default_approach_margin_ft")]
+    pub margin_ft: f64,
+    /// ...and rising at least this fast
+    #[serde(default = "default_approach_min_rate_ft_per_hour")]
+    pub min_rate_ft_per_hour: f64,
+    /// Trailing window the rate of rise is fit over, in hours
+    #[serde(default = "default_approach_window_hours")]
+    pub window_hours: i64,
+}
+
+impl Default for BackwaterOnsetConfig {
+    fn default() -> Self {
+        Self {
+            window_hours: default_onset_window_hours(),
+            min_differential_ft: default_onset_min_differential_ft(),
+            min_rate_ft_per_hour: default_onset_min_rate_ft_per_hour(),
+            align_tolerance_minutes: default_onset_align_tolerance_minutes(),
+        }
+    }
+}
+
+impl Default for SustainedHighWaterConfig {
+    fn default() -> Self {
+        Self {
+            min_hours: default_sustained_min_hours(),
+            threshold_ft: None,
+        }
+    }
+}
+
+impl Default for ApproachingFloodStageConfig {
+    fn default() -> Self {
+        Self {
+            margin_ft: default_approach_margin_ft(),
+            min_rate_ft_per_hour: default_approach_min_rate_ft_per_hour(),
+            window_hours: default_approach_window_hours(),
+        }
+    }
+}
+
+fn default_approach_margin_ft() -> f64 {
+    1.0
+}
+
+fn default_approach_min_rate_ft_per_hour() -> f64 {
+    0.02
+}
+
+fn default_approach_window_hours() -> i64 {
+    6
+}
+
+fn default_sustained_min_hours() -> i32 {
+    48
+}
+
+fn default_onset_window_hours() -> i32 {
+    6
+}
+
+fn default_onset_align_tolerance_minutes() -> i64 {
+    crate::analysis::align::DEFAULT_ALIGN_TOLERANCE_MINUTES
+}
+
+fn default_onset_min_differential_ft() -> f64 {
+    -2.0
+}
+
+fn default_onset_min_rate_ft_per_hour() -> f64 {
+    0.2
+}
+
 impl AlertingConfig {
     pub fn load() -> Result<Self, Box<dyn std::error::Error>> {
         let contents = fs::read_to_string("alerting.toml")
             .map_err(|e| format!("Failed to read alerting.toml: {}", e))?;
         let config: AlertingConfig = toml::from_str(&contents)
             .map_err(|e| format!("Failed to parse alerting.toml: {}", e))?;
+        if let Some(ref quiet) = config.alerting.quiet_hours {
+            crate::alert::quiet_hours::QuietHours::from_config(quiet)
+                .map_err(|e| format!("Invalid [alerting.quiet_hours] in alerting.toml: {}", e))?;
+        }
         Ok(config)
     }
 }
@@ -0,0 +1,243 @@
+//! Canonical alert level shared by every API response.
+//!
+//! Zones, the basin roll-up, and per-station flood categories used to each
+//! have their own vocabulary ("WARNING"/"CRITICAL", "FLOOD_WATCH"/
+//! "FLOOD_WARNING", "action"/"flood"/"major"). They all map onto one
+//! ordered `AlertLevel` here, serialized in SCREAMING_SNAKE_CASE, with the
+//! flood tiers named after the NWS flood categories:
+//!
+//! | Level    | Zone (old) | Basin (old)   | Flood category |
+//! |----------|------------|---------------|----------------|
+//! | WATCH    | WATCH      | ELEVATED      |                |
+//! | ACTION   | WARNING    | FLOOD_WATCH   | action         |
+//! | FLOOD    | CRITICAL   | FLOOD_WARNING | flood / minor  |
+//! | MODERATE |            |               | moderate       |
+//! | MAJOR    |            |               | major          |
+//!
+//! Each level also has a `LevelDisplay` (label + suggested color) so
+//! clients don't need their own mapping. Defaults follow the NWS hydrograph
+//! palette and can be overridden per level in the `[display]` section of
+//! zones.toml.
+
+use serde::{Deserialize, Serialize};
+
+use crate::alert::thresholds::FloodSeverity;
+
+/// Alert level, in ascending order of urgency. The data-quality levels
+/// (`Unknown`, `Degraded`) rank below `Normal` so a worst-of roll-up is
+/// driven by flooding, not by a dead sensor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AlertLevel {
+    /// No sensors to judge by
+    Unknown,
+    /// Too many stale sensors to trust a "normal" reading
+    Degraded,
+    Normal,
+    /// An exceedance that does not warrant action on its own (e.g. a proxy
+    /// sensor at action stage)
+    Watch,
+    /// Action stage
+    Action,
+    /// Flood stage (NWS minor flooding)
+    Flood,
+    Moderate,
+    Major,
+}
+
+impl AlertLevel {
+    pub const ALL: [AlertLevel; 8] = [
+        AlertLevel::Unknown,
+        AlertLevel::Degraded,
+        AlertLevel::Normal,
+        AlertLevel::Watch,
+        AlertLevel::Action,
+        AlertLevel::Flood,
+        AlertLevel::Moderate,
+        AlertLevel::Major,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertLevel::Unknown => "UNKNOWN",
+            AlertLevel::Degraded => "DEGRADED",
+            AlertLevel::Normal => "NORMAL",
+            AlertLevel::Watch => "WATCH",
+            AlertLevel::Action => "ACTION",
+            AlertLevel::Flood => "FLOOD",
+            AlertLevel::Moderate => "MODERATE",
+            AlertLevel::Major => "MAJOR",
+        }
+    }
+
+    /// Parse a canonical level or any of the legacy zone, basin, and
+    /// flood-event strings (case-insensitive).
+    pub fn parse(s: &str) -> Option<Self> {
+        let level = match s.trim().to_ascii_uppercase().as_str() {
+            "UNKNOWN" => AlertLevel::Unknown,
+            "DEGRADED" => AlertLevel::Degraded,
+            "NORMAL" => AlertLevel::Normal,
+            "WATCH" | "ELEVATED" => AlertLevel::Watch,
+            "ACTION" | "WARNING" | "FLOOD_WATCH" => AlertLevel::Action,
+            "FLOOD" | "MINOR" | "CRITICAL" | "FLOOD_WARNING" => AlertLevel::Flood,
+            "MODERATE" => AlertLevel::Moderate,
+            "MAJOR" | "EXTREME" => AlertLevel::Major,
+            _ => return None,
+        };
+        Some(level)
+    }
+
+    /// Whether this level reflects an actual threshold exceedance.
+    pub fn is_elevated(&self) -> bool {
+        *self >= AlertLevel::Watch
+    }
+
+    fn default_display(&self) -> (&'static str, &'static str) {
+        match self {
+            AlertLevel::Unknown => ("No data", "#BDBDBD"),
+            AlertLevel::Degraded => ("Sensors degraded", "#9E9E9E"),
+            AlertLevel::Normal => ("Normal", "#00C853"),
+            AlertLevel::Watch => ("Watch", "#FFFF99"),
+            AlertLevel::Action => ("Action stage", "#FFFF00"),
+            AlertLevel::Flood => ("Minor flooding", "#FF9900"),
+            AlertLevel::Moderate => ("Moderate flooding", "#FF0000"),
+            AlertLevel::Major => ("Major flooding", "#CC33FF"),
+        }
+    }
+}
+
+impl From<&FloodSeverity> for AlertLevel {
+    fn from(severity: &FloodSeverity) -> Self {
+        match severity {
+            FloodSeverity::Action => AlertLevel::Action,
+            FloodSeverity::Flood => AlertLevel::Flood,
+            FloodSeverity::Moderate => AlertLevel::Moderate,
+            FloodSeverity::Major => AlertLevel::Major,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Display
+// ---------------------------------------------------------------------------
+
+/// Human-facing label and suggested color for an alert level.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LevelDisplay {
+    pub label: String,
+    /// Hex RGB, e.g. "#FF9900"
+    pub color: String,
+}
+
+/// Override for one level; either field may be left at its default.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct DisplayOverride {
+    pub label: Option<String>,
+    pub color: Option<String>,
+}
+
+/// `[display.<level>]` overrides from zones.toml, keyed by lowercase level.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisplayConfig {
+    pub unknown: Option<DisplayOverride>,
+    pub degraded: Option<DisplayOverride>,
+    pub normal: Option<DisplayOverride>,
+    pub watch: Option<DisplayOverride>,
+    pub action: Option<DisplayOverride>,
+    pub flood: Option<DisplayOverride>,
+    pub moderate: Option<DisplayOverride>,
+    pub major: Option<DisplayOverride>,
+}
+
+impl DisplayConfig {
+    fn override_for(&self, level: AlertLevel) -> Option<&DisplayOverride> {
+        match level {
+            AlertLevel::Unknown => self.unknown.as_ref(),
+            AlertLevel::Degraded => self.degraded.as_ref(),
+            AlertLevel::Normal => self.normal.as_ref(),
+            AlertLevel::Watch => self.watch.as_ref(),
+            AlertLevel::Action => self.action.as_ref(),
+            AlertLevel::Flood => self.flood.as_ref(),
+            AlertLevel::Moderate => self.moderate.as_ref(),
+            AlertLevel::Major => self.major.as_ref(),
+        }
+    }
+
+    /// Label and color for `level`, with any configured override applied.
+    pub fn display_for(&self, level: AlertLevel) -> LevelDisplay {
+        let (label, color) = level.default_display();
+        let overrides = self.override_for(level);
+
+        LevelDisplay {
+            label: overrides.and_then(|o| o.label.clone()).unwrap_or_else(|| label.to_string()),
+            color: overrides.and_then(|o| o.color.clone()).unwrap_or_else(|| color.to_string()),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_strings_map_onto_canonical_levels() {
+        // Zone vocabulary
+        assert_eq!(AlertLevel::parse("WATCH"), Some(AlertLevel::Watch));
+        assert_eq!(AlertLevel::parse("WARNING"), Some(AlertLevel::Action));
+        assert_eq!(AlertLevel::parse("CRITICAL"), Some(AlertLevel::Flood));
+        // Basin vocabulary
+        assert_eq!(AlertLevel::parse("ELEVATED"), Some(AlertLevel::Watch));
+        assert_eq!(AlertLevel::parse("FLOOD_WATCH"), Some(AlertLevel::Action));
+        assert_eq!(AlertLevel::parse("FLOOD_WARNING"), Some(AlertLevel::Flood));
+        // Flood event vocabulary
+        assert_eq!(AlertLevel::parse("flood"), Some(AlertLevel::Flood));
+        assert_eq!(AlertLevel::parse("moderate"), Some(AlertLevel::Moderate));
+        assert_eq!(AlertLevel::parse("major"), Some(AlertLevel::Major));
+
+        assert_eq!(AlertLevel::parse("SEVERE"), None);
+    }
+
+    #[test]
+    fn test_canonical_strings_round_trip() {
+        for level in AlertLevel::ALL {
+            assert_eq!(AlertLevel::parse(level.as_str()), Some(level));
+            assert_eq!(serde_json::to_value(level).unwrap(), level.as_str());
+        }
+    }
+
+    #[test]
+    fn test_ordering_puts_flooding_above_data_quality() {
+        assert!(AlertLevel::Major > AlertLevel::Flood);
+        assert!(AlertLevel::Watch > AlertLevel::Normal);
+        assert!(AlertLevel::Normal > AlertLevel::Degraded);
+        assert!(!AlertLevel::Degraded.is_elevated());
+        assert!(AlertLevel::Watch.is_elevated());
+    }
+
+    #[test]
+    fn test_display_overrides_apply_per_field() {
+        let config: DisplayConfig = toml::from_str(
+            r##"
+            [action]
+            label = "Minor concern"
+            "##,
+        ).unwrap();
+
+        let action = config.display_for(AlertLevel::Action);
+        assert_eq!(action.label, "Minor concern");
+        assert_eq!(action.color, "#FFFF00", "unset color keeps the default");
+
+        assert_eq!(config.display_for(AlertLevel::Major).label, "Major flooding");
+    }
+
+    #[test]
+    fn test_display_config_rejects_unknown_level() {
+        assert!(toml::from_str::<DisplayConfig>("[critical]\ncolor = \"#FF0000\"").is_err());
+    }
+}
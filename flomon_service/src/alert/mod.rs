@@ -1,6 +1,8 @@
 pub mod config;
+pub mod level;
 pub mod notify;
 pub mod pubsub;
+pub mod quiet_hours;
 pub mod state;
 pub mod stalenesses;
 pub mod thresholds;
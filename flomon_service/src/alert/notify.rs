@@ -9,19 +9,31 @@
 ///
 /// The daemon should call `process_reading_alert` for every USGS stage reading
 /// and `send_daily_digest` once per day if a digest is configured.
+///
+/// When `[alerting.quiet_hours]` is configured, non-critical alerts raised
+/// inside the window — action-level alerts, all-clears, and sensor
+/// degraded/recovered notices — are held (latest per site) and released by
+/// `flush_deferred` once the window closes. Every kind goes through
+/// `deliver`, so only critical (flood stage and above) alerts go out.
 
 use crate::alert::config::AlertingConfig;
+use crate::analysis::backwater::{self, BackwaterOnset};
+use crate::analysis::sustained::SustainedEvent;
 use crate::alert::pubsub::{self, AlertMessage};
+use crate::alert::quiet_hours::{self, QuietHours};
 use crate::alert::state::AlertStateStore;
-use crate::alert::thresholds::{check_flood_stage, FloodAlert, FloodSeverity};
+use crate::alert::thresholds::{check_flood_stage, ApproachingFloodAlert, FloodAlert, FloodSeverity};
 use crate::model::{FloodThresholds, GaugeReading};
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::error::Error;
 
 pub struct Notifier {
     config: AlertingConfig,
     state: AlertStateStore,
     http: reqwest::blocking::Client,
+    quiet_hours: Option<QuietHours>,
+    /// Non-critical messages held during quiet hours, at most one per site.
+    deferred: Vec<AlertMessage>,
 }
 
 impl Notifier {
@@ -32,6 +44,7 @@ impl Notifier {
     pub fn try_load() -> Option<Self> {
         match AlertingConfig::load() {
             Ok(config) => {
+                crate::config_status::record_loaded("alerting.toml");
                 if !config.alerting.enabled {
                     println!("ℹ Alerting disabled in alerting.toml — skipping notifications");
                     return None;
@@ -46,10 +59,21 @@ impl Notifier {
                     config.alerting.pubsub_topic,
                     !config.alerting.pubsub_enabled,
                 );
+                // Already validated by AlertingConfig::load
+                let quiet_hours = config.alerting.quiet_hours.as_ref()
+                    .and_then(|q| QuietHours::from_config(q).ok());
+                if let Some(ref q) = quiet_hours {
+                    println!(
+                        "🌙 Quiet hours {}–{} {} — non-critical alerts held until morning",
+                        q.start.format("%H:%M"), q.end.format("%H:%M"), q.timezone,
+                    );
+                }
                 Some(Self {
                     config,
                     state: AlertStateStore::new(),
                     http,
+                    quiet_hours,
+                    deferred: Vec::new(),
                 })
             }
             Err(e) => {
@@ -98,26 +122,224 @@ impl Notifier {
             site_code: reading.site_code.clone(),
         };
 
-        match pubsub::publish(
-            &self.http,
-            &self.config.alerting.pubsub_project,
-            &self.config.alerting.pubsub_topic,
-            &message,
-            self.config.alerting.pubsub_enabled,
-        ) {
-            Ok(_) => {
-                self.state.record_notification(
-                    &reading.site_code,
-                    alert.map(|a| a.severity),
-                    now,
-                );
-            }
-            Err(e) => {
+        if let Err(e) = self.deliver(&reading.site_code, message, alert.map(|a| a.severity), now) {
+            eprintln!(
+                "Warning: Failed to publish alert for {}: {}",
+                reading.site_code, e
+            );
+        }
+    }
+
+    /// Alert when LaGrange backwater is both high and building fast,
+    /// per `[alerting.backwater_onset]`. No-op when that section is absent.
+    ///
+    /// Treated as flood severity: it uses the flood update interval and is
+    /// never held for quiet hours. An all-clear follows once it eases, held
+    /// like any other.
+    pub fn process_backwater_onset(&mut self, onset: &BackwaterOnset) {
+        let Some(cfg) = self.config.alerting.backwater_onset.clone() else {
+            return;
+        };
+
+        let rapid = backwater::is_rapid_onset(onset, cfg.min_differential_ft, cfg.min_rate_ft_per_hour);
+        let severity = rapid.then_some(FloodSeverity::Flood);
+
+        let interval = self.interval_for(severity.as_ref());
+        let now = Utc::now();
+
+        if !self.state.should_notify(BACKWATER_ALERT_KEY, severity.as_ref(), interval, now) {
+            return;
+        }
+
+        let (body, severity_tag) = if rapid {
+            (
+                format!(
+                    "BACKWATER ONSET at LaGrange L&D — tailwater {:.1} ft relative to pool and rising {:.2} ft/hr over {}h. Mississippi backwater is building toward Peoria.",
+                    onset.differential_ft, onset.rate_ft_per_hour, cfg.window_hours
+                ),
+                "backwater_onset".to_string(),
+            )
+        } else {
+            (
+                format!(
+                    "Backwater onset at LaGrange has eased — tailwater {:.1} ft relative to pool, changing {:.2} ft/hr.",
+                    onset.differential_ft, onset.rate_ft_per_hour
+                ),
+                "all_clear".to_string(),
+            )
+        };
+
+        let message = AlertMessage {
+            body,
+            recipients: self.config.alerting.recipients.numbers.clone(),
+            event_time: now.to_rfc3339(),
+            severity: severity_tag,
+            site_code: BACKWATER_ALERT_KEY.to_string(),
+        };
+
+        if let Err(e) = self.deliver(BACKWATER_ALERT_KEY, message, severity, now) {
+            eprintln!("Warning: Failed to publish backwater onset alert: {}", e);
+        }
+    }
+
+    /// Alert when stage at `site_code` has stayed above its threshold for
+    /// the configured duration (`event` is `Some`), and send an all-clear
+    /// once it drops (`None` after an alert).
+    ///
+    /// Treated as action severity, so it is held over quiet hours. Tracked
+    /// under its own alert-state key so it doesn't interfere with the
+    /// site's threshold-crossing alerts.
+    pub fn process_sustained_high_water(
+        &mut self,
+        site_code: &str,
+        site_name: &str,
+        event: Option<&SustainedEvent>,
+    ) {
+        let key = sustained_alert_key(site_code);
+        let severity = event.map(|_| FloodSeverity::Action);
+
+        let interval = self.interval_for(severity.as_ref());
+        let now = Utc::now();
+
+        if !self.state.should_notify(&key, severity.as_ref(), interval, now) {
+            return;
+        }
+
+        let (body, severity_tag) = match event {
+            Some(e) => (
+                format!(
+                    "SUSTAINED HIGH WATER at {} — stage has held above {:.1} ft for {:.0} hours (now {:.2} ft, peak {:.2} ft). Expect saturated ground and slow drainage.",
+                    site_name, e.threshold_ft, e.duration_hours, e.latest_ft, e.peak_ft
+                ),
+                "sustained_high_water".to_string(),
+            ),
+            None => (
+                format!("Sustained high water at {} has ended — stage is back below threshold.", site_name),
+                "all_clear".to_string(),
+            ),
+        };
+
+        let message = AlertMessage {
+            body,
+            recipients: self.config.alerting.recipients.numbers.clone(),
+            event_time: event.map(|e| e.latest_at).unwrap_or(now).to_rfc3339(),
+            severity: severity_tag,
+            site_code: key.clone(),
+        };
+
+        if let Err(e) = self.deliver(&key, message, severity, now) {
+            eprintln!("Warning: Failed to publish sustained high water alert for {}: {}", site_code, e);
+        }
+    }
+
+    /// Heads-up while a stage rises toward flood stage (`alert` is `Some`),
+    /// and a follow-up once the rise stalls (`None` after an alert).
+    ///
+    /// Uses the action update interval under its own alert-state key, so
+    /// the site's crossing alerts are unaffected. Action severity, so held
+    /// over quiet hours; the flood-stage crossing itself is not.
+    pub fn process_approaching_flood_stage(
+        &mut self,
+        site_code: &str,
+        site_name: &str,
+        stage_ft: f64,
+        alert: Option<&ApproachingFloodAlert>,
+    ) {
+        let key = approaching_alert_key(site_code);
+        let severity = alert.map(|_| FloodSeverity::Action);
+
+        let interval = self.interval_for(severity.as_ref());
+        let now = Utc::now();
+
+        if !self.state.should_notify(&key, severity.as_ref(), interval, now) {
+            return;
+        }
+
+        let (body, severity_tag) = match alert {
+            Some(a) => (a.message.clone(), "approaching_flood_stage".to_string()),
+            None => (
+                format!("{} is no longer rising toward flood stage — stage {:.2} ft.", site_name, stage_ft),
+                "all_clear".to_string(),
+            ),
+        };
+
+        let message = AlertMessage {
+            body,
+            recipients: self.config.alerting.recipients.numbers.clone(),
+            event_time: now.to_rfc3339(),
+            severity: severity_tag,
+            site_code: key.clone(),
+        };
+
+        if let Err(e) = self.deliver(&key, message, severity, now) {
+            eprintln!("Warning: Failed to publish approaching flood stage alert for {}: {}", site_code, e);
+        }
+    }
+
+    /// Notice that a station's sensor has degraded (`reason` is `Some`, e.g.
+    /// a flatlined stage) and, once it recovers, that it is reporting
+    /// normally again (`None` after a notice).
+    ///
+    /// Never critical: held over quiet hours. Tracked under its own
+    /// alert-state key at action severity, repeating on the action interval
+    /// while the sensor stays degraded.
+    pub fn process_sensor_degraded(&mut self, site_code: &str, reason: Option<&str>) {
+        let key = degraded_alert_key(site_code);
+        let severity = reason.map(|_| FloodSeverity::Action);
+
+        let interval = self.interval_for(severity.as_ref());
+        let now = Utc::now();
+
+        if !self.state.should_notify(&key, severity.as_ref(), interval, now) {
+            return;
+        }
+
+        let (body, severity_tag) = match reason {
+            Some(reason) => (
+                format!("SENSOR DEGRADED at {} — {}. Readings from this gauge may not reflect the river.", site_code, reason),
+                "degraded".to_string(),
+            ),
+            None => (
+                format!("Sensor at {} is reporting normally again.", site_code),
+                "all_clear".to_string(),
+            ),
+        };
+
+        let message = AlertMessage {
+            body,
+            recipients: self.config.alerting.recipients.numbers.clone(),
+            event_time: now.to_rfc3339(),
+            severity: severity_tag,
+            site_code: key.clone(),
+        };
+
+        if let Err(e) = self.deliver(&key, message, severity, now) {
+            eprintln!("Warning: Failed to publish sensor degraded alert for {}: {}", site_code, e);
+        }
+    }
+
+    /// Deliver alerts held during quiet hours once the window has closed.
+    ///
+    /// Call once per poll cycle. Messages that fail to publish stay queued.
+    pub fn flush_deferred(&mut self) {
+        if self.deferred.is_empty() || self.in_quiet_hours(Utc::now()) {
+            return;
+        }
+
+        let pending = std::mem::take(&mut self.deferred);
+        for message in pending {
+            if let Err(e) = pubsub::publish(
+                &self.http,
+                &self.config.alerting.pubsub_project,
+                &self.config.alerting.pubsub_topic,
+                &message,
+                self.config.alerting.pubsub_enabled,
+            ) {
                 eprintln!(
-                    "Warning: Failed to publish alert for {}: {}",
-                    reading.site_code, e
+                    "Warning: Failed to publish deferred alert for {}: {}",
+                    message.site_code, e
                 );
-                // Do NOT record the notification — will retry next poll cycle.
+                self.deferred.push(message);
             }
         }
     }
@@ -168,6 +390,41 @@ impl Notifier {
     // Helpers
     // -----------------------------------------------------------------------
 
+    /// Publish `message` and record it under `key`, or — for a non-critical
+    /// `severity` during quiet hours — hold it until `flush_deferred`,
+    /// replacing any message already held for the same key. A held message
+    /// is recorded as notified so the cooldown doesn't re-queue it every
+    /// poll cycle; a failed publish is not recorded, so it retries next
+    /// cycle.
+    fn deliver(
+        &mut self,
+        key: &str,
+        message: AlertMessage,
+        severity: Option<FloodSeverity>,
+        now: DateTime<Utc>,
+    ) -> Result<(), Box<dyn Error>> {
+        if !quiet_hours::is_critical(severity.as_ref()) && self.in_quiet_hours(now) {
+            self.deferred.retain(|m| m.site_code != message.site_code);
+            self.deferred.push(message);
+            self.state.record_notification(key, severity, now);
+            return Ok(());
+        }
+
+        pubsub::publish(
+            &self.http,
+            &self.config.alerting.pubsub_project,
+            &self.config.alerting.pubsub_topic,
+            &message,
+            self.config.alerting.pubsub_enabled,
+        )?;
+        self.state.record_notification(key, severity, now);
+        Ok(())
+    }
+
+    fn in_quiet_hours(&self, now: DateTime<Utc>) -> bool {
+        self.quiet_hours.as_ref().is_some_and(|q| q.contains(now))
+    }
+
     fn interval_for(&self, severity: Option<&FloodSeverity>) -> u64 {
         let iv = &self.config.alerting.intervals_minutes;
         match severity {
@@ -180,6 +437,24 @@ impl Notifier {
     }
 }
 
+/// Alert-state key for the basin-level backwater onset alert.
+const BACKWATER_ALERT_KEY: &str = "backwater:LaGrange";
+
+/// Alert-state key for a site's sustained high water alert.
+fn sustained_alert_key(site_code: &str) -> String {
+    format!("sustained:{}", site_code)
+}
+
+/// Alert-state key for a site's approaching-flood-stage alert.
+fn approaching_alert_key(site_code: &str) -> String {
+    format!("approaching:{}", site_code)
+}
+
+/// Alert-state key for a site's sensor degraded notice.
+fn degraded_alert_key(site_code: &str) -> String {
+    format!("degraded:{}", site_code)
+}
+
 fn severity_tag(s: &FloodSeverity) -> String {
     match s {
         FloodSeverity::Action => "action",
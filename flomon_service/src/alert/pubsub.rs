@@ -18,7 +18,7 @@ use std::error::Error;
 ///
 /// The `sms_gateway` subscriber reads `body` and forwards it to every
 /// phone number listed in `recipients`.
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct AlertMessage {
     /// Plain-text body of the SMS to deliver.
     pub body: String,
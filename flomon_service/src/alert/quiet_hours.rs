@@ -0,0 +1,128 @@
+//! Quiet-hours window for non-critical alerts.
+//!
+//! During quiet hours only critical alerts (flood stage and above) are
+//! delivered immediately. Everything else — action-stage notices and
+//! all-clears — is held by the `Notifier` and sent once the window closes.
+//!
+//! The window is expressed in station-local wall-clock time (e.g. 22:00 to
+//! 07:00 America/Chicago) so it tracks CST/CDT without reconfiguration.
+
+use crate::alert::config::QuietHoursConfig;
+use crate::alert::thresholds::FloodSeverity;
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::Tz;
+
+/// A parsed, validated quiet-hours window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuietHours {
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+    pub timezone: Tz,
+}
+
+impl QuietHours {
+    /// Parse the raw alerting.toml section. Times are "HH:MM".
+    pub fn from_config(config: &QuietHoursConfig) -> Result<Self, String> {
+        let parse_time = |s: &str| {
+            NaiveTime::parse_from_str(s, "%H:%M")
+                .map_err(|_| format!("Invalid quiet-hours time '{}' (expected HH:MM)", s))
+        };
+
+        let timezone: Tz = config.timezone.parse()
+            .map_err(|_| format!("Invalid quiet-hours timezone '{}'", config.timezone))?;
+
+        Ok(Self {
+            start: parse_time(&config.start)?,
+            end: parse_time(&config.end)?,
+            timezone,
+        })
+    }
+
+    /// Returns true if `now` falls inside the window.
+    ///
+    /// The start is inclusive and the end exclusive. A window whose end is
+    /// earlier than its start wraps past midnight (22:00–07:00).
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let local = now.with_timezone(&self.timezone).time();
+
+        if self.start <= self.end {
+            local >= self.start && local < self.end
+        } else {
+            local >= self.start || local < self.end
+        }
+    }
+}
+
+/// Critical alerts bypass quiet hours: flood stage and above.
+///
+/// `None` is an all-clear, which is never critical.
+pub fn is_critical(severity: Option<&FloodSeverity>) -> bool {
+    matches!(
+        severity,
+        Some(FloodSeverity::Flood) | Some(FloodSeverity::Moderate) | Some(FloodSeverity::Major)
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn overnight() -> QuietHours {
+        QuietHours::from_config(&QuietHoursConfig {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            timezone: "America/Chicago".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_overnight_window_wraps_midnight() {
+        let quiet = overnight();
+        // 2024-07-15 08:00 UTC = 03:00 CDT
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 7, 15, 8, 0, 0).unwrap()));
+        // 2024-07-15 03:30 UTC = 22:30 CDT (previous evening)
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 7, 15, 3, 30, 0).unwrap()));
+        // 2024-07-15 18:00 UTC = 13:00 CDT
+        assert!(!quiet.contains(Utc.with_ymd_and_hms(2024, 7, 15, 18, 0, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_window_follows_standard_time_in_winter() {
+        let quiet = overnight();
+        // 2024-01-15 12:30 UTC = 06:30 CST — still quiet
+        assert!(quiet.contains(Utc.with_ymd_and_hms(2024, 1, 15, 12, 30, 0).unwrap()));
+        // 2024-07-15 12:30 UTC = 07:30 CDT — window closed
+        assert!(!quiet.contains(Utc.with_ymd_and_hms(2024, 7, 15, 12, 30, 0).unwrap()));
+    }
+
+    #[test]
+    fn test_invalid_config_is_rejected() {
+        let bad_time = QuietHoursConfig {
+            start: "10pm".to_string(),
+            end: "07:00".to_string(),
+            timezone: "America/Chicago".to_string(),
+        };
+        assert!(QuietHours::from_config(&bad_time).is_err());
+
+        let bad_tz = QuietHoursConfig {
+            start: "22:00".to_string(),
+            end: "07:00".to_string(),
+            timezone: "Central".to_string(),
+        };
+        assert!(QuietHours::from_config(&bad_tz).is_err());
+    }
+
+    #[test]
+    fn test_only_flood_stage_and_above_is_critical() {
+        assert!(!is_critical(None));
+        assert!(!is_critical(Some(&FloodSeverity::Action)));
+        assert!(is_critical(Some(&FloodSeverity::Flood)));
+        assert!(is_critical(Some(&FloodSeverity::Major)));
+    }
+}
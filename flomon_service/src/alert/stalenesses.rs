@@ -70,11 +70,12 @@ pub fn is_stale(reading: &GaugeReading, max_age_minutes: u64) -> Result<bool, St
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::model::GaugeReading;
+    use crate::model::{GaugeReading, ReadingSource};
     use chrono::{TimeZone, Utc};
 
     fn reading_at(datetime: &str) -> GaugeReading {
         GaugeReading {
+            agency_code: "USGS".to_string(),
             site_code: "05568500".to_string(),
             site_name: "Illinois River at Kingston Mines, IL".to_string(),
             parameter_code: "00060".to_string(),
@@ -82,6 +83,7 @@ mod tests {
             value: 42_300.0,
             datetime: datetime.to_string(),
             qualifier: "P".to_string(),
+            source: ReadingSource::UsgsIv,
         }
     }
 
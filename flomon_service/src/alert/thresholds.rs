@@ -31,6 +31,17 @@ pub struct FloodAlert {
     pub message: String,
 }
 
+/// Heads-up that a rising stage is closing in on flood stage.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ApproachingFloodAlert {
+    pub stage_ft: f64,
+    pub flood_stage_ft: f64,
+    pub rate_ft_per_hour: f64,
+    /// Time to reach flood stage if the current rate holds
+    pub hours_to_flood_stage: f64,
+    pub message: String,
+}
+
 /// Checks if a stage reading exceeds any flood thresholds and returns an
 /// alert if so.
 ///
@@ -83,3 +94,92 @@ pub fn check_flood_stage(
         None
     }
 }
+
+/// Checks whether a stage below flood stage is within `margin_ft` of it
+/// and rising at least `min_rate_ft_per_hour` (and above zero).
+///
+/// Returns `None` at or above flood stage — `check_flood_stage` covers
+/// that — and whenever the trend is unknown, flat, or falling.
+pub fn check_approaching_flood_stage(
+    site_name: &str,
+    stage_ft: f64,
+    rate_ft_per_hour: Option<f64>,
+    thresholds: &FloodThresholds,
+    margin_ft: f64,
+    min_rate_ft_per_hour: f64,
+) -> Option<ApproachingFloodAlert> {
+    let flood_stage_ft = thresholds.flood_stage_ft;
+    let below_ft = flood_stage_ft - stage_ft;
+    if below_ft <= 0.0 || below_ft > margin_ft {
+        return None;
+    }
+
+    let rate = rate_ft_per_hour.filter(|&r| r > 0.0 && r >= min_rate_ft_per_hour)?;
+    let hours_to_flood_stage = below_ft / rate;
+
+    Some(ApproachingFloodAlert {
+        stage_ft,
+        flood_stage_ft,
+        rate_ft_per_hour: rate,
+        hours_to_flood_stage,
+        message: format!(
+            "APPROACHING FLOOD STAGE at {}: {:.2} ft, {:.2} ft below flood stage ({:.2} ft) and rising {:.2} ft/hr — flood stage in about {:.0} hours at this rate.",
+            site_name, stage_ft, below_ft, flood_stage_ft, rate, hours_to_flood_stage
+        ),
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kingston_mines() -> FloodThresholds {
+        FloodThresholds {
+            action_stage_ft: 14.0,
+            flood_stage_ft: 16.0,
+            moderate_flood_stage_ft: 20.0,
+            major_flood_stage_ft: 24.0,
+        }
+    }
+
+    #[test]
+    fn test_approaching_needs_margin_and_rise() {
+        let thresholds = kingston_mines();
+        let check = |stage, rate| check_approaching_flood_stage("Kingston Mines", stage, rate, &thresholds, 1.0, 0.05);
+
+        let alert = check(15.2, Some(0.1)).unwrap();
+        assert!((alert.hours_to_flood_stage - 8.0).abs() < 1e-9);
+        assert!(alert.message.starts_with("APPROACHING FLOOD STAGE at Kingston Mines: 15.20 ft, 0.80 ft below"));
+
+        // Outside the margin, already over, or not rising fast enough
+        assert_eq!(check(14.8, Some(0.1)), None);
+        assert_eq!(check(16.0, Some(0.1)), None);
+        assert_eq!(check(15.5, Some(0.02)), None);
+        assert_eq!(check(15.5, Some(-0.1)), None);
+        assert_eq!(check(15.5, None), None);
+    }
+
+    #[test]
+    fn test_approaching_is_distinct_from_crossing() {
+        let thresholds = kingston_mines();
+        let reading = GaugeReading {
+            agency_code: "USGS".to_string(),
+            site_code: "05568500".to_string(),
+            site_name: "Kingston Mines".to_string(),
+            parameter_code: "00065".to_string(),
+            unit: "ft".to_string(),
+            value: 15.5,
+            datetime: "2019-05-01T12:00:00.000-05:00".to_string(),
+            qualifier: "P".to_string(),
+            source: crate::model::ReadingSource::UsgsIv,
+        };
+
+        // Still only an action-stage crossing, plus the approach heads-up
+        assert_eq!(check_flood_stage(&reading, &thresholds).unwrap().severity, FloodSeverity::Action);
+        assert!(check_approaching_flood_stage(&reading.site_name, reading.value, Some(0.2), &thresholds, 1.0, 0.05).is_some());
+    }
+}
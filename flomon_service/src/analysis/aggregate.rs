@@ -0,0 +1,64 @@
+//! Hourly aggregates of high-resolution readings.
+//!
+//! USGS instantaneous values arrive every 15 minutes, so a month of one
+//! parameter is ~3,000 rows. For wide time ranges (charts, seasonal and
+//! daily percentile work) the per-hour min/mean/max/count carries the
+//! same shape at a quarter of the size, and the database does the
+//! reduction in one `date_trunc('hour', ...)` GROUP BY.
+
+use chrono::{DateTime, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+/// Summary of one hour of readings.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HourlyStat {
+    /// Start of the hour (UTC)
+    pub hour: DateTime<Utc>,
+    pub min: f64,
+    pub mean: f64,
+    pub max: f64,
+    /// Readings that fell in the hour; 4 for a complete 15-minute hour
+    pub count: i64,
+}
+
+/// Per-hour min/mean/max/count columns over a `value` column, in
+/// `HourlyStat` order after the hour. Shared with the zone history query.
+pub const HOURLY_STATS_SQL: &str =
+    "MIN(value)::float8, AVG(value)::float8, MAX(value)::float8, COUNT(value)";
+
+/// Per-hour min/mean/max/count of `parameter_code` at `site_code` over
+/// `start..end`, oldest first. Hours without readings are omitted.
+pub fn hourly_aggregate(
+    site_code: &str,
+    parameter_code: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    client: &mut Client,
+) -> Result<Vec<HourlyStat>, String> {
+    let sql = format!(
+        "SELECT date_trunc('hour', reading_time) AS hour, {}
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1
+           AND parameter_code = $2
+           AND reading_time >= $3
+           AND reading_time < $4
+         GROUP BY 1
+         ORDER BY 1 ASC",
+        HOURLY_STATS_SQL
+    );
+    let rows = client.query(
+        &sql,
+        &[&site_code, &parameter_code, &start, &end]
+    ).map_err(|e| format!("Failed to aggregate hourly readings for {}: {}", site_code, e))?;
+
+    Ok(rows.iter()
+        .map(|row| HourlyStat {
+            hour: row.get(0),
+            min: row.get(1),
+            mean: row.get(2),
+            max: row.get(3),
+            count: row.get(4),
+        })
+        .collect())
+}
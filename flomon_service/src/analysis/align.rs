@@ -0,0 +1,104 @@
+//! Tolerance-based alignment of two time series.
+//!
+//! Sources report on different cadences: USGS IV lands on :00/:15/:30/:45,
+//! while CWMS gauges often report a few minutes off the hour (:07, :22, ...).
+//! Joining on exact timestamps pairs almost nothing, and bucketing into
+//! hours averages away the timing. `align_series` instead pairs each reading
+//! with the nearest reading of the other series within a tolerance, so a
+//! difference is only ever taken between observations of the same moment.
+
+use chrono::{DateTime, Duration, Utc};
+
+use crate::analysis::interpolate::TimedValue;
+
+/// Default pairing window: one full USGS IV interval (15 minutes) either
+/// side. Half that would cover any off-cadence reading; the extra slack lets
+/// a reading still pair across a single missed report on the other series.
+pub const DEFAULT_ALIGN_TOLERANCE_MINUTES: i64 = 15;
+
+/// Merge two series (each sorted ascending) into one timeline.
+///
+/// Readings within `tolerance` of each other are paired and stamped with
+/// `a`'s timestamp; a reading with no partner appears alone with `None` on
+/// the other side. Each reading is used at most once, and when several
+/// candidates are in range the closer pair wins.
+pub fn align_series(
+    a: &[TimedValue],
+    b: &[TimedValue],
+    tolerance: Duration,
+) -> Vec<(DateTime<Utc>, Option<f64>, Option<f64>)> {
+    let gap = |x: &TimedValue, y: &TimedValue| (x.timestamp - y.timestamp).abs();
+    let mut aligned = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        let (ra, rb) = (&a[i], &b[j]);
+        let distance = gap(ra, rb);
+
+        // Defer if the next reading on either side is a better partner
+        let better_a = a.get(i + 1).is_some_and(|next| gap(next, rb) < distance);
+        let better_b = b.get(j + 1).is_some_and(|next| gap(ra, next) < distance);
+
+        if distance <= tolerance && !better_a && !better_b {
+            aligned.push((ra.timestamp, Some(ra.value), Some(rb.value)));
+            i += 1;
+            j += 1;
+        } else if ra.timestamp <= rb.timestamp {
+            aligned.push((ra.timestamp, Some(ra.value), None));
+            i += 1;
+        } else {
+            aligned.push((rb.timestamp, None, Some(rb.value)));
+            j += 1;
+        }
+    }
+
+    aligned.extend(a[i..].iter().map(|ra| (ra.timestamp, Some(ra.value), None)));
+    aligned.extend(b[j..].iter().map(|rb| (rb.timestamp, None, Some(rb.value))));
+    aligned
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(minutes: &[(i64, f64)]) -> Vec<TimedValue> {
+        let t0 = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        minutes.iter()
+            .map(|&(m, value)| TimedValue { timestamp: t0 + Duration::minutes(m), value })
+            .collect()
+    }
+
+    #[test]
+    fn test_off_cadence_readings_pair_within_tolerance() {
+        // USGS on the quarter hour, CWMS at :07 past
+        let usgs = at(&[(0, 1.0), (15, 2.0), (30, 3.0), (45, 4.0), (60, 5.0)]);
+        let cwms = at(&[(7, 10.0), (67, 20.0)]);
+
+        let aligned = align_series(&usgs, &cwms, Duration::minutes(10));
+        let paired: Vec<_> = aligned.iter().filter(|(_, x, y)| x.is_some() && y.is_some()).collect();
+
+        assert_eq!(aligned.len(), 5, "every USGS reading kept, CWMS folded in");
+        assert_eq!(paired.len(), 2);
+        // :07 is closer to :00 than :15, :67 closest to :60
+        assert_eq!(paired[0].1, Some(1.0));
+        assert_eq!(paired[1].1, Some(5.0));
+        assert_eq!(paired[1].2, Some(20.0));
+    }
+
+    #[test]
+    fn test_readings_outside_tolerance_stay_unpaired() {
+        let a = at(&[(0, 1.0)]);
+        let b = at(&[(30, 2.0)]);
+
+        let aligned = align_series(&a, &b, Duration::minutes(15));
+        assert_eq!(aligned, vec![
+            (a[0].timestamp, Some(1.0), None),
+            (b[0].timestamp, None, Some(2.0)),
+        ]);
+    }
+}
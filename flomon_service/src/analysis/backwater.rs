@@ -0,0 +1,266 @@
+//! Backwater onset dynamics at LaGrange Lock and Dam.
+//!
+//! `cwms::classify_backwater_severity` describes a single snapshot, but the
+//! dangerous signal is Mississippi backwater *building*: LaGrange tailwater
+//! climbing toward (or past) pool faster than upstream flow explains.
+//!
+//! The differential here is tailwater minus pool, so it follows the same
+//! sign convention as `cwms::detect_backwater` (downstream minus upstream):
+//! strongly negative while the dam holds pool, approaching zero as backwater
+//! builds, and positive once hydraulic control is lost. A positive rate
+//! means backwater is building.
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+
+use crate::analysis::align::align_series;
+use crate::analysis::interpolate::TimedValue;
+use crate::ingest::cwms::timeseries_base_parameter;
+
+/// CWMS location LaGrange Lock and Dam's elevations are stored under
+/// (`location_id`, the first segment of the timeseries ID).
+pub const LAGRANGE_LOCATION_ID: &str = "IL08";
+
+/// Parameter segments of the LaGrange pool and tailwater elevations, as
+/// in `IL08.Elev.Inst.~1Hour.0.CBT-RAW` and
+/// `IL08.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW`.
+pub const LAGRANGE_POOL_PARAMETER: &str = "Elev";
+pub const LAGRANGE_TAILWATER_PARAMETER: &str = "Elev-Tailwater";
+
+/// Current differential and how fast it is changing.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackwaterOnset {
+    /// Latest tailwater minus pool, in feet
+    pub differential_ft: f64,
+    /// Least-squares trend over the window, in feet per hour
+    pub rate_ft_per_hour: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Rate of Change
+// ---------------------------------------------------------------------------
+
+/// Least-squares slope of `series` in units per hour.
+///
+/// A fitted slope rather than last-minus-first keeps one noisy hourly
+/// reading from triggering or masking an onset. Returns `None` with fewer
+/// than two points or no time span.
+pub fn onset_rate(series: &[TimedValue]) -> Option<f64> {
+    if series.len() < 2 {
+        return None;
+    }
+
+    let t0 = series[0].timestamp;
+    let hours: Vec<f64> = series.iter()
+        .map(|tv| (tv.timestamp - t0).num_seconds() as f64 / 3600.0)
+        .collect();
+
+    let n = series.len() as f64;
+    let mean_t = hours.iter().sum::<f64>() / n;
+    let mean_v = series.iter().map(|tv| tv.value).sum::<f64>() / n;
+
+    let (mut cov, mut var) = (0.0, 0.0);
+    for (t, tv) in hours.iter().zip(series) {
+        cov += (t - mean_t) * (tv.value - mean_v);
+        var += (t - mean_t).powi(2);
+    }
+
+    if var == 0.0 {
+        return None;
+    }
+
+    Some(cov / var)
+}
+
+/// True when backwater is both already significant and building fast.
+pub fn is_rapid_onset(
+    onset: &BackwaterOnset,
+    min_differential_ft: f64,
+    min_rate_ft_per_hour: f64,
+) -> bool {
+    onset.differential_ft >= min_differential_ft && onset.rate_ft_per_hour >= min_rate_ft_per_hour
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Stored readings at CWMS `location_id` over the `hours` before `now`,
+/// oldest first, each with its timeseries ID.
+fn cwms_location_readings(
+    client: &mut Client,
+    location_id: &str,
+    hours: i32,
+    now: DateTime<Utc>,
+) -> Result<Vec<(String, TimedValue)>, String> {
+    let rows = client.query(
+        "SELECT timeseries_id, timestamp, value::float8
+         FROM usace.cwms_timeseries
+         WHERE location_id = $1
+           AND timestamp >= $3 - make_interval(hours => $2)
+           AND timestamp <= $3
+         ORDER BY timestamp ASC",
+        &[&location_id, &hours, &now]
+    ).map_err(|e| format!("Failed to fetch {} readings: {}", location_id, e))?;
+
+    Ok(rows.iter()
+        .map(|row| (row.get(0), TimedValue {
+            timestamp: row.get(1),
+            value: row.get(2),
+        }))
+        .collect())
+}
+
+/// The readings whose timeseries has parameter segment `parameter`
+/// (case-insensitive), e.g. only the tailwater from a lock's readings.
+pub fn readings_for_parameter(readings: &[(String, TimedValue)], parameter: &str) -> Vec<TimedValue> {
+    readings.iter()
+        .filter(|(ts_id, _)| timeseries_base_parameter(ts_id).is_some_and(|p| p.eq_ignore_ascii_case(parameter)))
+        .map(|(_, reading)| reading.clone())
+        .collect()
+}
+
+/// LaGrange tailwater-minus-pool differential over the `hours` before
+/// `now`, oldest first. Tailwater readings are paired with the nearest pool
+/// reading within `tolerance` (see `align::align_series`); readings with no
+/// partner are skipped.
+pub fn differential_series(
+    client: &mut Client,
+    hours: i32,
+    tolerance: Duration,
+    now: DateTime<Utc>,
+) -> Result<Vec<TimedValue>, String> {
+    let readings = cwms_location_readings(client, LAGRANGE_LOCATION_ID, hours, now)?;
+    Ok(lagrange_differential(&readings, tolerance))
+}
+
+/// Tailwater minus pool from LaGrange's stored readings (tagged with their
+/// timeseries IDs); anything else stored at the lock is ignored.
+pub fn lagrange_differential(readings: &[(String, TimedValue)], tolerance: Duration) -> Vec<TimedValue> {
+    let tailwater = readings_for_parameter(readings, LAGRANGE_TAILWATER_PARAMETER);
+    let pool = readings_for_parameter(readings, LAGRANGE_POOL_PARAMETER);
+    paired_differential(&tailwater, &pool, tolerance)
+}
+
+/// `a - b` wherever the two series align within `tolerance`.
+pub fn paired_differential(a: &[TimedValue], b: &[TimedValue], tolerance: Duration) -> Vec<TimedValue> {
+    align_series(a, b, tolerance)
+        .into_iter()
+        .filter_map(|(timestamp, a, b)| Some(TimedValue { timestamp, value: a? - b? }))
+        .collect()
+}
+
+/// Rate of change (ft/hour) of the LaGrange tailwater-minus-pool
+/// differential over the `hours` before `now`. `Ok(None)` if there is not
+/// enough paired data in the window.
+pub fn backwater_onset_rate(
+    client: &mut Client,
+    hours: i32,
+    tolerance: Duration,
+    now: DateTime<Utc>,
+) -> Result<Option<f64>, String> {
+    Ok(onset_rate(&differential_series(client, hours, tolerance, now)?))
+}
+
+/// Latest differential together with its rate over the `hours` before `now`.
+pub fn backwater_onset(
+    client: &mut Client,
+    hours: i32,
+    tolerance: Duration,
+    now: DateTime<Utc>,
+) -> Result<Option<BackwaterOnset>, String> {
+    let series = differential_series(client, hours, tolerance, now)?;
+
+    Ok(match (series.last(), onset_rate(&series)) {
+        (Some(latest), Some(rate)) => Some(BackwaterOnset {
+            differential_ft: latest.value,
+            rate_ft_per_hour: rate,
+        }),
+        _ => None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn hourly(values: &[f64]) -> Vec<TimedValue> {
+        values.iter().enumerate()
+            .map(|(i, &value)| TimedValue {
+                timestamp: Utc.with_ymd_and_hms(2019, 6, 1, i as u32, 0, 0).unwrap(),
+                value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_onset_rate_of_steady_rise() {
+        let rate = onset_rate(&hourly(&[-6.0, -5.5, -5.0, -4.5])).unwrap();
+        assert!((rate - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_onset_rate_damps_single_spike() {
+        // Flat except one noisy hour in the middle
+        let rate = onset_rate(&hourly(&[-6.0, -6.0, -3.0, -6.0, -6.0])).unwrap();
+        assert!(rate.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_onset_rate_needs_two_points() {
+        assert_eq!(onset_rate(&hourly(&[-6.0])), None);
+        assert_eq!(onset_rate(&[]), None);
+    }
+
+    #[test]
+    fn test_rapid_onset_requires_high_and_rising() {
+        let high_rising = BackwaterOnset { differential_ft: -1.0, rate_ft_per_hour: 0.4 };
+        let high_steady = BackwaterOnset { differential_ft: -1.0, rate_ft_per_hour: 0.0 };
+        let low_rising = BackwaterOnset { differential_ft: -8.0, rate_ft_per_hour: 0.4 };
+
+        assert!(is_rapid_onset(&high_rising, -2.0, 0.2));
+        assert!(!is_rapid_onset(&high_steady, -2.0, 0.2));
+        assert!(!is_rapid_onset(&low_rising, -2.0, 0.2));
+    }
+
+    #[test]
+    fn test_paired_differential_aligns_off_cadence_readings() {
+        // Pool logged 7 minutes past each tailwater reading
+        let tailwater = hourly(&[430.0, 431.0, 432.0]);
+        let pool: Vec<TimedValue> = hourly(&[436.0, 436.0, 436.0]).into_iter()
+            .map(|tv| TimedValue { timestamp: tv.timestamp + Duration::minutes(7), ..tv })
+            .collect();
+
+        let series = paired_differential(&tailwater, &pool, Duration::minutes(15));
+        let values: Vec<f64> = series.iter().map(|tv| tv.value).collect();
+        assert_eq!(values, vec![-6.0, -5.0, -4.0]);
+        assert_eq!(series[0].timestamp, tailwater[0].timestamp);
+
+        assert!(paired_differential(&tailwater, &pool, Duration::minutes(5)).is_empty());
+    }
+
+    #[test]
+    fn test_differential_from_stored_lock_readings() {
+        // As discovery stores them: pool and tailwater both under IL08
+        let tagged = |ts_id: &str, values: &[f64]| -> Vec<(String, TimedValue)> {
+            hourly(values).into_iter().map(|tv| (ts_id.to_string(), tv)).collect()
+        };
+        let mut readings = tagged("IL08.Elev.Inst.~1Hour.0.CBT-RAW", &[436.0, 436.0, 436.0]);
+        readings.extend(tagged("IL08.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW", &[430.0, 431.5, 433.0]));
+        readings.extend(tagged("IL08.Flow.Inst.~1Hour.0.CBT-RAW", &[95_000.0, 97_000.0, 99_000.0]));
+
+        let tailwater = readings_for_parameter(&readings, LAGRANGE_TAILWATER_PARAMETER);
+        let pool = readings_for_parameter(&readings, LAGRANGE_POOL_PARAMETER);
+        assert_eq!((tailwater.len(), pool.len()), (3, 3));
+
+        let series = lagrange_differential(&readings, Duration::minutes(15));
+        let values: Vec<f64> = series.iter().map(|tv| tv.value).collect();
+        assert_eq!(values, vec![-6.0, -4.5, -3.0]);
+        assert!((onset_rate(&series).unwrap() - 1.5).abs() < 1e-9);
+    }
+}
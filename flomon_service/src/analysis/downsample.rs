@@ -0,0 +1,102 @@
+//! Downsampling long series for charts.
+//!
+//! A week of 15-minute stage is ~670 points per parameter, and a month is
+//! ~2,900; a chart a few hundred pixels wide can't show more than it has
+//! columns. `lttb` (Largest-Triangle-Three-Buckets, Steinarsson 2013)
+//! keeps the points that carry the visual shape — crests and troughs
+//! survive, unlike plain bucket averages, which flatten a sharp peak.
+
+use crate::analysis::interpolate::TimedValue;
+
+/// At most `max_points` of `series` (oldest first), always keeping the
+/// first and last. The rest are chosen one per bucket: the point forming
+/// the largest triangle with the previous pick and the next bucket's
+/// average. A series already within `max_points` is returned as-is.
+pub fn lttb(series: &[TimedValue], max_points: usize) -> Vec<TimedValue> {
+    if series.len() <= max_points {
+        return series.to_vec();
+    }
+    match max_points {
+        0 => return Vec::new(),
+        1 => return vec![series[0].clone()],
+        2 => return vec![series[0].clone(), series[series.len() - 1].clone()],
+        _ => {}
+    }
+
+    let x = |p: &TimedValue| p.timestamp.timestamp() as f64;
+    let last = series.len() - 1;
+    // Interior points split into max_points - 2 buckets
+    let bucket_size = (series.len() - 2) as f64 / (max_points - 2) as f64;
+    let bucket_start = |i: usize| 1 + (i as f64 * bucket_size) as usize;
+
+    let mut sampled = Vec::with_capacity(max_points);
+    sampled.push(series[0].clone());
+    let mut anchor = 0;
+
+    for bucket in 0..max_points - 2 {
+        let (start, end) = (bucket_start(bucket), bucket_start(bucket + 1).min(last));
+
+        // Average of the next bucket (just the last point for the final one)
+        let next = &series[end..bucket_start(bucket + 2).min(last).max(end + 1)];
+        let avg_x = next.iter().map(x).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|p| p.value).sum::<f64>() / next.len() as f64;
+
+        let (ax, ay) = (x(&series[anchor]), series[anchor].value);
+        let mut best = start;
+        let mut best_area = -1.0;
+        for (i, point) in series[start..end].iter().enumerate() {
+            let area = ((ax - avg_x) * (point.value - ay) - (ax - x(point)) * (avg_y - ay)).abs();
+            if area > best_area {
+                best_area = area;
+                best = start + i;
+            }
+        }
+
+        sampled.push(series[best].clone());
+        anchor = best;
+    }
+
+    sampled.push(series[last].clone());
+    sampled
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone, Utc};
+
+    fn series(values: &[f64]) -> Vec<TimedValue> {
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 0, 0, 0).unwrap();
+        values.iter().enumerate()
+            .map(|(i, &value)| TimedValue { timestamp: start + Duration::minutes(15 * i as i64), value })
+            .collect()
+    }
+
+    #[test]
+    fn test_lttb_keeps_endpoints_and_peak() {
+        // A week of 15-minute stage with one sharp crest
+        let mut values: Vec<f64> = (0..672).map(|i| 12.0 + (i as f64) * 0.001).collect();
+        values[400] = 25.0;
+        let week = series(&values);
+
+        let sampled = lttb(&week, 100);
+        assert_eq!(sampled.len(), 100);
+        assert_eq!(sampled.first(), week.first());
+        assert_eq!(sampled.last(), week.last());
+        assert!(sampled.iter().any(|p| p.value == 25.0), "crest should survive");
+        assert!(sampled.windows(2).all(|w| w[0].timestamp < w[1].timestamp));
+    }
+
+    #[test]
+    fn test_lttb_short_series_and_tiny_budgets() {
+        let short = series(&[1.0, 2.0, 3.0]);
+        assert_eq!(lttb(&short, 500), short);
+        assert_eq!(lttb(&short, 2), vec![short[0].clone(), short[2].clone()]);
+        assert!(lttb(&short, 0).is_empty());
+        assert_eq!(lttb(&series(&[1.0, 5.0, 2.0, 3.0]), 3).len(), 3);
+    }
+}
@@ -0,0 +1,248 @@
+//! Current conditions framed against a remembered flood.
+//!
+//! "Three feet below the 2013 peak, rising at half the 2013 rate" means more
+//! to a resident than a stage and a slope. `analog_events` picks, for each
+//! station, the `flood_analysis.events` row that stands for a past flood
+//! (one event id, or a calendar year), and `compare_to_event` measures the
+//! current stage and 24-hour trend against that event's crest and average
+//! rise rate.
+
+use chrono::{DateTime, Datelike, Duration, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::analysis::backwater::onset_rate;
+use crate::analysis::interpolate::TimedValue;
+use crate::model::PARAM_STAGE;
+
+/// Events at other stations belong to the same flood as a chosen event if
+/// they crested within this many days of it.
+pub const SAME_FLOOD_WINDOW_DAYS: i32 = 21;
+
+/// Hours of recent stage the current trend is fit over.
+pub const CURRENT_TREND_HOURS: i64 = 24;
+
+/// Below this rate (either way) a station is described as steady.
+pub const STEADY_RATE_FT_PER_DAY: f64 = 0.1;
+
+/// Which past flood to compare against.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EventSelector {
+    /// One `flood_analysis.events` id, plus the same flood at other stations
+    Id(i32),
+    /// Each station's highest event cresting in this year
+    Year(i32),
+}
+
+/// The part of a past event a comparison needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnalogEvent {
+    pub event_id: i32,
+    pub site_code: String,
+    pub event_peak: DateTime<Utc>,
+    pub peak_stage_ft: f64,
+    pub average_rise_rate_ft_per_day: Option<f64>,
+    pub severity: String,
+}
+
+/// One station's current conditions against its analog event.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EventComparison {
+    pub site_code: String,
+    pub event_id: i32,
+    pub event_peak: DateTime<Utc>,
+    pub event_severity: String,
+    pub event_peak_stage_ft: f64,
+    pub current_stage_ft: f64,
+    /// Positive when below the event's crest
+    pub below_event_peak_ft: f64,
+    pub current_rate_ft_per_day: Option<f64>,
+    pub event_rise_rate_ft_per_day: Option<f64>,
+    /// Current rate as a multiple of the event's average rise rate, when
+    /// both are rising
+    pub rate_ratio: Option<f64>,
+    pub summary: String,
+}
+
+/// Compare `current_stage_ft` and `current_rate_ft_per_day` against `event`.
+pub fn compare_to_event(
+    current_stage_ft: f64,
+    current_rate_ft_per_day: Option<f64>,
+    event: &AnalogEvent,
+) -> EventComparison {
+    let below_event_peak_ft = event.peak_stage_ft - current_stage_ft;
+    let rate_ratio = match (current_rate_ft_per_day, event.average_rise_rate_ft_per_day) {
+        (Some(now), Some(then)) if now >= STEADY_RATE_FT_PER_DAY && then > 0.0 => Some(now / then),
+        _ => None,
+    };
+
+    EventComparison {
+        site_code: event.site_code.clone(),
+        event_id: event.event_id,
+        event_peak: event.event_peak,
+        event_severity: event.severity.clone(),
+        event_peak_stage_ft: event.peak_stage_ft,
+        current_stage_ft,
+        below_event_peak_ft,
+        current_rate_ft_per_day,
+        event_rise_rate_ft_per_day: event.average_rise_rate_ft_per_day,
+        rate_ratio,
+        summary: describe_comparison(below_event_peak_ft, current_rate_ft_per_day, rate_ratio, event.event_peak.year()),
+    }
+}
+
+/// "currently 3.0 ft below the 2013 peak, rising at half the 2013 rate"
+pub fn describe_comparison(
+    below_event_peak_ft: f64,
+    current_rate_ft_per_day: Option<f64>,
+    rate_ratio: Option<f64>,
+    year: i32,
+) -> String {
+    let level = if below_event_peak_ft.abs() < 0.05 {
+        format!("currently at the {} peak", year)
+    } else if below_event_peak_ft > 0.0 {
+        format!("currently {:.1} ft below the {} peak", below_event_peak_ft, year)
+    } else {
+        format!("currently {:.1} ft above the {} peak", -below_event_peak_ft, year)
+    };
+
+    let trend = match (current_rate_ft_per_day, rate_ratio) {
+        (None, _) => return level,
+        (Some(_), Some(ratio)) => format!("rising at {} the {} rate", ratio_words(ratio), year),
+        (Some(rate), None) if rate >= STEADY_RATE_FT_PER_DAY => format!("rising {:.1} ft/day", rate),
+        (Some(rate), None) if rate <= -STEADY_RATE_FT_PER_DAY => format!("falling {:.1} ft/day", -rate),
+        (Some(_), None) => "holding steady".to_string(),
+    };
+
+    format!("{}, {}", level, trend)
+}
+
+/// A rate multiple in words: "half", "about", "twice", or "1.4x".
+fn ratio_words(ratio: f64) -> String {
+    if (ratio - 1.0).abs() < 0.1 {
+        "about".to_string()
+    } else if (ratio - 0.5).abs() < 0.05 {
+        "half".to_string()
+    } else if (ratio - 2.0).abs() < 0.2 {
+        "twice".to_string()
+    } else {
+        format!("{:.1}x", ratio)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// One analog event per station for `selector`: the chosen event and
+/// whatever else crested within `SAME_FLOOD_WINDOW_DAYS` of it, or each
+/// station's highest crest in the year. Highest crest wins per station.
+pub fn analog_events(selector: EventSelector, client: &mut Client) -> Result<Vec<AnalogEvent>, String> {
+    let rows = match selector {
+        EventSelector::Id(event_id) => client.query(
+            "SELECT DISTINCT ON (e.site_code)
+                    e.id, e.site_code, e.event_peak, e.peak_stage_ft::float8,
+                    e.average_rise_rate_ft_per_day::float8, e.severity
+             FROM flood_analysis.events e
+             JOIN flood_analysis.events anchor ON anchor.id = $1
+             WHERE e.peak_stage_ft IS NOT NULL
+               AND e.event_peak BETWEEN anchor.event_peak - make_interval(days => $2)
+                                    AND anchor.event_peak + make_interval(days => $2)
+             ORDER BY e.site_code, (e.id = $1) DESC, e.peak_stage_ft DESC",
+            &[&event_id, &SAME_FLOOD_WINDOW_DAYS]
+        ),
+        EventSelector::Year(year) => client.query(
+            "SELECT DISTINCT ON (site_code)
+                    id, site_code, event_peak, peak_stage_ft::float8,
+                    average_rise_rate_ft_per_day::float8, severity
+             FROM flood_analysis.events
+             WHERE peak_stage_ft IS NOT NULL
+               AND EXTRACT(YEAR FROM event_peak)::int = $1
+             ORDER BY site_code, peak_stage_ft DESC",
+            &[&year]
+        ),
+    }.map_err(|e| format!("Failed to fetch flood events for {:?}: {}", selector, e))?;
+
+    Ok(rows.iter()
+        .map(|row| AnalogEvent {
+            event_id: row.get(0),
+            site_code: row.get(1),
+            event_peak: row.get(2),
+            peak_stage_ft: row.get(3),
+            average_rise_rate_ft_per_day: row.get(4),
+            severity: row.get(5),
+        })
+        .collect())
+}
+
+/// Fitted stage trend at `site_code` over the last `CURRENT_TREND_HOURS`,
+/// in ft/day. `None` with fewer than two readings.
+pub fn current_rate_ft_per_day(
+    site_code: &str,
+    now: DateTime<Utc>,
+    client: &mut Client,
+) -> Result<Option<f64>, String> {
+    let since = now - Duration::hours(CURRENT_TREND_HOURS);
+    let rows = client.query(
+        "SELECT reading_time, value::float8 FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2
+           AND reading_time > $3 AND reading_time <= $4
+         ORDER BY reading_time ASC",
+        &[&site_code, &PARAM_STAGE, &since, &now]
+    ).map_err(|e| format!("Failed to fetch recent stage for {}: {}", site_code, e))?;
+
+    let series: Vec<TimedValue> = rows.iter()
+        .map(|row| TimedValue { timestamp: row.get(0), value: row.get(1) })
+        .collect();
+    Ok(onset_rate(&series).map(|per_hour| per_hour * 24.0))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event_2013() -> AnalogEvent {
+        AnalogEvent {
+            event_id: 42,
+            site_code: "05567500".to_string(),
+            event_peak: Utc.with_ymd_and_hms(2013, 4, 23, 6, 0, 0).unwrap(),
+            peak_stage_ft: 29.35,
+            average_rise_rate_ft_per_day: Some(1.6),
+            severity: "extreme".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_compare_below_peak_rising_slower() {
+        let comparison = compare_to_event(26.35, Some(0.8), &event_2013());
+
+        assert!((comparison.below_event_peak_ft - 3.0).abs() < 1e-9);
+        assert!((comparison.rate_ratio.unwrap() - 0.5).abs() < 1e-9);
+        assert_eq!(comparison.summary, "currently 3.0 ft below the 2013 peak, rising at half the 2013 rate");
+    }
+
+    #[test]
+    fn test_compare_above_peak_or_falling() {
+        let falling = compare_to_event(30.55, Some(-0.4), &event_2013());
+        assert_eq!(falling.rate_ratio, None);
+        assert_eq!(falling.summary, "currently 1.2 ft above the 2013 peak, falling 0.4 ft/day");
+
+        let steady = compare_to_event(20.0, Some(0.02), &event_2013());
+        assert!(steady.summary.ends_with("holding steady"));
+
+        let no_trend = compare_to_event(29.35, None, &event_2013());
+        assert_eq!(no_trend.summary, "currently at the 2013 peak");
+    }
+
+    #[test]
+    fn test_ratio_words() {
+        assert_eq!(ratio_words(1.05), "about");
+        assert_eq!(ratio_words(2.1), "twice");
+        assert_eq!(ratio_words(1.4), "1.4x");
+    }
+}
@@ -0,0 +1,228 @@
+//! One stage projection from the official forecast and our own trend.
+//!
+//! NWS AHPS forecasts are issued every 6-24 hours at 6-hour steps, so for
+//! the next few hours they lag what the gauge is visibly doing; beyond
+//! that, their routing and QPF beat anything a straight-line trend can
+//! see. `blend_forecast` leans on `extrapolate_stage` for the first
+//! `extrapolation_only_hours`, hands over linearly to the official forecast
+//! by `official_only_hours`, and widens an uncertainty band with lead time
+//! and with how much the two sources disagree.
+
+use chrono::{DateTime, Duration, Utc};
+use serde::Serialize;
+
+use crate::analysis::backwater::onset_rate;
+use crate::analysis::interpolate::{interpolate_series, TimedValue};
+
+/// Recent readings the trend is fit over, in hours.
+pub const TREND_WINDOW_HOURS: i64 = 6;
+
+/// How far ahead the trend is projected, in hours. Past this the blend is
+/// official-only anyway.
+pub const EXTRAPOLATION_HORIZON_HOURS: i64 = 12;
+
+/// A projected stage at one instant.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ForecastPoint {
+    pub valid_time: DateTime<Utc>,
+    pub stage_ft: f64,
+}
+
+/// How much each source is trusted by lead time, and how fast the band grows.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlendWeights {
+    /// Up to this lead, the extrapolation alone is used
+    pub extrapolation_only_hours: f64,
+    /// From this lead on, the official forecast alone is used
+    pub official_only_hours: f64,
+    /// Band half-width at zero lead
+    pub base_uncertainty_ft: f64,
+    /// Band half-width added per hour of lead
+    pub uncertainty_growth_ft_per_hour: f64,
+}
+
+impl Default for BlendWeights {
+    fn default() -> Self {
+        BlendWeights {
+            extrapolation_only_hours: 3.0,
+            official_only_hours: 12.0,
+            base_uncertainty_ft: 0.1,
+            uncertainty_growth_ft_per_hour: 0.05,
+        }
+    }
+}
+
+/// One step of the blended projection, with the components it came from.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BlendedPoint {
+    pub valid_time: DateTime<Utc>,
+    pub lead_hours: f64,
+    pub stage_ft: f64,
+    pub lower_ft: f64,
+    pub upper_ft: f64,
+    /// Official forecast at this instant, interpolated between its steps
+    pub official_ft: Option<f64>,
+    pub extrapolated_ft: Option<f64>,
+    /// Share of the blend taken from the official forecast (0-1)
+    pub official_weight: f64,
+}
+
+/// Weight given to the official forecast at `lead_hours`: 0 through the
+/// extrapolation-only window, rising linearly to 1 at `official_only_hours`.
+pub fn official_weight(lead_hours: f64, weights: &BlendWeights) -> f64 {
+    let span = weights.official_only_hours - weights.extrapolation_only_hours;
+    if span <= 0.0 {
+        return if lead_hours < weights.official_only_hours { 0.0 } else { 1.0 };
+    }
+    ((lead_hours - weights.extrapolation_only_hours) / span).clamp(0.0, 1.0)
+}
+
+/// Project the least-squares trend of `recent` (ascending) forward from its
+/// latest reading, hourly out to `EXTRAPOLATION_HORIZON_HOURS` past `now`.
+/// Empty with fewer than two readings.
+pub fn extrapolate_stage(recent: &[TimedValue], now: DateTime<Utc>) -> Vec<ForecastPoint> {
+    let (Some(latest), Some(rate)) = (recent.last(), onset_rate(recent)) else {
+        return Vec::new();
+    };
+
+    (1..=EXTRAPOLATION_HORIZON_HOURS)
+        .map(|h| now + Duration::hours(h))
+        .map(|valid_time| ForecastPoint {
+            valid_time,
+            stage_ft: latest.value + rate * (valid_time - latest.timestamp).num_seconds() as f64 / 3600.0,
+        })
+        .collect()
+}
+
+/// Value of `points` (ascending) at `at`, interpolated within its span only.
+fn value_at(points: &[ForecastPoint], at: DateTime<Utc>) -> Option<f64> {
+    let series: Vec<TimedValue> = points.iter()
+        .map(|p| TimedValue { timestamp: p.valid_time, value: p.stage_ft })
+        .collect();
+    interpolate_series(&series, at, Duration::zero()).map(|v| v.value)
+}
+
+/// Blend the official (`nws_points`) and extrapolated projections, both
+/// ascending, into one series over the union of their valid times.
+///
+/// Where only one source covers an instant it is used as-is. The band is
+/// `base + growth * lead`, plus half the gap between the two sources where
+/// both exist.
+pub fn blend_forecast(
+    nws_points: &[ForecastPoint],
+    extrapolated_points: &[ForecastPoint],
+    weights: &BlendWeights,
+    now: DateTime<Utc>,
+) -> Vec<BlendedPoint> {
+    let mut times: Vec<DateTime<Utc>> = nws_points.iter()
+        .chain(extrapolated_points)
+        .map(|p| p.valid_time)
+        .filter(|t| *t >= now)
+        .collect();
+    times.sort();
+    times.dedup();
+
+    times.into_iter()
+        .filter_map(|valid_time| {
+            let lead_hours = (valid_time - now).num_seconds() as f64 / 3600.0;
+            let official = value_at(nws_points, valid_time);
+            let extrapolated = value_at(extrapolated_points, valid_time);
+
+            let (stage_ft, official_weight, disagreement) = match (official, extrapolated) {
+                (Some(o), Some(e)) => {
+                    let w = official_weight(lead_hours, weights);
+                    (w * o + (1.0 - w) * e, w, (o - e).abs())
+                }
+                (Some(o), None) => (o, 1.0, 0.0),
+                (None, Some(e)) => (e, 0.0, 0.0),
+                (None, None) => return None,
+            };
+
+            let half_width = weights.base_uncertainty_ft
+                + weights.uncertainty_growth_ft_per_hour * lead_hours
+                + disagreement / 2.0;
+
+            Some(BlendedPoint {
+                valid_time,
+                lead_hours,
+                stage_ft,
+                lower_ft: stage_ft - half_width,
+                upper_ft: stage_ft + half_width,
+                official_ft: official,
+                extrapolated_ft: extrapolated,
+                official_weight,
+            })
+        })
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn now() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2019, 5, 1, 12, 0, 0).unwrap()
+    }
+
+    fn point(hours: i64, stage_ft: f64) -> ForecastPoint {
+        ForecastPoint { valid_time: now() + Duration::hours(hours), stage_ft }
+    }
+
+    #[test]
+    fn test_extrapolation_projects_recent_trend() {
+        // Rising 0.1 ft/hour, last reading 30 minutes before now
+        let recent: Vec<TimedValue> = (0..6)
+            .map(|h| TimedValue {
+                timestamp: now() - Duration::minutes(330) + Duration::hours(h),
+                value: 15.0 + 0.1 * h as f64,
+            })
+            .collect();
+
+        let projected = extrapolate_stage(&recent, now());
+        assert_eq!(projected.len(), EXTRAPOLATION_HORIZON_HOURS as usize);
+        assert_eq!(projected[0].valid_time, now() + Duration::hours(1));
+        assert!((projected[0].stage_ft - 15.65).abs() < 1e-9);
+        assert!(extrapolate_stage(&recent[..1], now()).is_empty());
+    }
+
+    #[test]
+    fn test_blend_trusts_extrapolation_early_and_official_late() {
+        let weights = BlendWeights::default();
+        let official = vec![point(0, 16.0), point(6, 17.0), point(12, 18.0), point(18, 19.0)];
+        let extrapolated: Vec<ForecastPoint> = (1..=12).map(|h| point(h, 16.0)).collect();
+
+        let blend = blend_forecast(&official, &extrapolated, &weights, now());
+        let at = |h: f64| blend.iter().find(|p| p.lead_hours == h).unwrap();
+
+        // 2h: extrapolation only, band widened by the sources' disagreement
+        assert_eq!(at(2.0).official_weight, 0.0);
+        assert_eq!(at(2.0).stage_ft, 16.0);
+        assert!((at(2.0).official_ft.unwrap() - (16.0 + 1.0 / 3.0)).abs() < 1e-9);
+        assert!(at(2.0).upper_ft - at(2.0).stage_ft > weights.base_uncertainty_ft + 0.1);
+
+        // Partway through the handover: 5/9 of the way from 3h to 12h
+        let mid = at(8.0);
+        assert!((mid.official_weight - 5.0 / 9.0).abs() < 1e-9);
+        assert!((mid.stage_ft - (5.0 * (17.0 + 1.0 / 3.0) + 4.0 * 16.0) / 9.0).abs() < 1e-9);
+
+        // Past the extrapolation horizon: official alone
+        assert_eq!(at(18.0).stage_ft, 19.0);
+        assert_eq!(at(18.0).extrapolated_ft, None);
+    }
+
+    #[test]
+    fn test_blend_without_official_forecast_is_extrapolation() {
+        let extrapolated = vec![point(1, 15.2), point(2, 15.4)];
+        let blend = blend_forecast(&[], &extrapolated, &BlendWeights::default(), now());
+
+        assert_eq!(blend.len(), 2);
+        assert!(blend.iter().all(|p| p.official_ft.is_none() && p.official_weight == 0.0));
+        assert_eq!(blend[1].stage_ft, 15.4);
+        assert!(blend[1].upper_ft - blend[1].lower_ft > blend[0].upper_ft - blend[0].lower_ft);
+    }
+}
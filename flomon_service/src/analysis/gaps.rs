@@ -0,0 +1,77 @@
+//! Gaps in a sensor's stored record.
+//!
+//! A gap is a stretch longer than `GAP_TOLERANCE_INTERVALS` expected
+//! intervals with no readings, so the odd skipped 15-minute value is not
+//! flagged. The backfill coverage check and the `/sensor/{id}/gaps`
+//! endpoint both use `detect_gaps`, so they agree on what a gap is.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// Expected spacing of USGS instantaneous values.
+pub const USGS_IV_INTERVAL_MINUTES: i64 = 15;
+
+/// Expected spacing of CWMS and ASOS readings. Many CWMS series are
+/// hourly even where others at the same location are 15-minute.
+pub const HOURLY_INTERVAL_MINUTES: i64 = 60;
+
+/// Missing intervals in a row before a stretch counts as a gap.
+pub const GAP_TOLERANCE_INTERVALS: i32 = 2;
+
+/// A gap as (last reading before it, first reading after it).
+pub type Gap = (DateTime<Utc>, DateTime<Utc>);
+
+/// Stretches of `start..=end` longer than `GAP_TOLERANCE_INTERVALS` ×
+/// `expected_interval` with no timestamp, as (last reading or `start`,
+/// next reading or `end`). `timestamps` must be ascending.
+pub fn detect_gaps(
+    timestamps: &[DateTime<Utc>],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    expected_interval: Duration,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let max_spacing = expected_interval * GAP_TOLERANCE_INTERVALS;
+    let mut gaps = Vec::new();
+    let mut previous = start;
+
+    for &t in timestamps.iter().filter(|&&t| t >= start && t <= end) {
+        if t - previous > max_spacing {
+            gaps.push((previous, t));
+        }
+        previous = t;
+    }
+    if end - previous > max_spacing {
+        gaps.push((previous, end));
+    }
+
+    gaps
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_skipped_reading_within_tolerance_is_not_a_gap() {
+        let start = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        let interval = Duration::minutes(15);
+        // 00:00, 00:15, (00:30 missing), 00:45, 01:00
+        let timestamps: Vec<_> = [0, 1, 3, 4].iter().map(|&i| start + interval * i).collect();
+
+        assert!(detect_gaps(&timestamps, start, start + interval * 4, interval).is_empty());
+    }
+
+    #[test]
+    fn test_trailing_silence_is_a_gap_to_end() {
+        let start = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        let interval = Duration::minutes(60);
+        let end = start + Duration::hours(6);
+        let timestamps = vec![start, start + interval];
+
+        assert_eq!(detect_gaps(&timestamps, start, end, interval), vec![(start + interval, end)]);
+    }
+}
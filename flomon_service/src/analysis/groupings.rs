@@ -25,7 +25,8 @@ use crate::zones::{ZonesConfig, Sensor, get_all_zones};
 // Grouping
 // ---------------------------------------------------------------------------
 
-/// Groups a flat list of `GaugeReading`s into a map keyed by site code.
+/// Groups a flat list of `GaugeReading`s into a map keyed by
+/// `GaugeReading::site_key` (the bare site code for USGS sites).
 ///
 /// Within each `SiteReadings`, `discharge_cfs` is populated from the reading
 /// with `parameter_code == "00060"` and `stage_ft` from `"00065"`. If
@@ -36,11 +37,9 @@ pub fn group_by_site(readings: Vec<GaugeReading>) -> HashMap<String, SiteReading
     let mut grouped: HashMap<String, SiteReadings> = HashMap::new();
     
     for reading in readings {
-        let site_code = reading.site_code.clone();
-        
         // Get or create the SiteReadings entry for this site
-        let site_readings = grouped.entry(site_code.clone()).or_insert_with(|| SiteReadings {
-            site_code: site_code.clone(),
+        let site_readings = grouped.entry(reading.site_key()).or_insert_with(|| SiteReadings {
+            site_code: reading.site_code.clone(),
             discharge_cfs: None,
             stage_ft: None,
         });
@@ -92,7 +91,7 @@ pub fn group_by_zone(readings: Vec<GaugeReading>, zones_config: &ZonesConfig) ->
                 site_grouped.get(usgs_id).cloned()
             } else {
                 // For non-USGS sensors (CWMS, ASOS), we'll handle them separately
-                // in the endpoint layer by querying appropriate tables
+                // in `status::assess_sensor` by querying appropriate tables
                 None
             };
             
@@ -121,7 +120,7 @@ mod tests {
     use super::*;
     use crate::alert::thresholds::{check_flood_stage, FloodSeverity};
     use crate::ingest::{fixtures::*, usgs::parse_iv_response};
-    use crate::model::FloodThresholds;
+    use crate::model::{FloodThresholds, ReadingSource};
     use crate::stations::find_station;
 
     // --- Grouping: basic correctness ----------------------------------------
@@ -260,6 +259,7 @@ mod tests {
 
         // Build a synthetic reading below action stage.
         let low_reading = GaugeReading {
+            agency_code: "USGS".to_string(),
             site_code: "05568500".to_string(),
             site_name: "Illinois River at Kingston Mines, IL".to_string(),
             parameter_code: "00065".to_string(),
@@ -267,6 +267,7 @@ mod tests {
             value: 12.0,
             datetime: "2024-05-01T12:00:00.000-05:00".to_string(),
             qualifier: "P".to_string(),
+            source: ReadingSource::UsgsIv,
         };
 
         let alert = check_flood_stage(&low_reading, &thresholds);
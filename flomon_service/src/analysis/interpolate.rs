@@ -0,0 +1,156 @@
+//! Point-in-time interpolation over stored time series.
+//!
+//! Readings arrive at irregular instants (15-minute IV, hourly CWMS, gaps
+//! during outages), but aligning with external datasets often needs the
+//! value at an exact timestamp. `interpolate_between` estimates it linearly
+//! from the readings on either side, snapping to the nearest reading when
+//! one is within the tolerance; `interpolate_series` does the bracketing
+//! lookup over a sorted slice.
+
+use chrono::{DateTime, Duration, Utc};
+
+/// A single observation in a time series.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimedValue {
+    pub timestamp: DateTime<Utc>,
+    pub value: f64,
+}
+
+/// Estimated value at a requested instant.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InterpolatedValue {
+    pub value: f64,
+    /// False when a stored reading within tolerance was used as-is
+    pub interpolated: bool,
+    pub before: Option<TimedValue>,
+    pub after: Option<TimedValue>,
+}
+
+/// Estimate the value at `at` from the readings immediately before and
+/// after it.
+///
+/// - If either reading is within `tolerance` of `at`, the nearer one is
+///   returned unchanged (`interpolated: false`).
+/// - Otherwise, if both exist, the value is linearly interpolated.
+/// - Returns `None` when `at` is not bracketed and no reading is close
+///   enough.
+pub fn interpolate_between(
+    before: Option<&TimedValue>,
+    after: Option<&TimedValue>,
+    at: DateTime<Utc>,
+    tolerance: Duration,
+) -> Option<InterpolatedValue> {
+    let distance = |tv: &TimedValue| (tv.timestamp - at).abs();
+
+    let nearest = match (before, after) {
+        (Some(b), Some(a)) => Some(if distance(b) <= distance(a) { b } else { a }),
+        (Some(b), None) => Some(b),
+        (None, Some(a)) => Some(a),
+        (None, None) => None,
+    };
+
+    if let Some(near) = nearest
+        && distance(near) <= tolerance
+    {
+        return Some(InterpolatedValue {
+            value: near.value,
+            interpolated: false,
+            before: before.cloned(),
+            after: after.cloned(),
+        });
+    }
+
+    let (b, a) = (before?, after?);
+    let span = (a.timestamp - b.timestamp).num_milliseconds() as f64;
+    let fraction = if span > 0.0 {
+        (at - b.timestamp).num_milliseconds() as f64 / span
+    } else {
+        0.0
+    };
+
+    Some(InterpolatedValue {
+        value: b.value + (a.value - b.value) * fraction,
+        interpolated: true,
+        before: Some(b.clone()),
+        after: Some(a.clone()),
+    })
+}
+
+/// Estimate the value at `at` from a series sorted by timestamp ascending.
+pub fn interpolate_series(
+    series: &[TimedValue],
+    at: DateTime<Utc>,
+    tolerance: Duration,
+) -> Option<InterpolatedValue> {
+    let idx = series.partition_point(|tv| tv.timestamp <= at);
+    let before = idx.checked_sub(1).and_then(|i| series.get(i));
+    let after = series.get(idx);
+
+    interpolate_between(before, after, at, tolerance)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn tv(hour: u32, minute: u32, value: f64) -> TimedValue {
+        TimedValue {
+            timestamp: Utc.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap(),
+            value,
+        }
+    }
+
+    fn at(hour: u32, minute: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 5, 1, hour, minute, 0).unwrap()
+    }
+
+    #[test]
+    fn test_linear_interpolation_midpoint() {
+        let series = vec![tv(12, 0, 10.0), tv(13, 0, 12.0)];
+        let result = interpolate_series(&series, at(12, 30), Duration::minutes(5)).unwrap();
+
+        assert!(result.interpolated);
+        assert!((result.value - 11.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_snaps_to_nearest_within_tolerance() {
+        let series = vec![tv(12, 0, 10.0), tv(12, 15, 10.5)];
+        let result = interpolate_series(&series, at(12, 13), Duration::minutes(5)).unwrap();
+
+        assert!(!result.interpolated);
+        assert_eq!(result.value, 10.5);
+    }
+
+    #[test]
+    fn test_exact_match_is_not_interpolated() {
+        let series = vec![tv(12, 0, 10.0), tv(12, 15, 10.5), tv(12, 30, 11.0)];
+        let result = interpolate_series(&series, at(12, 15), Duration::zero()).unwrap();
+
+        assert!(!result.interpolated);
+        assert_eq!(result.value, 10.5);
+    }
+
+    #[test]
+    fn test_outside_range_returns_none() {
+        let series = vec![tv(12, 0, 10.0), tv(13, 0, 12.0)];
+
+        assert!(interpolate_series(&series, at(14, 0), Duration::minutes(5)).is_none());
+        assert!(interpolate_series(&series, at(11, 0), Duration::minutes(5)).is_none());
+        assert!(interpolate_series(&[], at(12, 0), Duration::minutes(5)).is_none());
+    }
+
+    #[test]
+    fn test_just_outside_range_within_tolerance_uses_nearest() {
+        let series = vec![tv(12, 0, 10.0), tv(13, 0, 12.0)];
+        let result = interpolate_series(&series, at(13, 3), Duration::minutes(5)).unwrap();
+
+        assert!(!result.interpolated);
+        assert_eq!(result.value, 12.0);
+    }
+}
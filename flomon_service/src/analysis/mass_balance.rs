@@ -0,0 +1,200 @@
+//! Basin mass-balance check on discharge.
+//!
+//! Water entering a reach has to leave it: mainstem flow at the top plus
+//! gauged tributaries should roughly equal mainstem flow at the bottom,
+//! once each inflow is shifted by its travel time. Ungauged creeks and
+//! storage in Peoria Lake make this approximate, so only a large mismatch
+//! is flagged — at that size the likelier explanation is a bad gauge
+//! (shifted rating, ice, a stuck sensor) rather than hydrology.
+//!
+//! The checked reach is Henry (mainstem in) plus the Mackinaw (tributary
+//! in) against Kingston Mines (out). Each inflow's lag is its
+//! `travel_time_to_peoria_hours` from usgs_stations.toml, since Kingston
+//! Mines is the Peoria reference gauge.
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::model::{ReadingSource, PARAM_DISCHARGE};
+use crate::stations;
+
+/// Downstream (outflow) gauge: Illinois River at Kingston Mines.
+pub const MASS_BALANCE_OUTFLOW_SITE: &str = "05568500";
+
+/// Gauged inflows to the reach: Illinois River at Henry, Mackinaw River
+/// near Green Valley.
+pub const MASS_BALANCE_INFLOW_SITES: [&str; 2] = ["05557000", "05568580"];
+
+/// Window the mean discharges are taken over, in hours. Long enough that
+/// travel-time error and lake storage mostly average out.
+pub const MASS_BALANCE_WINDOW_HOURS: i64 = 72;
+
+/// Outflow-relative mismatch above which the reach is flagged.
+pub const MAX_IMBALANCE_FRACTION: f64 = 0.25;
+
+/// Mean discharge of one gauge over its (lagged) window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FlowContribution {
+    pub site_code: String,
+    pub lag_hours: f64,
+    /// `None` when the gauge has no discharge in the window
+    pub mean_cfs: Option<f64>,
+}
+
+/// Inflow vs outflow for the reach over a recent window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MassBalanceReport {
+    /// Outflow window; each inflow's window is shifted earlier by its lag
+    pub window_start: DateTime<Utc>,
+    pub window_end: DateTime<Utc>,
+    pub inflows: Vec<FlowContribution>,
+    pub outflow: FlowContribution,
+    /// Sum of inflows; `None` unless every inflow reported
+    pub inflow_cfs: Option<f64>,
+    /// (outflow - inflow) / outflow; positive = more water leaving than
+    /// measured coming in
+    pub imbalance_fraction: Option<f64>,
+    /// Mismatch exceeds `MAX_IMBALANCE_FRACTION`
+    pub flagged: bool,
+}
+
+/// Fractional imbalance `(outflow - inflow) / outflow`, or `None` without
+/// a positive outflow to compare against.
+pub fn imbalance_fraction(inflow_cfs: f64, outflow_cfs: f64) -> Option<f64> {
+    (outflow_cfs > 0.0).then(|| (outflow_cfs - inflow_cfs) / outflow_cfs)
+}
+
+/// Assemble the report from measured contributions.
+pub fn balance(
+    inflows: Vec<FlowContribution>,
+    outflow: FlowContribution,
+    window_start: DateTime<Utc>,
+    window_end: DateTime<Utc>,
+) -> MassBalanceReport {
+    let inflow_cfs = inflows.iter().map(|c| c.mean_cfs).sum::<Option<f64>>();
+    let imbalance = inflow_cfs
+        .zip(outflow.mean_cfs)
+        .and_then(|(inflow, outflow)| imbalance_fraction(inflow, outflow));
+
+    MassBalanceReport {
+        window_start,
+        window_end,
+        inflows,
+        outflow,
+        inflow_cfs,
+        imbalance_fraction: imbalance,
+        flagged: imbalance.is_some_and(|f| f.abs() > MAX_IMBALANCE_FRACTION),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Query
+// ---------------------------------------------------------------------------
+
+/// Mean instantaneous discharge at `site_code` over (`start`, `end`].
+fn mean_discharge(
+    site_code: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    client: &mut Client,
+) -> Result<Option<f64>, String> {
+    let row = client.query_one(
+        "SELECT AVG(value)::float8
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1
+           AND parameter_code = $2
+           AND source IS DISTINCT FROM $3
+           AND reading_time > $4
+           AND reading_time <= $5",
+        &[&site_code, &PARAM_DISCHARGE, &ReadingSource::UsgsDv.as_str(), &start, &end]
+    ).map_err(|e| format!("Failed to fetch discharge for {}: {}", site_code, e))?;
+
+    Ok(row.get(0))
+}
+
+/// Compare lagged inflow to the reach with its outflow over the
+/// `MASS_BALANCE_WINDOW_HOURS` before `now`.
+pub fn mass_balance_check(now: DateTime<Utc>, client: &mut Client) -> Result<MassBalanceReport, String> {
+    let station_map = stations::load_stations_map();
+    let window_start = now - Duration::hours(MASS_BALANCE_WINDOW_HOURS);
+
+    let mut inflows = Vec::new();
+    for site_code in MASS_BALANCE_INFLOW_SITES {
+        let lag_hours = station_map.get(site_code)
+            .map(|s| s.travel_time_to_peoria_hours)
+            .unwrap_or(0.0);
+        let lag = Duration::minutes((lag_hours * 60.0).round() as i64);
+
+        inflows.push(FlowContribution {
+            site_code: site_code.to_string(),
+            lag_hours,
+            mean_cfs: mean_discharge(site_code, window_start - lag, now - lag, client)?,
+        });
+    }
+
+    let outflow = FlowContribution {
+        site_code: MASS_BALANCE_OUTFLOW_SITE.to_string(),
+        lag_hours: 0.0,
+        mean_cfs: mean_discharge(MASS_BALANCE_OUTFLOW_SITE, window_start, now, client)?,
+    };
+
+    Ok(balance(inflows, outflow, window_start, now))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn flow(site_code: &str, mean_cfs: Option<f64>) -> FlowContribution {
+        FlowContribution { site_code: site_code.to_string(), lag_hours: 0.0, mean_cfs }
+    }
+
+    #[test]
+    fn test_balance_within_tolerance_is_not_flagged() {
+        let (start, end) = (
+            Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap(),
+            Utc.with_ymd_and_hms(2019, 6, 4, 0, 0, 0).unwrap(),
+        );
+        let report = balance(
+            vec![flow("05557000", Some(60000.0)), flow("05568580", Some(8000.0))],
+            flow("05568500", Some(74000.0)),
+            start,
+            end,
+        );
+
+        assert_eq!(report.inflow_cfs, Some(68000.0));
+        assert!((report.imbalance_fraction.unwrap() - 6000.0 / 74000.0).abs() < 1e-12);
+        assert!(!report.flagged);
+    }
+
+    #[test]
+    fn test_large_mismatch_is_flagged_and_missing_gauge_is_not() {
+        let now = Utc.with_ymd_and_hms(2019, 6, 4, 0, 0, 0).unwrap();
+
+        // Outflow reading half of what came in: likely a bad rating
+        let suspect = balance(
+            vec![flow("05557000", Some(60000.0)), flow("05568580", Some(8000.0))],
+            flow("05568500", Some(34000.0)),
+            now,
+            now,
+        );
+        assert!(suspect.flagged);
+        assert!(suspect.imbalance_fraction.unwrap() < -MAX_IMBALANCE_FRACTION);
+
+        // A silent tributary means no verdict rather than a false alarm
+        let incomplete = balance(
+            vec![flow("05557000", Some(60000.0)), flow("05568580", None)],
+            flow("05568500", Some(34000.0)),
+            now,
+            now,
+        );
+        assert_eq!(incomplete.inflow_cfs, None);
+        assert!(!incomplete.flagged);
+    }
+}
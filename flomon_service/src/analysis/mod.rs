@@ -6,6 +6,60 @@
 /// database.
 ///
 /// Submodules:
+/// - `aggregate` — per-hour min/mean/max/count of 15-minute readings.
+/// - `align` — pairs readings from two series logged within a time tolerance.
+/// - `backwater` — rate of change of the LaGrange tailwater-pool differential.
+/// - `downsample` — thins long series for charts, keeping peaks (LTTB).
+/// - `event_analog` — current stage and trend against a remembered past flood.
+/// - `forecast_blend` — official forecast and trend extrapolation merged by lead time.
+/// - `gaps` — stretches of a sensor's record with missing readings.
 /// - `groupings` — organizes flat ingest output into per-site structures.
+/// - `interpolate` — estimates a series value at an arbitrary instant.
+/// - `mass_balance` — lagged inflow vs outflow discharge, to catch bad gauges.
+/// - `qualifiers` — pulls stored readings by USGS qualifier for QA audits.
+/// - `replay` — runs a stored flood through the live alert detectors.
+/// - `rating` — drift of recent stage-discharge pairs from the historical rating.
+/// - `precip` — rolls ASOS precipitation up to basins and zones.
+/// - `scoring` — 0-100 compound flood score from stage, upstream rise, backwater, and rain.
+/// - `return_period` — flood frequency (log-Pearson III) of annual peak stages.
+/// - `seasonal` — ranks current stage against the same calendar window historically.
+/// - `status` — current sensor, zone, and basin flood status (shared by `/status` and the daemon).
+/// - `sustained` — stage held above a threshold for a long stretch (nuisance flooding).
+/// - `travel_time` — fits the empirical lag from upstream gauges to Peoria.
 
+pub mod aggregate;
+pub mod align;
+pub mod backwater;
+pub mod downsample;
+pub mod event_analog;
+pub mod forecast_blend;
+pub mod gaps;
 pub mod groupings;
+pub mod interpolate;
+pub mod mass_balance;
+pub mod precip;
+pub mod qualifiers;
+pub mod rating;
+pub mod replay;
+pub mod return_period;
+pub mod scoring;
+pub mod seasonal;
+pub mod status;
+pub mod sustained;
+pub mod travel_time;
+
+pub use aggregate::hourly_aggregate;
+pub use backwater::backwater_onset_rate;
+pub use downsample::lttb;
+pub use event_analog::compare_to_event;
+pub use forecast_blend::blend_forecast;
+pub use mass_balance::mass_balance_check;
+pub use precip::basin_precip_totals;
+pub use qualifiers::readings_by_qualifier;
+pub use rating::detect_rating_drift;
+pub use replay::replay_event;
+pub use return_period::return_period;
+pub use scoring::compute_flood_score;
+pub use seasonal::stage_percentile;
+pub use status::basin_status;
+pub use sustained::sustained_high_water;
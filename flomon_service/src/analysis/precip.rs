@@ -0,0 +1,205 @@
+//! Basin- and zone-level precipitation rollups.
+//!
+//! ASOS stations report precipitation individually, but flood response is
+//! driven by how much rain fell over a whole catchment. These helpers sum
+//! each station's hourly precipitation over one or more trailing windows and
+//! then aggregate the stations belonging to a basin (from iem_asos.toml) or
+//! to a zone (from zones.toml) into a single `PrecipTotals`.
+//!
+//! Stations with no observations in a window are treated as not reporting
+//! rather than as zero rainfall, so a dead station can't drag the basin
+//! mean down.
+
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::asos_locations;
+use crate::zones::Zone;
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+/// Aggregate precipitation for one trailing window.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrecipWindowTotal {
+    pub hours: i32,
+    /// Mean accumulation across reporting stations, in inches
+    pub mean_in: Option<f64>,
+    /// Largest single-station accumulation, in inches
+    pub max_in: Option<f64>,
+    pub reporting_stations: usize,
+}
+
+/// Precipitation rolled up across a group of ASOS stations.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PrecipTotals {
+    pub station_ids: Vec<String>,
+    pub windows: Vec<PrecipWindowTotal>,
+}
+
+/// Per-station accumulations, keyed by (station_id, window hours).
+pub type StationPrecip = HashMap<(String, i32), f64>;
+
+// ---------------------------------------------------------------------------
+// Aggregation
+// ---------------------------------------------------------------------------
+
+/// Rolls per-station accumulations up into a single `PrecipTotals`.
+///
+/// Windows are reported in the order given. A window with no reporting
+/// stations has `None` for both mean and max.
+pub fn rollup_precip(station_ids: &[String], per_station: &StationPrecip, windows: &[i32]) -> PrecipTotals {
+    let windows = windows.iter().map(|&hours| {
+        let values: Vec<f64> = station_ids.iter()
+            .filter_map(|id| per_station.get(&(id.clone(), hours)).copied())
+            .collect();
+
+        let (mean_in, max_in) = if values.is_empty() {
+            (None, None)
+        } else {
+            let sum: f64 = values.iter().sum();
+            let max = values.iter().cloned().fold(f64::MIN, f64::max);
+            (Some(sum / values.len() as f64), Some(max))
+        };
+
+        PrecipWindowTotal {
+            hours,
+            mean_in,
+            max_in,
+            reporting_stations: values.len(),
+        }
+    }).collect();
+
+    PrecipTotals {
+        station_ids: station_ids.to_vec(),
+        windows,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Sums `precip_1hr_in` for each station over each window trailing `now`.
+///
+/// Station IDs must be in database form (no leading "K").
+pub fn fetch_station_precip(
+    client: &mut Client,
+    station_ids: &[String],
+    windows: &[i32],
+    now: DateTime<Utc>,
+) -> Result<StationPrecip, String> {
+    let mut per_station = HashMap::new();
+
+    for &hours in windows {
+        let rows = client.query(
+            "SELECT station_id, COALESCE(SUM(precip_1hr_in), 0.0)
+             FROM asos_observations
+             WHERE station_id = ANY($1)
+               AND observation_time >= $3 - INTERVAL '1 hour' * $2::int
+               AND observation_time <= $3
+             GROUP BY station_id",
+            &[&station_ids, &hours, &now]
+        ).map_err(|e| format!("Failed to fetch precipitation totals: {}", e))?;
+
+        for row in rows {
+            let station_id: String = row.get(0);
+            let total: f64 = row.get(1);
+            per_station.insert((station_id, hours), total);
+        }
+    }
+
+    Ok(per_station)
+}
+
+/// Precipitation totals for every basin in iem_asos.toml, keyed by basin name.
+pub fn basin_precip_totals(
+    client: &mut Client,
+    windows: &[i32],
+    now: DateTime<Utc>,
+) -> Result<HashMap<String, PrecipTotals>, String> {
+    let locations = asos_locations::load_locations("iem_asos.toml")
+        .map_err(|e| format!("Failed to load iem_asos.toml: {}", e))?;
+
+    let mut basins: HashMap<String, Vec<String>> = HashMap::new();
+    for location in &locations {
+        basins.entry(location.basin.clone())
+            .or_default()
+            .push(location.db_station_id().to_string());
+    }
+
+    let all_ids: Vec<String> = locations.iter().map(|l| l.db_station_id().to_string()).collect();
+    let per_station = fetch_station_precip(client, &all_ids, windows, now)?;
+
+    Ok(basins.into_iter()
+        .map(|(basin, ids)| {
+            let totals = rollup_precip(&ids, &per_station, windows);
+            (basin, totals)
+        })
+        .collect())
+}
+
+/// Precipitation totals across a zone's ASOS sensors, or `None` if the zone
+/// has no precipitation sensors.
+pub fn zone_precip_totals(
+    client: &mut Client,
+    zone: &Zone,
+    windows: &[i32],
+    now: DateTime<Utc>,
+) -> Result<Option<PrecipTotals>, String> {
+    let station_ids: Vec<String> = zone.asos_sensors().iter()
+        .filter_map(|s| s.station_id.clone())
+        .collect();
+
+    if station_ids.is_empty() {
+        return Ok(None);
+    }
+
+    let per_station = fetch_station_precip(client, &station_ids, windows, now)?;
+    Ok(Some(rollup_precip(&station_ids, &per_station, windows)))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_rollup_mean_and_max() {
+        let mut per_station = StationPrecip::new();
+        per_station.insert(("BMI".to_string(), 24), 1.0);
+        per_station.insert(("PIA".to_string(), 24), 2.0);
+
+        let totals = rollup_precip(&ids(&["BMI", "PIA"]), &per_station, &[24]);
+
+        assert_eq!(totals.windows.len(), 1);
+        assert_eq!(totals.windows[0].mean_in, Some(1.5));
+        assert_eq!(totals.windows[0].max_in, Some(2.0));
+        assert_eq!(totals.windows[0].reporting_stations, 2);
+    }
+
+    #[test]
+    fn test_rollup_ignores_non_reporting_stations() {
+        let mut per_station = StationPrecip::new();
+        per_station.insert(("BMI".to_string(), 6), 0.8);
+
+        let totals = rollup_precip(&ids(&["BMI", "GBG"]), &per_station, &[6, 24]);
+
+        assert_eq!(totals.windows[0].mean_in, Some(0.8));
+        assert_eq!(totals.windows[0].reporting_stations, 1);
+        assert_eq!(totals.windows[1].mean_in, None);
+        assert_eq!(totals.windows[1].reporting_stations, 0);
+    }
+}
+
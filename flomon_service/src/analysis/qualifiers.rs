@@ -0,0 +1,99 @@
+//! Data-quality auditing by USGS qualifier code.
+//!
+//! Every row in `usgs_raw.gauge_readings` carries the qualifier USGS
+//! attached to it at ingest time — "P" (provisional), "A" (approved),
+//! "e" (estimated), and so on. `readings_by_qualifier` pulls back only the
+//! rows matching one qualifier, which is the basis for QA work such as
+//! comparing provisional values against their later approved revisions or
+//! auditing every estimated reading at a site.
+//!
+//! Qualifier matching is case-sensitive because USGS codes are: "e"
+//! (estimated) and "E" are distinct.
+
+use chrono::{DateTime, Utc};
+use postgres::Client;
+
+use crate::model::{GaugeReading, ReadingSource};
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+/// Returns true if `qualifier` looks like a USGS qualifier code.
+///
+/// Qualifiers are short alphanumeric codes. Anything else (empty strings,
+/// whitespace, punctuation) is rejected before it reaches the database.
+pub fn is_valid_qualifier(qualifier: &str) -> bool {
+    !qualifier.is_empty() && qualifier.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Fetches every reading for `site_code` with the given qualifier since
+/// `since`, oldest first. Both discharge and stage rows are returned; the
+/// caller can split them by `parameter_code`.
+pub fn readings_by_qualifier(
+    site_code: &str,
+    qualifier: &str,
+    since: DateTime<Utc>,
+    client: &mut Client,
+) -> Result<Vec<GaugeReading>, String> {
+    if !is_valid_qualifier(qualifier) {
+        return Err(format!("Invalid qualifier '{}'", qualifier));
+    }
+
+    let rows = client.query(
+        "SELECT site_code, parameter_code, unit, value, reading_time, qualifier, source, agency_code
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1
+           AND qualifier = $2
+           AND reading_time >= $3
+         ORDER BY reading_time ASC, parameter_code",
+        &[&site_code, &qualifier, &since]
+    ).map_err(|e| format!("Failed to fetch readings by qualifier: {}", e))?;
+
+    let readings = rows.iter().map(|row| {
+        let site_code: String = row.get(0);
+        let value: rust_decimal::Decimal = row.get(3);
+        let reading_time: DateTime<Utc> = row.get(4);
+
+        GaugeReading {
+            agency_code: row.get(7),
+            site_name: site_code.clone(),
+            site_code,
+            parameter_code: row.get(1),
+            unit: row.get(2),
+            value: value.to_string().parse().unwrap_or(0.0),
+            datetime: reading_time.to_rfc3339(),
+            qualifier: row.get(5),
+            source: ReadingSource::from_db_str(row.get(6)),
+        }
+    }).collect();
+
+    Ok(readings)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_qualifiers() {
+        assert!(is_valid_qualifier("P"));
+        assert!(is_valid_qualifier("A"));
+        assert!(is_valid_qualifier("e"));
+    }
+
+    #[test]
+    fn test_invalid_qualifiers() {
+        assert!(!is_valid_qualifier(""));
+        assert!(!is_valid_qualifier(" "));
+        assert!(!is_valid_qualifier("P'; DROP"));
+    }
+}
@@ -0,0 +1,218 @@
+//! Stage-discharge rating drift.
+//!
+//! USGS computes discharge from stage through a rating curve, and the curve
+//! is only as good as the channel it was measured in. Scour, fill, and
+//! vegetation move the control, so the same stage passes a different flow
+//! than it used to — and a flood stage set decades ago no longer means the
+//! same volume of water. USGS applies shifts to published discharge as
+//! field measurements come in, so the stored stage-discharge pairs drift
+//! when the channel does.
+//!
+//! `detect_rating_drift` fits log-discharge against stage over the
+//! historical record, then asks how far the last `RECENT_WINDOW_DAYS` of
+//! pairs sit from that fit. Pairs are reduced to daily means first so the
+//! dense IV record doesn't outweigh decades of DV data.
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::model::{PARAM_DISCHARGE, PARAM_STAGE};
+
+/// Span of the "recent" cloud, in days; everything earlier is history.
+pub const RECENT_WINDOW_DAYS: i64 = 365;
+
+/// Minimum paired days on each side before a comparison is meaningful.
+pub const MIN_HISTORICAL_DAYS: usize = 365;
+pub const MIN_RECENT_DAYS: usize = 30;
+
+/// Discharge shift at equal stage (fractional) above which a rating is
+/// flagged, provided it also stands clear of the historical scatter.
+pub const RATING_DRIFT_THRESHOLD: f64 = 0.10;
+
+/// How a site's recent stage-discharge pairs compare with its history.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RatingDrift {
+    pub site_code: String,
+    pub historical_days: usize,
+    /// Recent days whose stage falls inside the historical range
+    pub recent_days: usize,
+    /// Median fractional change in discharge at equal stage; negative =
+    /// the same stage now carries less water (aggradation, weeds, ice)
+    pub discharge_shift_fraction: f64,
+    /// Equivalent stage offset in feet: how much higher (positive) the
+    /// river now reads for the same flow
+    pub stage_offset_ft: f64,
+    /// Median absolute log residual of the history against its own fit
+    pub historical_scatter: f64,
+    pub flagged: bool,
+}
+
+// ---------------------------------------------------------------------------
+// Fit
+// ---------------------------------------------------------------------------
+
+/// Least-squares fit of ln(discharge) = intercept + slope * stage.
+/// Returns `(intercept, slope)`, or `None` for fewer than two pairs, a
+/// flat stage record, or non-positive discharges only.
+pub fn fit_log_rating(pairs: &[(f64, f64)]) -> Option<(f64, f64)> {
+    let points: Vec<(f64, f64)> = pairs.iter()
+        .filter(|(_, q)| *q > 0.0)
+        .map(|&(h, q)| (h, q.ln()))
+        .collect();
+    if points.len() < 2 {
+        return None;
+    }
+
+    let n = points.len() as f64;
+    let mean_h = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_q = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|p| (p.0 - mean_h).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|p| (p.0 - mean_h) * (p.1 - mean_q)).sum();
+
+    if sxx <= 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    Some((mean_q - slope * mean_h, slope))
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
+
+/// Compare `recent` (stage, discharge) pairs against a fit of `historical`.
+///
+/// Recent pairs outside the historical stage range are dropped, since the
+/// fit says nothing about extrapolated stages. Returns `None` when either
+/// side has too few pairs or the history can't be fit.
+pub fn rating_drift(site_code: &str, historical: &[(f64, f64)], recent: &[(f64, f64)]) -> Option<RatingDrift> {
+    let historical: Vec<(f64, f64)> = historical.iter().copied().filter(|(_, q)| *q > 0.0).collect();
+    if historical.len() < MIN_HISTORICAL_DAYS {
+        return None;
+    }
+    let (intercept, slope) = fit_log_rating(&historical)?;
+    if slope <= 0.0 {
+        return None;
+    }
+
+    let residual = |&(h, q): &(f64, f64)| q.ln() - (intercept + slope * h);
+    let (min_h, max_h) = historical.iter()
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &(h, _)| (lo.min(h), hi.max(h)));
+
+    let recent: Vec<(f64, f64)> = recent.iter()
+        .copied()
+        .filter(|&(h, q)| q > 0.0 && h >= min_h && h <= max_h)
+        .collect();
+    if recent.len() < MIN_RECENT_DAYS {
+        return None;
+    }
+
+    let scatter = median(historical.iter().map(|p| residual(p).abs()).collect())?;
+    let shift = median(recent.iter().map(residual).collect())?;
+    let discharge_shift_fraction = shift.exp() - 1.0;
+
+    Some(RatingDrift {
+        site_code: site_code.to_string(),
+        historical_days: historical.len(),
+        recent_days: recent.len(),
+        discharge_shift_fraction,
+        stage_offset_ft: -shift / slope,
+        historical_scatter: scatter,
+        flagged: discharge_shift_fraction.abs() > RATING_DRIFT_THRESHOLD && shift.abs() > 2.0 * scatter,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Query
+// ---------------------------------------------------------------------------
+
+/// Compare the last `RECENT_WINDOW_DAYS` of stage-discharge pairs at
+/// `site_code` with everything stored before that.
+///
+/// Returns `Ok(None)` when the site lacks enough paired history (e.g. a
+/// stage-only gauge).
+pub fn detect_rating_drift(
+    site_code: &str,
+    now: DateTime<Utc>,
+    client: &mut Client,
+) -> Result<Option<RatingDrift>, String> {
+    let rows = client.query(
+        "SELECT s.reading_time::date, AVG(s.value)::float8, AVG(q.value)::float8
+         FROM usgs_raw.gauge_readings s
+         JOIN usgs_raw.gauge_readings q
+           ON q.site_code = s.site_code
+          AND q.reading_time = s.reading_time
+          AND q.parameter_code = $3
+         WHERE s.site_code = $1
+           AND s.parameter_code = $2
+           AND s.reading_time <= $4
+         GROUP BY 1",
+        &[&site_code, &PARAM_STAGE, &PARAM_DISCHARGE, &now]
+    ).map_err(|e| format!("Failed to fetch stage-discharge pairs for {}: {}", site_code, e))?;
+
+    let cutoff = (now - Duration::days(RECENT_WINDOW_DAYS)).date_naive();
+    let (mut historical, mut recent) = (Vec::new(), Vec::new());
+    for row in rows {
+        let day: NaiveDate = row.get(0);
+        let pair = (row.get(1), row.get(2));
+        if day > cutoff { recent.push(pair) } else { historical.push(pair) }
+    }
+
+    Ok(rating_drift(site_code, &historical, &recent))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Daily pairs on Q = 1000 * e^(0.3 * stage), scaled by `factor`, with a
+    /// small alternating wobble so the history has nonzero scatter.
+    fn cloud(days: usize, factor: f64) -> Vec<(f64, f64)> {
+        (0..days)
+            .map(|i| {
+                let stage = 2.0 + (i % 20) as f64;
+                let wobble = if i % 2 == 0 { 1.02 } else { 0.98 };
+                (stage, factor * wobble * 1000.0 * (0.3 * stage).exp())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_fit_recovers_exponential_rating() {
+        let pairs: Vec<(f64, f64)> = (0..10).map(|h| (h as f64, 500.0 * (0.25 * h as f64).exp())).collect();
+        let (intercept, slope) = fit_log_rating(&pairs).unwrap();
+        assert!((slope - 0.25).abs() < 1e-9);
+        assert!((intercept - 500f64.ln()).abs() < 1e-9);
+        assert_eq!(fit_log_rating(&[(3.0, 100.0), (3.0, 200.0)]), None);
+    }
+
+    #[test]
+    fn test_stable_rating_is_not_flagged() {
+        let drift = rating_drift("05567500", &cloud(400, 1.0), &cloud(60, 1.0)).unwrap();
+        assert!(drift.discharge_shift_fraction.abs() < 0.01);
+        assert!(!drift.flagged);
+    }
+
+    #[test]
+    fn test_shifted_rating_is_flagged_with_stage_offset() {
+        // Same stage now carries 20% less water: the channel has filled in
+        let drift = rating_drift("05567500", &cloud(400, 1.0), &cloud(60, 0.8)).unwrap();
+        assert!((drift.discharge_shift_fraction + 0.2).abs() < 0.01);
+        // ln(0.8) / 0.3 ≈ 0.74 ft higher for the same flow
+        assert!((drift.stage_offset_ft - 0.744).abs() < 0.01);
+        assert!(drift.flagged);
+
+        // Too little recent data: no verdict
+        assert_eq!(rating_drift("05567500", &cloud(400, 1.0), &cloud(10, 0.8)), None);
+    }
+}
@@ -0,0 +1,469 @@
+//! Replay a stored flood through the live alert detectors.
+//!
+//! Alert thresholds are tuned against floods we have already recorded, not
+//! the next one. `replay_event` pulls a `flood_analysis.events` row's
+//! observation window and steps through it one poll cycle at a time,
+//! running the same checks the daemon runs each cycle — flood-stage
+//! crossings (`check_flood_stage`), approaching flood stage, sustained high
+//! water, and LaGrange backwater onset — and returns the alerts that would
+//! have gone out, oldest first.
+//!
+//! Alerts are deduplicated the way the notifier does it (`AlertStateStore`,
+//! one state key per alert kind and site), but without the periodic
+//! re-sends: the timeline holds each alert's onset, every change in
+//! severity, and its all-clear. Quiet hours are ignored.
+//!
+//! Rate-based detectors fit a trend over a few hours of readings. Where a
+//! window only has daily values stored (older floods), they see too few
+//! readings and stay silent; crossings still replay.
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::alert::config::{
+    AlertingConfig, ApproachingFloodStageConfig, BackwaterOnsetConfig, SustainedHighWaterConfig,
+};
+use crate::alert::state::AlertStateStore;
+use crate::alert::thresholds::{check_approaching_flood_stage, check_flood_stage, FloodSeverity};
+use crate::analysis::backwater::{self, BackwaterOnset};
+use crate::analysis::interpolate::TimedValue;
+use crate::analysis::sustained;
+use crate::model::{FloodThresholds, GaugeReading, ReadingSource, PARAM_STAGE};
+
+/// Days of record replayed before the event (or its precursor window)
+/// starts, so the lead-up alerts are included.
+pub const REPLAY_LEAD_DAYS: i64 = 7;
+
+/// Days replayed after the crest when the event has no recorded end.
+pub const REPLAY_TAIL_DAYS: i64 = 7;
+
+/// Simulated poll interval, matching the daemon's default.
+pub const REPLAY_STEP_MINUTES: i64 = 15;
+
+/// Alert-state key for the basin-level backwater onset alert.
+const BACKWATER_KEY: &str = "backwater:lagrange";
+
+/// Which detector raised a simulated alert.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    FloodStage,
+    ApproachingFloodStage,
+    SustainedHighWater,
+    BackwaterOnset,
+}
+
+/// One alert the live system would have sent.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SimulatedAlert {
+    /// Poll cycle that would have sent it
+    pub at: DateTime<Utc>,
+    pub kind: AlertKind,
+    /// Station site code, or "LaGrange" for backwater onset
+    pub site_code: String,
+    /// "action" … "major", or "all_clear"
+    pub severity: String,
+    pub message: String,
+}
+
+/// Detector settings used for a replay.
+#[derive(Debug, Clone)]
+pub struct ReplayConfig {
+    pub step_minutes: i64,
+    pub approaching_flood_stage: ApproachingFloodStageConfig,
+    pub sustained_high_water: SustainedHighWaterConfig,
+    pub backwater_onset: BackwaterOnsetConfig,
+}
+
+impl Default for ReplayConfig {
+    fn default() -> Self {
+        Self {
+            step_minutes: REPLAY_STEP_MINUTES,
+            approaching_flood_stage: ApproachingFloodStageConfig::default(),
+            sustained_high_water: SustainedHighWaterConfig::default(),
+            backwater_onset: BackwaterOnsetConfig::default(),
+        }
+    }
+}
+
+impl ReplayConfig {
+    /// Settings from alerting.toml, with the defaults for any detector
+    /// section it leaves out (or all of them without the file), so every
+    /// detector runs in a replay.
+    pub fn load() -> Self {
+        let defaults = Self::default();
+        let Ok(config) = AlertingConfig::load() else {
+            return defaults;
+        };
+        let alerting = config.alerting;
+        Self {
+            approaching_flood_stage: alerting.approaching_flood_stage.unwrap_or(defaults.approaching_flood_stage),
+            sustained_high_water: alerting.sustained_high_water.unwrap_or(defaults.sustained_high_water),
+            backwater_onset: alerting.backwater_onset.unwrap_or(defaults.backwater_onset),
+            ..defaults
+        }
+    }
+}
+
+/// Stored stage of one station over the replay window.
+#[derive(Debug, Clone)]
+pub struct StationSeries {
+    pub site_code: String,
+    pub site_name: String,
+    pub thresholds: FloodThresholds,
+    /// Ascending by timestamp
+    pub stage: Vec<TimedValue>,
+}
+
+// ---------------------------------------------------------------------------
+// Simulation
+// ---------------------------------------------------------------------------
+
+/// Readings of ascending `series` in `(from, to]`.
+fn window(series: &[TimedValue], from: DateTime<Utc>, to: DateTime<Utc>) -> &[TimedValue] {
+    let lo = series.partition_point(|tv| tv.timestamp <= from);
+    let hi = series.partition_point(|tv| tv.timestamp <= to);
+    &series[lo..hi.max(lo)]
+}
+
+fn severity_tag(severity: &FloodSeverity) -> &'static str {
+    match severity {
+        FloodSeverity::Action => "action",
+        FloodSeverity::Flood => "flood",
+        FloodSeverity::Moderate => "moderate",
+        FloodSeverity::Major => "major",
+    }
+}
+
+/// Records the alerts `AlertStateStore` would let through.
+struct Timeline {
+    state: AlertStateStore,
+    alerts: Vec<SimulatedAlert>,
+}
+
+impl Timeline {
+    /// Add an alert for `key` if `severity` differs from what was last
+    /// sent; `message` is only built when one is.
+    fn observe(
+        &mut self,
+        key: &str,
+        kind: AlertKind,
+        site_code: &str,
+        severity: Option<FloodSeverity>,
+        at: DateTime<Utc>,
+        message: impl FnOnce() -> String,
+    ) {
+        if !self.state.should_notify(key, severity.as_ref(), 0, at) {
+            return;
+        }
+        self.alerts.push(SimulatedAlert {
+            at,
+            kind,
+            site_code: site_code.to_string(),
+            severity: severity.as_ref().map_or("all_clear", severity_tag).to_string(),
+            message: message(),
+        });
+        self.state.record_notification(key, severity, at);
+    }
+}
+
+/// Step from `start` to `end` one poll cycle at a time, running each
+/// detector on the readings stored up to that cycle. `differential` is the
+/// LaGrange tailwater-minus-pool series (`backwater::paired_differential`).
+pub fn simulate(
+    stations: &[StationSeries],
+    differential: &[TimedValue],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    config: &ReplayConfig,
+) -> Vec<SimulatedAlert> {
+    let step = Duration::minutes(config.step_minutes.max(1));
+    let approach = &config.approaching_flood_stage;
+    let sustained_cfg = &config.sustained_high_water;
+    let onset_cfg = &config.backwater_onset;
+
+    let mut timeline = Timeline { state: AlertStateStore::new(), alerts: Vec::new() };
+    let mut cycle = start + step;
+    while cycle <= end {
+        for station in stations {
+            let new_readings = window(&station.stage, cycle - step, cycle);
+
+            // Each new reading, as `process_reading_alert` sees them
+            for tv in new_readings {
+                let reading = GaugeReading {
+                    agency_code: "USGS".to_string(),
+                    site_code: station.site_code.clone(),
+                    site_name: station.site_name.clone(),
+                    parameter_code: PARAM_STAGE.to_string(),
+                    unit: "ft".to_string(),
+                    value: tv.value,
+                    datetime: tv.timestamp.to_rfc3339(),
+                    qualifier: String::new(),
+                    source: ReadingSource::Unknown,
+                };
+                let alert = check_flood_stage(&reading, &station.thresholds);
+                timeline.observe(
+                    &station.site_code,
+                    AlertKind::FloodStage,
+                    &station.site_code,
+                    alert.as_ref().map(|a| a.severity.clone()),
+                    cycle,
+                    || alert.as_ref().map(|a| a.message.clone()).unwrap_or_else(|| format!(
+                        "All clear at {} — stage {:.2} ft is below action stage.",
+                        station.site_name, tv.value
+                    )),
+                );
+            }
+            let Some(latest) = new_readings.last() else {
+                continue;
+            };
+
+            let recent = window(&station.stage, cycle - Duration::hours(approach.window_hours), cycle);
+            if latest.value < station.thresholds.flood_stage_ft {
+                let alert = check_approaching_flood_stage(
+                    &station.site_name,
+                    latest.value,
+                    backwater::onset_rate(recent),
+                    &station.thresholds,
+                    approach.margin_ft,
+                    approach.min_rate_ft_per_hour,
+                );
+                timeline.observe(
+                    &format!("approaching:{}", station.site_code),
+                    AlertKind::ApproachingFloodStage,
+                    &station.site_code,
+                    alert.as_ref().map(|_| FloodSeverity::Action),
+                    cycle,
+                    || alert.as_ref().map(|a| a.message.clone()).unwrap_or_else(|| format!(
+                        "{} is no longer rising toward flood stage — stage {:.2} ft.",
+                        station.site_name, latest.value
+                    )),
+                );
+            }
+
+            let threshold_ft = sustained_cfg.threshold_ft.unwrap_or(station.thresholds.action_stage_ft);
+            let lookback = window(&station.stage, cycle - Duration::days(sustained::SUSTAINED_LOOKBACK_DAYS as i64), cycle);
+            let event = sustained::sustained_event(&station.site_code, lookback, threshold_ft, sustained_cfg.min_hours, cycle);
+            timeline.observe(
+                &format!("sustained:{}", station.site_code),
+                AlertKind::SustainedHighWater,
+                &station.site_code,
+                event.as_ref().map(|_| FloodSeverity::Action),
+                cycle,
+                || match &event {
+                    Some(e) => format!(
+                        "SUSTAINED HIGH WATER at {} — stage has held above {:.1} ft for {:.0} hours (now {:.2} ft, peak {:.2} ft).",
+                        station.site_name, e.threshold_ft, e.duration_hours, e.latest_ft, e.peak_ft
+                    ),
+                    None => format!("Sustained high water at {} has ended — stage is back below threshold.", station.site_name),
+                },
+            );
+        }
+
+        let recent = window(differential, cycle - Duration::hours(onset_cfg.window_hours as i64), cycle);
+        if let (Some(latest), Some(rate)) = (recent.last(), backwater::onset_rate(recent)) {
+            let onset = BackwaterOnset { differential_ft: latest.value, rate_ft_per_hour: rate };
+            let rapid = backwater::is_rapid_onset(&onset, onset_cfg.min_differential_ft, onset_cfg.min_rate_ft_per_hour);
+            timeline.observe(
+                BACKWATER_KEY,
+                AlertKind::BackwaterOnset,
+                "LaGrange",
+                rapid.then_some(FloodSeverity::Flood),
+                cycle,
+                || if rapid {
+                    format!(
+                        "BACKWATER ONSET at LaGrange L&D — tailwater {:.1} ft relative to pool and rising {:.2} ft/hr over {}h.",
+                        onset.differential_ft, onset.rate_ft_per_hour, onset_cfg.window_hours
+                    )
+                } else {
+                    format!(
+                        "Backwater onset at LaGrange has eased — tailwater {:.1} ft relative to pool, changing {:.2} ft/hr.",
+                        onset.differential_ft, onset.rate_ft_per_hour
+                    )
+                },
+            );
+        }
+
+        cycle += step;
+    }
+
+    timeline.alerts
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Replay window for `event_id`: `REPLAY_LEAD_DAYS` before the event (or
+/// its precursor window, if earlier) through its end, or
+/// `REPLAY_TAIL_DAYS` past the crest while it has none.
+pub fn replay_window(event_id: i32, client: &mut Client) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let row = client.query_opt(
+        "SELECT LEAST(event_start, COALESCE(precursor_window_start, event_start)), event_peak, event_end
+         FROM flood_analysis.events WHERE id = $1",
+        &[&event_id]
+    ).map_err(|e| format!("Failed to fetch flood event {}: {}", event_id, e))?
+        .ok_or_else(|| format!("No flood event with id {}", event_id))?;
+
+    let start: DateTime<Utc> = row.get(0);
+    let peak: DateTime<Utc> = row.get(1);
+    let end: Option<DateTime<Utc>> = row.get(2);
+    Ok((
+        start - Duration::days(REPLAY_LEAD_DAYS),
+        end.unwrap_or(peak + Duration::days(REPLAY_TAIL_DAYS)),
+    ))
+}
+
+/// Alerts the live detectors would have sent over `event_id`'s window,
+/// with settings from alerting.toml (`ReplayConfig::load`).
+pub fn replay_event(event_id: i32, client: &mut Client) -> Result<Vec<SimulatedAlert>, String> {
+    replay_event_with(event_id, &ReplayConfig::load(), client)
+}
+
+/// `replay_event` with explicit detector settings, for trying out
+/// thresholds.
+pub fn replay_event_with(
+    event_id: i32,
+    config: &ReplayConfig,
+    client: &mut Client,
+) -> Result<Vec<SimulatedAlert>, String> {
+    let (start, end) = replay_window(event_id, client)?;
+    // Earlier readings feed the trend and sustained windows of the first cycles
+    let lookback = Duration::days(sustained::SUSTAINED_LOOKBACK_DAYS as i64);
+
+    let mut stations = Vec::new();
+    for station in crate::stations::load_stations() {
+        let Some(thresholds) = station.thresholds else {
+            continue;
+        };
+        let rows = client.query(
+            "SELECT reading_time, value::float8 FROM usgs_raw.gauge_readings
+             WHERE site_code = $1 AND parameter_code = $2
+               AND reading_time > $3 AND reading_time <= $4
+             ORDER BY reading_time ASC",
+            &[&station.site_code, &PARAM_STAGE, &(start - lookback), &end]
+        ).map_err(|e| format!("Failed to fetch stage for {}: {}", station.site_code, e))?;
+
+        stations.push(StationSeries {
+            site_code: station.site_code,
+            site_name: station.name,
+            thresholds,
+            stage: rows.iter()
+                .map(|row| TimedValue { timestamp: row.get(0), value: row.get(1) })
+                .collect(),
+        });
+    }
+
+    let hours = (end - start + lookback).num_hours() as i32;
+    let tolerance = Duration::minutes(config.backwater_onset.align_tolerance_minutes);
+    let differential = backwater::differential_series(client, hours, tolerance, end)?;
+
+    Ok(simulate(&stations, &differential, start, end, config))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn start() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap()
+    }
+
+    /// Hourly stage readings starting at `start()`
+    fn station(stages: &[f64]) -> StationSeries {
+        StationSeries {
+            site_code: "05568500".to_string(),
+            site_name: "Kingston Mines".to_string(),
+            thresholds: FloodThresholds {
+                action_stage_ft: 14.0,
+                flood_stage_ft: 16.0,
+                moderate_flood_stage_ft: 20.0,
+                major_flood_stage_ft: 24.0,
+            },
+            stage: stages.iter().enumerate()
+                .map(|(i, &value)| TimedValue { timestamp: start() + Duration::hours(i as i64), value })
+                .collect(),
+        }
+    }
+
+    fn quiet_config() -> ReplayConfig {
+        ReplayConfig {
+            sustained_high_water: SustainedHighWaterConfig { min_hours: 1000, threshold_ft: None },
+            ..ReplayConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_rise_and_fall_replays_crossings_in_order() {
+        let stages = [13.0, 14.2, 15.4, 16.3, 17.0, 15.0, 13.5, 13.0];
+        let end = start() + Duration::hours(8);
+        let alerts: Vec<SimulatedAlert> = simulate(&[station(&stages)], &[], start(), end, &quiet_config())
+            .into_iter()
+            .filter(|a| a.kind == AlertKind::FloodStage)
+            .collect();
+
+        let severities: Vec<&str> = alerts.iter().map(|a| a.severity.as_str()).collect();
+        assert_eq!(severities, vec!["action", "flood", "action", "all_clear"]);
+        assert_eq!(alerts[1].at, start() + Duration::hours(3));
+        assert!(alerts[1].message.starts_with("FLOOD at Kingston Mines: 16.30 ft"));
+        assert!(alerts.windows(2).all(|w| w[0].at <= w[1].at));
+    }
+
+    #[test]
+    fn test_approaching_fires_before_the_crossing() {
+        // 0.25 ft/hr rise through the 1 ft margin below flood stage
+        let stages: Vec<f64> = (0..12).map(|h| 14.0 + 0.25 * h as f64).collect();
+        let end = start() + Duration::hours(12);
+        let alerts = simulate(&[station(&stages)], &[], start(), end, &quiet_config());
+
+        let approaching = alerts.iter().find(|a| a.kind == AlertKind::ApproachingFloodStage).unwrap();
+        let flood = alerts.iter().find(|a| a.severity == "flood").unwrap();
+        assert!(approaching.at < flood.at);
+        assert_eq!(approaching.severity, "action");
+    }
+
+    #[test]
+    fn test_backwater_onset_from_differential() {
+        // Tailwater climbing 0.5 ft/hr toward pool
+        let differential: Vec<TimedValue> = (0..10)
+            .map(|h| TimedValue { timestamp: start() + Duration::hours(h), value: -6.0 + 0.5 * h as f64 })
+            .collect();
+        let end = start() + Duration::hours(10);
+        let alerts = simulate(&[], &differential, start(), end, &quiet_config());
+
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].kind, AlertKind::BackwaterOnset);
+        assert_eq!(alerts[0].severity, "flood");
+        // Differential first reaches the -2 ft default at hour 8
+        assert_eq!(alerts[0].at, start() + Duration::hours(8));
+    }
+
+    #[test]
+    fn test_backwater_onset_from_stored_lagrange_readings() {
+        // IL08 pool and tailwater as discovery stores them, pool logged a
+        // few minutes off the tailwater cadence, plus outflow at the lock
+        let mut readings = Vec::new();
+        for h in 0..10 {
+            let at = start() + Duration::hours(h);
+            let tagged = |ts_id: &str, timestamp, value| (ts_id.to_string(), TimedValue { timestamp, value });
+            readings.push(tagged("IL08.Elev.Inst.~1Hour.0.CBT-RAW", at + Duration::minutes(5), 436.0));
+            readings.push(tagged("IL08.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW", at, 430.0 + 0.5 * h as f64));
+            readings.push(tagged("IL08.Flow-Out.Inst.~1Hour.0.CBT-RAW", at, 90_000.0));
+        }
+        let config = quiet_config();
+        let tolerance = Duration::minutes(config.backwater_onset.align_tolerance_minutes);
+        let differential = backwater::lagrange_differential(&readings, tolerance);
+        assert_eq!(differential.len(), 10);
+
+        let end = start() + Duration::hours(10);
+        let alerts = simulate(&[], &differential, start(), end, &config);
+        let onset = alerts.iter().find(|a| a.kind == AlertKind::BackwaterOnset).expect("onset should replay");
+        assert_eq!(onset.at, start() + Duration::hours(8));
+    }
+}
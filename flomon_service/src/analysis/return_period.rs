@@ -0,0 +1,320 @@
+//! Flood frequency: how often a stage is reached, as a return period.
+//!
+//! "The 100-year flood" is the stage with a 1% chance of being exceeded in
+//! any water year. `return_period` fits a log-Pearson Type III distribution
+//! (the Bulletin 17 method) to a station's annual peak stages and reads off
+//! the stage for each of `RETURN_PERIODS_YEARS`; `return_period_of_stage`
+//! inverts that curve so a current reading can be framed as "about a
+//! 25-year event", or as beyond anything the curve was fit to.
+//!
+//! Annual peaks are the USGS Peak Streamflow records in
+//! `usgs_raw.annual_peaks`, loaded by the daemon at startup. Where the log
+//! transform isn't possible (a stage at or below the gauge datum), Weibull
+//! plotting positions of the observed peaks are used instead. Curves are
+//! cached per site for `CURVE_CACHE_TTL`, since the peaks behind them
+//! change at most once a year.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use postgres::Client;
+
+/// Return periods reported, in years.
+pub const RETURN_PERIODS_YEARS: [f64; 7] = [2.0, 5.0, 10.0, 25.0, 50.0, 100.0, 500.0];
+
+/// Fewest annual peaks a curve is fit to.
+pub const MIN_ANNUAL_PEAKS: usize = 10;
+
+/// How long a site's fitted curve is reused before it is refit.
+pub const CURVE_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// Fitted curves by site code, with when each was fit.
+type CurveCache = Mutex<HashMap<String, (Instant, Vec<(f64, f64)>)>>;
+
+// ---------------------------------------------------------------------------
+// Distribution
+// ---------------------------------------------------------------------------
+
+/// Standard normal quantile (Acklam's rational approximation, relative
+/// error below 1.2e-9). `p` must be in (0, 1).
+pub fn inverse_normal(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2,
+        1.38357751867269e2, -3.066479806614716e1, 2.506628277459239,
+    ];
+    const B: [f64; 5] = [
+        -5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2,
+        6.680131188771972e1, -1.328068155288572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838,
+        -2.549732539343734, 4.374664141464968, 2.938163982698783,
+    ];
+    const D: [f64; 4] = [
+        7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416,
+    ];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Pearson III frequency factor for skew `g` at `return_period_years`
+/// (Wilson-Hilferty approximation; exact normal quantile at zero skew).
+pub fn frequency_factor(skew: f64, return_period_years: f64) -> f64 {
+    let z = inverse_normal(1.0 - 1.0 / return_period_years);
+    if skew.abs() < 1e-6 {
+        return z;
+    }
+    let k = skew / 6.0;
+    ((1.0 + k * z - k * k).powi(3) - 1.0) / (3.0 * k)
+}
+
+/// Mean, sample standard deviation, and bias-corrected skew of `values`.
+fn moments(values: &[f64]) -> (f64, f64, f64) {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let m2 = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>();
+    let m3 = values.iter().map(|v| (v - mean).powi(3)).sum::<f64>();
+    let sd = (m2 / (n - 1.0)).sqrt();
+    let skew = if sd > 0.0 { n * m3 / ((n - 1.0) * (n - 2.0) * sd.powi(3)) } else { 0.0 };
+    (mean, sd, skew)
+}
+
+/// Log-Pearson III stage for each of `RETURN_PERIODS_YEARS`, fit to
+/// `peaks`. `None` with fewer than `MIN_ANNUAL_PEAKS` peaks or any peak at
+/// or below zero.
+pub fn log_pearson3(peaks: &[f64]) -> Option<Vec<(f64, f64)>> {
+    if peaks.len() < MIN_ANNUAL_PEAKS || peaks.iter().any(|&p| p <= 0.0) {
+        return None;
+    }
+
+    let logs: Vec<f64> = peaks.iter().map(|p| p.log10()).collect();
+    let (mean, sd, skew) = moments(&logs);
+
+    Some(RETURN_PERIODS_YEARS.iter()
+        .map(|&t| (t, 10f64.powf(mean + frequency_factor(skew, t) * sd)))
+        .collect())
+}
+
+/// Empirical (return period, stage) of each observed peak by Weibull
+/// plotting position, T = (n + 1) / rank, ascending in T.
+pub fn weibull_positions(peaks: &[f64]) -> Vec<(f64, f64)> {
+    let mut sorted = peaks.to_vec();
+    sorted.sort_by(|a, b| a.total_cmp(b));
+    let n = sorted.len() as f64;
+
+    sorted.iter()
+        .enumerate()
+        .map(|(i, &stage)| ((n + 1.0) / (n - i as f64), stage))
+        .collect()
+}
+
+/// Where a stage falls on a return-period curve.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StageFrequency {
+    /// Read off the curve, in years
+    Years(f64),
+    /// Above the curve's rarest stage (`max_years`); the fit says nothing
+    /// about how rare it is beyond that
+    AboveCurve { max_years: f64 },
+}
+
+impl StageFrequency {
+    /// Return period in years, when the stage is on the curve
+    pub fn years(&self) -> Option<f64> {
+        match self {
+            StageFrequency::Years(years) => Some(*years),
+            StageFrequency::AboveCurve { .. } => None,
+        }
+    }
+
+    /// "≈ 25-year event", or "> 500-year event (above max fitted)"
+    pub fn describe(&self) -> String {
+        match self {
+            StageFrequency::Years(years) => describe_return_period(*years),
+            StageFrequency::AboveCurve { max_years } => format!("> {}-year event (above max fitted)", max_years),
+        }
+    }
+}
+
+/// Where `stage` falls on `curve` ((years, stage) ascending), linear in
+/// log-years between points.
+///
+/// `None` below the curve's most frequent stage (a routine level, not an
+/// event).
+pub fn return_period_of_stage(stage: f64, curve: &[(f64, f64)]) -> Option<StageFrequency> {
+    let (first, last) = (curve.first()?, curve.last()?);
+    if stage < first.1 {
+        return None;
+    }
+    if stage > last.1 {
+        return Some(StageFrequency::AboveCurve { max_years: last.0 });
+    }
+    if stage == last.1 {
+        return Some(StageFrequency::Years(last.0));
+    }
+
+    curve.windows(2)
+        .find(|w| stage >= w[0].1 && stage < w[1].1)
+        .map(|w| {
+            let fraction = (stage - w[0].1) / (w[1].1 - w[0].1);
+            StageFrequency::Years((w[0].0.ln() + fraction * (w[1].0.ln() - w[0].0.ln())).exp())
+        })
+}
+
+/// "≈ 25-year event" wording for a return period, rounded to the nearest
+/// reported period at or below it.
+pub fn describe_return_period(years: f64) -> String {
+    let period = RETURN_PERIODS_YEARS.iter()
+        .rev()
+        .find(|&&t| years >= t - 1e-9)
+        .copied()
+        .unwrap_or(years);
+    format!("≈ {}-year event", period)
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// USGS annual peak stages stored for `site_code`.
+pub fn annual_peak_stages(site_code: &str, client: &mut Client) -> Result<Vec<f64>, String> {
+    let rows = client.query(
+        "SELECT peak_stage_ft::float8
+         FROM usgs_raw.annual_peaks
+         WHERE site_code = $1",
+        &[&site_code]
+    ).map_err(|e| format!("Failed to fetch annual peaks for {}: {}", site_code, e))?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Return-period curve fit to `peaks`: log-Pearson III, or the empirical
+/// plotting positions where that fit isn't possible. Empty with fewer than
+/// `MIN_ANNUAL_PEAKS` peaks.
+pub fn fit_curve(peaks: &[f64]) -> Vec<(f64, f64)> {
+    if peaks.len() < MIN_ANNUAL_PEAKS {
+        return Vec::new();
+    }
+    log_pearson3(peaks).unwrap_or_else(|| weibull_positions(peaks))
+}
+
+/// (return period in years, stage in ft) for `site_code` (see
+/// `fit_curve`), reusing a curve fit within the last `CURVE_CACHE_TTL`.
+pub fn return_period(site_code: &str, client: &mut Client) -> Result<Vec<(f64, f64)>, String> {
+    static CURVES: OnceLock<CurveCache> = OnceLock::new();
+    let curves = CURVES.get_or_init(Default::default);
+
+    if let Some((fitted, curve)) = curves.lock().unwrap_or_else(|e| e.into_inner()).get(site_code)
+        && fitted.elapsed() < CURVE_CACHE_TTL {
+        return Ok(curve.clone());
+    }
+
+    let curve = fit_curve(&annual_peak_stages(site_code, client)?);
+    curves.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(site_code.to_string(), (Instant::now(), curve.clone()));
+    Ok(curve)
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ingest::peak_flow::parse_rdb;
+    use chrono::NaiveDate;
+
+    #[test]
+    fn test_inverse_normal_and_frequency_factor() {
+        assert!(inverse_normal(0.5).abs() < 1e-9);
+        assert!((inverse_normal(0.99) - 2.326348).abs() < 1e-6);
+        assert!((inverse_normal(0.01) + 2.326348).abs() < 1e-6);
+
+        // Bulletin 17B table values
+        assert!((frequency_factor(0.0, 100.0) - 2.326).abs() < 1e-3);
+        assert!((frequency_factor(0.5, 100.0) - 2.686).abs() < 5e-3);
+        assert!((frequency_factor(-0.5, 100.0) - 1.955).abs() < 5e-3);
+    }
+
+    #[test]
+    fn test_symmetric_log_peaks_fit_lognormal() {
+        // log10 peaks symmetric about 1.3 (20 ft): zero skew, so the
+        // 2-year stage is the geometric mean and the 100-year stage sits
+        // 2.326 standard deviations above it
+        let offsets = [-0.1, -0.06, -0.03, -0.01, 0.0, 0.0, 0.01, 0.03, 0.06, 0.1];
+        let peaks: Vec<f64> = offsets.iter().map(|d| 10f64.powf(1.3 + d)).collect();
+        let (_, sd, _) = moments(&peaks.iter().map(|p| p.log10()).collect::<Vec<_>>());
+
+        let curve = log_pearson3(&peaks).unwrap();
+        let stage_at = |t: f64| curve.iter().find(|(years, _)| *years == t).unwrap().1;
+
+        assert!((stage_at(2.0) - 10f64.powf(1.3)).abs() < 1e-9);
+        assert!((stage_at(100.0) - 10f64.powf(1.3 + 2.326348 * sd)).abs() < 1e-3);
+        assert!(curve.windows(2).all(|w| w[1].1 > w[0].1));
+    }
+
+    #[test]
+    fn test_peoria_peak_record_and_current_stage_lookup() {
+        // Illinois River at Peoria, USGS Peak Streamflow database
+        let records = parse_rdb(include_str!("../../tests/data/peak_05567500_peoria.rdb")).unwrap();
+        let peak_1983 = records.iter()
+            .find(|r| r.peak_date == NaiveDate::from_ymd_opt(1983, 5, 3).unwrap())
+            .unwrap();
+        assert_eq!(peak_1983.peak_stage_ft(), Some(13.34));
+
+        let peaks: Vec<f64> = records.iter().filter_map(|r| r.peak_stage_ft()).collect();
+        assert_eq!(peaks.len(), records.len());
+        let curve = fit_curve(&peaks);
+        assert_eq!(curve.len(), RETURN_PERIODS_YEARS.len());
+
+        // The median annual peak is close to the 2-year stage
+        let mut sorted = peaks.clone();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+        let median = sorted[sorted.len() / 2];
+        let two_year = curve[0].1;
+        assert!((two_year - median).abs() < 0.5, "2-year stage {} vs median {}", two_year, median);
+
+        // The record crest (20.21 ft, Dec 1982) is rare but within the curve
+        let record = return_period_of_stage(20.21, &curve).and_then(|f| f.years()).unwrap();
+        assert!(record > 10.0 && record < 500.0, "record return period {}", record);
+
+        assert_eq!(return_period_of_stage(10.0, &curve), None);
+        assert_eq!(describe_return_period(37.0), "≈ 25-year event");
+    }
+
+    #[test]
+    fn test_stage_above_fitted_curve_is_not_a_500_year_event() {
+        let curve = [(2.0, 14.0), (100.0, 20.0), (500.0, 22.0)];
+        let above = return_period_of_stage(30.0, &curve).unwrap();
+        assert_eq!(above, StageFrequency::AboveCurve { max_years: 500.0 });
+        assert_eq!(above.years(), None);
+        assert_eq!(above.describe(), "> 500-year event (above max fitted)");
+
+        assert_eq!(return_period_of_stage(22.0, &curve), Some(StageFrequency::Years(500.0)));
+        assert_eq!(return_period_of_stage(14.0, &curve).unwrap().describe(), "≈ 2-year event");
+    }
+
+    #[test]
+    fn test_weibull_positions() {
+        let positions = weibull_positions(&[3.0, 1.0, 2.0, 4.0]);
+        assert_eq!(positions, vec![(1.25, 1.0), (5.0 / 3.0, 2.0), (2.5, 3.0), (5.0, 4.0)]);
+    }
+}
@@ -0,0 +1,355 @@
+//! Compound flood-risk score for the basin.
+//!
+//! `/status` reports a coarse LOW/MODERATE/HIGH from which zones are
+//! active. This blends the signals that actually drive a Peoria flood into
+//! one 0-100 number with its breakdown:
+//!
+//! - Kingston Mines stage against its NWS thresholds
+//! - the fastest stage rise at any upstream or tributary gauge
+//! - the LaGrange tailwater-minus-pool differential (backwater)
+//! - mean basin precipitation over the last `PRECIP_WINDOW_HOURS`
+//!
+//! Each is scaled to 0-1 and weighted by `ScoreWeights`. A component with
+//! no data is left out and the remaining weights are rescaled, so a dead
+//! ASOS feed can't pull the score down. Left-out components are marked
+//! unavailable and listed in `unavailable_components`, so a score computed
+//! without backwater (say) says so.
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::analysis::align::DEFAULT_ALIGN_TOLERANCE_MINUTES;
+use crate::analysis::backwater;
+use crate::analysis::interpolate::TimedValue;
+use crate::analysis::precip::{fetch_station_precip, rollup_precip};
+use crate::asos_locations;
+use crate::model::{FloodThresholds, PARAM_STAGE};
+use crate::stations;
+
+/// The gauge whose stage stands for flood status at the property.
+pub const KINGSTON_MINES_SITE_CODE: &str = "05568500";
+
+/// Window for the latest stage, rates of rise, and backwater differential.
+pub const SCORE_WINDOW_HOURS: i32 = 6;
+
+/// Upstream rise (ft/hr) that scores as fully alarming.
+pub const RATE_SATURATION_FT_PER_HOUR: f64 = 0.5;
+
+/// LaGrange differential (tailwater minus pool, ft) at or below which the
+/// dam is holding pool and backwater scores zero; it scores fully at 0 ft,
+/// when hydraulic control is lost.
+pub const BACKWATER_HELD_DIFFERENTIAL_FT: f64 = -4.0;
+
+/// Trailing window for basin precipitation.
+pub const PRECIP_WINDOW_HOURS: i32 = 48;
+
+/// Basin-mean precipitation (inches over `PRECIP_WINDOW_HOURS`) that
+/// scores as fully alarming.
+pub const PRECIP_SATURATION_IN: f64 = 3.0;
+
+/// Scores at or above these are MODERATE and HIGH.
+pub const MODERATE_SCORE: f64 = 30.0;
+pub const HIGH_SCORE: f64 = 60.0;
+
+/// Relative weight of each component. Only the ratios matter.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct ScoreWeights {
+    pub stage: f64,
+    pub upstream_rate: f64,
+    pub backwater: f64,
+    pub precipitation: f64,
+}
+
+impl Default for ScoreWeights {
+    fn default() -> Self {
+        Self {
+            stage: 0.40,
+            upstream_rate: 0.25,
+            backwater: 0.20,
+            precipitation: 0.15,
+        }
+    }
+}
+
+/// Raw inputs to the score; `None` where there is no recent data.
+#[derive(Debug, Clone, Default)]
+pub struct ScoreInputs {
+    /// Latest Kingston Mines stage, in feet
+    pub stage_ft: Option<f64>,
+    pub thresholds: Option<FloodThresholds>,
+    /// Fastest rise among upstream and tributary gauges, in ft/hr
+    pub upstream_rate_ft_per_hour: Option<f64>,
+    /// Latest LaGrange tailwater minus pool, in feet
+    pub backwater_differential_ft: Option<f64>,
+    /// Mean basin precipitation over `PRECIP_WINDOW_HOURS`, in inches
+    pub precip_in: Option<f64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ScoreBand {
+    Low,
+    Moderate,
+    High,
+}
+
+impl ScoreBand {
+    pub fn from_score(score: f64) -> Self {
+        if score >= HIGH_SCORE {
+            ScoreBand::High
+        } else if score >= MODERATE_SCORE {
+            ScoreBand::Moderate
+        } else {
+            ScoreBand::Low
+        }
+    }
+}
+
+/// One input's share of the score.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScoreComponent {
+    pub name: &'static str,
+    /// Raw input, in `unit`
+    pub value: Option<f64>,
+    pub unit: &'static str,
+    /// Input scaled to 0-1
+    pub normalized: Option<f64>,
+    /// False when there was no recent data and the weight went elsewhere
+    pub available: bool,
+    /// Weight after rescaling over the components with data
+    pub weight: f64,
+    /// Points contributed to the 0-100 score
+    pub points: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FloodScore {
+    /// 0 (quiet) to 100 (every signal saturated)
+    pub score: f64,
+    pub band: ScoreBand,
+    pub components: Vec<ScoreComponent>,
+    /// Components without data, whose weight was spread over the rest
+    pub unavailable_components: Vec<&'static str>,
+    pub weights: ScoreWeights,
+    pub computed_at: DateTime<Utc>,
+}
+
+// ---------------------------------------------------------------------------
+// Scoring
+// ---------------------------------------------------------------------------
+
+/// Stage scaled against flood categories: 0 at action stage, 0.5 at flood
+/// stage, 1 at major flood stage, linear in between.
+fn stage_component(stage_ft: f64, t: &FloodThresholds) -> f64 {
+    let ramp = |low: f64, high: f64| if high > low { ((stage_ft - low) / (high - low)).clamp(0.0, 1.0) } else { 1.0 };
+    if stage_ft < t.flood_stage_ft {
+        0.5 * ramp(t.action_stage_ft, t.flood_stage_ft)
+    } else {
+        0.5 + 0.5 * ramp(t.flood_stage_ft, t.major_flood_stage_ft)
+    }
+}
+
+/// Score and breakdown for `inputs`.
+pub fn score(inputs: &ScoreInputs, weights: &ScoreWeights, computed_at: DateTime<Utc>) -> FloodScore {
+    let stage = match (inputs.stage_ft, &inputs.thresholds) {
+        (Some(stage_ft), Some(t)) => Some(stage_component(stage_ft, t)),
+        _ => None,
+    };
+    let rate = inputs.upstream_rate_ft_per_hour
+        .map(|r| (r / RATE_SATURATION_FT_PER_HOUR).clamp(0.0, 1.0));
+    let backwater = inputs.backwater_differential_ft
+        .map(|d| ((d - BACKWATER_HELD_DIFFERENTIAL_FT) / -BACKWATER_HELD_DIFFERENTIAL_FT).clamp(0.0, 1.0));
+    let precip = inputs.precip_in
+        .map(|p| (p / PRECIP_SATURATION_IN).clamp(0.0, 1.0));
+
+    let parts = [
+        ("stage", inputs.stage_ft, "ft", stage, weights.stage),
+        ("upstream_rate", inputs.upstream_rate_ft_per_hour, "ft/hr", rate, weights.upstream_rate),
+        ("backwater", inputs.backwater_differential_ft, "ft", backwater, weights.backwater),
+        ("precipitation", inputs.precip_in, "in", precip, weights.precipitation),
+    ];
+
+    let total_weight: f64 = parts.iter()
+        .filter(|p| p.3.is_some())
+        .map(|p| p.4.max(0.0))
+        .sum();
+
+    let components: Vec<ScoreComponent> = parts.into_iter()
+        .map(|(name, value, unit, normalized, weight)| {
+            let weight = match normalized {
+                Some(_) if total_weight > 0.0 => weight.max(0.0) / total_weight,
+                _ => 0.0,
+            };
+            ScoreComponent {
+                name,
+                value,
+                unit,
+                normalized,
+                available: normalized.is_some(),
+                weight,
+                points: 100.0 * weight * normalized.unwrap_or(0.0),
+            }
+        })
+        .collect();
+
+    let score = components.iter().map(|c| c.points).sum::<f64>().clamp(0.0, 100.0);
+    let unavailable_components = components.iter()
+        .filter(|c| !c.available)
+        .map(|c| c.name)
+        .collect();
+    FloodScore {
+        score,
+        band: ScoreBand::from_score(score),
+        components,
+        unavailable_components,
+        weights: *weights,
+        computed_at,
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Stored stage at `site_code` over the `hours` before `now`, oldest first.
+fn recent_stage(client: &mut Client, site_code: &str, hours: i32, now: DateTime<Utc>) -> Result<Vec<TimedValue>, String> {
+    let rows = client.query(
+        "SELECT reading_time, value::float8
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1
+           AND parameter_code = $2
+           AND reading_time > $4 - make_interval(hours => $3)
+           AND reading_time <= $4
+         ORDER BY reading_time ASC",
+        &[&site_code, &PARAM_STAGE, &hours, &now]
+    ).map_err(|e| format!("Failed to fetch stage for {}: {}", site_code, e))?;
+
+    Ok(rows.iter()
+        .map(|row| TimedValue {
+            timestamp: row.get(0),
+            value: row.get(1),
+        })
+        .collect())
+}
+
+/// Gather the inputs as of `now` from stored readings.
+pub fn fetch_score_inputs(client: &mut Client, now: DateTime<Utc>) -> Result<ScoreInputs, String> {
+    let registry = stations::load_stations();
+
+    let stage_ft = recent_stage(client, KINGSTON_MINES_SITE_CODE, SCORE_WINDOW_HOURS, now)?
+        .last()
+        .map(|r| r.value);
+    let thresholds = registry.iter()
+        .find(|s| s.site_code == KINGSTON_MINES_SITE_CODE)
+        .and_then(|s| s.thresholds.clone());
+
+    // Least-squares slope, as for backwater onset, so one noisy reading
+    // doesn't read as a surge
+    let mut upstream_rate_ft_per_hour: Option<f64> = None;
+    for station in registry.iter().filter(|s| {
+        s.distance_direction.starts_with("upstream") || s.distance_direction.starts_with("tributary")
+    }) {
+        let series = recent_stage(client, &station.site_code, SCORE_WINDOW_HOURS, now)?;
+        if let Some(rate) = backwater::onset_rate(&series) {
+            upstream_rate_ft_per_hour = Some(upstream_rate_ft_per_hour.map_or(rate, |r| r.max(rate)));
+        }
+    }
+
+    let tolerance = Duration::minutes(DEFAULT_ALIGN_TOLERANCE_MINUTES);
+    let backwater_differential_ft = backwater::differential_series(client, SCORE_WINDOW_HOURS, tolerance, now)?
+        .last()
+        .map(|d| d.value);
+
+    // Without iem_asos.toml the score goes on without precipitation
+    let precip_in = match asos_locations::load_locations("iem_asos.toml") {
+        Ok(locations) => {
+            let ids: Vec<String> = locations.iter().map(|l| l.db_station_id().to_string()).collect();
+            let per_station = fetch_station_precip(client, &ids, &[PRECIP_WINDOW_HOURS], now)?;
+            rollup_precip(&ids, &per_station, &[PRECIP_WINDOW_HOURS]).windows
+                .first()
+                .and_then(|w| w.mean_in)
+        }
+        Err(e) => {
+            eprintln!("Flood score without precipitation: failed to load iem_asos.toml: {}", e);
+            None
+        }
+    };
+
+    Ok(ScoreInputs {
+        stage_ft,
+        thresholds,
+        upstream_rate_ft_per_hour,
+        backwater_differential_ft,
+        precip_in,
+    })
+}
+
+/// Current compound flood score from stored readings.
+pub fn compute_flood_score(client: &mut Client, weights: &ScoreWeights, now: DateTime<Utc>) -> Result<FloodScore, String> {
+    Ok(score(&fetch_score_inputs(client, now)?, weights, now))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kingston_mines() -> FloodThresholds {
+        FloodThresholds {
+            action_stage_ft: 14.0,
+            flood_stage_ft: 16.0,
+            moderate_flood_stage_ft: 20.0,
+            major_flood_stage_ft: 24.0,
+        }
+    }
+
+    #[test]
+    fn test_high_stage_rising_with_backwater_scores_high() {
+        let inputs = ScoreInputs {
+            stage_ft: Some(19.0),
+            thresholds: Some(kingston_mines()),
+            upstream_rate_ft_per_hour: Some(0.4),
+            backwater_differential_ft: Some(-0.5),
+            precip_in: Some(1.0),
+        };
+
+        let result = score(&inputs, &ScoreWeights::default(), Utc::now());
+        assert_eq!(result.band, ScoreBand::High, "score was {:.1}", result.score);
+        assert!(result.score < 100.0);
+        let points: f64 = result.components.iter().map(|c| c.points).sum();
+        assert!((points - result.score).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_quiet_basin_scores_low_and_missing_inputs_are_reweighted() {
+        let quiet = ScoreInputs {
+            stage_ft: Some(10.0),
+            thresholds: Some(kingston_mines()),
+            upstream_rate_ft_per_hour: Some(-0.05),
+            backwater_differential_ft: Some(-6.0),
+            precip_in: Some(0.1),
+        };
+        let result = score(&quiet, &ScoreWeights::default(), Utc::now());
+        assert_eq!(result.band, ScoreBand::Low);
+        assert!(result.unavailable_components.is_empty());
+        assert!(result.score < 5.0);
+
+        // At major flood stage with nothing else known, stage carries it all
+        let stage_only = ScoreInputs {
+            stage_ft: Some(24.0),
+            thresholds: Some(kingston_mines()),
+            ..ScoreInputs::default()
+        };
+        let result = score(&stage_only, &ScoreWeights::default(), Utc::now());
+        assert!((result.score - 100.0).abs() < 1e-9);
+        assert_eq!(result.components[0].weight, 1.0);
+        assert!(result.components[1..].iter().all(|c| !c.available && c.normalized.is_none() && c.points == 0.0));
+        assert_eq!(result.unavailable_components, vec!["upstream_rate", "backwater", "precipitation"]);
+
+        assert_eq!(score(&ScoreInputs::default(), &ScoreWeights::default(), Utc::now()).score, 0.0);
+    }
+}
@@ -0,0 +1,168 @@
+//! Seasonal context for current stage readings.
+//!
+//! A stage that is routine during April snowmelt can be alarming in June.
+//! `stage_percentile` ranks a stage against the historical record for the
+//! same calendar window (± `SEASONAL_WINDOW_DAYS` around the day of year,
+//! across every year on file — DV data back to 1939 for most sites).
+//!
+//! History is reduced to daily means (Central calendar days) before ranking
+//! so the dense 15-minute IV record of recent years doesn't outweigh decades
+//! of once-a-day DV data. A site's daily means are read once and cached for
+//! `CLIMATOLOGY_CACHE_TTL`; no index covers a day-of-year filter, so
+//! querying the window per request would scan the whole record every time.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use postgres::Client;
+
+use crate::model::PARAM_STAGE;
+
+/// Half-width of the calendar window compared against, in days.
+pub const SEASONAL_WINDOW_DAYS: i32 = 15;
+
+/// Minimum number of historical days required before a percentile is
+/// meaningful.
+pub const MIN_HISTORY_DAYS: usize = 30;
+
+/// How long a site's daily-mean climatology is reused before it is reread.
+/// A day's new readings barely move decades of history.
+pub const CLIMATOLOGY_CACHE_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+/// (day of year, daily mean stage) for one site.
+type Climatology = Arc<Vec<(u32, f64)>>;
+
+/// Climatologies by site code, with when each was read.
+type ClimatologyCache = Mutex<HashMap<String, (Instant, Climatology)>>;
+
+// ---------------------------------------------------------------------------
+// Percentile
+// ---------------------------------------------------------------------------
+
+/// Percentile rank (0–100) of `value` within `history`.
+///
+/// Uses the mean-rank definition: values equal to `value` count half, so a
+/// stage matching the historical median ranks at 50 rather than being
+/// pushed up by ties. Returns `None` for an empty history.
+pub fn percentile_rank(value: f64, history: &[f64]) -> Option<f64> {
+    if history.is_empty() {
+        return None;
+    }
+
+    let below = history.iter().filter(|&&h| h < value).count() as f64;
+    let equal = history.iter().filter(|&&h| h == value).count() as f64;
+
+    Some(100.0 * (below + 0.5 * equal) / history.len() as f64)
+}
+
+/// Daily means from `climatology` within ±`SEASONAL_WINDOW_DAYS` of
+/// `day_of_year`. The distance is circular, so a window around Jan 3
+/// includes late December.
+pub fn seasonal_window(climatology: &[(u32, f64)], day_of_year: u32) -> Vec<f64> {
+    climatology.iter()
+        .filter(|(doy, _)| {
+            let distance = doy.abs_diff(day_of_year);
+            distance.min(366 - distance) <= SEASONAL_WINDOW_DAYS as u32
+        })
+        .map(|&(_, mean)| mean)
+        .collect()
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Every Central calendar day's mean stage at `site_code`, keyed by day of
+/// year.
+pub fn daily_mean_stages(site_code: &str, client: &mut Client) -> Result<Vec<(u32, f64)>, String> {
+    let rows = client.query(
+        "SELECT EXTRACT(DOY FROM day)::int, mean
+         FROM (
+             SELECT (reading_time AT TIME ZONE 'America/Chicago')::date AS day,
+                    AVG(value)::float8 AS mean
+             FROM usgs_raw.gauge_readings
+             WHERE site_code = $1
+               AND parameter_code = $2
+             GROUP BY day
+         ) daily",
+        &[&site_code, &PARAM_STAGE]
+    ).map_err(|e| format!("Failed to fetch stage history: {}", e))?;
+
+    Ok(rows.iter()
+        .map(|row| (row.get::<_, i32>(0) as u32, row.get(1)))
+        .collect())
+}
+
+/// `daily_mean_stages` for `site_code`, reusing a copy read within the last
+/// `CLIMATOLOGY_CACHE_TTL`.
+fn cached_climatology(site_code: &str, client: &mut Client) -> Result<Climatology, String> {
+    static CLIMATOLOGIES: OnceLock<ClimatologyCache> = OnceLock::new();
+    let climatologies = CLIMATOLOGIES.get_or_init(Default::default);
+
+    if let Some((read, climatology)) = climatologies.lock().unwrap_or_else(|e| e.into_inner()).get(site_code)
+        && read.elapsed() < CLIMATOLOGY_CACHE_TTL {
+        return Ok(Arc::clone(climatology));
+    }
+
+    let climatology = Arc::new(daily_mean_stages(site_code, client)?);
+    climatologies.lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .insert(site_code.to_string(), (Instant::now(), Arc::clone(&climatology)));
+    Ok(climatology)
+}
+
+/// Percentile of a stage `value` at `site_code` against historical daily
+/// mean stages within ±`SEASONAL_WINDOW_DAYS` of `day_of_year` (a Central
+/// calendar day).
+///
+/// Returns `Ok(None)` when fewer than `MIN_HISTORY_DAYS` days of history
+/// fall in the window.
+pub fn stage_percentile(
+    site_code: &str,
+    value: f64,
+    day_of_year: u32,
+    client: &mut Client,
+) -> Result<Option<f64>, String> {
+    let history = seasonal_window(&cached_climatology(site_code, client)?, day_of_year);
+
+    if history.len() < MIN_HISTORY_DAYS {
+        return Ok(None);
+    }
+
+    Ok(percentile_rank(value, &history))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_percentile_rank_extremes() {
+        let history = [10.0, 11.0, 12.0, 13.0];
+        assert_eq!(percentile_rank(9.0, &history), Some(0.0));
+        assert_eq!(percentile_rank(14.0, &history), Some(100.0));
+    }
+
+    #[test]
+    fn test_percentile_rank_ties_count_half() {
+        let history = [10.0, 12.0, 12.0, 14.0];
+        assert_eq!(percentile_rank(12.0, &history), Some(50.0));
+    }
+
+    #[test]
+    fn test_percentile_rank_empty_history() {
+        assert_eq!(percentile_rank(12.0, &[]), None);
+    }
+
+    #[test]
+    fn test_seasonal_window_wraps_the_year_end() {
+        let climatology = [(1, 10.0), (20, 11.0), (180, 12.0), (355, 13.0), (366, 14.0)];
+        assert_eq!(seasonal_window(&climatology, 3), vec![10.0, 13.0, 14.0]);
+        assert_eq!(seasonal_window(&climatology, 170), vec![12.0]);
+    }
+}
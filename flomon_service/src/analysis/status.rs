@@ -0,0 +1,1010 @@
+//! Current sensor, zone, and basin flood status.
+//!
+//! Each sensor's current reading (USGS, falling back to the CWMS/ASOS
+//! tables, or a composite of USGS and CWMS) is checked against its action
+//! and flood thresholds and its staleness cutoff, weighted by role, and
+//! rolled up into a zone alert level. The basin status then combines the
+//! active zones with the backwater risk, the upstream flood pulse, and
+//! official NWS alerts.
+//!
+//! The HTTP endpoint renders these for `/zone`, `/group`, `/status`, and
+//! `/forecast`; the daemon records `basin_status` every poll cycle so
+//! `/status/history` can replay it. Every zone is assessed from a single
+//! latest-readings query.
+
+use chrono::{DateTime, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::alert::level::{AlertLevel, LevelDisplay};
+use crate::analysis::backwater;
+use crate::analysis::groupings::{group_by_zone, SensorWithData};
+use crate::analysis::travel_time::PEORIA_SITE_CODE;
+use crate::model::{GaugeReading, ReadingSource, SiteReadings, PARAM_STAGE};
+use crate::monitor;
+use crate::stations;
+use crate::zones::{self, FeedSource, PrimaryParameter, ZoneMetadata, ZonesConfig, get_zone};
+
+// ---------------------------------------------------------------------------
+// Types
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CompositeReadingResponse {
+    pub primary: String,  // "usgs" or "cwms"
+    pub backup: String,
+    pub used: String,
+    pub fell_back: bool,
+}
+
+/// One sensor's current reading and where it stands against its thresholds
+#[derive(Debug)]
+pub struct SensorAssessment {
+    pub current: CurrentReading,
+    /// The USGS reading behind `current`, when the USGS feed is the one used
+    pub usgs_reading: Option<GaugeReading>,
+    pub composite: Option<CompositeReadingResponse>,
+    /// `current` is a USGS daily mean standing in for a missing IV reading
+    pub daily_fallback: bool,
+    pub future_dated: bool,
+    /// No reading, or one past the sensor's staleness threshold
+    pub stale: bool,
+    pub above_action: bool,
+    pub above_flood: bool,
+    /// Role-weighted exceedance (see `exceedance_severity`)
+    pub severity: f64,
+}
+
+/// A zone's (or group's) sensors assessed and rolled up
+#[derive(Debug)]
+pub struct ZoneAssessment {
+    /// One per sensor, in the order given
+    pub sensors: Vec<SensorAssessment>,
+    pub above_action: Vec<String>,
+    pub above_flood: Vec<String>,
+    /// Sensors with any current reading
+    pub active: usize,
+    /// Sensors with no reading or one past their staleness threshold
+    pub stale: usize,
+    pub alert_level: AlertLevel,
+}
+
+/// Overall basin status
+#[derive(Debug, Serialize)]
+pub struct BasinStatusResponse {
+    pub overall_status: AlertLevel,
+    pub overall_display: LevelDisplay,
+    pub active_zones: Vec<ActiveZoneStatus>,
+    pub backwater_risk: BackwaterRiskResponse,
+    pub upstream_flood_pulse: UpstreamFloodPulseResponse,
+    pub compound_event_risk: String,  // "LOW", "MODERATE", "HIGH"
+    /// Worst-case hours until an active zone's flooding reaches the property
+    pub min_lead_time_hours: Option<i64>,
+    pub official_alerts: Vec<NwsAlertResponse>,
+    pub last_updated: DateTime<Utc>,
+}
+
+/// Active NWS flood product, shown alongside our computed status
+#[derive(Debug, Serialize)]
+pub struct NwsAlertResponse {
+    pub event: String,  // "Flood Warning", "Flood Watch", ...
+    pub severity: String,
+    pub headline: Option<String>,
+    pub effective: Option<DateTime<Utc>>,
+    pub expires: Option<DateTime<Utc>>,
+    pub areas: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ActiveZoneStatus {
+    pub zone_id: usize,
+    pub zone_name: String,
+    pub status: AlertLevel,
+    pub display: LevelDisplay,
+    pub lead_time_hours: Option<i64>,
+    pub key_sensors_elevated: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BackwaterRiskResponse {
+    pub risk_level: String,  // "LOW", "MODERATE", "HIGH", "CRITICAL"
+    pub grafton_stage_ft: Option<f64>,
+    pub lagrange_pool_ft: Option<f64>,
+    pub lagrange_tailwater_ft: Option<f64>,
+    pub pool_tailwater_differential_ft: Option<f64>,
+    /// Peoria stage as an elevation (needs the gage datum in usgs_stations.toml)
+    pub peoria_elevation_ft_ngvd29: Option<f64>,
+    /// Peoria elevation minus LaGrange pool elevation; shrinks toward zero
+    /// as Mississippi backwater flattens the reach
+    pub peoria_head_over_lagrange_pool_ft: Option<f64>,
+    pub explanation: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UpstreamFloodPulseResponse {
+    pub pulse_detected: bool,
+    pub estimated_arrival_hours: Option<i64>,
+    pub source_zones: Vec<usize>,
+    pub explanation: String,
+}
+
+/// Backwater risk ladder, lowest to highest
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RiskLevel {
+    Unknown,
+    Low,
+    Moderate,
+    High,
+    Critical,
+}
+
+impl RiskLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RiskLevel::Unknown => "UNKNOWN",
+            RiskLevel::Low => "LOW",
+            RiskLevel::Moderate => "MODERATE",
+            RiskLevel::High => "HIGH",
+            RiskLevel::Critical => "CRITICAL",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Readings
+// ---------------------------------------------------------------------------
+
+/// How far back `fetch_all_recent_readings` looks for a daily value when a
+/// site/parameter has no reading in the last 4 hours.
+const DV_FALLBACK_DAYS: i32 = 7;
+
+/// Fetch the latest USGS reading per site and parameter from the last 4
+/// hours. Where the IV feed has nothing in that window, fall back to the
+/// most recent daily value from the last `DV_FALLBACK_DAYS`; those come
+/// back with `source: UsgsDv` so callers can label them as daily means.
+pub fn fetch_all_recent_readings(client: &mut Client) -> Result<Vec<GaugeReading>, String> {
+    let rows = client.query(
+        "WITH recent AS (
+            SELECT DISTINCT ON (agency_code, site_code, parameter_code)
+                site_code, parameter_code, unit, value, reading_time, qualifier, source, agency_code
+            FROM usgs_raw.gauge_readings
+            WHERE reading_time >= NOW() - INTERVAL '4 hours'
+            ORDER BY agency_code, site_code, parameter_code, reading_time DESC
+         ), latest_daily AS (
+            SELECT DISTINCT ON (agency_code, site_code, parameter_code)
+                site_code, parameter_code, unit, value, reading_time, qualifier, source, agency_code
+            FROM usgs_raw.gauge_readings
+            WHERE source = 'usgs_dv'
+              AND reading_time >= NOW() - make_interval(days => $1)
+            ORDER BY agency_code, site_code, parameter_code, reading_time DESC
+         )
+         SELECT * FROM recent
+         UNION ALL
+         SELECT * FROM latest_daily d
+         WHERE NOT EXISTS (
+            SELECT 1 FROM recent r
+            WHERE r.agency_code = d.agency_code AND r.site_code = d.site_code
+              AND r.parameter_code = d.parameter_code
+         )",
+        &[&DV_FALLBACK_DAYS]
+    ).map_err(|e| format!("Failed to fetch recent readings: {}", e))?;
+    
+    let mut readings = Vec::new();
+    
+    for row in rows {
+        let site_code: String = row.get(0);
+        let parameter_code: String = row.get(1);
+        let unit: String = row.get(2);
+        let value: rust_decimal::Decimal = row.get(3);
+        let reading_time: DateTime<Utc> = row.get(4);
+        let qualifier: String = row.get(5);
+        let source: String = row.get(6);
+        let agency_code: String = row.get(7);
+        
+        readings.push(GaugeReading {
+            agency_code,
+            site_code: site_code.clone(),
+            site_name: site_code.clone(),  // Will be enriched later
+            parameter_code,
+            unit,
+            value: value.to_string().parse().unwrap_or(0.0),
+            datetime: reading_time.to_rfc3339(),
+            qualifier,
+            source: ReadingSource::from_db_str(&source),
+        });
+    }
+    
+    Ok(readings)
+}
+
+/// Latest reading as (value, unit, RFC 3339 timestamp, age in minutes)
+pub type CurrentReading = (Option<f64>, Option<String>, Option<String>, Option<i64>);
+
+/// Current reading from a USGS gauge reading
+pub fn current_from_gauge(reading: &GaugeReading) -> CurrentReading {
+    let staleness = DateTime::parse_from_rfc3339(&reading.datetime)
+        .ok()
+        .map(|dt| (Utc::now() - dt.with_timezone(&Utc)).num_minutes());
+    
+    (Some(reading.value), Some(reading.unit.clone()), Some(reading.datetime.clone()), staleness)
+}
+
+/// Latest CWMS stage/elevation or ASOS precipitation for a sensor without
+/// a USGS reading
+fn fetch_sensor_reading(
+    client: &mut Client,
+    sensor: &zones::Sensor
+) -> Result<CurrentReading, String> {
+    
+    if sensor.is_cwms() {
+        // Query CWMS timeseries table
+        if let Some(cwms_loc) = &sensor.cwms_location {
+            // Stage or elevation only: discharge shares the location
+            let rows = client.query(
+                "SELECT value, unit, timestamp
+                 FROM usace.cwms_timeseries
+                 WHERE location_id = $1 AND split_part(parameter_id, '-', 1) IN ('Stage', 'Elev')
+                 ORDER BY timestamp DESC
+                 LIMIT 1",
+                &[cwms_loc]
+            ).map_err(|e| format!("CWMS query failed: {}", e))?;
+            
+            if let Some(row) = rows.first() {
+                let value: rust_decimal::Decimal = row.get(0);
+                let unit: String = row.get(1);
+                let timestamp: DateTime<Utc> = row.get(2);
+                let staleness = (Utc::now() - timestamp).num_minutes();
+                
+                return Ok((
+                    Some(value.to_string().parse().unwrap_or(0.0)),
+                    Some(unit),
+                    Some(timestamp.to_rfc3339()),
+                    Some(staleness)
+                ));
+            }
+        }
+    } else if sensor.is_asos() {
+        // Query ASOS observations table
+        if let Some(station_id) = &sensor.station_id {
+            let rows = client.query(
+                "SELECT precip_1hr_in, observation_time
+                 FROM public.asos_observations
+                 WHERE station_id = $1
+                 ORDER BY observation_time DESC
+                 LIMIT 1",
+                &[station_id]
+            ).map_err(|e| {
+                eprintln!("ASOS query error for station {}: {:?}", station_id, e);
+                format!("ASOS query failed: {}", e)
+            })?;
+            
+            if let Some(row) = rows.first() {
+                let value_opt: Option<f64> = row.get(0);
+                let timestamp: DateTime<Utc> = row.get(1);
+                let staleness = (Utc::now() - timestamp).num_minutes();
+                
+                if let Some(value) = value_opt {
+                    return Ok((
+                        Some(value),
+                        Some("in".to_string()),
+                        Some(timestamp.to_rfc3339()),
+                        Some(staleness)
+                    ));
+                }
+            }
+        }
+    }
+    
+    Ok((None, None, None, None))
+}
+
+/// Latest stored stage at a USGS site, within the last 4 hours
+fn fetch_usgs_stage(client: &mut Client, site_code: &str) -> Result<Option<f64>, String> {
+    let rows = client.query(
+        "SELECT value::float8
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2
+           AND reading_time >= NOW() - INTERVAL '4 hours'
+         ORDER BY reading_time DESC
+         LIMIT 1",
+        &[&site_code, &PARAM_STAGE]
+    ).map_err(|e| format!("USGS stage query failed for {}: {}", site_code, e))?;
+    
+    Ok(rows.first().map(|row| row.get(0)))
+}
+
+/// CWMS location Grafton stage is stored under
+const GRAFTON_CWMS_LOCATION: &str = "Grafton-Mississippi";
+
+/// Latest CWMS reading at `location_id` with parameter segment `parameter`
+/// ("Stage", "Elev", "Elev-Tailwater"); discharge stored at the same
+/// location never stands in for it.
+fn fetch_cwms_stage(client: &mut Client, location_id: &str, parameter: &str) -> Result<Option<f64>, String> {
+    let rows = client.query(
+        "SELECT value
+         FROM usace.cwms_timeseries
+         WHERE location_id = $1 AND parameter_id = $2
+         ORDER BY timestamp DESC
+         LIMIT 1",
+        &[&location_id, &parameter]
+    ).map_err(|e| format!("CWMS stage query failed for {} {}: {}", location_id, parameter, e))?;
+    
+    if let Some(row) = rows.first() {
+        let value: rust_decimal::Decimal = row.get(0);
+        Ok(Some(value.to_string().parse().unwrap_or(0.0)))
+    } else {
+        Ok(None)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Sensor and Zone Assessment
+// ---------------------------------------------------------------------------
+
+/// The sensor's primary USGS reading, falling back to the other parameter
+/// when the gauge hasn't reported it.
+fn primary_reading(readings: &SiteReadings, primary: PrimaryParameter) -> Option<&GaugeReading> {
+    let (stage, discharge) = (readings.stage_ft.as_ref(), readings.discharge_cfs.as_ref());
+    match primary {
+        PrimaryParameter::Stage => stage.or(discharge),
+        PrimaryParameter::Discharge => discharge.or(stage),
+    }
+}
+
+/// The USGS reading a sensor shows. A composite sensor's CWMS feed is
+/// always stage, so its USGS feed is stage only: falling back to discharge
+/// would swap cfs in for feet.
+fn usgs_feed_reading(readings: &SiteReadings, primary: PrimaryParameter, composite: bool) -> Option<&GaugeReading> {
+    if composite {
+        readings.stage_ft.as_ref()
+    } else {
+        primary_reading(readings, primary)
+    }
+}
+
+/// Whether a reading of age `staleness` minutes (`None` = unknown) is too
+/// old for `threshold` — or so far in the future that the source clock is
+/// wrong and the reading proves nothing about the sensor being alive.
+pub fn is_stale_age(staleness: Option<i64>, threshold: i64) -> bool {
+    match staleness {
+        Some(age) => age > threshold || monitor::is_future_dated_age(age),
+        None => true,
+    }
+}
+
+/// Whether a composite sensor should show its backup feed instead of its
+/// primary, given each feed's reading age in minutes (`None` = no reading).
+///
+/// A fresh primary always wins. Otherwise a fresh backup is used. If neither
+/// is fresh, the more recent reading is shown, with ties going to the
+/// primary.
+fn use_composite_backup(primary_staleness: Option<i64>, backup_staleness: Option<i64>, threshold: i64) -> bool {
+    match (primary_staleness, backup_staleness) {
+        (Some(p), _) if p <= threshold => false,
+        (_, Some(b)) if b <= threshold => true,
+        (None, Some(_)) => true,
+        (Some(p), Some(b)) => b < p,
+        (_, None) => false,
+    }
+}
+
+/// Severity of an exceedance at full weight: action stage → ACTION,
+/// flood stage → FLOOD.
+const ACTION_SEVERITY: f64 = 2.0;
+const FLOOD_SEVERITY: f64 = 3.0;
+
+/// Whether `value` is at or above the action and flood thresholds. Callers
+/// pass `None` for a daily-mean fallback: it can be days old and smooths
+/// out the crest, so it's shown but never compared against thresholds.
+pub fn stage_exceedance(value: Option<f64>, action: Option<f64>, flood: Option<f64>) -> (bool, bool) {
+    let at_or_above = |threshold: Option<f64>| matches!((value, threshold), (Some(v), Some(t)) if v >= t);
+    (at_or_above(action), at_or_above(flood))
+}
+
+/// Severity one sensor contributes to its zone, scaled by its role weight
+fn exceedance_severity(above_action: bool, above_flood: bool, role_weight: f64) -> f64 {
+    let base = if above_flood {
+        FLOOD_SEVERITY
+    } else if above_action {
+        ACTION_SEVERITY
+    } else {
+        0.0
+    };
+    base * role_weight
+}
+
+/// Zone alert level from the worst role-weighted exceedance and staleness.
+///
+/// Any weighted exceedance is at least WATCH. UNKNOWN when the zone has no
+/// sensors to judge by.
+pub fn zone_alert_level(sensor_count: usize, stale_count: usize, severity: f64) -> AlertLevel {
+    if sensor_count == 0 {
+        AlertLevel::Unknown
+    } else if severity >= FLOOD_SEVERITY {
+        AlertLevel::Flood
+    } else if severity >= ACTION_SEVERITY {
+        AlertLevel::Action
+    } else if severity > 0.0 {
+        AlertLevel::Watch
+    } else if stale_count > sensor_count / 2 {
+        AlertLevel::Degraded
+    } else {
+        AlertLevel::Normal
+    }
+}
+
+/// Current reading and threshold standing of one sensor, falling back to
+/// the CWMS/ASOS tables where there's no USGS reading.
+pub fn assess_sensor(client: &mut Client, sensor_data: &SensorWithData, zones_config: &ZonesConfig) -> SensorAssessment {
+    let sensor = &sensor_data.sensor;
+    let threshold = sensor.staleness_threshold_minutes();
+    
+    let composite_feeds = sensor.composite_feeds();
+    let usgs_reading = sensor_data.readings.as_ref()
+        .and_then(|readings| usgs_feed_reading(readings, sensor.primary_parameter(), composite_feeds.is_some()));
+    let usgs_current = || usgs_reading.map(current_from_gauge).unwrap_or_default();
+    
+    // For CWMS/ASOS sensors, fetch from appropriate tables
+    let table_current = |client: &mut Client| match fetch_sensor_reading(client, sensor) {
+        Ok(current) => current,
+        Err(e) => {
+            // Log error but continue processing other sensors
+            eprintln!("Failed to fetch sensor {}: {}", sensor.primary_id(), e);
+            CurrentReading::default()
+        }
+    };
+    
+    let mut composite = None;
+    let (current, used_usgs) = match composite_feeds {
+        Some((primary, backup)) => {
+            let (usgs, cwms) = (usgs_current(), table_current(client));
+            let (primary_current, backup_current) = match primary {
+                FeedSource::Usgs => (usgs, cwms),
+                FeedSource::Cwms => (cwms, usgs),
+            };
+            
+            // A future-dated feed can't be trusted to be fresh
+            let plausible = |age: Option<i64>| age.filter(|a| !monitor::is_future_dated_age(*a));
+            let fell_back = use_composite_backup(plausible(primary_current.3), plausible(backup_current.3), threshold);
+            let used = if fell_back { backup } else { primary };
+            composite = Some(CompositeReadingResponse {
+                primary: primary.as_str().to_string(),
+                backup: backup.as_str().to_string(),
+                used: used.as_str().to_string(),
+                fell_back,
+            });
+            
+            (if fell_back { backup_current } else { primary_current }, used == FeedSource::Usgs)
+        }
+        None if sensor_data.readings.is_some() => (usgs_current(), true),
+        None => (table_current(client), false),
+    };
+    let usgs_reading = usgs_reading.filter(|_| used_usgs);
+    let daily_fallback = usgs_reading.is_some_and(|r| r.source == ReadingSource::UsgsDv);
+    
+    // Thresholds in the units of whichever reading is shown: a CWMS
+    // (composite) reading is always stage
+    let shown_parameter = usgs_reading.map_or(PARAM_STAGE, |r| r.parameter_code.as_str());
+    let (action_threshold, flood_threshold) = sensor.thresholds_for(shown_parameter);
+    
+    let threshold_value = current.0.filter(|_| !daily_fallback);
+    let (above_action, above_flood) = stage_exceedance(threshold_value, action_threshold, flood_threshold);
+    let weight = zones_config.role_weights.weight_for(&sensor.role);
+    
+    SensorAssessment {
+        usgs_reading: usgs_reading.cloned(),
+        composite,
+        daily_fallback,
+        future_dated: current.3.is_some_and(monitor::is_future_dated_age),
+        stale: current.0.is_none() || is_stale_age(current.3, threshold),
+        above_action,
+        above_flood,
+        severity: exceedance_severity(above_action, above_flood, weight),
+        current,
+    }
+}
+
+/// Assess each sensor of a zone or group and roll them up into an alert level
+pub fn assess_zone(client: &mut Client, sensor_data: &[SensorWithData], zones_config: &ZonesConfig) -> ZoneAssessment {
+    let sensors: Vec<SensorAssessment> = sensor_data.iter()
+        .map(|data| assess_sensor(client, data, zones_config))
+        .collect();
+    let ids = |pick: fn(&SensorAssessment) -> bool| -> Vec<String> {
+        sensor_data.iter()
+            .zip(&sensors)
+            .filter(|(_, assessed)| pick(assessed))
+            .map(|(data, _)| data.sensor.primary_id())
+            .collect()
+    };
+    
+    let above_action = ids(|s| s.above_action);
+    let above_flood = ids(|s| s.above_flood);
+    let active = sensors.iter().filter(|s| s.current.0.is_some()).count();
+    let stale = sensors.iter().filter(|s| s.stale).count();
+    let severity = sensors.iter().map(|s| s.severity).fold(0.0, f64::max);
+    
+    ZoneAssessment {
+        above_action,
+        above_flood,
+        active,
+        stale,
+        alert_level: zone_alert_level(sensors.len(), stale, severity),
+        sensors,
+    }
+}
+
+/// Every configured zone assessed, by zone ID, from one latest-readings query
+pub fn assess_zones(client: &mut Client, zones_config: &ZonesConfig) -> Result<Vec<(usize, ZoneAssessment)>, String> {
+    let readings = fetch_all_recent_readings(client)?;
+    
+    Ok(group_by_zone(readings, zones_config)
+        .into_iter()
+        .map(|zone| (zone.zone_id, assess_zone(client, &zone.sensors, zones_config)))
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// Basin Status
+// ---------------------------------------------------------------------------
+
+/// Basin level from the levels of its active zones: the worst of them, and
+/// at least WATCH once any zone is active.
+pub fn basin_alert_level(active_zone_levels: &[AlertLevel]) -> AlertLevel {
+    active_zone_levels.iter()
+        .map(|level| (*level).max(AlertLevel::Watch))
+        .max()
+        .unwrap_or(AlertLevel::Normal)
+}
+
+/// Every zone at Watch or above, or with a sensor above action stage
+pub fn active_zone_statuses(zones: &[(usize, ZoneAssessment)], zones_config: &ZonesConfig) -> Vec<ActiveZoneStatus> {
+    zones.iter()
+        .filter(|(_, zone)| zone.alert_level.is_elevated() || !zone.above_action.is_empty())
+        .filter_map(|(zone_id, zone)| {
+            let config = get_zone(zones_config, *zone_id)?;
+            Some(ActiveZoneStatus {
+                zone_id: *zone_id,
+                zone_name: config.name.clone(),
+                status: zone.alert_level,
+                display: zones_config.display.display_for(zone.alert_level),
+                lead_time_hours: ZoneMetadata::for_zone(*zone_id).lead_time_hours_max,
+                key_sensors_elevated: zone.above_action.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Lead time left once a zone has been active for `hours_active`, floored
+/// at zero (the pulse may already be at the property).
+pub fn remaining_lead_time(lead_time_hours: i64, hours_active: i64) -> i64 {
+    (lead_time_hours - hours_active.max(0)).max(0)
+}
+
+/// Start of a zone's current run of activity in `basin_status_history`:
+/// the first snapshot listing it active since the last one that didn't.
+/// `None` if the history has no record of the zone being active.
+pub fn zone_active_since(client: &mut Client, zone_id: usize) -> Result<Option<DateTime<Utc>>, String> {
+    let zone_filter = serde_json::json!([{ "zone_id": zone_id }]);
+    
+    let row = client.query_one(
+        "SELECT MIN(recorded_at)
+         FROM public.basin_status_history
+         WHERE active_zones @> $1
+           AND recorded_at > COALESCE(
+                 (SELECT MAX(recorded_at)
+                  FROM public.basin_status_history
+                  WHERE NOT active_zones @> $1),
+                 '-infinity'::timestamptz)",
+        &[&zone_filter]
+    ).map_err(|e| format!("Failed to query basin status history: {}", e))?;
+    
+    Ok(row.get(0))
+}
+
+/// Typical hours for an upstream zone's pulse to reach the property
+pub fn upstream_arrival_hours(zone_id: usize) -> Option<i64> {
+    match zone_id {
+        6 => Some(72),  // 3 days from Chicago
+        5 => Some(48),  // 2 days from Dresden Island
+        4 => Some(24),  // 1 day from Starved Rock
+        _ => None,
+    }
+}
+
+/// Detect upstream flood pulse
+pub fn detect_upstream_flood_pulse(active_zones: &[ActiveZoneStatus]) -> UpstreamFloodPulseResponse {
+    let upstream_active: Vec<usize> = active_zones.iter()
+        .filter(|z| z.zone_id >= 4)  // Zones 4, 5, 6
+        .map(|z| z.zone_id)
+        .collect();
+    
+    let pulse_detected = !upstream_active.is_empty();
+    
+    // The farthest active zone sets the pulse's arrival
+    let estimated_arrival = upstream_active.iter().filter_map(|&z| upstream_arrival_hours(z)).max();
+    
+    let explanation = if pulse_detected {
+        format!(
+            "Upstream flood pulse detected in zones: {}. Estimated arrival at property in {} hours.",
+            upstream_active.iter().map(|z| z.to_string()).collect::<Vec<_>>().join(", "),
+            estimated_arrival.unwrap_or(0)
+        )
+    } else {
+        "No upstream flood pulse detected in upper basin zones.".to_string()
+    };
+    
+    UpstreamFloodPulseResponse {
+        pulse_detected,
+        estimated_arrival_hours: estimated_arrival,
+        source_zones: upstream_active,
+        explanation,
+    }
+}
+
+/// Classify backwater risk from Grafton stage and the LaGrange
+/// pool-minus-tailwater differential (both ft), with a human-readable reason.
+///
+/// Thresholds are strict: Grafton must *exceed* its stage and the
+/// differential must be *below* its limit.
+/// - CRITICAL: Grafton > 25 and differential < 0.5
+/// - HIGH:     Grafton > 20 and differential < 1.0
+/// - MODERATE: Grafton > 18 or differential < 2.0
+/// - LOW:      otherwise
+/// - UNKNOWN:  either input missing
+pub fn classify_backwater(grafton_stage: Option<f64>, differential: Option<f64>) -> (RiskLevel, String) {
+    let level = match (grafton_stage, differential) {
+        (Some(grafton), Some(diff)) => {
+            if grafton > 25.0 && diff < 0.5 {
+                RiskLevel::Critical
+            } else if grafton > 20.0 && diff < 1.0 {
+                RiskLevel::High
+            } else if grafton > 18.0 || diff < 2.0 {
+                RiskLevel::Moderate
+            } else {
+                RiskLevel::Low
+            }
+        }
+        _ => RiskLevel::Unknown,
+    };
+    
+    let describe = |value: Option<f64>| match value {
+        Some(ft) => format!("{:.1} ft", ft),
+        None => "data unavailable".to_string(),
+    };
+    
+    let reason = format!(
+        "Backwater risk is {} based on Grafton stage ({}) and LaGrange pool-tailwater differential ({}). \
+         When Grafton exceeds 20ft and LaGrange differential drops below 1ft, Mississippi backwater is dominating Illinois River drainage.",
+        level.as_str(),
+        describe(grafton_stage),
+        describe(differential)
+    );
+    
+    (level, reason)
+}
+
+/// Analyze backwater flood risk
+pub fn analyze_backwater_risk(client: &mut Client) -> Result<BackwaterRiskResponse, String> {
+    // Fetch key sensors from Zone 0 (Mississippi) and Zone 1 (LaGrange)
+    let grafton_stage = fetch_cwms_stage(client, GRAFTON_CWMS_LOCATION, "Stage")?;
+    let lagrange_pool = fetch_cwms_stage(client, backwater::LAGRANGE_LOCATION_ID, backwater::LAGRANGE_POOL_PARAMETER)?;
+    let lagrange_tailwater = fetch_cwms_stage(client, backwater::LAGRANGE_LOCATION_ID, backwater::LAGRANGE_TAILWATER_PARAMETER)?;
+    
+    let differential = match (lagrange_pool, lagrange_tailwater) {
+        (Some(pool), Some(tw)) => Some(pool - tw),
+        _ => None,
+    };
+    
+    let (risk_level, explanation) = classify_backwater(grafton_stage, differential);
+    
+    // Stage and pool elevation only compare once both are on NGVD29
+    let peoria_elevation = fetch_usgs_stage(client, PEORIA_SITE_CODE)?
+        .and_then(|stage| stations::stage_to_elevation(PEORIA_SITE_CODE, stage));
+    let peoria_head = match (peoria_elevation, lagrange_pool) {
+        (Some(peoria), Some(pool)) => Some(peoria - pool),
+        _ => None,
+    };
+    
+    Ok(BackwaterRiskResponse {
+        risk_level: risk_level.as_str().to_string(),
+        grafton_stage_ft: grafton_stage,
+        lagrange_pool_ft: lagrange_pool,
+        lagrange_tailwater_ft: lagrange_tailwater,
+        pool_tailwater_differential_ft: differential,
+        peoria_elevation_ft_ngvd29: peoria_elevation,
+        peoria_head_over_lagrange_pool_ft: peoria_head,
+        explanation,
+    })
+}
+
+/// Active (unexpired) NWS flood alerts from the last poll.
+///
+/// Official alerts are supplementary context, so a missing table or query
+/// failure yields an empty list rather than failing /status.
+fn fetch_active_nws_alerts(client: &mut Client) -> Vec<NwsAlertResponse> {
+    let rows = match client.query(
+        "SELECT event, severity, headline, effective, expires, areas
+         FROM nws.alerts
+         WHERE (expires IS NULL OR expires > NOW())
+           -- Cancelled alerts drop off the active list and stop being seen
+           AND last_seen >= COALESCE((SELECT MAX(polled_at) FROM nws.alert_polls), '-infinity')
+         ORDER BY effective DESC NULLS LAST",
+        &[]
+    ) {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("Warning: failed to fetch NWS alerts: {}", e);
+            return Vec::new();
+        }
+    };
+    
+    rows.iter()
+        .map(|row| NwsAlertResponse {
+            event: row.get(0),
+            severity: row.get(1),
+            headline: row.get(2),
+            effective: row.get(3),
+            expires: row.get(4),
+            areas: row.get(5),
+        })
+        .collect()
+}
+
+/// Overall basin status from the sensors in `zones_config`
+pub fn basin_status(client: &mut Client, zones_config: &ZonesConfig) -> Result<BasinStatusResponse, String> {
+    let zones = assess_zones(client, zones_config)?;
+    let active_zones = active_zone_statuses(&zones, zones_config);
+    
+    let active_levels: Vec<AlertLevel> = active_zones.iter().map(|z| z.status).collect();
+    let overall_status = basin_alert_level(&active_levels);
+    
+    // Backwater risk analysis
+    let backwater_risk = analyze_backwater_risk(client)?;
+    
+    // Upstream flood pulse detection
+    let upstream_pulse = detect_upstream_flood_pulse(&active_zones);
+    
+    // Compound event risk
+    let zone_0_active = active_zones.iter().any(|z| z.zone_id == 0);
+    let zone_4_plus_active = active_zones.iter().any(|z| z.zone_id >= 4);
+    
+    let compound_risk = if zone_0_active && zone_4_plus_active {
+        "HIGH"
+    } else if zone_0_active || zone_4_plus_active {
+        "MODERATE"
+    } else {
+        "LOW"
+    };
+    
+    // Single countdown: the soonest any active zone could reach the property,
+    // less the time it has already been propagating
+    let now = Utc::now();
+    let mut min_lead_time_hours: Option<i64> = None;
+    for zone in &active_zones {
+        let Some(lead_time) = ZoneMetadata::for_zone(zone.zone_id).lead_time_hours_min else {
+            continue;
+        };
+        let hours_active = zone_active_since(client, zone.zone_id)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to determine how long zone {} has been active: {}", zone.zone_id, e);
+                None
+            })
+            .map_or(0, |since| (now - since).num_hours());
+        
+        let remaining = remaining_lead_time(lead_time, hours_active);
+        min_lead_time_hours = Some(min_lead_time_hours.map_or(remaining, |m| m.min(remaining)));
+    }
+    
+    Ok(BasinStatusResponse {
+        overall_status,
+        overall_display: zones_config.display.display_for(overall_status),
+        active_zones,
+        backwater_risk,
+        upstream_flood_pulse: upstream_pulse,
+        compound_event_risk: compound_risk.to_string(),
+        min_lead_time_hours,
+        official_alerts: fetch_active_nws_alerts(client),
+        last_updated: Utc::now(),
+    })
+}
+
+/// Persist a computed basin status to `basin_status_history`.
+///
+/// Called by the daemon once per poll cycle, from every configured sensor,
+/// so past assessments can be replayed through `/status/history`.
+pub fn record_basin_status(client: &mut Client, status: &BasinStatusResponse) -> Result<(), String> {
+    let active_zones = serde_json::to_value(&status.active_zones)
+        .map_err(|e| format!("Failed to serialize active zones: {}", e))?;
+    let backwater_risk = serde_json::to_value(&status.backwater_risk)
+        .map_err(|e| format!("Failed to serialize backwater risk: {}", e))?;
+    
+    client.execute(
+        "INSERT INTO public.basin_status_history
+         (recorded_at, overall_status, compound_event_risk, active_zones, backwater_risk)
+         VALUES ($1, $2, $3, $4, $5)",
+        &[&status.last_updated, &status.overall_status.as_str(), &status.compound_event_risk,
+          &active_zones, &backwater_risk]
+    ).map_err(|e| format!("Failed to record basin status: {}", e))?;
+    
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::PARAM_DISCHARGE;
+
+    #[test]
+    fn test_zone_alert_level() {
+        assert_eq!(zone_alert_level(0, 0, 0.0), AlertLevel::Unknown);
+        assert_eq!(zone_alert_level(4, 0, FLOOD_SEVERITY), AlertLevel::Flood);
+        assert_eq!(zone_alert_level(4, 0, ACTION_SEVERITY), AlertLevel::Action);
+        assert_eq!(zone_alert_level(4, 0, 1.0), AlertLevel::Watch);
+        assert_eq!(zone_alert_level(4, 3, 0.0), AlertLevel::Degraded);
+        assert_eq!(zone_alert_level(4, 2, 0.0), AlertLevel::Normal);
+    }
+
+    #[test]
+    fn test_role_weighted_exceedance() {
+        let weights = zones::RoleWeights::default();
+        let level = |above_action, above_flood, role| {
+            zone_alert_level(4, 0, exceedance_severity(above_action, above_flood, weights.weight_for(role)))
+        };
+
+        assert_eq!(level(true, false, "direct"), AlertLevel::Action);
+        assert_eq!(level(true, true, "direct"), AlertLevel::Flood);
+        assert_eq!(level(true, false, "proxy"), AlertLevel::Watch);
+        assert_eq!(level(true, true, "boundary"), AlertLevel::Action);
+        assert_eq!(level(false, false, "direct"), AlertLevel::Normal);
+    }
+
+    #[test]
+    fn test_large_negative_age_is_stale_not_fresh() {
+        assert!(!is_stale_age(Some(10), 120));
+        assert!(!is_stale_age(Some(-2), 120), "small drift is still fresh");
+        assert!(is_stale_age(Some(-180), 120));
+        assert!(is_stale_age(Some(121), 120));
+        assert!(is_stale_age(None, 120));
+    }
+
+    #[test]
+    fn test_stage_exceedance() {
+        let (action, flood) = (Some(14.0), Some(16.0));
+        assert_eq!(stage_exceedance(Some(13.9), action, flood), (false, false));
+        assert_eq!(stage_exceedance(Some(14.0), action, flood), (true, false));
+        assert_eq!(stage_exceedance(Some(17.5), action, flood), (true, true));
+        assert_eq!(stage_exceedance(Some(17.5), None, None), (false, false));
+        // What assess_sensor passes for a daily-mean fallback
+        assert_eq!(stage_exceedance(None, action, flood), (false, false));
+    }
+
+    #[test]
+    fn test_primary_reading_falls_back_to_other_parameter() {
+        let reading = |parameter_code: &str, value: f64| GaugeReading {
+            agency_code: "USGS".to_string(),
+            site_code: "05586100".to_string(),
+            site_name: "Illinois River at Valley City, IL".to_string(),
+            parameter_code: parameter_code.to_string(),
+            unit: String::new(),
+            value,
+            datetime: "2019-06-01T12:00:00Z".to_string(),
+            qualifier: "P".to_string(),
+            source: ReadingSource::UsgsIv,
+        };
+        let both = SiteReadings {
+            site_code: "05586100".to_string(),
+            discharge_cfs: Some(reading(PARAM_DISCHARGE, 92000.0)),
+            stage_ft: Some(reading(PARAM_STAGE, 21.4)),
+        };
+        
+        assert_eq!(primary_reading(&both, PrimaryParameter::Stage).map(|r| r.value), Some(21.4));
+        assert_eq!(primary_reading(&both, PrimaryParameter::Discharge).map(|r| r.value), Some(92000.0));
+        
+        let stage_only = SiteReadings { discharge_cfs: None, ..both };
+        assert_eq!(primary_reading(&stage_only, PrimaryParameter::Discharge).map(|r| r.value), Some(21.4));
+    }
+
+    #[test]
+    fn test_composite_usgs_feed_is_stage_only() {
+        let reading = |parameter_code: &str, value: f64| GaugeReading {
+            agency_code: "USGS".to_string(),
+            site_code: "05587450".to_string(),
+            site_name: "Mississippi River at Grafton, IL".to_string(),
+            parameter_code: parameter_code.to_string(),
+            unit: String::new(),
+            value,
+            datetime: "2019-06-01T12:00:00Z".to_string(),
+            qualifier: "P".to_string(),
+            source: ReadingSource::UsgsIv,
+        };
+        let discharge_only = SiteReadings {
+            site_code: "05587450".to_string(),
+            discharge_cfs: Some(reading(PARAM_DISCHARGE, 410000.0)),
+            stage_ft: None,
+        };
+        
+        // Standalone: discharge stands in for missing stage
+        assert_eq!(usgs_feed_reading(&discharge_only, PrimaryParameter::Stage, false).map(|r| r.value), Some(410000.0));
+        // Composite: no USGS stage means no USGS feed, never cfs against the CWMS stage
+        assert!(usgs_feed_reading(&discharge_only, PrimaryParameter::Stage, true).is_none());
+        
+        let with_stage = SiteReadings { stage_ft: Some(reading(PARAM_STAGE, 29.8)), ..discharge_only };
+        assert_eq!(usgs_feed_reading(&with_stage, PrimaryParameter::Stage, true).map(|r| r.value), Some(29.8));
+    }
+
+    #[test]
+    fn test_composite_prefers_fresh_primary_then_fresh_backup() {
+        assert!(!use_composite_backup(Some(30), Some(5), 120), "fresh primary wins even if backup is newer");
+        assert!(use_composite_backup(Some(300), Some(30), 120));
+        assert!(use_composite_backup(None, Some(30), 120));
+        assert!(!use_composite_backup(Some(300), None, 120));
+    }
+
+    #[test]
+    fn test_composite_with_both_stale_shows_newer() {
+        assert!(use_composite_backup(Some(600), Some(300), 120));
+        assert!(!use_composite_backup(Some(300), Some(600), 120));
+        assert!(!use_composite_backup(Some(300), Some(300), 120), "tie goes to primary");
+        assert!(!use_composite_backup(None, None, 120));
+    }
+
+    #[test]
+    fn test_basin_alert_level_is_worst_active_zone() {
+        assert_eq!(basin_alert_level(&[]), AlertLevel::Normal);
+        assert_eq!(basin_alert_level(&[AlertLevel::Watch, AlertLevel::Flood]), AlertLevel::Flood);
+        assert_eq!(basin_alert_level(&[AlertLevel::Action]), AlertLevel::Action);
+        // Active only through a sensor above action (role weight 0)
+        assert_eq!(basin_alert_level(&[AlertLevel::Normal]), AlertLevel::Watch);
+    }
+
+    #[test]
+    fn test_remaining_lead_time() {
+        assert_eq!(remaining_lead_time(18, 0), 18);
+        assert_eq!(remaining_lead_time(18, 12), 6);
+        assert_eq!(remaining_lead_time(18, 30), 0, "pulse already past its lead time");
+        assert_eq!(remaining_lead_time(0, 5), 0);
+    }
+
+    #[test]
+    fn test_classify_backwater_boundaries() {
+        let level = |grafton, diff| classify_backwater(Some(grafton), Some(diff)).0;
+
+        // CRITICAL needs both strictly past their limits
+        assert_eq!(level(25.1, 0.4), RiskLevel::Critical);
+        assert_eq!(level(25.0, 0.4), RiskLevel::High);
+        assert_eq!(level(25.1, 0.5), RiskLevel::High);
+
+        // HIGH
+        assert_eq!(level(20.1, 0.9), RiskLevel::High);
+        assert_eq!(level(20.0, 0.9), RiskLevel::Moderate);
+        assert_eq!(level(20.1, 1.0), RiskLevel::Moderate);
+
+        // MODERATE on either condition alone
+        assert_eq!(level(18.1, 5.0), RiskLevel::Moderate);
+        assert_eq!(level(10.0, 1.9), RiskLevel::Moderate);
+
+        // LOW at the boundaries themselves
+        assert_eq!(level(18.0, 2.0), RiskLevel::Low);
+        assert_eq!(level(10.0, 8.0), RiskLevel::Low);
+    }
+
+    #[test]
+    fn test_classify_backwater_missing_data() {
+        let (level, reason) = classify_backwater(Some(22.0), None);
+        assert_eq!(level, RiskLevel::Unknown);
+        assert!(reason.contains("differential (data unavailable)"), "{}", reason);
+        assert!(!reason.contains("99.0"));
+
+        let (level, reason) = classify_backwater(None, Some(0.3));
+        assert_eq!(level, RiskLevel::Unknown);
+        assert!(reason.contains("Grafton stage (data unavailable)"), "{}", reason);
+
+        let (_, reason) = classify_backwater(Some(26.04), Some(0.3));
+        assert!(reason.starts_with("Backwater risk is CRITICAL"));
+        assert!(reason.contains("(26.0 ft)") && reason.contains("(0.3 ft)"));
+    }
+}
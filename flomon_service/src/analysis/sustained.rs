@@ -0,0 +1,182 @@
+//! Sustained high water ("nuisance flooding").
+//!
+//! Threshold-crossing alerts fire on the way up and again on the way down,
+//! but a river that sits just above action stage for days does its own
+//! damage: saturated ground, backed-up storm drains, closed low roads. This
+//! module detects a stage that has stayed above a threshold *continuously*
+//! for at least a minimum duration, and is still above it now.
+//!
+//! A run is broken by any reading at or below the threshold, or by a gap in
+//! the record longer than `MAX_READING_GAP_HOURS` (we can't vouch for the
+//! stage while the gauge was silent).
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+use serde::Serialize;
+
+use crate::analysis::interpolate::TimedValue;
+use crate::model::PARAM_STAGE;
+
+/// How far back `sustained_high_water` reads; longer events report a
+/// duration of at least this long.
+pub const SUSTAINED_LOOKBACK_DAYS: i32 = 14;
+
+/// Longest silence inside a run before it is considered broken.
+pub const MAX_READING_GAP_HOURS: i64 = 3;
+
+/// An ongoing stretch of stage above a threshold.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SustainedEvent {
+    pub site_code: String,
+    pub threshold_ft: f64,
+    /// First reading of the current run above the threshold
+    pub started_at: DateTime<Utc>,
+    pub latest_at: DateTime<Utc>,
+    pub duration_hours: f64,
+    pub peak_ft: f64,
+    pub latest_ft: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Run Detection
+// ---------------------------------------------------------------------------
+
+/// The trailing run of `series` (ascending) above `threshold_ft`, if the
+/// latest reading is above it and no later than `MAX_READING_GAP_HOURS`
+/// before `now`.
+pub fn trailing_run_above(series: &[TimedValue], threshold_ft: f64, now: DateTime<Utc>) -> Option<&[TimedValue]> {
+    let max_gap = Duration::hours(MAX_READING_GAP_HOURS);
+    let latest = series.last()?;
+    if latest.value <= threshold_ft || now - latest.timestamp > max_gap {
+        return None;
+    }
+
+    let mut start = series.len() - 1;
+    while start > 0 {
+        let previous = &series[start - 1];
+        if previous.value <= threshold_ft || series[start].timestamp - previous.timestamp > max_gap {
+            break;
+        }
+        start -= 1;
+    }
+
+    Some(&series[start..])
+}
+
+/// `SustainedEvent` for the trailing run above `threshold_ft` if it has
+/// lasted at least `min_hours`.
+pub fn sustained_event(
+    site_code: &str,
+    series: &[TimedValue],
+    threshold_ft: f64,
+    min_hours: i32,
+    now: DateTime<Utc>,
+) -> Option<SustainedEvent> {
+    let run = trailing_run_above(series, threshold_ft, now)?;
+    let (first, last) = (run.first()?, run.last()?);
+
+    let duration = last.timestamp - first.timestamp;
+    if duration < Duration::hours(min_hours as i64) {
+        return None;
+    }
+
+    Some(SustainedEvent {
+        site_code: site_code.to_string(),
+        threshold_ft,
+        started_at: first.timestamp,
+        latest_at: last.timestamp,
+        duration_hours: duration.num_minutes() as f64 / 60.0,
+        peak_ft: run.iter().map(|tv| tv.value).fold(f64::NEG_INFINITY, f64::max),
+        latest_ft: last.value,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Query
+// ---------------------------------------------------------------------------
+
+/// Whether stage at `site_code` has stayed above `threshold_ft` for at
+/// least `min_hours` up to `now`. Reads the last `SUSTAINED_LOOKBACK_DAYS`
+/// of stored stage.
+pub fn sustained_high_water(
+    site_code: &str,
+    threshold_ft: f64,
+    min_hours: i32,
+    now: DateTime<Utc>,
+    client: &mut Client,
+) -> Result<Option<SustainedEvent>, String> {
+    let rows = client.query(
+        "SELECT reading_time, value::float8
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1
+           AND parameter_code = $2
+           AND reading_time > $4 - make_interval(days => $3)
+           AND reading_time <= $4
+         ORDER BY reading_time ASC",
+        &[&site_code, &PARAM_STAGE, &SUSTAINED_LOOKBACK_DAYS, &now]
+    ).map_err(|e| format!("Failed to fetch stage history for {}: {}", site_code, e))?;
+
+    let series: Vec<TimedValue> = rows.iter()
+        .map(|row| TimedValue {
+            timestamp: row.get(0),
+            value: row.get(1),
+        })
+        .collect();
+
+    Ok(sustained_event(site_code, &series, threshold_ft, min_hours, now))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn hourly(values: &[f64]) -> Vec<TimedValue> {
+        let t0 = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        values.iter().enumerate()
+            .map(|(i, &value)| TimedValue {
+                timestamp: t0 + Duration::hours(i as i64),
+                value,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sustained_event_measures_trailing_run() {
+        // Dips to 17.9 at hour 2, then stays above 18.0 for hours 3..=8
+        let series = hourly(&[18.5, 18.2, 17.9, 18.1, 18.4, 18.9, 18.6, 18.3, 18.2]);
+        let now = series.last().unwrap().timestamp;
+
+        let event = sustained_event("05567500", &series, 18.0, 4, now).expect("5h run should qualify");
+        assert_eq!(event.started_at, series[3].timestamp);
+        assert_eq!(event.duration_hours, 5.0);
+        assert_eq!(event.peak_ft, 18.9);
+        assert_eq!(event.latest_ft, 18.2);
+
+        assert_eq!(sustained_event("05567500", &series, 18.0, 6, now), None);
+    }
+
+    #[test]
+    fn test_run_must_be_current_and_unbroken() {
+        let series = hourly(&[18.5, 18.5, 18.5, 18.5]);
+        let last = series.last().unwrap().timestamp;
+
+        // Gauge has gone quiet since
+        assert!(trailing_run_above(&series, 18.0, last + Duration::hours(4)).is_none());
+
+        // A silent stretch inside the run restarts it
+        let mut gapped = series.clone();
+        for tv in gapped.iter_mut().skip(2) {
+            tv.timestamp += Duration::hours(5);
+        }
+        let now = gapped.last().unwrap().timestamp;
+        assert_eq!(trailing_run_above(&gapped, 18.0, now).map(<[_]>::len), Some(2));
+
+        // Latest reading back below the threshold
+        assert!(trailing_run_above(&hourly(&[18.5, 18.5, 17.5]), 18.0, last).is_none());
+    }
+}
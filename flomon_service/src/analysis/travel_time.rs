@@ -0,0 +1,307 @@
+//! Flood-wave travel time from upstream gauges to Peoria.
+//!
+//! `usgs_stations.toml` carries a nominal `travel_time_to_peoria_hours` per
+//! station. This module checks it against the record: it cross-correlates
+//! hourly stage *changes* at an upstream gauge with those at Peoria and
+//! reports the lag with the strongest match. Changes rather than levels are
+//! correlated because stage is so autocorrelated that level correlation
+//! barely varies with lag; a rise shows up as a sharp peak.
+//!
+//! A lag is only reported as calibrated when the match is strong enough and
+//! backed by enough overlapping hours, so a quiet month with no rises to
+//! line up yields `None` rather than a spurious lag.
+//!
+//! `compare_aligned` applies the same idea to any upstream/downstream pair:
+//! it shifts the upstream gauge by the difference of the two nominal travel
+//! times and checks whether its rises and peak are reaching the downstream
+//! gauge on that schedule.
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+
+use crate::model::PARAM_STAGE;
+
+/// USGS site treated as "the property" for lead-time purposes.
+pub const PEORIA_SITE_CODE: &str = "05567500";
+
+/// How much recent history the empirical lag is fitted over, in days.
+pub const LAG_CALIBRATION_DAYS: i32 = 30;
+
+/// Longest lag searched, in hours.
+pub const MAX_LAG_HOURS: usize = 96;
+
+/// Minimum correlation of hourly stage changes to accept a lag.
+pub const MIN_LAG_CORRELATION: f64 = 0.5;
+
+/// Minimum paired hours at the chosen lag to accept it.
+pub const MIN_LAG_OVERLAP_HOURS: usize = 72;
+
+/// Fractional spread around a single travel time when only one estimate
+/// is available to bound the arrival window.
+pub const ARRIVAL_WINDOW_SPREAD: f64 = 0.25;
+
+/// How much recent history a pairwise comparison looks at, in days. Long
+/// enough for `best_lag` to search `MAX_LAG_HOURS` and still have
+/// `MIN_LAG_OVERLAP_HOURS` pairs left.
+pub const COMPARE_WINDOW_DAYS: i32 = 14;
+
+/// Empirically fitted lag between an upstream gauge and Peoria.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CalibratedLag {
+    pub lag_hours: usize,
+    /// Correlation of hourly stage changes at this lag
+    pub correlation: f64,
+}
+
+// ---------------------------------------------------------------------------
+// Lag Estimation
+// ---------------------------------------------------------------------------
+
+/// Hour-over-hour change; `None` where either hour is missing.
+fn hourly_changes(series: &[Option<f64>]) -> Vec<Option<f64>> {
+    series.windows(2)
+        .map(|w| match (w[0], w[1]) {
+            (Some(a), Some(b)) => Some(b - a),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Pearson correlation of `upstream[t]` against `downstream[t + lag]`,
+/// with the number of pairs used. `None` if the pairs have no variance.
+fn lagged_correlation(upstream: &[Option<f64>], downstream: &[Option<f64>], lag: usize) -> Option<(f64, usize)> {
+    let pairs: Vec<(f64, f64)> = upstream.iter()
+        .zip(downstream.iter().skip(lag))
+        .filter_map(|(u, d)| Some(((*u)?, (*d)?)))
+        .collect();
+
+    if pairs.len() < 2 {
+        return None;
+    }
+
+    let n = pairs.len() as f64;
+    let mean_u = pairs.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_d = pairs.iter().map(|p| p.1).sum::<f64>() / n;
+
+    let (mut cov, mut var_u, mut var_d) = (0.0, 0.0, 0.0);
+    for (u, d) in &pairs {
+        cov += (u - mean_u) * (d - mean_d);
+        var_u += (u - mean_u).powi(2);
+        var_d += (d - mean_d).powi(2);
+    }
+
+    if var_u == 0.0 || var_d == 0.0 {
+        return None;
+    }
+
+    Some((cov / (var_u.sqrt() * var_d.sqrt()), pairs.len()))
+}
+
+/// Lag (0..=`max_lag_hours`) at which hourly stage changes upstream best
+/// match those downstream. Both series must be on the same hourly grid.
+///
+/// Returns `None` unless the best match reaches `MIN_LAG_CORRELATION` over
+/// at least `MIN_LAG_OVERLAP_HOURS` pairs.
+pub fn best_lag(
+    upstream: &[Option<f64>],
+    downstream: &[Option<f64>],
+    max_lag_hours: usize,
+) -> Option<CalibratedLag> {
+    let up = hourly_changes(upstream);
+    let down = hourly_changes(downstream);
+
+    (0..=max_lag_hours)
+        .filter_map(|lag| {
+            let (correlation, pairs) = lagged_correlation(&up, &down, lag)?;
+            (pairs >= MIN_LAG_OVERLAP_HOURS).then_some(CalibratedLag { lag_hours: lag, correlation })
+        })
+        .max_by(|a, b| a.correlation.total_cmp(&b.correlation))
+        .filter(|best| best.correlation >= MIN_LAG_CORRELATION)
+}
+
+/// Window in which a stage observed upstream at `observed_at` should reach
+/// Peoria.
+///
+/// Spans the nominal and empirical travel times when both are known;
+/// otherwise ±`ARRIVAL_WINDOW_SPREAD` around the nominal time.
+pub fn arrival_window(
+    observed_at: DateTime<Utc>,
+    nominal_hours: f64,
+    observed_lag_hours: Option<f64>,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let (earliest, latest) = match observed_lag_hours {
+        Some(lag) => (nominal_hours.min(lag), nominal_hours.max(lag)),
+        None => (
+            nominal_hours * (1.0 - ARRIVAL_WINDOW_SPREAD),
+            nominal_hours * (1.0 + ARRIVAL_WINDOW_SPREAD),
+        ),
+    };
+
+    let offset = |hours: f64| observed_at + Duration::minutes((hours * 60.0).round() as i64);
+    (offset(earliest), offset(latest))
+}
+
+/// Two hourly stage series lined up at a nominal offset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlignedComparison {
+    /// Hours the upstream series was shifted forward
+    pub offset_hours: usize,
+    /// Correlation of hourly stage changes at `offset_hours`; `None` if
+    /// either series is flat or too sparse to pair
+    pub correlation: Option<f64>,
+    /// Hours paired for `correlation`
+    pub paired_hours: usize,
+    /// Best-fitting lag in the record, if one is convincing
+    pub observed: Option<CalibratedLag>,
+    /// Highest upstream stage as (hour index, value)
+    pub upstream_peak: Option<(usize, f64)>,
+    /// Highest downstream stage as (hour index, value)
+    pub downstream_peak: Option<(usize, f64)>,
+}
+
+/// Highest value in an hourly series and its index; the latest on ties,
+/// so a crest that plateaus is dated by when it started to fall.
+fn peak(series: &[Option<f64>]) -> Option<(usize, f64)> {
+    series.iter()
+        .enumerate()
+        .filter_map(|(i, v)| Some((i, (*v)?)))
+        .max_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)))
+}
+
+/// Line `upstream` up with `downstream` (same hourly grid) by shifting it
+/// `offset_hours` forward, and report how well their changes match there,
+/// the lag the record itself prefers, and each series' peak.
+pub fn compare_aligned(upstream: &[Option<f64>], downstream: &[Option<f64>], offset_hours: usize) -> AlignedComparison {
+    let at_offset = lagged_correlation(&hourly_changes(upstream), &hourly_changes(downstream), offset_hours);
+
+    AlignedComparison {
+        offset_hours,
+        correlation: at_offset.map(|(correlation, _)| correlation),
+        paired_hours: at_offset.map_or(0, |(_, pairs)| pairs),
+        observed: best_lag(upstream, downstream, MAX_LAG_HOURS),
+        upstream_peak: peak(upstream),
+        downstream_peak: peak(downstream),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+/// Hourly mean stage at `site_code` over the `days` before `now`, oldest
+/// first, on a fixed hourly grid (`None` for hours without readings).
+pub fn hourly_stage(
+    client: &mut Client,
+    site_code: &str,
+    days: i32,
+    now: DateTime<Utc>,
+) -> Result<Vec<Option<f64>>, String> {
+    let rows = client.query(
+        "WITH grid AS (
+             SELECT generate_series(
+                 date_trunc('hour', $4 - make_interval(days => $3)),
+                 date_trunc('hour', $4),
+                 INTERVAL '1 hour'
+             ) AS hour
+         ),
+         hourly AS (
+             SELECT date_trunc('hour', reading_time) AS hour, AVG(value)::float8 AS stage
+             FROM usgs_raw.gauge_readings
+             WHERE site_code = $1
+               AND parameter_code = $2
+               AND reading_time >= date_trunc('hour', $4 - make_interval(days => $3))
+               AND reading_time <= $4
+             GROUP BY 1
+         )
+         SELECT hourly.stage
+         FROM grid LEFT JOIN hourly USING (hour)
+         ORDER BY grid.hour ASC",
+        &[&site_code, &PARAM_STAGE, &days, &now]
+    ).map_err(|e| format!("Failed to fetch hourly stage for {}: {}", site_code, e))?;
+
+    Ok(rows.iter().map(|row| row.get(0)).collect())
+}
+
+/// Empirical lag from `site_code` to Peoria over the `LAG_CALIBRATION_DAYS`
+/// before `now`, given Peoria's hourly stage on the same grid.
+pub fn calibrate_lag(
+    client: &mut Client,
+    site_code: &str,
+    peoria_hourly: &[Option<f64>],
+    now: DateTime<Utc>,
+) -> Result<Option<CalibratedLag>, String> {
+    let upstream = hourly_stage(client, site_code, LAG_CALIBRATION_DAYS, now)?;
+    Ok(best_lag(&upstream, peoria_hourly, MAX_LAG_HOURS))
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// Irregular rises and falls so only the true lag lines up
+    fn wave(len: usize) -> Vec<Option<f64>> {
+        (0..len)
+            .map(|i| Some(10.0 + (i as f64 * 0.3).sin() * 2.0 + (i as f64 * 0.07).cos()))
+            .collect()
+    }
+
+    #[test]
+    fn test_best_lag_recovers_shift() {
+        let upstream = wave(400);
+        let mut downstream = vec![Some(8.0); 18];
+        downstream.extend(upstream.iter().take(400 - 18).map(|v| v.map(|s| s - 2.0)));
+
+        let lag = best_lag(&upstream, &downstream, 48).unwrap();
+        assert_eq!(lag.lag_hours, 18);
+        assert!(lag.correlation > 0.99);
+    }
+
+    #[test]
+    fn test_best_lag_rejects_flat_or_sparse_record() {
+        let flat = vec![Some(10.0); 400];
+        assert_eq!(best_lag(&flat, &flat, 48), None);
+
+        let sparse = wave(40);
+        assert_eq!(best_lag(&sparse, &sparse, 12), None, "too few overlapping hours");
+    }
+
+    #[test]
+    fn test_compare_aligned_lines_up_peaks() {
+        // Upstream crest at hour 100, reaching downstream 12 hours later
+        let upstream: Vec<Option<f64>> = wave(300).into_iter().enumerate()
+            .map(|(i, v)| v.map(|s| if i == 100 { s + 8.0 } else { s }))
+            .collect();
+        let mut downstream = vec![Some(8.0); 12];
+        downstream.extend(upstream.iter().take(300 - 12).map(|v| v.map(|s| s - 2.0)));
+
+        let aligned = compare_aligned(&upstream, &downstream, 12);
+        assert_eq!(aligned.offset_hours, 12);
+        assert!(aligned.correlation.unwrap() > 0.99);
+        assert_eq!(aligned.observed.map(|l| l.lag_hours), Some(12));
+        assert_eq!(aligned.upstream_peak.unwrap().0, 100);
+        assert_eq!(aligned.downstream_peak.unwrap().0, 112);
+
+        // Off schedule, the match at the nominal offset is much weaker
+        let late = compare_aligned(&upstream, &downstream, 2);
+        assert!(late.correlation.unwrap() < aligned.correlation.unwrap());
+        assert_eq!(late.observed.map(|l| l.lag_hours), Some(12));
+    }
+
+    #[test]
+    fn test_arrival_window() {
+        let t = Utc.with_ymd_and_hms(2019, 5, 1, 12, 0, 0).unwrap();
+
+        let (earliest, latest) = arrival_window(t, 18.0, Some(22.0));
+        assert_eq!(earliest, t + Duration::hours(18));
+        assert_eq!(latest, t + Duration::hours(22));
+
+        let (earliest, latest) = arrival_window(t, 36.0, None);
+        assert_eq!(earliest, t + Duration::hours(27));
+        assert_eq!(latest, t + Duration::hours(45));
+    }
+}
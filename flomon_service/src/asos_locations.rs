@@ -92,7 +92,7 @@ pub struct AsosLocation {
 
 /// Load ASOS stations from TOML file
 pub fn load_locations<P: AsRef<Path>>(path: P) -> Result<Vec<AsosLocation>, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(path)?;
+    let content = fs::read_to_string(&path)?;
     let config: AsosConfig = toml::from_str(&content)?;
     
     let locations: Vec<AsosLocation> = config.stations.into_iter()
@@ -146,6 +146,18 @@ pub struct PrecipThresholds {
 }
 
 impl AsosLocation {
+    /// Station ID as stored in the database and returned by the IEM API.
+    ///
+    /// IEM uses 3-letter codes ("PIA"), so the leading "K" of a 4-letter
+    /// ICAO identifier ("KPIA") is stripped.
+    pub fn db_station_id(&self) -> &str {
+        if self.station_id.starts_with('K') && self.station_id.len() == 4 {
+            &self.station_id[1..]
+        } else {
+            &self.station_id
+        }
+    }
+    
     /// Get precipitation thresholds for this basin
     pub fn precip_thresholds(&self) -> PrecipThresholds {
         match self.basin.as_str() {
@@ -256,5 +268,6 @@ mod tests {
         };
         
         assert_eq!(mackinaw.tributary_lag_hours(), 12);
+        assert_eq!(mackinaw.db_station_id(), "BMI");
     }
 }
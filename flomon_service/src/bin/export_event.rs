@@ -0,0 +1,108 @@
+//! Export one historical flood event's observation window as a tidy CSV.
+//!
+//! Usage:
+//!   cargo run --release --bin export_event -- --event-id 12             # CSV to stdout
+//!   cargo run --release --bin export_event -- --event-id 12 --output event_12.csv
+//!
+//! Columns: event_id, source, site_id, parameter, timestamp, value, unit,
+//! phase, hours_before_peak. See `flomon_service::event_export`.
+//!
+//! Environment:
+//!   DATABASE_URL - PostgreSQL connection string
+//!   DATABASE_URL_FILE - File containing the connection string (overrides DATABASE_URL)
+
+use flomon_service::db;
+use flomon_service::event_export;
+use std::env;
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+
+fn usage(program: &str) -> ! {
+    eprintln!("Usage: {} --event-id N [--output PATH]", program);
+    std::process::exit(1);
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let mut event_id: Option<i32> = None;
+    let mut output: Option<String> = None;
+
+    let mut i = 1;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--event-id" => {
+                event_id = args.get(i + 1).and_then(|v| v.parse().ok());
+                if event_id.is_none() {
+                    eprintln!("Error: --event-id requires a numeric event id");
+                    usage(&args[0]);
+                }
+                i += 2;
+            }
+            "--output" => {
+                output = args.get(i + 1).cloned();
+                if output.is_none() {
+                    eprintln!("Error: --output requires a path");
+                    usage(&args[0]);
+                }
+                i += 2;
+            }
+            other => {
+                eprintln!("Unknown argument: {}", other);
+                usage(&args[0]);
+            }
+        }
+    }
+
+    let Some(event_id) = event_id else {
+        usage(&args[0]);
+    };
+
+    let mut client = match db::connect_simple() {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("❌ Database connection failed: {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let event = match event_export::fetch_event(&mut client, event_id) {
+        Ok(Some(event)) => event,
+        Ok(None) => {
+            eprintln!("❌ No event {} in flood_analysis.events", event_id);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let rows = match event_export::event_rows(&mut client, &event) {
+        Ok(rows) => rows,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            std::process::exit(1);
+        }
+    };
+
+    let written = match &output {
+        Some(path) => File::create(path).and_then(|file| {
+            let mut out = BufWriter::new(file);
+            event_export::write_csv(&mut out, &event, &rows)?;
+            out.flush()
+        }),
+        None => {
+            let mut out = io::stdout().lock();
+            event_export::write_csv(&mut out, &event, &rows)
+        }
+    };
+
+    if let Err(e) = written {
+        eprintln!("❌ Failed to write CSV: {}", e);
+        std::process::exit(1);
+    }
+
+    if let Some(path) = output {
+        eprintln!("✓ Wrote {} rows for event {} ({}) to {}", rows.len(), event.id, event.site_code, path);
+    }
+}
@@ -0,0 +1,95 @@
+//! Pluggable source of "now".
+//!
+//! Staleness, analysis windows, and backfill ranges all depend on the
+//! current time. Code that holds a `SharedClock` instead of calling
+//! `Utc::now()` can be driven by a `MockClock` in tests, so an hour of
+//! staleness is one `advance` call rather than a sleep or a hand-built
+//! offset from the real time.
+//!
+//! Everything defaults to `SystemClock`.
+
+use chrono::{DateTime, Duration, Utc};
+use std::sync::{Arc, Mutex};
+
+/// Source of the current time.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// A clock shared between the daemon, caches, and worker threads.
+pub type SharedClock = Arc<dyn Clock>;
+
+/// The real wall clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// Shared handle to the real wall clock.
+pub fn system_clock() -> SharedClock {
+    Arc::new(SystemClock)
+}
+
+/// A clock that only moves when told to.
+#[derive(Debug)]
+pub struct MockClock {
+    now: Mutex<DateTime<Utc>>,
+}
+
+impl MockClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self { now: Mutex::new(now) }
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner()) = now;
+    }
+
+    pub fn advance(&self, by: Duration) {
+        let mut now = self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        *now += by;
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.now.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_mock_clock_moves_only_when_told() {
+        let t0 = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        let clock = MockClock::new(t0);
+        assert_eq!(clock.now(), t0);
+
+        clock.advance(Duration::minutes(90));
+        assert_eq!(clock.now(), t0 + Duration::minutes(90));
+
+        clock.set(t0);
+        assert_eq!(clock.now(), t0);
+    }
+
+    #[test]
+    fn test_shared_mock_clock_is_seen_by_all_holders() {
+        let t0 = Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap();
+        let mock = Arc::new(MockClock::new(t0));
+        let shared: SharedClock = mock.clone();
+
+        mock.advance(Duration::hours(2));
+        assert_eq!(shared.now(), t0 + Duration::hours(2));
+    }
+}
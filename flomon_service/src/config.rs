@@ -5,10 +5,11 @@
 /// recompiling the service.
 
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::fs;
 
 use crate::model::FloodThresholds;
+use crate::zones::{self, ZonesConfig};
 
 /// Station metadata loaded from usgs_stations.toml configuration file
 #[derive(Debug, Clone, Deserialize)]
@@ -32,6 +33,15 @@ pub struct StationConfig {
     // Expected USGS parameters at this site
     pub expected_parameters: Vec<String>,  // e.g., ["00060", "00065"]
     
+    // Elevation of the gage's zero (USGS "Datum of gage"), ft above NGVD29
+    pub gage_datum_ft_ngvd29: Option<f64>,
+    
+    // NWS AHPS location ID (e.g. "KINI2"), for stations with official forecasts
+    pub nws_id: Option<String>,
+    
+    // SHEF location ID for relaying readings to NWS; defaults to nws_id
+    pub shef_id: Option<String>,
+    
     // Peak flow data metadata (optional)
     pub peak_flow: Option<PeakFlowMetadata>,
 }
@@ -60,7 +70,7 @@ pub struct PeakFlowMetadata {
 
 /// Root configuration structure for TOML parsing
 #[derive(Debug, Deserialize)]
-struct StationRegistry {
+pub(crate) struct StationRegistry {
     station: Vec<StationConfig>,
 }
 
@@ -75,15 +85,21 @@ struct StationRegistry {
 /// Expects `usgs_stations.toml` in the current working directory (project root
 /// when running via `cargo run`).
 pub fn load_config() -> Vec<StationConfig> {
+    try_load_config().unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// `load_config` without the panic, for reloading a running service
+/// where a bad edit shouldn't take it down.
+pub fn try_load_config() -> Result<Vec<StationConfig>, String> {
     let config_path = "usgs_stations.toml";
     
     let contents = fs::read_to_string(config_path)
-        .unwrap_or_else(|e| panic!("Failed to read {}: {}", config_path, e));
+        .map_err(|e| format!("Failed to read {}: {}", config_path, e))?;
     
     let registry: StationRegistry = toml::from_str(&contents)
-        .unwrap_or_else(|e| panic!("Failed to parse {}: {}", config_path, e));
+        .map_err(|e| format!("Failed to parse {}: {}", config_path, e))?;
     
-    registry.station
+    Ok(registry.station)
 }
 
 /// Loads station registry and builds a lookup map keyed by site code.
@@ -111,6 +127,121 @@ impl From<&ThresholdConfig> for FloodThresholds {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Cross-reference validation
+// ---------------------------------------------------------------------------
+
+/// Check that every zones.toml sensor's `usgs_id`, `cwms_location`, and
+/// `station_id` is configured in usgs_stations.toml, usace_stations.toml,
+/// and iem_asos.toml respectively, and that every threshold set is in
+/// ascending order. A dangling reference otherwise only shows up as a
+/// sensor that never has readings.
+///
+/// Every problem found is returned, not just the first; a file that can't
+/// be loaded is itself a problem.
+pub fn validate_cross_references() -> Result<(), Vec<String>> {
+    let mut problems = Vec::new();
+    
+    let zones = zones::load_zones_default()
+        .map_err(|e| problems.push(format!("zones.toml: {}", e)))
+        .ok();
+    let stations = try_load_config()
+        .map_err(|e| problems.push(e))
+        .unwrap_or_default();
+    let cwms_locations: HashSet<String> = crate::usace_locations::load_locations()
+        .map_err(|e| problems.push(format!("usace_stations.toml: {}", e)))
+        .unwrap_or_default()
+        .into_iter()
+        .map(|l| l.cwms_location)
+        .collect();
+    // Sensors may name an ASOS station by ICAO ("KPIA") or IEM ("PIA") ID
+    let asos_stations: HashSet<String> = crate::asos_locations::load_locations("iem_asos.toml")
+        .map_err(|e| problems.push(format!("iem_asos.toml: {}", e)))
+        .unwrap_or_default()
+        .iter()
+        .flat_map(|l| [l.station_id.clone(), l.db_station_id().to_string()])
+        .collect();
+    
+    if let Some(zones) = &zones {
+        problems.extend(cross_reference_problems(zones, &stations, &cwms_locations, &asos_stations));
+    } else {
+        problems.extend(threshold_order_problems(&stations));
+    }
+    
+    if problems.is_empty() { Ok(()) } else { Err(problems) }
+}
+
+/// `validate_cross_references` over already-loaded configuration.
+pub fn cross_reference_problems(
+    zones: &ZonesConfig,
+    stations: &[StationConfig],
+    cwms_locations: &HashSet<String>,
+    asos_stations: &HashSet<String>,
+) -> Vec<String> {
+    let site_codes: HashSet<&str> = stations.iter().map(|s| s.site_code.as_str()).collect();
+    let mut problems = threshold_order_problems(stations);
+    
+    for (zone_id, zone) in zones::get_all_zones(zones) {
+        for sensor in &zone.sensors {
+            let label = sensor.sensor_id.as_deref()
+                .or(sensor.usgs_id.as_deref())
+                .or(sensor.station_id.as_deref())
+                .or(sensor.cwms_location.as_deref())
+                .unwrap_or(&sensor.location);
+            let mut problem = |message: String| problems.push(format!("zones.toml zone {} sensor {}: {}", zone_id, label, message));
+            
+            if let Some(id) = &sensor.usgs_id
+                && !site_codes.contains(id.as_str()) {
+                problem(format!("usgs_id '{}' is not in usgs_stations.toml", id));
+            }
+            if let Some(id) = &sensor.cwms_location
+                && !cwms_locations.contains(id) {
+                problem(format!("cwms_location '{}' is not in usace_stations.toml", id));
+            }
+            if let Some(id) = &sensor.station_id
+                && !asos_stations.contains(id) {
+                problem(format!("station_id '{}' is not in iem_asos.toml", id));
+            }
+            let levels = [
+                ("action_stage_ft", sensor.action_stage_ft),
+                ("flood_stage_ft", sensor.flood_stage_ft),
+                ("moderate_flood_ft", sensor.moderate_flood_ft),
+                ("major_flood_ft", sensor.major_flood_ft),
+            ];
+            if let Some(message) = out_of_order(&levels) {
+                problem(message);
+            }
+        }
+    }
+    
+    problems
+}
+
+/// Threshold ordering problems within usgs_stations.toml itself.
+fn threshold_order_problems(stations: &[StationConfig]) -> Vec<String> {
+    stations.iter()
+        .filter_map(|station| {
+            let t = station.thresholds.as_ref()?;
+            let levels = [
+                ("action_stage_ft", Some(t.action_stage_ft)),
+                ("flood_stage_ft", Some(t.flood_stage_ft)),
+                ("moderate_flood_stage_ft", Some(t.moderate_flood_stage_ft)),
+                ("major_flood_stage_ft", Some(t.major_flood_stage_ft)),
+            ];
+            out_of_order(&levels).map(|message| format!("usgs_stations.toml {}: {}", station.site_code, message))
+        })
+        .collect()
+}
+
+/// The first pair of set levels (lowest first) where a higher level is
+/// below a lower one; unset levels are skipped.
+fn out_of_order(levels: &[(&str, Option<f64>)]) -> Option<String> {
+    let set: Vec<(&str, f64)> = levels.iter().filter_map(|&(name, value)| Some((name, value?))).collect();
+    set.windows(2)
+        .find(|pair| pair[1].1 < pair[0].1)
+        .map(|pair| format!("{} ({}) is below {} ({})", pair[1].0, pair[1].1, pair[0].0, pair[0].1))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -214,4 +345,35 @@ mod tests {
         assert_eq!(thresholds.action_stage_ft, 14.0);
         assert_eq!(thresholds.flood_stage_ft, 16.0);
     }
+
+    #[test]
+    fn test_dangling_sensor_references_are_all_reported() {
+        let mut zones = zones::load_zones_default().expect("zones.toml should parse");
+        let mut stations = load_config();
+        let cwms: HashSet<String> = ["IL07".to_string()].into();
+        let asos: HashSet<String> = ["KPIA".to_string(), "PIA".to_string()].into();
+
+        let mut dangling = zones.zones.zone_2.sensors[0].clone();
+        dangling.sensor_id = Some("DANGLING".to_string());
+        dangling.usgs_id = Some("99999999".to_string());
+        dangling.cwms_location = Some("IL99".to_string());
+        dangling.station_id = Some("KXYZ".to_string());
+        dangling.action_stage_ft = Some(18.0);
+        dangling.flood_stage_ft = Some(16.0);
+        zones.zones.zone_2.sensors = vec![dangling];
+        for zone in [&mut zones.zones.zone_0, &mut zones.zones.zone_1, &mut zones.zones.zone_3,
+                     &mut zones.zones.zone_4, &mut zones.zones.zone_5, &mut zones.zones.zone_6] {
+            zone.sensors.clear();
+        }
+        let kingston = stations.iter_mut().find(|s| s.site_code == "05568500").unwrap();
+        kingston.thresholds.as_mut().unwrap().major_flood_stage_ft = 19.0;
+
+        let problems = cross_reference_problems(&zones, &stations, &cwms, &asos);
+        assert_eq!(problems.len(), 5, "{:#?}", problems);
+        assert!(problems.contains(&"usgs_stations.toml 05568500: major_flood_stage_ft (19) is below moderate_flood_stage_ft (20)".to_string()));
+        assert!(problems.contains(&"zones.toml zone 2 sensor DANGLING: usgs_id '99999999' is not in usgs_stations.toml".to_string()));
+        assert!(problems.iter().any(|p| p.ends_with("cwms_location 'IL99' is not in usace_stations.toml")));
+        assert!(problems.iter().any(|p| p.ends_with("station_id 'KXYZ' is not in iem_asos.toml")));
+        assert!(problems.iter().any(|p| p.ends_with("flood_stage_ft (16) is below action_stage_ft (18)")));
+    }
 }
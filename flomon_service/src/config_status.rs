@@ -0,0 +1,157 @@
+//! Loaded vs. on-disk state of the TOML configuration files.
+//!
+//! The station registries and alerting.toml are read once at startup, so a
+//! running service keeps using whatever was on disk then. The startup loads
+//! and the explicit reloads (`reload_zones`, `reload_stations`) call
+//! `record_loaded`, which remembers the file's modification time at that
+//! moment; `check_all` compares it with the file as it is now and re-parses
+//! it. That lets `/health/config` answer "someone edited usgs_stations.toml
+//! but didn't restart" (drift) and "the file on disk no longer parses".
+//!
+//! The loaders themselves don't record: several are also called per request
+//! (groups.toml, iem_asos.toml for precipitation totals), and each of those
+//! reads would reset the baseline and hide the drift. groups.toml is only
+//! ever read per request, so it never drifts and only its parse is checked.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::SystemTime;
+
+/// Modification time of each config file when it was last loaded, keyed by
+/// the path it was loaded from.
+static LOADED: Mutex<BTreeMap<String, Option<SystemTime>>> = Mutex::new(BTreeMap::new());
+
+/// A config file and a check that its contents still deserialize.
+struct ConfigFile {
+    path: &'static str,
+    parse: fn(&str) -> Result<(), String>,
+}
+
+fn parses_as<T: serde::de::DeserializeOwned>(contents: &str) -> Result<(), String> {
+    toml::from_str::<T>(contents).map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Every config file the service reads, relative to the working directory.
+const CONFIG_FILES: &[ConfigFile] = &[
+    ConfigFile { path: "usgs_stations.toml", parse: parses_as::<crate::config::StationRegistry> },
+    ConfigFile { path: "usace_stations.toml", parse: parses_as::<crate::usace_locations::UsaceConfig> },
+    ConfigFile { path: "iem_asos.toml", parse: parses_as::<crate::asos_locations::AsosConfig> },
+    ConfigFile { path: "zones.toml", parse: parses_as::<crate::zones::ZonesConfig> },
+    ConfigFile { path: "groups.toml", parse: parses_as::<crate::groups::GroupsConfig> },
+    ConfigFile { path: "alerting.toml", parse: parses_as::<crate::alert::config::AlertingConfig> },
+];
+
+/// How one config file compares with what the service has loaded.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConfigFileStatus {
+    pub file: String,
+    pub exists: bool,
+    /// `None` when the file is missing
+    pub parses: Option<bool>,
+    pub parse_error: Option<String>,
+    /// Whether a startup load or reload of the file has been recorded
+    pub loaded: bool,
+    pub loaded_modified: Option<DateTime<Utc>>,
+    pub disk_modified: Option<DateTime<Utc>>,
+    /// Loaded, and the file on disk has changed (or gone) since
+    pub drifted: bool,
+}
+
+fn modified(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
+/// Remember `path`'s current modification time as the loaded version.
+/// Called after a successful startup load or explicit reload, never from a
+/// loader that may also run per request.
+pub fn record_loaded<P: AsRef<Path>>(path: P) {
+    let path = path.as_ref();
+    LOADED.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .insert(path.display().to_string(), modified(path));
+}
+
+/// Status of the file at `path`, judged by `parse`.
+fn check(path: &str, parse: fn(&str) -> Result<(), String>) -> ConfigFileStatus {
+    let loaded = LOADED.lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(path)
+        .copied();
+    let disk_modified = modified(Path::new(path));
+
+    let (exists, parse_result) = match fs::read_to_string(path) {
+        Ok(contents) => (true, Some(parse(&contents))),
+        Err(_) => (false, None),
+    };
+
+    ConfigFileStatus {
+        file: path.to_string(),
+        exists,
+        parses: parse_result.as_ref().map(Result::is_ok),
+        parse_error: parse_result.and_then(Result::err),
+        loaded: loaded.is_some(),
+        loaded_modified: loaded.flatten().map(DateTime::<Utc>::from),
+        disk_modified: disk_modified.map(DateTime::<Utc>::from),
+        drifted: loaded.is_some_and(|at_load| at_load != disk_modified),
+    }
+}
+
+/// Status of every config file the service reads.
+pub fn check_all() -> Vec<ConfigFileStatus> {
+    CONFIG_FILES.iter().map(|f| check(f.path, f.parse)).collect()
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_config(name: &str, contents: &str) -> String {
+        let path = std::env::temp_dir().join(format!("flomon_config_status_{}_{}", std::process::id(), name));
+        fs::write(&path, contents).unwrap();
+        path.display().to_string()
+    }
+
+    #[test]
+    fn test_unloaded_file_is_not_drifted() {
+        let path = temp_config("unloaded.toml", "[display]\n");
+        let status = check(&path, parses_as::<toml::Table>);
+        assert!(status.exists);
+        assert_eq!(status.parses, Some(true));
+        assert!(!status.loaded && !status.drifted);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_edit_after_load_is_drift_and_bad_toml_is_reported() {
+        let path = temp_config("edited.toml", "key = 1\n");
+        record_loaded(&path);
+        assert!(!check(&path, parses_as::<toml::Table>).drifted);
+
+        // Force a different mtime regardless of filesystem timestamp resolution
+        fs::write(&path, "key = [unterminated\n").unwrap();
+        let earlier = SystemTime::now() - std::time::Duration::from_secs(3600);
+        fs::File::options().write(true).open(&path).unwrap().set_modified(earlier).unwrap();
+
+        let status = check(&path, parses_as::<toml::Table>);
+        assert!(status.drifted);
+        assert_eq!(status.parses, Some(false));
+        assert!(status.parse_error.is_some());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bundled_config_files_parse() {
+        // Run from the crate root, where the bundled TOML files live
+        for status in check_all().iter().filter(|s| s.exists) {
+            assert_eq!(status.parses, Some(true), "{}: {:?}", status.file, status.parse_error);
+        }
+    }
+}
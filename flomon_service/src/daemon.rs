@@ -9,26 +9,45 @@
 /// 6. Generates alerts for threshold exceedances and staleness
 
 use crate::alert::notify::Notifier;
+use crate::alert::thresholds::check_approaching_flood_stage;
+use crate::analysis::{backwater, gaps, status, sustained};
+use crate::analysis::interpolate::TimedValue;
+use crate::clock::{self, SharedClock};
+use crate::config;
+use crate::config_status;
 use crate::db;
 use crate::logging;
-use crate::model::{GaugeReading, PARAM_STAGE};
+use crate::metrics::{self, SharedMetrics};
+use crate::monitor::{self, StationStatus};
+use crate::monitor::flood_events::{self, FloodEventGate, FloodEventTracker, FloodEventUpdate};
+use crate::model::{central_to_utc, FloodThresholds, GaugeReading, PARAM_STAGE, QUALIFIER_APPROVED, QUALIFIER_PROVISIONAL};
 use crate::stations::{self, Station};
 use crate::usace_locations::{self, UsaceLocation};
 use crate::asos_locations::{self, AsosLocation};
-use crate::ingest::{usgs, cwms, iem};
-use chrono::{DateTime, Duration, Timelike, Utc};
-use postgres::Client;
-use std::collections::HashMap;
+use crate::ingest::{usgs, cwms, http, iem, nws, nws_alerts, peak_flow};
+use crate::webhook::{self, PollSummary, PollWebhook};
+use crate::write_buffer::{self, WriteBuffer};
+use crate::zones;
+use chrono::{DateTime, Duration, NaiveDate, Timelike, Utc};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
 use std::sync::mpsc;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Re-exported so callers of `verify_backfill` needn't reach into analysis.
+pub use crate::analysis::gaps::USGS_IV_INTERVAL_MINUTES;
 
 // ---------------------------------------------------------------------------
 // Configuration
 // ---------------------------------------------------------------------------
 
 /// Daemon configuration
+#[derive(Clone)]
 pub struct DaemonConfig {
-    /// How often to poll USGS API (default: 15 minutes to match USGS update frequency)
+    /// How often to poll USGS API (default: 15 minutes to match USGS update frequency).
+    /// USGS stations near or above action stage poll faster and quiet
+    /// ones slower; see `PollPriority`.
     pub poll_interval_minutes: u64,
     
     /// Maximum age of data before considered stale (default: 60 minutes)
@@ -36,6 +55,110 @@ pub struct DaemonConfig {
     
     /// How many days of historical data to backfill (default: 120 days)
     pub backfill_days: u64,
+    
+    /// Longest range a single `backfill_station` call will fetch (default:
+    /// 365 days). Anything older is queued in `backfill_queue` in pages of
+    /// at most this many days and drained one page per poll cycle, so an
+    /// empty database or a large `backfill_days` can't trigger one huge
+    /// request burst.
+    pub max_backfill_days: u64,
+    
+    /// Maximum USGS stations backfilled at once during startup (default: 3).
+    /// Each concurrent backfill holds its own database connection and one
+    /// in-flight USGS request, so this also caps load on NWIS.
+    pub backfill_concurrency: usize,
+    
+    /// HTTP timeout for USGS NWIS requests (default: 45 seconds)
+    pub usgs_timeout_secs: u64,
+    
+    /// HTTP timeout for USACE CWMS requests, including catalog discovery
+    /// (default: 15 seconds)
+    pub cwms_timeout_secs: u64,
+    
+    /// HTTP timeout for IEM/ASOS requests (default: 15 seconds)
+    pub iem_timeout_secs: u64,
+    
+    /// HTTP timeout for NWS api.weather.gov requests (default: 15 seconds)
+    pub nws_timeout_secs: u64,
+    
+    /// HTTP timeout for USGS backfill requests, which pull far larger
+    /// payloads than routine polls (default: 30 seconds)
+    pub usgs_backfill_timeout_secs: u64,
+    
+    /// HTTP timeout for CWMS backfill requests (default: 30 seconds)
+    pub cwms_backfill_timeout_secs: u64,
+    
+    /// HTTP timeout for IEM/ASOS backfill requests (default: 30 seconds)
+    pub iem_backfill_timeout_secs: u64,
+    
+    /// Attempts per USGS, CWMS, and IEM request; 5xx responses and network
+    /// errors are retried with exponential backoff (default: 3)
+    pub http_max_attempts: u32,
+    
+    /// Worker threads fetching USGS, CWMS, and ASOS data in parallel during
+    /// a poll cycle; database writes stay sequential (default: 4)
+    pub max_concurrent_fetches: usize,
+    
+    /// Identical consecutive stage readings tolerated before a sensor is
+    /// marked degraded as frozen (default: 12, i.e. 3 hours of IV data)
+    pub flatline_min_repeats: usize,
+    
+    /// Share of values in a DV backfill response allowed to be unparseable
+    /// before the response is rejected as malformed (default: 0.01)
+    pub dv_max_parse_failure_fraction: f64,
+    
+    /// Minutes a live flood-stage crossing must last before it is recorded
+    /// in `nws.flood_events` (default: 60)
+    pub flood_event_min_duration_minutes: i64,
+    
+    /// Feet above flood stage a live crossing must crest before it is
+    /// recorded (default: 0.0, i.e. duration alone decides)
+    pub flood_event_min_peak_above_flood_ft: f64,
+    
+    /// JSON-lines file where USGS readings that fail to warehouse are kept
+    /// for replay on the next cycle (default: None, i.e. disabled)
+    pub write_buffer_path: Option<std::path::PathBuf>,
+    
+    /// Most readings the write buffer holds; the oldest are dropped past
+    /// this (default: 50,000)
+    pub write_buffer_max_readings: usize,
+    
+    /// URL each poll cycle's summary is POSTed to (default: None, i.e.
+    /// disabled)
+    pub poll_webhook_url: Option<String>,
+    
+    /// Cycle summaries collected before each webhook POST (default: 1)
+    pub poll_webhook_batch_size: usize,
+    
+    /// Retries after a failed webhook POST before the batch is
+    /// dead-lettered (default: 3)
+    pub poll_webhook_max_retries: u32,
+    
+    /// JSON-lines file undeliverable webhook batches are appended to
+    /// (default: poll_webhook_dead_letter.jsonl)
+    pub poll_webhook_dead_letter_path: std::path::PathBuf,
+    
+    /// Overwrite a stored provisional reading when USGS re-sends it as
+    /// approved, logging each change to `usgs_raw.reading_revisions`
+    /// (default: false, i.e. the first value stored is kept)
+    pub apply_approved_revisions: bool,
+    
+    /// Postgres connections shared by the poll loop, backfill tasks, and
+    /// the HTTP endpoint (default: 4)
+    pub db_pool_size: usize,
+    
+    /// File rewritten after every successful poll cycle so a watchdog can
+    /// spot a hung daemon by its mtime (default: None, i.e. disabled)
+    pub heartbeat_path: Option<std::path::PathBuf>,
+    
+    /// Fetch and parse as usual but log what would be written instead of
+    /// writing it — readings, monitoring state, station health, flood
+    /// events, and queued backfill (default: false)
+    pub dry_run: bool,
+    
+    /// Fail `initialize` on any `config::validate_cross_references`
+    /// problem instead of printing it as a warning (default: false)
+    pub strict_config: bool,
 }
 
 impl Default for DaemonConfig {
@@ -44,10 +167,427 @@ impl Default for DaemonConfig {
             poll_interval_minutes: 15,
             staleness_threshold_minutes: 60,
             backfill_days: 120,
+            max_backfill_days: 365,
+            backfill_concurrency: 3,
+            usgs_timeout_secs: 45,
+            cwms_timeout_secs: 15,
+            iem_timeout_secs: 15,
+            nws_timeout_secs: 15,
+            usgs_backfill_timeout_secs: 30,
+            cwms_backfill_timeout_secs: 30,
+            iem_backfill_timeout_secs: 30,
+            http_max_attempts: http::DEFAULT_HTTP_MAX_ATTEMPTS,
+            max_concurrent_fetches: DEFAULT_MAX_CONCURRENT_FETCHES,
+            flatline_min_repeats: monitor::DEFAULT_FLATLINE_MIN_REPEATS,
+            dv_max_parse_failure_fraction: usgs::DEFAULT_MAX_DV_PARSE_FAILURE_FRACTION,
+            flood_event_min_duration_minutes: flood_events::DEFAULT_MIN_EVENT_DURATION_MINUTES,
+            flood_event_min_peak_above_flood_ft: flood_events::DEFAULT_MIN_EVENT_PEAK_ABOVE_FLOOD_FT,
+            write_buffer_path: None,
+            write_buffer_max_readings: write_buffer::DEFAULT_WRITE_BUFFER_MAX_READINGS,
+            poll_webhook_url: None,
+            poll_webhook_batch_size: webhook::DEFAULT_POLL_WEBHOOK_BATCH_SIZE,
+            poll_webhook_max_retries: webhook::DEFAULT_POLL_WEBHOOK_MAX_RETRIES,
+            poll_webhook_dead_letter_path: webhook::DEFAULT_POLL_WEBHOOK_DEAD_LETTER_PATH.into(),
+            apply_approved_revisions: false,
+            db_pool_size: db::DEFAULT_DB_POOL_SIZE,
+            heartbeat_path: None,
+            dry_run: false,
+            strict_config: false,
+        }
+    }
+}
+
+impl DaemonConfig {
+    /// Default configuration with per-source timeouts and backfill
+    /// concurrency overridable from the environment (`USGS_TIMEOUT_SECS`,
+    /// `CWMS_TIMEOUT_SECS`, `IEM_TIMEOUT_SECS`, `NWS_TIMEOUT_SECS`,
+    /// `USGS_BACKFILL_TIMEOUT_SECS`, `CWMS_BACKFILL_TIMEOUT_SECS`,
+    /// `IEM_BACKFILL_TIMEOUT_SECS`,
+    /// `HTTP_MAX_ATTEMPTS`, `MAX_CONCURRENT_FETCHES` (formerly `POLL_WORKERS`,
+    /// still read as a fallback), `BACKFILL_CONCURRENCY`, `MAX_BACKFILL_DAYS`, `FLATLINE_MIN_REPEATS`,
+    /// `DV_MAX_PARSE_FAILURE_FRACTION`, `FLOOD_EVENT_MIN_DURATION_MINUTES`,
+    /// `FLOOD_EVENT_MIN_PEAK_FT`, `WRITE_BUFFER_PATH`,
+    /// `WRITE_BUFFER_MAX_READINGS`, `POLL_WEBHOOK_URL`,
+    /// `POLL_WEBHOOK_BATCH_SIZE`, `POLL_WEBHOOK_MAX_RETRIES`,
+    /// `POLL_WEBHOOK_DEAD_LETTER_PATH`, `APPLY_APPROVED_REVISIONS`,
+    /// `DB_POOL_SIZE`, `HEARTBEAT_PATH`, `DRY_RUN`, `STRICT_CONFIG`).
+    pub fn from_env() -> Self {
+        let env_secs = |name: &str, default: u64| {
+            std::env::var(name)
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default)
+        };
+        
+        let defaults = Self::default();
+        Self {
+            usgs_timeout_secs: env_secs("USGS_TIMEOUT_SECS", defaults.usgs_timeout_secs),
+            cwms_timeout_secs: env_secs("CWMS_TIMEOUT_SECS", defaults.cwms_timeout_secs),
+            iem_timeout_secs: env_secs("IEM_TIMEOUT_SECS", defaults.iem_timeout_secs),
+            nws_timeout_secs: env_secs("NWS_TIMEOUT_SECS", defaults.nws_timeout_secs),
+            usgs_backfill_timeout_secs: env_secs("USGS_BACKFILL_TIMEOUT_SECS", defaults.usgs_backfill_timeout_secs),
+            cwms_backfill_timeout_secs: env_secs("CWMS_BACKFILL_TIMEOUT_SECS", defaults.cwms_backfill_timeout_secs),
+            iem_backfill_timeout_secs: env_secs("IEM_BACKFILL_TIMEOUT_SECS", defaults.iem_backfill_timeout_secs),
+            http_max_attempts: std::env::var("HTTP_MAX_ATTEMPTS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.http_max_attempts),
+            max_concurrent_fetches: std::env::var("MAX_CONCURRENT_FETCHES")
+                .or_else(|_| std::env::var("POLL_WORKERS"))
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_concurrent_fetches),
+            backfill_concurrency: std::env::var("BACKFILL_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.backfill_concurrency),
+            max_backfill_days: std::env::var("MAX_BACKFILL_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.max_backfill_days),
+            flatline_min_repeats: std::env::var("FLATLINE_MIN_REPEATS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.flatline_min_repeats),
+            dv_max_parse_failure_fraction: std::env::var("DV_MAX_PARSE_FAILURE_FRACTION")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|f: &f64| (0.0..=1.0).contains(f))
+                .unwrap_or(defaults.dv_max_parse_failure_fraction),
+            flood_event_min_duration_minutes: std::env::var("FLOOD_EVENT_MIN_DURATION_MINUTES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n >= 0)
+                .unwrap_or(defaults.flood_event_min_duration_minutes),
+            flood_event_min_peak_above_flood_ft: std::env::var("FLOOD_EVENT_MIN_PEAK_FT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&f: &f64| f >= 0.0)
+                .unwrap_or(defaults.flood_event_min_peak_above_flood_ft),
+            write_buffer_path: std::env::var("WRITE_BUFFER_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(std::path::PathBuf::from),
+            write_buffer_max_readings: std::env::var("WRITE_BUFFER_MAX_READINGS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.write_buffer_max_readings),
+            poll_webhook_url: std::env::var("POLL_WEBHOOK_URL")
+                .ok()
+                .filter(|s| !s.is_empty()),
+            poll_webhook_batch_size: std::env::var("POLL_WEBHOOK_BATCH_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.poll_webhook_batch_size),
+            poll_webhook_max_retries: std::env::var("POLL_WEBHOOK_MAX_RETRIES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(defaults.poll_webhook_max_retries),
+            poll_webhook_dead_letter_path: std::env::var("POLL_WEBHOOK_DEAD_LETTER_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(std::path::PathBuf::from)
+                .unwrap_or(defaults.poll_webhook_dead_letter_path),
+            apply_approved_revisions: std::env::var("APPLY_APPROVED_REVISIONS")
+                .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(defaults.apply_approved_revisions),
+            db_pool_size: std::env::var("DB_POOL_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .filter(|&n| n > 0)
+                .unwrap_or(defaults.db_pool_size),
+            heartbeat_path: std::env::var("HEARTBEAT_PATH")
+                .ok()
+                .filter(|s| !s.is_empty())
+                .map(std::path::PathBuf::from),
+            dry_run: std::env::var("DRY_RUN")
+                .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(defaults.dry_run),
+            strict_config: std::env::var("STRICT_CONFIG")
+                .map(|s| matches!(s.to_lowercase().as_str(), "1" | "true" | "yes"))
+                .unwrap_or(defaults.strict_config),
+            ..defaults
+        }
+    }
+    
+    /// The configured write buffer, if any
+    pub fn write_buffer(&self) -> Option<WriteBuffer> {
+        self.write_buffer_path.as_ref()
+            .map(|path| WriteBuffer::new(path, self.write_buffer_max_readings))
+    }
+    
+    /// The configured poll-summary webhook, if any
+    pub fn poll_webhook(&self) -> Option<PollWebhook> {
+        let url = self.poll_webhook_url.as_ref()?;
+        PollWebhook::new(
+            url,
+            self.poll_webhook_batch_size,
+            self.poll_webhook_max_retries,
+            &self.poll_webhook_dead_letter_path,
+        )
+        .map_err(|e| eprintln!("Warning: {} — poll webhook disabled", e))
+        .ok()
+    }
+    
+    /// Gate a live flood-stage crossing must pass to become a flood event
+    pub fn flood_event_gate(&self) -> FloodEventGate {
+        FloodEventGate {
+            min_duration_minutes: self.flood_event_min_duration_minutes,
+            min_peak_above_flood_ft: self.flood_event_min_peak_above_flood_ft,
+        }
+    }
+}
+
+/// Queued history pages span up to `max_backfill_days` of daily values, so
+/// they get this multiple of the USGS backfill timeout (60 s by default).
+const QUEUED_GAP_TIMEOUT_MULTIPLIER: u64 = 2;
+
+/// Queued history pages drained per poll cycle (see `max_backfill_days`)
+const BACKFILL_PAGES_PER_CYCLE: usize = 1;
+
+/// `backfill_queue` priority for deferred deep history — below any live gap
+const DEFERRED_HISTORY_PRIORITY: i32 = 10;
+
+/// Default `max_concurrent_fetches`
+pub const DEFAULT_MAX_CONCURRENT_FETCHES: usize = 4;
+
+/// How often the sleep between polls checks for a shutdown request
+const SHUTDOWN_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_millis(250);
+
+/// USGS poll interval for a station at or above action stage
+pub const FLOOD_POLL_INTERVAL_MINUTES: u64 = 5;
+
+/// USGS poll interval for a station well below action stage
+pub const QUIET_POLL_INTERVAL_MINUTES: u64 = 30;
+
+/// How far below action stage a station must be to back off to
+/// `QUIET_POLL_INTERVAL_MINUTES`
+pub const QUIET_BELOW_ACTION_FT: f64 = 3.0;
+
+/// How urgently a USGS station needs polling, judged from its latest
+/// stage against its thresholds (compare CWMS `MonitoringPriority`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollPriority {
+    /// At or above action stage: every `FLOOD_POLL_INTERVAL_MINUTES`
+    Flood,
+    /// Near action stage, or no stage or thresholds to judge by: every
+    /// `poll_interval_minutes`
+    Normal,
+    /// More than `QUIET_BELOW_ACTION_FT` below action stage: every
+    /// `QUIET_POLL_INTERVAL_MINUTES`
+    Quiet,
+}
+
+impl PollPriority {
+    /// Priority for a station whose latest stage is `stage_ft`
+    pub fn from_stage(stage_ft: Option<f64>, thresholds: Option<&FloodThresholds>) -> Self {
+        match (stage_ft, thresholds) {
+            (Some(stage), Some(t)) if stage >= t.action_stage_ft => PollPriority::Flood,
+            (Some(stage), Some(t)) if stage < t.action_stage_ft - QUIET_BELOW_ACTION_FT => PollPriority::Quiet,
+            _ => PollPriority::Normal,
+        }
+    }
+    
+    /// Poll interval in minutes, given the configured normal interval.
+    /// Flood never polls less often, nor Quiet more often, than normal.
+    pub fn poll_interval_minutes(&self, normal_minutes: u64) -> u64 {
+        match self {
+            PollPriority::Flood => FLOOD_POLL_INTERVAL_MINUTES.min(normal_minutes),
+            PollPriority::Normal => normal_minutes,
+            PollPriority::Quiet => QUIET_POLL_INTERVAL_MINUTES.max(normal_minutes),
         }
     }
 }
 
+/// Stage of the newest stage reading in `readings` that isn't future-dated
+fn latest_stage(readings: &[GaugeReading], now: DateTime<Utc>) -> Option<f64> {
+    readings.iter()
+        .filter(|r| r.parameter_code == PARAM_STAGE)
+        .filter_map(|r| Some((parse_reading_time(r).ok()?, r.value)))
+        .filter(|(at, _)| !monitor::is_future_dated(*at, now))
+        .max_by_key(|(at, _)| *at)
+        .map(|(_, stage)| stage)
+}
+
+/// Split `start..end` into consecutive pages of at most `max_days`,
+/// newest first, so the most recent history is filled soonest.
+fn backfill_pages(
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    max_days: u64,
+) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    let page = Duration::days(max_days.max(1) as i64);
+    let mut pages = Vec::new();
+    let mut page_end = end;
+    
+    while page_end > start {
+        let page_start = (page_end - page).max(start);
+        pages.push((page_start, page_end));
+        page_end = page_start;
+    }
+    
+    pages
+}
+
+/// Coverage of stored readings over a backfilled window.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CoverageReport {
+    pub site_code: String,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Interval slots in the window
+    pub expected_readings: usize,
+    /// Interval slots holding at least one reading
+    pub observed_readings: usize,
+    pub coverage_percent: f64,
+    /// Stretches with no readings, per `gaps::detect_gaps`
+    pub gaps: Vec<(DateTime<Utc>, DateTime<Utc>)>,
+}
+
+/// Coverage below this after a startup backfill is reported as a warning.
+pub const MIN_BACKFILL_COVERAGE_PERCENT: f64 = 90.0;
+
+/// Coverage of `timestamps` (ascending) over `start..end` at
+/// `expected_interval`.
+fn compute_coverage(
+    site_code: &str,
+    timestamps: &[DateTime<Utc>],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    expected_interval: Duration,
+) -> CoverageReport {
+    let interval_secs = expected_interval.num_seconds().max(1);
+    let span_secs = (end - start).num_seconds().max(0);
+    let expected_readings = ((span_secs + interval_secs - 1) / interval_secs) as usize;
+    
+    let mut slots: Vec<i64> = timestamps.iter()
+        .filter(|&&t| t >= start && t < end)
+        .map(|&t| (t - start).num_seconds() / interval_secs)
+        .collect();
+    slots.dedup();
+    let observed_readings = slots.len();
+    let gaps = gaps::detect_gaps(timestamps, start, end, expected_interval);
+    
+    let coverage_percent = if expected_readings == 0 {
+        100.0
+    } else {
+        100.0 * observed_readings as f64 / expected_readings as f64
+    };
+    
+    CoverageReport {
+        site_code: site_code.to_string(),
+        start,
+        end,
+        expected_readings,
+        observed_readings,
+        coverage_percent,
+        gaps,
+    }
+}
+
+/// Gaps between consecutive `timestamps` (ascending) at
+/// `expected_interval`. Silence before the first reading or after the last
+/// isn't counted — that is staleness or history not yet backfilled.
+fn interior_gaps(timestamps: &[DateTime<Utc>], expected_interval: Duration) -> Vec<(DateTime<Utc>, DateTime<Utc>)> {
+    match (timestamps.first(), timestamps.last()) {
+        (Some(&first), Some(&last)) => gaps::detect_gaps(timestamps, first, last, expected_interval),
+        _ => Vec::new(),
+    }
+}
+
+/// A USGS reading's timestamp in UTC. IV datetimes carry an offset; DV
+/// dates don't and are station-local (Central) days.
+fn parse_reading_time(reading: &GaugeReading) -> Result<DateTime<Utc>, String> {
+    if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&reading.datetime) {
+        return Ok(dt.with_timezone(&Utc));
+    }
+    let naive = chrono::NaiveDateTime::parse_from_str(&reading.datetime, "%Y-%m-%dT%H:%M:%S%.3f")
+        .or_else(|_| chrono::NaiveDate::parse_from_str(&reading.datetime, "%Y-%m-%d")
+            .map(|d| d.and_time(chrono::NaiveTime::MIN)))
+        .map_err(|e| format!("Failed to parse datetime '{}': {}", reading.datetime, e))?;
+    Ok(central_to_utc(naive))
+}
+
+/// Log what a dry run would have inserted, one line per (station,
+/// series) with its count and time range. Returns the total.
+fn log_dry_run(
+    source: logging::DataSource,
+    rows: impl IntoIterator<Item = (String, String, DateTime<Utc>)>,
+) -> usize {
+    let mut series: std::collections::BTreeMap<(String, String), Vec<DateTime<Utc>>> =
+        std::collections::BTreeMap::new();
+    for (station, name, time) in rows {
+        series.entry((station, name)).or_default().push(time);
+    }
+    
+    for ((station, name), times) in &series {
+        let (Some(first), Some(last)) = (times.iter().min(), times.iter().max()) else {
+            continue;
+        };
+        let message = format!("Dry run: would insert {} {} {} readings from {} to {}",
+                              times.len(), station, name, first.format("%Y-%m-%d %H:%M"), last.format("%Y-%m-%d %H:%M"));
+        logging::info(source.clone(), Some(station), &message);
+    }
+    series.values().map(Vec::len).sum()
+}
+
+/// Whether the daily digest should go out at `now`: within the configured
+/// UTC hour (negative disables it) and not already sent that day.
+fn digest_due(digest_hour_utc: i32, last_sent: Option<NaiveDate>, now: DateTime<Utc>) -> bool {
+    digest_hour_utc >= 0
+        && now.hour() == digest_hour_utc as u32
+        && last_sent != Some(now.date_naive())
+}
+
+/// Build a blocking HTTP client with the given timeout.
+fn http_client(timeout_secs: u64) -> Result<reqwest::blocking::Client, Box<dyn Error>> {
+    Ok(reqwest::blocking::Client::builder()
+        .timeout(std::time::Duration::from_secs(timeout_secs))
+        .build()?)
+}
+
+/// Jobs handed to the fetch pool, collected in submission order rather
+/// than completion order, so which server answers first never changes
+/// the order a cycle's writes happen in.
+struct PendingFetches<R> {
+    rx: mpsc::Receiver<(usize, R)>,
+}
+
+impl<R> PendingFetches<R> {
+    /// Block until every job has finished; a job that panicked is missing.
+    fn wait(self) -> Vec<R> {
+        let mut results: Vec<(usize, R)> = self.rx.iter().collect();
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
+}
+
+/// Run `fetch` on each of `items` on `pool`, at most the pool's size at once.
+fn spawn_fetches<T, R, F>(pool: &threadpool::ThreadPool, items: Vec<T>, fetch: F) -> PendingFetches<R>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    F: Fn(T) -> R + Send + Sync + 'static,
+{
+    let fetch = Arc::new(fetch);
+    let (tx, rx) = mpsc::channel();
+    for (i, item) in items.into_iter().enumerate() {
+        let tx = tx.clone();
+        let fetch = Arc::clone(&fetch);
+        pool.execute(move || {
+            // The receiver only goes away if the cycle was abandoned
+            let _ = tx.send((i, fetch(item)));
+        });
+    }
+    PendingFetches { rx }
+}
+
 // ---------------------------------------------------------------------------
 // Daemon State
 // ---------------------------------------------------------------------------
@@ -58,49 +598,151 @@ pub struct Daemon {
     stations: Vec<Station>,
     cwms_locations: Vec<UsaceLocation>,
     asos_locations: Vec<AsosLocation>,
-    client: Option<Client>,
+    /// Thresholds as written in usgs_stations.toml — the fallback when
+    /// nws.flood_thresholds has no row for a station.
+    toml_thresholds: HashMap<String, Option<FloodThresholds>>,
+    /// Shared connection pool — None until `initialize` connects
+    db: Option<db::DbPool>,
     /// Optional SMS notifier — None when alerting.toml is absent or disabled.
     notifier: Option<Notifier>,
     /// Thread pool for parallel HTTP requests
     thread_pool: threadpool::ThreadPool,
+    /// Source of "now" for staleness, backfill ranges, and analysis windows
+    clock: SharedClock,
+    /// Open live flood events (and not-yet-qualified candidates) per site
+    flood_events: FloodEventTracker,
+    /// Optional push of each cycle's summary — None unless configured
+    poll_webhook: Option<PollWebhook>,
+    /// Result keys (e.g. "USGS:05567500") that failed in the current cycle
+    poll_failures: Vec<String>,
+    /// When each USGS site is next due, per its `PollPriority`; a site
+    /// not in the map is due now
+    next_poll: HashMap<String, DateTime<Utc>>,
+    /// When CWMS, ASOS, and NWS are next due; they keep the configured
+    /// `poll_interval_minutes`. `None` means due now.
+    next_full_poll: Option<DateTime<Utc>>,
+    /// Whether the latest `poll_all_stations` was a full poll (CWMS, ASOS,
+    /// and NWS too) rather than only the USGS sites that had come due
+    full_cycle: bool,
+    /// UTC date the daily digest was last sent
+    last_digest: Option<NaiveDate>,
+    /// Set by a signal handler or `request_shutdown`; `run` returns after
+    /// the current cycle
+    shutdown: Arc<AtomicBool>,
+    /// Counters served at `/metrics`, shared with the endpoint server
+    metrics: SharedMetrics,
+    /// USGS sites the flatline check has marked degraded; seeded from
+    /// `monitoring_state` at startup so a restart can still clear them
+    flatlined: HashSet<String>,
 }
 
 impl Daemon {
     /// Create a new daemon instance with default configuration
     pub fn new() -> Self {
-        // Use worker count = number of stations for good parallelism
-        // Default to 8 workers (can handle 8 USGS + concurrent CWMS/ASOS)
-        let worker_count = std::env::var("POLL_WORKERS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(8);
-        
+        let config = DaemonConfig::from_env();
+        let worker_count = config.max_concurrent_fetches.max(1);
+        let poll_webhook = config.poll_webhook();
         Self {
-            config: DaemonConfig::default(),
+            config,
             stations: Vec::new(),
             cwms_locations: Vec::new(),
             asos_locations: Vec::new(),
-            client: None,
+            toml_thresholds: HashMap::new(),
+            db: None,
             notifier: None,
             thread_pool: threadpool::ThreadPool::new(worker_count),
+            clock: clock::system_clock(),
+            flood_events: FloodEventTracker::default(),
+            poll_webhook,
+            poll_failures: Vec::new(),
+            flatlined: HashSet::new(),
+            next_poll: HashMap::new(),
+            next_full_poll: None,
+            full_cycle: false,
+            last_digest: None,
+            shutdown: Arc::new(AtomicBool::new(false)),
+            metrics: metrics::shared(),
         }
     }
     
     /// Create daemon with custom configuration
     pub fn with_config(config: DaemonConfig) -> Self {
-        let worker_count = std::env::var("POLL_WORKERS")
-            .ok()
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(8);
-        
+        let worker_count = config.max_concurrent_fetches.max(1);
+        let poll_webhook = config.poll_webhook();
         Self {
             config,
             stations: Vec::new(),
             cwms_locations: Vec::new(),
             asos_locations: Vec::new(),
-            client: None,
+            toml_thresholds: HashMap::new(),
+            db: None,
             notifier: None,
             thread_pool: threadpool::ThreadPool::new(worker_count),
+            clock: clock::system_clock(),
+            flood_events: FloodEventTracker::default(),
+            poll_webhook,
+            poll_failures: Vec::new(),
+            flatlined: HashSet::new(),
+            next_poll: HashMap::new(),
+            next_full_poll: None,
+            full_cycle: false,
+            last_digest: None,
+            shutdown: Arc::new(AtomicBool::new(false)),
+            metrics: metrics::shared(),
+        }
+    }
+    
+    /// Replace the system clock, e.g. with a `MockClock` in tests.
+    pub fn with_clock(mut self, clock: SharedClock) -> Self {
+        self.clock = clock;
+        self
+    }
+    
+    /// Ask `run` to stop once the current poll cycle finishes. Takes
+    /// effect immediately if `run` is sleeping between polls.
+    pub fn request_shutdown(&self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+    }
+    
+    /// The flag `request_shutdown` sets, for a signal handler or another
+    /// thread to set while `run` holds the daemon.
+    pub fn shutdown_flag(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.shutdown)
+    }
+    
+    fn shutdown_requested(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
+    }
+    
+    /// Handle on the daemon's operational counters, for the endpoint's
+    /// `/metrics` route.
+    pub fn metrics(&self) -> SharedMetrics {
+        Arc::clone(&self.metrics)
+    }
+    
+    /// Lightweight daemon sharing an existing pool, used by concurrent
+    /// backfill tasks. No stations are loaded.
+    fn with_pool(config: DaemonConfig, pool: db::DbPool, clock: SharedClock, metrics: SharedMetrics) -> Self {
+        Self {
+            config,
+            stations: Vec::new(),
+            cwms_locations: Vec::new(),
+            asos_locations: Vec::new(),
+            toml_thresholds: HashMap::new(),
+            db: Some(pool),
+            notifier: None,
+            thread_pool: threadpool::ThreadPool::new(1),
+            clock,
+            flood_events: FloodEventTracker::default(),
+            poll_webhook: None,
+            poll_failures: Vec::new(),
+            flatlined: HashSet::new(),
+            next_poll: HashMap::new(),
+            next_full_poll: None,
+            full_cycle: false,
+            last_digest: None,
+            shutdown: Arc::new(AtomicBool::new(false)),
+            metrics,
         }
     }
     
@@ -108,6 +750,12 @@ impl Daemon {
     pub fn initialize(&mut self) -> Result<(), Box<dyn Error>> {
         // Validate database schemas
         let mut client = db::connect_and_verify(&["usgs_raw", "nws", "usace"])?;
+        db::verify_required_objects(&mut client)?;
+        
+        match db::schema_version(&mut client)? {
+            0 => eprintln!("Warning: Schema version is not tracked; run with --migrate to record it"),
+            version => println!("   Schema version: {}", version),
+        }
         
         // Load USGS station registry from TOML
         self.stations = stations::load_stations();
@@ -116,17 +764,26 @@ impl Daemon {
             return Err("No stations configured in usgs_stations.toml".into());
         }
         
+        // Zone sensors pointing at unconfigured stations never get readings
+        if let Err(problems) = config::validate_cross_references() {
+            if self.config.strict_config {
+                return Err(format!("Config cross-reference check failed:\n  {}", problems.join("\n  ")).into());
+            }
+            for problem in &problems {
+                eprintln!("Warning: {}", problem);
+            }
+        }
+        
         // Load CWMS locations from TOML
         let mut locations = usace_locations::load_locations()?;
+        config_status::record_loaded("usace_stations.toml");
         
         if locations.is_empty() {
             eprintln!("Warning: No USACE/CWMS locations configured in usace_stations.toml");
         } else {
             // Discover actual CWMS timeseries IDs from catalog endpoint
             println!("🔍 Discovering CWMS timeseries IDs from catalog...");
-            let http_client = reqwest::blocking::Client::builder()
-                .timeout(std::time::Duration::from_secs(15))
-                .build()?;
+            let http_client = http_client(self.config.cwms_timeout_secs)?;
             
             for location in &mut locations {
                 print!("   {} ... ", location.name);
@@ -137,6 +794,18 @@ impl Daemon {
                         eprintln!("      Warning: Will skip polling for {}", location.name);
                     }
                 }
+                
+                // Authoritative datum; polling doesn't depend on it
+                match usace_locations::update_with_location_metadata(location, &http_client) {
+                    Ok(_) if location.pool_target_datum_mismatch() => eprintln!(
+                        "      Warning: CWMS reports {} in {}, but pool_elevation_target_ft_ngvd29 is {}",
+                        location.name,
+                        location.vertical_datum().unwrap_or_default(),
+                        usace_locations::POOL_TARGET_DATUM,
+                    ),
+                    Ok(_) => {}
+                    Err(e) => eprintln!("      Warning: {} — using configured datum for {}", e, location.name),
+                }
             }
             
             // Filter to only locations with discovered timeseries
@@ -154,6 +823,7 @@ impl Daemon {
         let asos_path = std::path::Path::new("iem_asos.toml");
         if asos_path.exists() {
             let asos_locs = asos_locations::load_locations(asos_path)?;
+            config_status::record_loaded(asos_path);
             println!("📡 Loaded {} ASOS stations for precipitation monitoring", asos_locs.len());
             
             // Register ASOS stations in database
@@ -168,6 +838,9 @@ impl Daemon {
                 
                 println!("   {} ({}) - {} basin - Priority: {:?}",
                     loc.station_id, loc.name, loc.basin, loc.priority);
+                if self.config.dry_run {
+                    continue;
+                }
                 
                 // Insert or update station metadata
                 match client.execute(
@@ -208,7 +881,26 @@ impl Daemon {
             eprintln!("Warning: iem_asos.toml not found, skipping ASOS monitoring");
         }
         
-        self.client = Some(client);
+        self.db = Some(db::DbPool::with_client(client, self.config.db_pool_size));
+
+        // nws.flood_thresholds is authoritative; TOML values are the fallback
+        self.toml_thresholds = self.stations.iter()
+            .map(|s| (s.site_code.clone(), s.thresholds.clone()))
+            .collect();
+        match self.refresh_thresholds(true) {
+            Ok(count) => println!("📏 Loaded flood thresholds from database for {} stations", count),
+            Err(e) => eprintln!("Warning: Could not load nws.flood_thresholds ({}) — using usgs_stations.toml", e),
+        }
+
+        match self.resume_flood_events() {
+            Ok(0) => {}
+            Ok(count) => println!("🌊 Resumed {} open flood events", count),
+            Err(e) => eprintln!("Warning: Could not load open flood events: {}", e),
+        }
+
+        if let Err(e) = self.load_flatlined_sites() {
+            eprintln!("Warning: Could not load degraded stations: {}", e);
+        }
 
         // Load alerting configuration (optional — missing file is not fatal).
         self.notifier = Notifier::try_load();
@@ -216,6 +908,94 @@ impl Daemon {
         Ok(())
     }
     
+    /// Reload flood thresholds from `nws.flood_thresholds`, falling back to
+    /// the TOML value for stations without a complete database row.
+    ///
+    /// Called at startup (with `reconcile = true`, which warns wherever the
+    /// two sources disagree) and at the start of each poll cycle so revised
+    /// NWS stages take effect without a restart. Returns the number of
+    /// stations using database thresholds.
+    pub fn refresh_thresholds(&mut self, reconcile: bool) -> Result<usize, Box<dyn Error>> {
+        let client = &mut *self.db()?;
+        
+        let rows = client.query(
+            "SELECT site_code, action_stage_ft, flood_stage_ft,
+                    moderate_flood_stage_ft, major_flood_stage_ft
+             FROM nws.flood_thresholds",
+            &[]
+        )?;
+        
+        let to_f64 = |d: Option<rust_decimal::Decimal>| d.and_then(|v| v.to_string().parse::<f64>().ok());
+        let mut db_thresholds: HashMap<String, FloodThresholds> = HashMap::new();
+        for row in rows {
+            let site_code: String = row.get(0);
+            let levels = (to_f64(row.get(1)), to_f64(row.get(2)), to_f64(row.get(3)), to_f64(row.get(4)));
+            if let (Some(action), Some(flood), Some(moderate), Some(major)) = levels {
+                db_thresholds.insert(site_code, FloodThresholds {
+                    action_stage_ft: action,
+                    flood_stage_ft: flood,
+                    moderate_flood_stage_ft: moderate,
+                    major_flood_stage_ft: major,
+                });
+            } else if reconcile {
+                eprintln!("Warning: nws.flood_thresholds row for {} is incomplete — using usgs_stations.toml", site_code);
+            }
+        }
+        
+        let mut from_db = 0;
+        for station in &mut self.stations {
+            let toml = self.toml_thresholds.get(&station.site_code).cloned().flatten();
+            let db = db_thresholds.remove(&station.site_code);
+            
+            if reconcile {
+                match (&toml, &db) {
+                    (Some(t), Some(d)) => {
+                        let diffs = stations::threshold_differences(t, d);
+                        if !diffs.is_empty() {
+                            eprintln!("Warning: {} thresholds differ (usgs_stations.toml vs nws.flood_thresholds): {} — using database",
+                                     station.site_code, diffs.join(", "));
+                        }
+                    }
+                    (Some(_), None) => {
+                        eprintln!("Warning: {} has TOML thresholds but no nws.flood_thresholds row — using usgs_stations.toml",
+                                 station.site_code);
+                    }
+                    _ => {}
+                }
+            } else if let (Some(current), Some(d)) = (&station.thresholds, &db) {
+                let diffs = stations::threshold_differences(current, d);
+                if !diffs.is_empty() {
+                    println!("📏 {} thresholds updated from database: {}", station.site_code, diffs.join(", "));
+                }
+            }
+            
+            if db.is_some() {
+                from_db += 1;
+            }
+            station.thresholds = db.or(toml);
+        }
+        
+        Ok(from_db)
+    }
+    
+    /// Get reference to the daemon configuration
+    pub fn get_config(&self) -> &DaemonConfig {
+        &self.config
+    }
+    
+    /// Handle on the shared connection pool, for the HTTP endpoint and
+    /// anything else that should reuse the daemon's connections rather
+    /// than opening its own. None before `initialize`.
+    pub fn db_pool(&self) -> Option<db::DbPool> {
+        self.db.clone()
+    }
+    
+    /// Check a connection out of the shared pool
+    fn db(&self) -> Result<db::PooledClient, Box<dyn Error>> {
+        let pool = self.db.as_ref().ok_or("Daemon not initialized")?;
+        Ok(pool.get()?)
+    }
+    
     /// Get reference to loaded stations
     pub fn get_stations(&self) -> &[Station] {
         &self.stations
@@ -233,14 +1013,17 @@ impl Daemon {
     
     /// Check staleness of ASOS data for a specific station
     pub fn check_asos_staleness(&mut self, station_id: &str) -> Result<Option<Duration>, Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        let client = &mut *self.db()?;
         
+        // Future-dated rows (bad station clock) would make the station look
+        // fresh until real time caught up with them
+        let now = self.clock.now();
         let rows = client.query(
             "SELECT MAX(observation_time) as latest 
              FROM asos_observations 
-             WHERE station_id = $1",
-            &[&station_id]
+             WHERE station_id = $1
+               AND observation_time <= $2",
+            &[&station_id, &(now + Duration::minutes(monitor::MAX_FUTURE_SKEW_MINUTES))]
         )?;
         
         if rows.is_empty() {
@@ -250,21 +1033,23 @@ impl Daemon {
         let latest: Option<DateTime<Utc>> = rows[0].get(0);
         
         match latest {
-            Some(dt) => Ok(Some(Utc::now() - dt)),
+            Some(dt) => Ok(Some((now - dt).max(Duration::zero()))),
             None => Ok(None),
         }
     }
     
     /// Check staleness of data for a specific station
     pub fn check_staleness(&mut self, site_code: &str) -> Result<Option<Duration>, Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        let client = &mut *self.db()?;
         
+        // Ignore future-dated rows, as for ASOS; small drift clamps to zero
+        let now = self.clock.now();
         let rows = client.query(
             "SELECT MAX(reading_time) as latest 
              FROM usgs_raw.gauge_readings 
-             WHERE site_code = $1",
-            &[&site_code]
+             WHERE site_code = $1
+               AND reading_time <= $2",
+            &[&site_code, &(now + Duration::minutes(monitor::MAX_FUTURE_SKEW_MINUTES))]
         )?;
         
         if rows.is_empty() {
@@ -275,15 +1060,110 @@ impl Daemon {
         let latest: Option<DateTime<Utc>> = rows[0].get(0);
         
         match latest {
-            Some(dt) => Ok(Some(Utc::now() - dt)),
+            Some(dt) => Ok(Some((now - dt).max(Duration::zero()))),
             None => Ok(None), // No readings in database
         }
     }
     
+    /// Check how completely stored readings cover `start..end` for a
+    /// station, e.g. to confirm a backfill produced contiguous data rather
+    /// than a sparse subset.
+    pub fn verify_backfill(
+        &mut self,
+        site_code: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+        expected_interval: Duration,
+    ) -> Result<CoverageReport, Box<dyn Error>> {
+        let client = &mut *self.db()?;
+        
+        let rows = client.query(
+            "SELECT DISTINCT reading_time
+             FROM usgs_raw.gauge_readings
+             WHERE site_code = $1 AND reading_time BETWEEN $2 AND $3
+             ORDER BY reading_time",
+            &[&site_code, &start, &end]
+        )?;
+        
+        let timestamps: Vec<DateTime<Utc>> = rows.iter().map(|row| row.get(0)).collect();
+        
+        Ok(compute_coverage(site_code, &timestamps, start, end, expected_interval))
+    }
+    
+    /// Holes in a station's stored record of one parameter: stretches
+    /// between consecutive readings longer than `GAP_TOLERANCE_INTERVALS`
+    /// × `expected_interval_minutes`, as (last reading, next reading).
+    /// Only the last `IV_MAX_HISTORY_DAYS` are scanned, since older gaps
+    /// can't be refilled at IV resolution.
+    pub fn detect_gaps(
+        &mut self,
+        site_code: &str,
+        parameter_code: &str,
+        expected_interval_minutes: i64,
+    ) -> Result<Vec<gaps::Gap>, Box<dyn Error>> {
+        let since = self.clock.now() - Duration::days(usgs::IV_MAX_HISTORY_DAYS as i64);
+        let client = &mut *self.db()?;
+        
+        let rows = client.query(
+            "SELECT DISTINCT reading_time
+             FROM usgs_raw.gauge_readings
+             WHERE site_code = $1 AND parameter_code = $2 AND reading_time >= $3
+             ORDER BY reading_time",
+            &[&site_code, &parameter_code, &since]
+        )?;
+        
+        let timestamps: Vec<DateTime<Utc>> = rows.iter().map(|row| row.get(0)).collect();
+        
+        Ok(interior_gaps(&timestamps, Duration::minutes(expected_interval_minutes)))
+    }
+    
+    /// Refetch each of `gaps` from the IV API and warehouse what comes
+    /// back; returns readings inserted. A gap that fails to fetch is logged
+    /// and skipped so the rest still get filled.
+    pub fn fill_gaps(
+        &mut self,
+        site_code: &str,
+        gaps: &[gaps::Gap],
+    ) -> Result<usize, Box<dyn Error>> {
+        let parameters = self.expected_parameters(site_code);
+        let client = http_client(self.config.usgs_backfill_timeout_secs)?;
+        let mut inserted = 0;
+        
+        for &(start, end) in gaps {
+            let url = usgs::build_iv_range_url(
+                &[site_code],
+                &stations::usgs_parameters(&parameters),
+                start,
+                end,
+            );
+            
+            let readings = match http::get_with_retry(&client, &url, self.config.http_max_attempts)
+                .map_err(|e| e.to_string())
+                .and_then(|response| {
+                    if !response.status().is_success() {
+                        return Err(format!("USGS API returned status {}", response.status()));
+                    }
+                    response.text().map_err(|e| e.to_string())
+                })
+                .and_then(|body| usgs::parse_iv_response_all(&body).map_err(|e| e.to_string()))
+            {
+                Ok(readings) => readings,
+                Err(e) => {
+                    eprintln!("Warning: Could not fill {} gap {} to {}: {}",
+                             site_code, start.format("%Y-%m-%d %H:%M"), end.format("%Y-%m-%d %H:%M"), e);
+                    continue;
+                }
+            };
+            
+            inserted += self.warehouse_readings(&readings)?;
+        }
+        
+        Ok(inserted)
+    }
+    
     /// Check staleness of CWMS data for a specific location
     pub fn check_cwms_staleness(&mut self, location_id: &str) -> Result<Option<Duration>, Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        let client = &mut *self.db()?;
         
         let rows = client.query(
             "SELECT MAX(timestamp) as latest 
@@ -299,7 +1179,7 @@ impl Daemon {
         let latest: Option<DateTime<Utc>> = rows[0].get(0);
         
         match latest {
-            Some(dt) => Ok(Some(Utc::now() - dt)),
+            Some(dt) => Ok(Some(self.clock.now() - dt)),
             None => Ok(None),
         }
     }
@@ -317,8 +1197,12 @@ impl Daemon {
     
     /// Backfill historical data for a station
     /// Uses intelligent strategy: IV API for recent data (high-res), DV API for deep history
+    ///
+    /// Fetches at most `config.max_backfill_days` directly; older history is
+    /// queued for `process_backfill_queue` to page through on later cycles.
     pub fn backfill_station(&mut self, site_code: &str) -> Result<usize, Box<dyn Error>> {
-        let now = Utc::now();
+        let now = self.clock.now();
+        let max_days = self.config.max_backfill_days;
         
         // Check what data we already have
         let latest_data = self.check_staleness(site_code)?;
@@ -330,40 +1214,60 @@ impl Daemon {
                 // No data at all - get high-resolution recent data + optional deep history
                 println!("   Empty database for {} - fetching high-resolution data", site_code);
                 
-                // Always get the last 120 days as instantaneous values (high resolution)
-                match self.backfill_instantaneous_values(site_code, 120) {
+                // Get the last 120 days (or the cap, if smaller) as instantaneous values
+                let iv_days = max_days.min(usgs::IV_MAX_HISTORY_DAYS);
+                match self.backfill_instantaneous_values(site_code, iv_days) {
                     Ok(count) => {
                         total_inserted += count;
-                        println!("   Fetched {} instantaneous readings (last 120 days)", count);
+                        println!("   Fetched {} instantaneous readings (last {} days)", count, iv_days);
                     }
                     Err(e) => {
                         logging::log_usgs_failure(site_code, "IV backfill", &*e);
                         eprintln!("   Falling back to daily values for {}", site_code);
                         total_inserted += self.backfill_daily_values(
                             site_code, 
-                            now - Duration::days(120), 
+                            now - Duration::days(iv_days as i64), 
                             now
                         )?;
                     }
                 }
                 
-                // Optionally get older data as daily values if backfill_days > 120
-                if self.config.backfill_days > 120 {
-                    let deep_history_days = self.config.backfill_days - 120;
+                // Optionally get older data as daily values if backfill_days > 120,
+                // stopping the day before IV's first (partial) day
+                let direct_days = self.config.backfill_days.min(max_days);
+                if direct_days > usgs::IV_MAX_HISTORY_DAYS {
+                    let deep_history_days = direct_days - usgs::IV_MAX_HISTORY_DAYS;
                     println!("   Fetching {} additional days of daily values for historical context", deep_history_days);
                     
                     total_inserted += self.backfill_daily_values(
+                        site_code,
+                        now - Duration::days(direct_days as i64),
+                        now - Duration::days(usgs::IV_MAX_HISTORY_DAYS as i64),
+                    )?;
+                }
+                
+                // Anything beyond the cap is paged in over later cycles
+                let direct_days = direct_days.max(iv_days);
+                if self.config.backfill_days > direct_days {
+                    self.queue_deferred_history(
                         site_code,
                         now - Duration::days(self.config.backfill_days as i64),
-                        now - Duration::days(120),
+                        now - Duration::days(direct_days as i64),
                     )?;
                 }
             }
-            Some(staleness) => {
+            Some(mut staleness) => {
+                // Defer the part of a long outage beyond the cap
+                if staleness.num_days() > max_days as i64 {
+                    let cap = Duration::days(max_days as i64);
+                    self.queue_deferred_history(site_code, now - staleness, now - cap)?;
+                    staleness = cap;
+                }
+                
                 // We have some data - intelligently fill the gap
                 let gap_days = staleness.num_days();
                 
-                if gap_days <= 120 {
+                if gap_days <= usgs::IV_MAX_HISTORY_DAYS as i64 {
                     // Gap is within IV API range - get high-resolution data
                     println!("   Filling {}-day gap with instantaneous values (high-res)", gap_days);
                     
@@ -386,18 +1290,19 @@ impl Daemon {
                     // Gap is too large for IV API - use hybrid strategy
                     println!("   Large gap ({} days) - using hybrid backfill", gap_days);
                     
-                    // Get old data (beyond 120 days) as daily values
+                    // Get old data (beyond 120 days) as daily values, up to
+                    // but not including IV's first day (see dv_date_range)
                     let old_data_start = now - staleness;
-                    let old_data_end = now - Duration::days(120);
+                    let old_data_end = now - Duration::days(usgs::IV_MAX_HISTORY_DAYS as i64);
                     
                     if old_data_end > old_data_start {
                         let dv_count = self.backfill_daily_values(site_code, old_data_start, old_data_end)?;
                         total_inserted += dv_count;
-                        println!("   Fetched {} daily values for days {}-120", dv_count, gap_days);
+                        println!("   Fetched {} daily values for days {}-121", dv_count, gap_days);
                     }
                     
                     // Get recent 120 days as instantaneous values (high resolution)
-                    match self.backfill_instantaneous_values(site_code, 120) {
+                    match self.backfill_instantaneous_values(site_code, usgs::IV_MAX_HISTORY_DAYS) {
                         Ok(count) => {
                             total_inserted += count;
                             println!("   Fetched {} instantaneous readings (last 120 days)", count);
@@ -407,7 +1312,7 @@ impl Daemon {
                             eprintln!("   Falling back to daily values for {}", site_code);
                             total_inserted += self.backfill_daily_values(
                                 site_code, 
-                                now - Duration::days(120), 
+                                now - Duration::days(usgs::IV_MAX_HISTORY_DAYS as i64), 
                                 now
                             )?;
                         }
@@ -419,25 +1324,69 @@ impl Daemon {
         Ok(total_inserted)
     }
     
+    /// Backfill several USGS stations concurrently, at most
+    /// `config.backfill_concurrency` at a time.
+    ///
+    /// Each task checks connections out of the shared pool and runs
+    /// `backfill_station` independently. `on_complete` is called on the
+    /// calling thread as each station finishes, in completion order.
+    pub fn backfill_stations_concurrently<F>(&self, site_codes: &[String], mut on_complete: F)
+    where
+        F: FnMut(&str, Result<usize, String>),
+    {
+        let pool = threadpool::ThreadPool::new(self.config.backfill_concurrency.max(1));
+        let (tx, rx) = mpsc::channel();
+        
+        for site_code in site_codes {
+            let site_code = site_code.clone();
+            let config = self.config.clone();
+            let clock = self.clock.clone();
+            let metrics = self.metrics();
+            let db_pool = self.db.clone();
+            let tx = tx.clone();
+            
+            pool.execute(move || {
+                let result = db_pool
+                    .ok_or_else(|| -> Box<dyn Error> { "Daemon not initialized".into() })
+                    .and_then(|db_pool| {
+                        Daemon::with_pool(config, db_pool, clock, metrics).backfill_station(&site_code)
+                    })
+                    .map_err(|e| e.to_string());
+                tx.send((site_code, result)).expect("Failed to send result");
+            });
+        }
+        drop(tx); // rx ends once every task has reported
+        
+        for (site_code, result) in rx {
+            on_complete(&site_code, result);
+        }
+    }
+    
     /// Backfill using Daily Values API (coarse resolution, longer history)
-    fn backfill_daily_values(&mut self, site_code: &str, start_date: DateTime<Utc>, end_date: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+    ///
+    /// Covers the whole local days in `[start, end)`; the day `end` falls on
+    /// is left to the IV fetch or page that starts there (see
+    /// `usgs::dv_date_range`).
+    fn backfill_daily_values(&mut self, site_code: &str, start: DateTime<Utc>, end: DateTime<Utc>) -> Result<usize, Box<dyn Error>> {
+        let Some((start_date, end_date)) = usgs::dv_date_range(start, end) else {
+            return Ok(0);
+        };
         let start_date_str = start_date.format("%Y-%m-%d").to_string();
         let end_date_str = end_date.format("%Y-%m-%d").to_string();
         
+        let parameters = &self.expected_parameters(site_code);
         let url = usgs::build_dv_url(
             &[site_code],
-            &["00060", "00065"], // Discharge and stage
+            &stations::usgs_parameters(parameters),
             &start_date_str,
             &end_date_str,
         );
         
         println!("   Fetching daily values from {} to {}", start_date_str, end_date_str);
         
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        let client = http_client(self.config.usgs_backfill_timeout_secs)?;
         
-        let response = client.get(&url).send()?;
+        let response = http::get_with_retry(&client, &url, self.config.http_max_attempts)?;
         
         if !response.status().is_success() {
             return Err(format!("USGS API returned status {}", response.status()).into());
@@ -445,7 +1394,7 @@ impl Daemon {
         
         let body = response.text()?;
         
-        let readings = match usgs::parse_dv_response(&body) {
+        let readings = match usgs::parse_dv_response_with_limit(&body, self.config.dv_max_parse_failure_fraction) {
             Ok(r) => r,
             Err(e) => {
                 logging::log_usgs_failure(site_code, "DV API parsing", &e);
@@ -461,17 +1410,16 @@ impl Daemon {
         // Convert days to ISO 8601 period format (e.g. P30D for 30 days)
         let period = format!("P{}D", days);
         
+        let parameters = &self.expected_parameters(site_code);
         let url = usgs::build_iv_url(
             &[site_code],
-            &["00060", "00065"], // Discharge and stage
+            &stations::usgs_parameters(parameters),
             &period,
         );
         
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        let client = http_client(self.config.usgs_backfill_timeout_secs)?;
         
-        let response = client.get(&url).send()?;
+        let response = http::get_with_retry(&client, &url, self.config.http_max_attempts)?;
         
         if !response.status().is_success() {
             return Err(format!("USGS API returned status {}", response.status()).into());
@@ -491,54 +1439,50 @@ impl Daemon {
     
     /// Poll a single CWMS location for latest data
     pub fn poll_cwms_location(&mut self, location: &UsaceLocation) -> Result<usize, Box<dyn Error>> {
+        let series = Self::fetch_cwms_location(location, self.config.cwms_timeout_secs, self.config.http_max_attempts)?;
+        self.warehouse_cwms_series(&series)
+    }
+    
+    /// Recent pool elevation, tailwater elevation, stage, and discharge for
+    /// a CWMS location, whichever it has (can be called from threads). One series
+    /// failing is logged and doesn't drop the others.
+    fn fetch_cwms_location(
+        location: &UsaceLocation,
+        timeout_secs: u64,
+        max_attempts: u32,
+    ) -> Result<Vec<Vec<cwms::CwmsTimeseries>>, String> {
         // Skip if no timeseries discovered
-        let discovered = match &location.discovered_timeseries {
-            Some(d) => d,
-            None => return Ok(0), // No timeseries available, skip polling
+        let Some(discovered) = &location.discovered_timeseries else {
+            return Ok(Vec::new());
         };
         
-        let http_client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(15))
-            .build()?;
-        
-        let mut total_inserted = 0;
-        
-        // Fetch pool elevation if available
-        if let Some(ref ts_id) = discovered.pool_elevation {
-            match cwms::fetch_recent(&http_client, ts_id, &location.office, 4) {
-                Ok(timeseries) => {
-                    total_inserted += self.warehouse_cwms_timeseries(&timeseries)?;
-                }
-                Err(e) => {
-                    eprintln!("   Failed to fetch pool elevation for {}: {}", location.name, e);
-                }
-            }
-        }
-        
-        // Fetch tailwater elevation if available
-        if let Some(ref ts_id) = discovered.tailwater_elevation {
-            match cwms::fetch_recent(&http_client, ts_id, &location.office, 4) {
-                Ok(timeseries) => {
-                    total_inserted += self.warehouse_cwms_timeseries(&timeseries)?;
-                }
-                Err(e) => {
-                    eprintln!("   Failed to fetch tailwater elevation for {}: {}", location.name, e);
-                }
+        let http_client = http_client(timeout_secs).map_err(|e| e.to_string())?;
+        
+        let wanted = [
+            (&discovered.pool_elevation, "pool elevation"),
+            (&discovered.tailwater_elevation, "tailwater elevation"),
+            (&discovered.stage, "stage"), // river gauges
+            (&discovered.discharge, "discharge"),
+        ];
+        let mut series = Vec::new();
+        for (ts_id, label) in wanted {
+            let Some(ts_id) = ts_id else {
+                continue;
+            };
+            match cwms::fetch_recent(&http_client, ts_id, &location.office, 4, max_attempts) {
+                Ok(timeseries) => series.push(timeseries),
+                Err(e) => eprintln!("   Failed to fetch {} for {}: {}", label, location.name, e),
             }
         }
         
-        // Fetch stage if available (for river gauges)
-        if let Some(ref ts_id) = discovered.stage {
-            match cwms::fetch_recent(&http_client, ts_id, &location.office, 4) {
-                Ok(timeseries) => {
-                    total_inserted += self.warehouse_cwms_timeseries(&timeseries)?;
-                }
-                Err(e) => {
-                    eprintln!("   Failed to fetch stage for {}: {}", location.name, e);
-                }
-            }
+        Ok(series)
+    }
+    
+    fn warehouse_cwms_series(&mut self, series: &[Vec<cwms::CwmsTimeseries>]) -> Result<usize, Box<dyn Error>> {
+        let mut total_inserted = 0;
+        for timeseries in series {
+            total_inserted += self.warehouse_cwms_timeseries(timeseries)?;
         }
-        
         Ok(total_inserted)
     }
     
@@ -550,7 +1494,7 @@ impl Daemon {
             None => return Ok(0), // No timeseries available, skip backfill
         };
         
-        let now = Utc::now();
+        let now = self.clock.now();
         
         // Collect all timeseries IDs we need to backfill
         let mut timeseries_to_backfill = Vec::new();
@@ -563,6 +1507,9 @@ impl Daemon {
         if let Some(ref ts_id) = discovered.stage {
             timeseries_to_backfill.push((ts_id.clone(), "stage"));
         }
+        if let Some(ref ts_id) = discovered.discharge {
+            timeseries_to_backfill.push((ts_id.clone(), "discharge"));
+        }
         
         if timeseries_to_backfill.is_empty() {
             return Ok(0);
@@ -579,14 +1526,12 @@ impl Daemon {
                     // No data at all - get last 120 days
                     println!("   Empty database for {} ({}) - fetching CWMS data", location.name, param_type);
                     
-                    let http_client = reqwest::blocking::Client::builder()
-                        .timeout(std::time::Duration::from_secs(30))
-                        .build()?;
+                    let http_client = http_client(self.config.cwms_backfill_timeout_secs)?;
                     
                     let start = (now - Duration::days(120)).naive_utc();
                     let end = now.naive_utc();
                     
-                    match cwms::fetch_historical(&http_client, &ts_id, &location.office, start, end) {
+                    match cwms::fetch_historical(&http_client, &ts_id, &location.office, start, end, self.config.http_max_attempts) {
                         Ok(timeseries) => {
                             let inserted = self.warehouse_cwms_timeseries(&timeseries)?;
                             total_inserted += inserted;
@@ -604,14 +1549,12 @@ impl Daemon {
                     if gap_days > 1 {
                         println!("   Filling {}-day CWMS gap for {} ({})", gap_days, location.name, param_type);
                         
-                        let http_client = reqwest::blocking::Client::builder()
-                            .timeout(std::time::Duration::from_secs(30))
-                            .build()?;
+                        let http_client = http_client(self.config.cwms_backfill_timeout_secs)?;
                         
                         let start = (now - staleness).naive_utc();
                         let end = now.naive_utc();
                         
-                        match cwms::fetch_historical(&http_client, &ts_id, &location.office, start, end) {
+                        match cwms::fetch_historical(&http_client, &ts_id, &location.office, start, end, self.config.http_max_attempts) {
                             Ok(timeseries) => {
                                 let inserted = self.warehouse_cwms_timeseries(&timeseries)?;
                                 total_inserted += inserted;
@@ -631,12 +1574,33 @@ impl Daemon {
     
     /// Warehouse CWMS timeseries into database (idempotent)
     fn warehouse_cwms_timeseries(&mut self, timeseries: &[cwms::CwmsTimeseries]) -> Result<usize, Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        let now = self.clock.now();
+        if self.config.dry_run {
+            let would_insert = log_dry_run(logging::DataSource::Cwms, timeseries.iter()
+                .filter(|r| !monitor::is_future_dated(r.timestamp, now))
+                .filter(|r| !cwms::decode_quality(r.quality_code).is_unusable())
+                .map(|r| (r.location_id.clone(), r.timeseries_id.clone(), r.timestamp)));
+            metrics::lock(&self.metrics).record_inserted("cwms", would_insert);
+            return Ok(would_insert);
+        }
+        
+        let client = &mut *self.db()?;
         
+        let mut future_dated = monitor::FutureDatedTally::default();
         let mut inserted = 0;
+        let mut unusable = 0;
         
         for record in timeseries {
+            if future_dated.check(record.timestamp, now) {
+                continue;
+            }
+            
+            // CWMS flags some values missing or rejected; they're not readings
+            if cwms::decode_quality(record.quality_code).is_unusable() {
+                unusable += 1;
+                continue;
+            }
+            
             // Convert value to Decimal for PostgreSQL NUMERIC type
             let value_decimal = rust_decimal::Decimal::from_f64_retain(record.value)
                 .ok_or_else(|| format!("Failed to convert value {} to decimal", record.value))?;
@@ -652,10 +1616,10 @@ impl Daemon {
                     &record.location_id,
                     &record.timeseries_id,
                     &record.parameter_id,
-                    &"Inst",  // parameter_type - instantaneous
-                    &"15Minutes",  // interval
-                    &"0",  // duration
-                    &"Ccp-Rev",  // version
+                    &cwms::timeseries_parameter_type(&record.timeseries_id).unwrap_or("Inst"),
+                    &cwms::timeseries_interval(&record.timeseries_id).unwrap_or("15Minutes"),
+                    &cwms::timeseries_duration(&record.timeseries_id).unwrap_or("0"),
+                    &cwms::timeseries_version(&record.timeseries_id),
                     &record.timestamp,
                     &value_decimal,
                     &record.unit,
@@ -666,8 +1630,19 @@ impl Daemon {
             inserted += rows_affected as usize;
         }
         
-        Ok(inserted)
-    }
+        if let Some(warning) = future_dated.warning() {
+            let location_id = timeseries.first().map(|r| r.location_id.as_str());
+            logging::warn(logging::DataSource::Cwms, location_id, &warning);
+        }
+        if unusable > 0 {
+            let location_id = timeseries.first().map(|r| r.location_id.as_str());
+            let warning = format!("Skipped {} values flagged missing or rejected by CWMS quality codes", unusable);
+            logging::warn(logging::DataSource::Cwms, location_id, &warning);
+        }
+        
+        metrics::lock(&self.metrics).record_inserted("cwms", inserted);
+        Ok(inserted)
+    }
     
     // ---------------------------------------------------------------------------
     // ASOS Weather Data Warehousing
@@ -675,12 +1650,25 @@ impl Daemon {
     
     /// Warehouse ASOS observations into database (idempotent)
     fn warehouse_asos_observations(&mut self, observations: &[iem::AsosObservation]) -> Result<usize, Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        let now = self.clock.now();
+        if self.config.dry_run {
+            let would_insert = log_dry_run(logging::DataSource::Asos, observations.iter()
+                .filter(|o| !monitor::is_future_dated(o.timestamp, now))
+                .map(|o| (o.station_id.clone(), "observations".to_string(), o.timestamp)));
+            metrics::lock(&self.metrics).record_inserted("asos", would_insert);
+            return Ok(would_insert);
+        }
         
+        let client = &mut *self.db()?;
+        
+        let mut future_dated = monitor::FutureDatedTally::default();
         let mut inserted = 0;
         
         for obs in observations {
+            if future_dated.check(obs.timestamp, now) {
+                continue;
+            }
+            
             // Determine data source
             let data_source = "IEM_ASOS";
             
@@ -712,118 +1700,225 @@ impl Daemon {
             inserted += rows_affected as usize;
         }
         
+        if let Some(warning) = future_dated.warning() {
+            let station_id = observations.first().map(|o| o.station_id.as_str());
+            logging::warn(logging::DataSource::Asos, station_id, &warning);
+        }
+        
+        metrics::lock(&self.metrics).record_inserted("asos", inserted);
         Ok(inserted)
     }
     
-    /// Poll ASOS station for recent observations
-    fn poll_asos_station(&mut self, station_id: &str) -> Result<Vec<iem::AsosObservation>, Box<dyn Error>> {
-        let http_client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(15))
-            .build()?;
+    /// Recent observations for an ASOS station (can be called from threads)
+    fn fetch_asos_station(station_id: &str, timeout_secs: u64, max_attempts: u32) -> Result<iem::AsosResponse, String> {
+        let http_client = http_client(timeout_secs).map_err(|e| e.to_string())?;
         
         // Fetch last 4 hours for recent poll
-        let observations = iem::fetch_recent_precip(&http_client, station_id, 4)?;
-        
-        Ok(observations)
+        iem::fetch_recent_precip(&http_client, station_id, 4, max_attempts).map_err(|e| e.to_string())
     }
     
     /// Backfill ASOS historical data for a station
     pub fn backfill_asos_station(&mut self, station_id: &str, days: i64) -> Result<usize, Box<dyn Error>> {
-        let http_client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        let http_client = http_client(self.config.iem_backfill_timeout_secs)?;
         
         let hours = days * 24;
-        let observations = iem::fetch_recent_precip(&http_client, station_id, hours)?;
+        let observations = iem::fetch_recent_precip(&http_client, station_id, hours, self.config.http_max_attempts)?.into_observations();
         
         self.warehouse_asos_observations(&observations)
     }
     
+    // ---------------------------------------------------------------------------
+    // NWS Official Alerts
+    // ---------------------------------------------------------------------------
+    
+    /// Poll api.weather.gov for active flood alerts and store them.
+    ///
+    /// Alerts are upserted by NWS identifier with `last_seen` set to this
+    /// poll's time, which is then recorded in `nws.alert_polls`; an alert
+    /// last seen before the latest poll has been cancelled.
+    pub fn poll_nws_alerts(&mut self) -> Result<usize, Box<dyn Error>> {
+        let http_client = http_client(self.config.nws_timeout_secs)?;
+        let alerts = nws_alerts::fetch_active_flood_alerts(&http_client, nws_alerts::DEFAULT_ALERT_ZONES)?;
+        if self.config.dry_run {
+            return Ok(alerts.len());
+        }
+        
+        let polled_at = self.clock.now();
+        let client = &mut *self.db()?;
+        let mut transaction = client.transaction()?;
+        
+        for alert in &alerts {
+            transaction.execute(
+                "INSERT INTO nws.alerts
+                 (alert_id, event, severity, headline, effective, expires, areas, first_seen, last_seen)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $8)
+                 ON CONFLICT (alert_id) DO UPDATE SET
+                    severity = EXCLUDED.severity,
+                    headline = EXCLUDED.headline,
+                    expires = EXCLUDED.expires,
+                    areas = EXCLUDED.areas,
+                    last_seen = EXCLUDED.last_seen",
+                &[&alert.id, &alert.event, &alert.severity, &alert.headline,
+                  &alert.effective, &alert.expires, &alert.areas, &polled_at]
+            )?;
+        }
+        transaction.execute(
+            "INSERT INTO nws.alert_polls (polled_at, active_alerts) VALUES ($1, $2)
+             ON CONFLICT (polled_at) DO NOTHING",
+            &[&polled_at, &(alerts.len() as i32)]
+        )?;
+        transaction.commit()?;
+        
+        Ok(alerts.len())
+    }
+    
+    /// Fetch the current NWS stage forecast for every station with an
+    /// `nws_id` and store any points not already stored. Returns the number
+    /// of new forecast points; a station that fails is logged and skipped.
+    pub fn poll_nws_forecasts(&mut self) -> Result<usize, Box<dyn Error>> {
+        let forecast_points: Vec<String> = self.stations.iter()
+            .filter_map(|s| s.nws_id.clone())
+            .collect();
+        if forecast_points.is_empty() || self.config.dry_run {
+            return Ok(0);
+        }
+        
+        let http_client = http_client(self.config.nws_timeout_secs)?;
+        let client = &mut *self.db()?;
+        
+        let mut inserted = 0;
+        for nws_id in &forecast_points {
+            let forecasts = match nws::fetch_ahps_forecast(&http_client, nws_id) {
+                Ok(forecasts) => forecasts,
+                Err(e) => {
+                    eprintln!("Failed to fetch NWS forecast for {}: {}", nws_id, e);
+                    continue;
+                }
+            };
+            
+            for forecast in &forecasts {
+                inserted += client.execute(
+                    "INSERT INTO nws.stage_forecasts
+                     (nws_id, issued_time, valid_time, stage_ft, flow_cfs)
+                     VALUES ($1, $2, $3, $4::float8::numeric, $5::float8::numeric)
+                     ON CONFLICT (nws_id, issued_time, valid_time) DO NOTHING",
+                    &[&forecast.nws_id, &forecast.issued_time, &forecast.valid_time,
+                      &forecast.stage_ft, &forecast.flow_cfs]
+                )? as usize;
+            }
+        }
+        
+        Ok(inserted)
+    }
+    
+    /// Fetch each station's USGS annual peaks and store them for
+    /// return-period curves. USGS publishes a peak once a year, so this
+    /// runs at startup rather than every cycle. Returns the number of peaks
+    /// stored (fetched, in a dry run); a station that fails is logged and
+    /// skipped.
+    pub fn load_annual_peaks(&mut self) -> Result<usize, Box<dyn Error>> {
+        let site_codes: Vec<String> = self.stations.iter().map(|s| s.site_code.clone()).collect();
+        let http_client = http_client(self.config.usgs_timeout_secs)?;
+        
+        let mut stored = 0;
+        for site_code in &site_codes {
+            let body = http::get_with_retry(&http_client, &peak_flow::peak_flow_url(site_code), self.config.http_max_attempts)
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.text());
+            let records = match body.map_err(|e| e.to_string()).and_then(|text| peak_flow::parse_rdb(&text)) {
+                Ok(records) => records,
+                Err(e) => {
+                    eprintln!("Failed to fetch annual peaks for {}: {}", site_code, e);
+                    continue;
+                }
+            };
+            
+            if self.config.dry_run {
+                stored += records.iter().filter(|r| r.peak_stage_ft().is_some()).count();
+                continue;
+            }
+            let client = &mut *self.db()?;
+            stored += peak_flow::upsert_annual_peaks(client, &records)?;
+        }
+        
+        Ok(stored)
+    }
+    
     // ---------------------------------------------------------------------------
     // USGS Data Warehousing
     // ---------------------------------------------------------------------------
     
     /// Poll a single station for latest data
     pub fn poll_station(&mut self, site_code: &str) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
-        Self::fetch_usgs_readings(site_code)
+        Self::fetch_usgs_readings(
+            site_code,
+            &self.expected_parameters(site_code),
+            self.config.http_max_attempts,
+            self.config.usgs_timeout_secs,
+        )
+    }
+    
+    /// `expected_parameters` of `site_code`, from the loaded stations or,
+    /// for backfill tasks (which load none), the registry. Empty when the
+    /// station is unknown, so requests fall back to discharge and stage.
+    fn expected_parameters(&self, site_code: &str) -> Vec<String> {
+        self.stations.iter()
+            .find(|s| s.site_code == site_code)
+            .map(|s| s.expected_parameters.clone())
+            .or_else(|| stations::find_station(site_code).map(|s| s.expected_parameters))
+            .unwrap_or_default()
     }
 
     /// Static method to fetch USGS readings (can be called from threads)
-    fn fetch_usgs_readings(site_code: &str) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
+    fn fetch_usgs_readings(site_code: &str, parameters: &[String], max_attempts: u32, timeout_secs: u64) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
         // Try instantaneous values with retry logic
-        match Self::fetch_usgs_iv_with_retry(site_code, 3) {
+        match Self::fetch_usgs_iv_with_retry(site_code, parameters, max_attempts, timeout_secs) {
             Ok(readings) => Ok(readings),
             Err(e) => {
                 // If IV endpoint fails, fall back to daily values for last 2 days
                 eprintln!("IV endpoint failed for {}, trying daily values fallback: {}", site_code, e);
-                Self::fetch_usgs_dv_fallback(site_code)
+                Self::fetch_usgs_dv_fallback(site_code, parameters, max_attempts, timeout_secs)
             }
         }
     }
 
-    fn fetch_usgs_iv_with_retry(site_code: &str, max_attempts: u32) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
+    fn fetch_usgs_iv_with_retry(site_code: &str, parameters: &[String], max_attempts: u32, timeout_secs: u64) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
         let url = usgs::build_iv_url(
             &[site_code],
-            &["00060", "00065"], // Discharge and stage
+            &stations::usgs_parameters(parameters),
             "PT4H", // Last 4 hours
         );
         
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(45)) // Increased from 15s
-            .build()?;
+        let client = http_client(timeout_secs)?;
         
-        let mut last_error = None;
+        let response = http::get_with_retry(&client, &url, max_attempts)
+            .map_err(|e| format!("USGS {} IV request failed: {}", site_code, e))?;
         
-        for attempt in 1..=max_attempts {
-            match client.get(&url).send() {
-                Ok(response) => {
-                    if !response.status().is_success() {
-                        return Err(format!("USGS API returned status {}", response.status()).into());
-                    }
-                    
-                    let body = response.text()?;
-                    let readings = usgs::parse_iv_response(&body)?;
-                    
-                    if attempt > 1 {
-                        eprintln!("✓ USGS {} succeeded on attempt {}/{}", site_code, attempt, max_attempts);
-                    }
-                    
-                    return Ok(readings);
-                }
-                Err(e) => {
-                    last_error = Some(e);
-                    if attempt < max_attempts {
-                        let backoff_ms = 1000 * 2_u64.pow(attempt - 1); // Exponential: 1s, 2s, 4s
-                        eprintln!("USGS {} attempt {}/{} failed, retrying in {}ms...", 
-                                 site_code, attempt, max_attempts, backoff_ms);
-                        std::thread::sleep(std::time::Duration::from_millis(backoff_ms));
-                    }
-                }
-            }
+        if !response.status().is_success() {
+            return Err(format!("USGS API returned status {}", response.status()).into());
         }
         
-        Err(format!("USGS {} failed after {} attempts: {}", 
-                   site_code, max_attempts, 
-                   last_error.unwrap()).into())
+        let body = response.text()?;
+        let readings = usgs::parse_iv_response(&body)?;
+        
+        Ok(readings)
     }
 
-    fn fetch_usgs_dv_fallback(site_code: &str) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
+    fn fetch_usgs_dv_fallback(site_code: &str, parameters: &[String], max_attempts: u32, timeout_secs: u64) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
         let now = Utc::now();
         let start_date = (now - chrono::Duration::days(2)).format("%Y-%m-%d").to_string();
         let end_date = now.format("%Y-%m-%d").to_string();
         
         let url = usgs::build_dv_url(
             &[site_code],
-            &["00060", "00065"], // Discharge and stage
+            &stations::usgs_parameters(parameters),
             &start_date,
             &end_date,
         );
         
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(30))
-            .build()?;
+        let client = http_client(timeout_secs)?;
         
-        let response = client.get(&url).send()?;
+        let response = http::get_with_retry(&client, &url, max_attempts)?;
         
         if !response.status().is_success() {
             return Err(format!("USGS DV API returned status {}", response.status()).into());
@@ -841,48 +1936,183 @@ impl Daemon {
     
     /// Warehouse readings into database (idempotent)
     pub fn warehouse_readings(&mut self, readings: &[GaugeReading]) -> Result<usize, Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        let now = self.clock.now();
+        if self.config.dry_run {
+            let mut rows = Vec::new();
+            for reading in readings {
+                let reading_time = parse_reading_time(reading)?;
+                if !monitor::is_future_dated(reading_time, now) {
+                    rows.push((reading.site_code.clone(), reading.parameter_code.clone(), reading_time));
+                }
+            }
+            let would_insert = log_dry_run(logging::DataSource::Usgs, rows);
+            metrics::lock(&self.metrics).record_inserted("usgs", would_insert);
+            return Ok(would_insert);
+        }
+        
+        let client = &mut *self.db()?;
         
+        let mut future_dated = monitor::FutureDatedTally::default();
         let mut inserted = 0;
+        // (reading, reading_time, stored value, stored qualifier) replaced by an approved value
+        let mut revisions = Vec::new();
         
         for reading in readings {
-            // Parse datetime string to DateTime<Utc>
-            // Try RFC3339 first (for instantaneous values with timezone)
-            let reading_time = if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(&reading.datetime) {
-                dt.with_timezone(&Utc)
-            } else {
-                // Fall back to NaiveDateTime for daily values (no timezone)
-                // Assume local time is UTC for daily values
-                let naive = chrono::NaiveDateTime::parse_from_str(&reading.datetime, "%Y-%m-%dT%H:%M:%S%.3f")
-                    .or_else(|_| chrono::NaiveDateTime::parse_from_str(&reading.datetime, "%Y-%m-%d"))
-                    .map_err(|e| format!("Failed to parse datetime '{}': {}", reading.datetime, e))?;
-                chrono::DateTime::<Utc>::from_naive_utc_and_offset(naive, Utc)
-            };
+            let reading_time = parse_reading_time(reading)?;
+            
+            if future_dated.check(reading_time, now) {
+                continue;
+            }
             
             // Convert value to Decimal for PostgreSQL NUMERIC type
             let value_decimal = rust_decimal::Decimal::from_f64_retain(reading.value)
                 .ok_or_else(|| format!("Failed to convert value {} to decimal", reading.value))?;
             
-            // Use INSERT ... ON CONFLICT DO NOTHING for idempotency
-            let rows_affected = client.execute(
-                "INSERT INTO usgs_raw.gauge_readings 
-                 (site_code, parameter_code, unit, value, reading_time, qualifier)
-                 VALUES ($1, $2, $3, $4, $5, $6)
-                 ON CONFLICT (site_code, parameter_code, reading_time) DO NOTHING",
-                &[
-                    &reading.site_code,
-                    &reading.parameter_code,
-                    &reading.unit,
-                    &value_decimal,
-                    &reading_time,
-                    &reading.qualifier,
-                ]
-            )?;
+            let params: [&(dyn postgres::types::ToSql + Sync); 8] = [
+                &reading.site_code,
+                &reading.parameter_code,
+                &reading.unit,
+                &value_decimal,
+                &reading_time,
+                &reading.qualifier,
+                &reading.source.as_str(),
+                &reading.agency_code,
+            ];
             
-            inserted += rows_affected as usize;
+            if self.config.apply_approved_revisions {
+                // Same insert, but an approved value replaces a stored
+                // provisional one; `prior` sees the row as it was before
+                let rows = client.query(
+                    "WITH prior AS (
+                         SELECT value, qualifier FROM usgs_raw.gauge_readings
+                         WHERE agency_code = $8 AND site_code = $1
+                           AND parameter_code = $2 AND reading_time = $5
+                     ), upsert AS (
+                         INSERT INTO usgs_raw.gauge_readings
+                         (site_code, parameter_code, unit, value, reading_time, qualifier, source, agency_code)
+                         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                         ON CONFLICT (agency_code, site_code, parameter_code, reading_time) DO UPDATE
+                             SET value = EXCLUDED.value, qualifier = EXCLUDED.qualifier
+                             WHERE usgs_raw.gauge_readings.qualifier = $9 AND EXCLUDED.qualifier = $10
+                         RETURNING (xmax = 0) AS inserted
+                     )
+                     SELECT upsert.inserted, prior.value, prior.qualifier
+                     FROM upsert LEFT JOIN prior ON TRUE",
+                    &[&params[..], &[&QUALIFIER_PROVISIONAL, &QUALIFIER_APPROVED]].concat()
+                )?;
+                
+                match rows.first() {
+                    Some(row) if row.get::<_, bool>(0) => inserted += 1,
+                    Some(row) => revisions.push((
+                        reading,
+                        reading_time,
+                        row.get::<_, rust_decimal::Decimal>(1),
+                        row.get::<_, String>(2),
+                    )),
+                    None => {}
+                }
+            } else {
+                // Use INSERT ... ON CONFLICT DO NOTHING for idempotency
+                let rows_affected = client.execute(
+                    "INSERT INTO usgs_raw.gauge_readings 
+                     (site_code, parameter_code, unit, value, reading_time, qualifier, source, agency_code)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT (agency_code, site_code, parameter_code, reading_time) DO NOTHING",
+                    &params
+                )?;
+                
+                inserted += rows_affected as usize;
+            }
+        }
+        
+        if !revisions.is_empty() {
+            let site_code = readings.first().map(|r| r.site_code.as_str());
+            let mut changed = 0;
+            let mut max_change = 0.0f64;
+            
+            for (reading, reading_time, old_value, old_qualifier) in &revisions {
+                let old = f64::try_from(*old_value).unwrap_or(reading.value);
+                if (old - reading.value).abs() > 0.0 {
+                    changed += 1;
+                    max_change = max_change.max((old - reading.value).abs());
+                }
+                
+                // The revision itself is already stored; losing its audit
+                // row shouldn't fail the batch
+                if let Err(e) = client.execute(
+                    "INSERT INTO usgs_raw.reading_revisions
+                     (agency_code, site_code, parameter_code, reading_time,
+                      old_value, new_value, old_qualifier, new_qualifier)
+                     VALUES ($1, $2, $3, $4, $5, $6::float8::numeric, $7, $8)",
+                    &[
+                        &reading.agency_code,
+                        &reading.site_code,
+                        &reading.parameter_code,
+                        reading_time,
+                        old_value,
+                        &reading.value,
+                        old_qualifier,
+                        &reading.qualifier,
+                    ]
+                ) {
+                    logging::warn(logging::DataSource::Usgs, Some(&reading.site_code),
+                        &format!("Could not record revision at {}: {}", reading_time, e));
+                }
+            }
+            
+            logging::info(logging::DataSource::Usgs, site_code, &format!(
+                "Applied {} provisional-to-approved revisions ({} changed value, largest change {:.2})",
+                revisions.len(), changed, max_change
+            ));
+        }
+        
+        if let Some(warning) = future_dated.warning() {
+            let site_code = readings.first().map(|r| r.site_code.as_str());
+            logging::warn(logging::DataSource::Usgs, site_code, &warning);
+        }
+        
+        metrics::lock(&self.metrics).record_inserted("usgs", inserted);
+        Ok(inserted)
+    }
+    
+    /// Queue readings that could not be warehoused in the write buffer.
+    /// Returns false when no buffer is configured or it can't be written,
+    /// in which case the readings are lost as before.
+    fn buffer_readings(&self, readings: &[GaugeReading]) -> bool {
+        let Some(buffer) = self.config.write_buffer() else {
+            return false;
+        };
+        
+        match buffer.append(readings) {
+            Ok(dropped) => {
+                if dropped > 0 {
+                    eprintln!("Warning: Write buffer full; dropped {} oldest readings", dropped);
+                }
+                true
+            }
+            Err(e) => {
+                eprintln!("Warning: Could not write to {}: {}", buffer.path().display(), e);
+                false
+            }
         }
+    }
+    
+    /// Warehouse anything left in the write buffer by an earlier database
+    /// outage, and empty it once that succeeds. Inserts are idempotent, so
+    /// readings that did land the first time are skipped.
+    fn replay_write_buffer(&mut self) -> Result<usize, Box<dyn Error>> {
+        let Some(buffer) = self.config.write_buffer().filter(|_| !self.config.dry_run) else {
+            return Ok(0);
+        };
         
+        let buffered = buffer.load()?;
+        if buffered.is_empty() {
+            return Ok(0);
+        }
+        
+        let inserted = self.warehouse_readings(&buffered)?;
+        buffer.clear()?;
+        println!("✓ Replayed {} buffered readings ({} new)", buffered.len(), inserted);
         Ok(inserted)
     }
     
@@ -892,8 +2122,10 @@ impl Daemon {
         site_code: &str, 
         last_reading_time: Option<DateTime<Utc>>
     ) -> Result<(), Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        if self.config.dry_run {
+            return Ok(());
+        }
+        let client = &mut *self.db()?;
         
         // Update or insert monitoring state
         client.execute(
@@ -904,16 +2136,235 @@ impl Daemon {
                 last_poll_attempted = EXCLUDED.last_poll_attempted,
                 latest_reading_time = EXCLUDED.latest_reading_time,
                 consecutive_failures = 0",
-            &[&site_code, &Utc::now(), &last_reading_time]
+            &[&site_code, &self.clock.now(), &last_reading_time]
         )?;
         
         Ok(())
     }
     
+    /// Re-open live flood events left ongoing in the database, so a
+    /// restart mid-flood extends them instead of starting new ones.
+    fn resume_flood_events(&mut self) -> Result<usize, Box<dyn Error>> {
+        let thresholds: HashMap<String, FloodThresholds> = self.stations.iter()
+            .filter_map(|s| s.thresholds.clone().map(|t| (s.site_code.clone(), t)))
+            .collect();
+        let client = &mut *self.db()?;
+        
+        let events = flood_events::load_open_events(client, &thresholds)?;
+        let count = events.len();
+        for event in events {
+            self.flood_events.resume(event);
+        }
+        Ok(count)
+    }
+    
+    /// Feed a stage reading to the live flood-event detector and write any
+    /// resulting promotion, new crest, or end to `nws.flood_events`.
+    fn track_flood_event(&mut self, reading: &GaugeReading, thresholds: &FloodThresholds) -> Result<(), Box<dyn Error>> {
+        let Ok(time) = chrono::DateTime::parse_from_rfc3339(&reading.datetime) else {
+            return Ok(());
+        };
+        
+        let gate = self.config.flood_event_gate();
+        let update = self.flood_events.observe(
+            &reading.site_code,
+            reading.value,
+            time.with_timezone(&Utc),
+            thresholds.flood_stage_ft,
+            &gate,
+        );
+        if self.config.dry_run {
+            return Ok(());
+        }
+        
+        let client = &mut *self.db()?;
+        match update {
+            FloodEventUpdate::None => {}
+            FloodEventUpdate::Promote(event) => {
+                let id = flood_events::insert_event(client, &event, flood_events::event_severity(event.peak_ft, thresholds))?;
+                self.flood_events.mark_persisted(&event.site_code, id);
+                println!("🌊 Flood event opened at {} (crest {:.2} ft)", event.site_code, event.peak_ft);
+            }
+            FloodEventUpdate::NewCrest(event) => {
+                flood_events::update_crest(client, &event, flood_events::event_severity(event.peak_ft, thresholds))?;
+            }
+            FloodEventUpdate::End(event, end) => {
+                flood_events::close_event(client, &event, end)?;
+                println!("🌊 Flood event ended at {} (crest {:.2} ft)", event.site_code, event.peak_ft);
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Evaluate LaGrange backwater onset and alert if configured.
+    fn check_backwater_onset(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some((window_hours, tolerance)) = self.notifier.as_ref()
+            .and_then(|n| n.config().backwater_onset.as_ref())
+            .map(|cfg| (cfg.window_hours, Duration::minutes(cfg.align_tolerance_minutes)))
+        else {
+            return Ok(());
+        };
+        
+        let client = &mut *self.db()?;
+        
+        if let Some(onset) = backwater::backwater_onset(client, window_hours, tolerance, self.clock.now())?
+            && let Some(notifier) = self.notifier.as_mut()
+        {
+            notifier.process_backwater_onset(&onset);
+        }
+        
+        Ok(())
+    }
+    
+    /// Check every station with flood thresholds for sustained high water
+    /// and alert if configured.
+    fn check_sustained_high_water(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(cfg) = self.notifier.as_ref()
+            .and_then(|n| n.config().sustained_high_water.clone())
+        else {
+            return Ok(());
+        };
+        
+        let now = self.clock.now();
+        let client = &mut *self.db()?;
+        
+        for station in &self.stations {
+            let Some(thresholds) = &station.thresholds else {
+                continue;
+            };
+            let threshold_ft = cfg.threshold_ft.unwrap_or(thresholds.action_stage_ft);
+            
+            let event = sustained::sustained_high_water(&station.site_code, threshold_ft, cfg.min_hours, now, client)?;
+            if let Some(notifier) = self.notifier.as_mut() {
+                notifier.process_sustained_high_water(&station.site_code, &station.name, event.as_ref());
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Check every station with flood thresholds for a stage rising toward
+    /// flood stage and alert if configured. Stations already at or above
+    /// flood stage are left to the crossing alerts.
+    fn check_approaching_flood_stage(&mut self) -> Result<(), Box<dyn Error>> {
+        let Some(cfg) = self.notifier.as_ref()
+            .and_then(|n| n.config().approaching_flood_stage.clone())
+        else {
+            return Ok(());
+        };
+        
+        let now = self.clock.now();
+        let since = now - Duration::hours(cfg.window_hours);
+        let client = &mut *self.db()?;
+        
+        for station in &self.stations {
+            let Some(thresholds) = &station.thresholds else {
+                continue;
+            };
+            
+            let rows = client.query(
+                "SELECT reading_time, value::float8 FROM usgs_raw.gauge_readings
+                 WHERE site_code = $1 AND parameter_code = $2
+                   AND reading_time > $3 AND reading_time <= $4
+                 ORDER BY reading_time ASC",
+                &[&station.site_code, &PARAM_STAGE, &since, &now]
+            )?;
+            let series: Vec<TimedValue> = rows.iter()
+                .map(|row| TimedValue { timestamp: row.get(0), value: row.get(1) })
+                .collect();
+            let Some(latest) = series.last() else {
+                continue;
+            };
+            if latest.value >= thresholds.flood_stage_ft {
+                continue;
+            }
+            
+            let alert = check_approaching_flood_stage(
+                &station.name,
+                latest.value,
+                backwater::onset_rate(&series),
+                thresholds,
+                cfg.margin_ft,
+                cfg.min_rate_ft_per_hour,
+            );
+            if let Some(notifier) = self.notifier.as_mut() {
+                notifier.process_approaching_flood_stage(&station.site_code, &station.name, latest.value, alert.as_ref());
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Compute the current basin status from every configured sensor and
+    /// store it in `basin_status_history`.
+    fn record_basin_status(&mut self) -> Result<(), Box<dyn Error>> {
+        if self.config.dry_run {
+            return Ok(());
+        }
+        let client = &mut *self.db()?;
+        
+        let zones_config = zones::cached_zones()?;
+        let status = status::basin_status(client, &zones_config)?;
+        status::record_basin_status(client, &status)?;
+        Ok(())
+    }
+    
+    /// Remember which stations a previous run left degraded, so
+    /// `check_flatline` can mark them active again once they move.
+    fn load_flatlined_sites(&mut self) -> Result<(), Box<dyn Error>> {
+        let client = &mut *self.db()?;
+        let rows = client.query(
+            "SELECT site_code FROM usgs_raw.monitoring_state WHERE status = 'degraded'",
+            &[]
+        )?;
+        self.flatlined = rows.iter().map(|row| row.get(0)).collect();
+        Ok(())
+    }
+    
+    /// Mark a station degraded when its stage sensor flatlines, and active
+    /// again once it moves. Only called after a poll that stored new
+    /// readings; the status is written only on those two transitions.
+    fn check_flatline(&mut self, site_code: &str) -> Result<(), Box<dyn Error>> {
+        if self.config.dry_run {
+            return Ok(());
+        }
+        let min_repeats = self.config.flatline_min_repeats;
+        let client = &mut *self.db()?;
+        
+        let flatlined = monitor::query_flatline(site_code, PARAM_STAGE, client, min_repeats)?;
+        let status = match (flatlined, self.flatlined.contains(site_code)) {
+            (true, false) => {
+                eprintln!("⚠ {} stage unchanged for more than {} readings — possible frozen sensor", site_code, min_repeats);
+                StationStatus::Degraded
+            }
+            (false, true) => {
+                println!("✓ {} stage moving again", site_code);
+                StationStatus::Active
+            }
+            _ => return Ok(()),
+        };
+        
+        monitor::update_station_status(client, site_code, &status)?;
+        if flatlined {
+            self.flatlined.insert(site_code.to_string());
+        } else {
+            self.flatlined.remove(site_code);
+        }
+        if let Some(notifier) = self.notifier.as_mut() {
+            let reason = format!("stage unchanged for more than {} readings, possible frozen sensor", min_repeats);
+            notifier.process_sensor_degraded(site_code, flatlined.then_some(reason.as_str()));
+        }
+        Ok(())
+    }
+    
     /// Record a polling failure
     pub fn record_failure(&mut self, site_code: &str) -> Result<(), Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        metrics::lock(&self.metrics).record_poll_failure("usgs", site_code);
+        if self.config.dry_run {
+            return Ok(());
+        }
+        let client = &mut *self.db()?;
         
         client.execute(
             "INSERT INTO usgs_raw.monitoring_state 
@@ -922,7 +2373,7 @@ impl Daemon {
              ON CONFLICT (site_code) DO UPDATE SET
                 last_poll_attempted = EXCLUDED.last_poll_attempted,
                 consecutive_failures = monitoring_state.consecutive_failures + 1",
-            &[&site_code, &Utc::now()]
+            &[&site_code, &self.clock.now()]
         )?;
         
         Ok(())
@@ -936,10 +2387,12 @@ impl Daemon {
         last_reading_time: Option<DateTime<Utc>>,
         inserted_count: usize
     ) -> Result<(), Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        if self.config.dry_run {
+            return Ok(());
+        }
+        let client = &mut *self.db()?;
         
-        let now = Utc::now();
+        let now = self.clock.now();
         
         // Update last_successful_warehouse only if we inserted new data
         if inserted_count > 0 {
@@ -984,8 +2437,10 @@ impl Daemon {
         station_id: &str,
         error: &str
     ) -> Result<(), Box<dyn Error>> {
-        let client = self.client.as_mut()
-            .ok_or("Daemon not initialized")?;
+        if self.config.dry_run {
+            return Ok(());
+        }
+        let client = &mut *self.db()?;
         
         client.execute(
             "INSERT INTO public.station_health 
@@ -995,18 +2450,55 @@ impl Daemon {
                 consecutive_failures = station_health.consecutive_failures + 1,
                 last_error = EXCLUDED.last_error,
                 updated_at = EXCLUDED.updated_at",
-            &[&source_type, &station_id, &error, &Utc::now()]
+            &[&source_type, &station_id, &error, &self.clock.now()]
         )?;
         
         Ok(())
     }
     
+    /// Queue `start..end` for a USGS station in `backfill_queue`, split into
+    /// pages of at most `max_backfill_days`. Pages already queued and not
+    /// yet completed are skipped, so re-running a backfill doesn't duplicate
+    /// them.
+    fn queue_deferred_history(
+        &mut self,
+        site_code: &str,
+        start: DateTime<Utc>,
+        end: DateTime<Utc>,
+    ) -> Result<usize, Box<dyn Error>> {
+        let pages = backfill_pages(start, end, self.config.max_backfill_days);
+        if self.config.dry_run {
+            println!("   Dry run: would queue {} page(s) of older history", pages.len());
+            return Ok(pages.len());
+        }
+        let client = &mut *self.db()?;
+        
+        let mut queued = 0;
+        for (gap_start, gap_end) in &pages {
+            queued += client.execute(
+                "INSERT INTO public.backfill_queue (source_type, station_id, gap_start, gap_end, priority)
+                 SELECT 'USGS', $1, $2, $3, $4
+                 WHERE NOT EXISTS (
+                     SELECT 1 FROM public.backfill_queue
+                     WHERE source_type = 'USGS' AND station_id = $1
+                       AND gap_start = $2 AND gap_end = $3
+                       AND status IN ('pending', 'in_progress')
+                 )",
+                &[&site_code, gap_start, gap_end, &DEFERRED_HISTORY_PRIORITY]
+            )? as usize;
+        }
+        
+        println!("   Queued {} page(s) of older history ({} to {}) for later cycles",
+                queued, start.format("%Y-%m-%d"), end.format("%Y-%m-%d"));
+        
+        Ok(queued)
+    }
+    
     /// Process backfill queue (run periodically, e.g. once per hour)
     pub fn process_backfill_queue(&mut self, max_items: usize) -> Result<usize, Box<dyn Error>> {
         // First, fetch all pending items (read-only operation)
         let pending_items: Vec<(i32, String, String, DateTime<Utc>, DateTime<Utc>)> = {
-            let client = self.client.as_mut()
-                .ok_or("Daemon not initialized")?;
+            let client = &mut *self.db()?;
             
             let rows = client.query(
                 "SELECT id, source_type, station_id, gap_start, gap_end
@@ -1027,18 +2519,25 @@ impl Daemon {
         for (queue_id, source_type, station_id, gap_start, gap_end) in pending_items {
             // Mark as in_progress
             {
-                let client = self.client.as_mut().unwrap();
+                let client = &mut *self.db()?;
                 client.execute(
                     "UPDATE public.backfill_queue 
                      SET status = 'in_progress', last_attempt_at = $1, attempts = attempts + 1
                      WHERE id = $2",
-                    &[&Utc::now(), &queue_id]
+                    &[&self.clock.now(), &queue_id]
                 )?;
             }
             
-            // Fetch data (no self.client borrow here)
+            // Fetch data (no connection held here)
             let fetch_result = match source_type.as_str() {
-                "USGS" => Self::fetch_usgs_dv_gap(&station_id, gap_start, gap_end),
+                "USGS" => Self::fetch_usgs_dv_gap(
+                    &station_id,
+                    &self.expected_parameters(&station_id),
+                    gap_start,
+                    gap_end,
+                    self.config.http_max_attempts,
+                    self.config.usgs_backfill_timeout_secs * QUEUED_GAP_TIMEOUT_MULTIPLIER,
+                ),
                 "CWMS" => {
                     eprintln!("CWMS backfill not yet implemented for {}", station_id);
                     Ok(Vec::new())
@@ -1050,7 +2549,7 @@ impl Daemon {
                 _ => Err(format!("Unknown source type: {}", source_type).into())
             };
             
-            // Now warehouse the results (separate self.client borrow)
+            // Now warehouse the results (separate checkout)
             match fetch_result {
                 Ok(readings) => {
                     let inserted = if !readings.is_empty() {
@@ -1060,12 +2559,12 @@ impl Daemon {
                     };
                     
                     // Mark as completed
-                    let client = self.client.as_mut().unwrap();
+                    let client = &mut *self.db()?;
                     client.execute(
                         "UPDATE public.backfill_queue 
                          SET status = 'completed', completed_at = $1
                          WHERE id = $2",
-                        &[&Utc::now(), &queue_id]
+                        &[&self.clock.now(), &queue_id]
                     )?;
                     
                     // Record in history
@@ -1085,7 +2584,7 @@ impl Daemon {
                 Err(e) => {
                     // Mark as failed but allow retry
                     let error_msg = format!("{}", e);
-                    let client = self.client.as_mut().unwrap();
+                    let client = &mut *self.db()?;
                     client.execute(
                         "UPDATE public.backfill_queue 
                          SET status = 'failed', last_error = $1
@@ -1104,24 +2603,29 @@ impl Daemon {
     /// Fetch USGS daily values for a gap (static method for use in backfill)
     fn fetch_usgs_dv_gap(
         site_code: &str,
+        parameters: &[String],
         gap_start: DateTime<Utc>,
-        gap_end: DateTime<Utc>
+        gap_end: DateTime<Utc>,
+        max_attempts: u32,
+        timeout_secs: u64,
     ) -> Result<Vec<GaugeReading>, Box<dyn Error>> {
-        let start_date = gap_start.format("%Y-%m-%d").to_string();
-        let end_date = gap_end.format("%Y-%m-%d").to_string();
+        // Queued pages are contiguous; each leaves its end day to the next
+        let Some((start_date, end_date)) = usgs::dv_date_range(gap_start, gap_end) else {
+            return Ok(Vec::new());
+        };
+        let start_date = start_date.format("%Y-%m-%d").to_string();
+        let end_date = end_date.format("%Y-%m-%d").to_string();
         
         let url = usgs::build_dv_url(
             &[site_code],
-            &["00060", "00065"], // Discharge and stage
+            &stations::usgs_parameters(parameters),
             &start_date,
             &end_date,
         );
         
-        let client = reqwest::blocking::Client::builder()
-            .timeout(std::time::Duration::from_secs(60))
-            .build()?;
+        let client = http_client(timeout_secs)?;
         
-        let response = client.get(&url).send()?;
+        let response = http::get_with_retry(&client, &url, max_attempts)?;
         
         if !response.status().is_success() {
             return Err(format!("USGS DV API returned status {}", response.status()).into());
@@ -1133,30 +2637,95 @@ impl Daemon {
         Ok(readings)
     }
     
-    /// Run one iteration of the monitoring loop for all stations
+    /// Whether `site_code` is due for a USGS poll at `now`
+    fn is_due(&self, site_code: &str, now: DateTime<Utc>) -> bool {
+        self.next_poll.get(site_code).is_none_or(|due| *due <= now)
+    }
+    
+    /// Schedule `site_code`'s next USGS poll from its latest stage.
+    fn schedule_next_poll(&mut self, site_code: &str, stage_ft: Option<f64>, now: DateTime<Utc>) {
+        let thresholds = self.stations.iter()
+            .find(|s| s.site_code == site_code)
+            .and_then(|s| s.thresholds.as_ref());
+        let priority = PollPriority::from_stage(stage_ft, thresholds);
+        let minutes = priority.poll_interval_minutes(self.config.poll_interval_minutes);
+        self.next_poll.insert(site_code.to_string(), now + Duration::minutes(minutes as i64));
+    }
+    
+    /// Earliest time any station or source is next due; now if any is
+    /// unscheduled.
+    fn next_due(&self) -> DateTime<Utc> {
+        let now = self.clock.now();
+        let Some(full) = self.next_full_poll else {
+            return now;
+        };
+        self.stations.iter()
+            .map(|s| self.next_poll.get(&s.site_code).copied().unwrap_or(now))
+            .fold(full, DateTime::min)
+    }
+    
+    /// Run one iteration of the monitoring loop: every USGS station that is
+    /// due (see `PollPriority`), plus CWMS, ASOS, and NWS when their
+    /// regular interval is up
     pub fn poll_all_stations(&mut self) -> Result<HashMap<String, usize>, Box<dyn Error>> {
         let mut results = HashMap::new();
+        self.poll_failures.clear();
+        let cycle_start = self.clock.now();
+        let poll_others = self.next_full_poll.is_none_or(|due| due <= cycle_start);
+        self.full_cycle = poll_others;
+        
+        // Pick up any revised NWS thresholds before evaluating alerts
+        if let Err(e) = self.refresh_thresholds(false) {
+            eprintln!("Warning: Could not refresh flood thresholds ({}) — keeping current values", e);
+        }
         
-        // Poll USGS stations in parallel using thread pool
-        let stations_snapshot = self.stations.clone();
-        let (tx, rx) = mpsc::channel();
+        // Readings an earlier outage kept out of the database
+        if let Err(e) = self.replay_write_buffer() {
+            eprintln!("Warning: Could not replay write buffer ({}) — keeping it for next cycle", e);
+        }
         
-        // Submit all USGS polls to thread pool
-        for station in &stations_snapshot {
-            let site_code = station.site_code.clone();
-            let tx = tx.clone();
-            
-            self.thread_pool.execute(move || {
-                // Fetch readings and convert error to String for Send compatibility
-                let result = Self::fetch_usgs_readings(&site_code)
+        // Fetch every source on the bounded pool at once; errors become
+        // Strings to cross threads
+        let max_attempts = self.config.http_max_attempts;
+        let stations_snapshot: Vec<Station> = self.stations.iter()
+            .filter(|s| self.is_due(&s.site_code, cycle_start))
+            .cloned()
+            .collect();
+        let cwms_due = if poll_others { self.cwms_locations.clone() } else { Vec::new() };
+        let asos_due = if poll_others { self.asos_locations.clone() } else { Vec::new() };
+        let usgs_timeout_secs = self.config.usgs_timeout_secs;
+        let usgs_fetches = spawn_fetches(
+            &self.thread_pool,
+            stations_snapshot.iter().map(|s| (s.site_code.clone(), s.expected_parameters.clone())).collect(),
+            move |(site_code, parameters): (String, Vec<String>)| {
+                let result = Self::fetch_usgs_readings(&site_code, &parameters, max_attempts, usgs_timeout_secs)
                     .map_err(|e| e.to_string());
-                tx.send((site_code, result)).expect("Failed to send result");
-            });
-        }
-        drop(tx); // Drop original sender so rx knows when all threads are done
+                (site_code, result)
+            },
+        );
+        
+        let cwms_timeout_secs = self.config.cwms_timeout_secs;
+        let cwms_fetches = spawn_fetches(
+            &self.thread_pool,
+            cwms_due,
+            move |location: UsaceLocation| {
+                let result = Self::fetch_cwms_location(&location, cwms_timeout_secs, max_attempts);
+                (location, result)
+            },
+        );
+        
+        let iem_timeout_secs = self.config.iem_timeout_secs;
+        let asos_fetches = spawn_fetches(
+            &self.thread_pool,
+            asos_due.iter().map(|l| l.station_id.clone()).collect(),
+            move |station_id: String| {
+                let result = Self::fetch_asos_station(&station_id, iem_timeout_secs, max_attempts);
+                (station_id, result)
+            },
+        );
         
-        // Collect results and warehouse them sequentially (database writes must be sequential)
-        for (site_code, fetch_result) in rx {
+        // Warehouse sequentially, in configuration order
+        for (site_code, fetch_result) in usgs_fetches.wait() {
             let station = stations_snapshot.iter().find(|s| s.site_code == site_code);
             
             // Convert String error back to Box<dyn Error> for compatibility with existing code
@@ -1164,7 +2733,18 @@ impl Daemon {
             
             match fetch_result {
                 Ok(readings) => {
-                    let inserted = self.warehouse_readings(&readings)?;
+                    let inserted = match self.warehouse_readings(&readings) {
+                        Ok(inserted) => inserted,
+                        // Keep what was fetched; the rest of this station's
+                        // bookkeeping needs the database too
+                        Err(e) if self.buffer_readings(&readings) => {
+                            eprintln!("Buffered {} readings for {} after warehouse failure: {}", readings.len(), site_code, e);
+                            results.insert(format!("USGS:{}", site_code), 0);
+                            self.schedule_next_poll(&site_code, latest_stage(&readings, cycle_start), cycle_start);
+                            continue;
+                        }
+                        Err(e) => return Err(e),
+                    };
 
                     // Fire SMS alerts for stage readings that have configured thresholds.
                     if let Some(station) = station {
@@ -1174,18 +2754,34 @@ impl Daemon {
                                     notifier.process_reading_alert(reading, thresholds);
                                 }
                             }
+                            for reading in readings.iter().filter(|r| r.parameter_code == PARAM_STAGE) {
+                                if let Err(e) = self.track_flood_event(reading, thresholds) {
+                                    eprintln!("Warning: Flood event tracking failed for {}: {}", site_code, e);
+                                }
+                            }
                         }
                     }
 
-                    // Get latest timestamp from readings
+                    // Get latest timestamp from readings, ignoring any that
+                    // warehousing rejected as future-dated
+                    let now = self.clock.now();
                     let latest = readings.iter()
                         .filter_map(|r| chrono::DateTime::parse_from_rfc3339(&r.datetime).ok())
                         .map(|dt| dt.with_timezone(&Utc))
+                        .filter(|dt| !monitor::is_future_dated(*dt, now))
                         .max();
 
+                    if let Some(latest) = latest {
+                        metrics::lock(&self.metrics).record_latest_reading(&site_code, latest);
+                    }
                     self.update_monitoring_state(&site_code, latest)?;
+                    if inserted > 0
+                        && let Err(e) = self.check_flatline(&site_code) {
+                        eprintln!("Warning: Flatline check failed for {}: {}", site_code, e);
+                    }
                     self.update_station_health_success("USGS", &site_code, latest, inserted)?;
                     results.insert(format!("USGS:{}", site_code), inserted);
+                    self.schedule_next_poll(&site_code, latest_stage(&readings, now), cycle_start);
                 }
                 Err(e) => {
                     let error_msg = format!("{}", e);
@@ -1193,13 +2789,17 @@ impl Daemon {
                     self.record_failure(&site_code)?;
                     self.update_station_health_failure("USGS", &site_code, &error_msg)?;
                     results.insert(format!("USGS:{}", site_code), 0);
+                    self.poll_failures.push(format!("USGS:{}", site_code));
+                    self.schedule_next_poll(&site_code, None, cycle_start);
                 }
             }
         }
         
-        // Poll CWMS locations
-        for location in &self.cwms_locations.clone() {
-            match self.poll_cwms_location(&location) {
+        for (location, fetch_result) in cwms_fetches.wait() {
+            let warehoused = fetch_result
+                .map_err(|e| -> Box<dyn Error> { e.into() })
+                .and_then(|series| self.warehouse_cwms_series(&series));
+            match warehoused {
                 Ok(inserted) => {
                     // Update station health for CWMS
                     self.update_station_health_success("CWMS", &location.cwms_location, None, inserted)?;
@@ -1208,42 +2808,84 @@ impl Daemon {
                 Err(e) => {
                     let error_msg = format!("{}", e);
                     eprintln!("Failed to poll CWMS {}: {}", location.name, error_msg);
+                    metrics::lock(&self.metrics).record_poll_failure("cwms", &location.cwms_location);
                     self.update_station_health_failure("CWMS", &location.cwms_location, &error_msg)?;
                     results.insert(format!("CWMS:{}", location.name), 0);
+                    self.poll_failures.push(format!("CWMS:{}", location.name));
                 }
             }
         }
         
-        // Poll ASOS stations (based on priority)
-        for location in &self.asos_locations.clone() {
-            match self.poll_asos_station(&location.station_id) {
-                Ok(observations) => {
-                    let inserted = self.warehouse_asos_observations(&observations)?;
-                    self.update_station_health_success("ASOS", &location.station_id, None, inserted)?;
-                    results.insert(format!("ASOS:{}", location.station_id), inserted);
+        for (station_id, fetch_result) in asos_fetches.wait() {
+            match fetch_result {
+                Ok(response) => {
+                    // A header-only response is a quiet station, not an
+                    // outage: still a successful poll
+                    if response.is_empty() {
+                        println!("   ASOS {}: no observations in the poll window", station_id);
+                    }
+                    let inserted = self.warehouse_asos_observations(&response.into_observations())?;
+                    self.update_station_health_success("ASOS", &station_id, None, inserted)?;
+                    results.insert(format!("ASOS:{}", station_id), inserted);
                 }
-                Err(e) => {
-                    let error_msg = format!("{}", e);
-                    eprintln!("Failed to poll ASOS {}: {}", location.station_id, error_msg);
-                    self.update_station_health_failure("ASOS", &location.station_id, &error_msg)?;
-                    results.insert(format!("ASOS:{}", location.station_id), 0);
+                Err(error_msg) => {
+                    eprintln!("Failed to poll ASOS {}: {}", station_id, error_msg);
+                    metrics::lock(&self.metrics).record_poll_failure("asos", &station_id);
+                    self.update_station_health_failure("ASOS", &station_id, &error_msg)?;
+                    results.insert(format!("ASOS:{}", station_id), 0);
+                    self.poll_failures.push(format!("ASOS:{}", station_id));
                 }
             }
         }
         
+        if poll_others {
+            // Official NWS flood alerts (for cross-checking computed status)
+            match self.poll_nws_alerts() {
+                Ok(count) if count > 0 => println!("⚠ {} active NWS flood alert(s) for monitored counties", count),
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to poll NWS alerts: {}", e),
+            }
+            
+            // Official NWS stage forecasts (stored as issued)
+            match self.poll_nws_forecasts() {
+                Ok(count) if count > 0 => println!("✓ {} new NWS forecast point(s)", count),
+                Ok(_) => {}
+                Err(e) => eprintln!("Failed to poll NWS forecasts: {}", e),
+            }
+            
+            self.next_full_poll = Some(cycle_start + Duration::minutes(self.config.poll_interval_minutes as i64));
+        }
+        
+        metrics::lock(&self.metrics).record_poll(self.clock.now());
         Ok(results)
     }
     
-    /// Main daemon loop (runs indefinitely)
+    /// Main daemon loop: poll whatever is due (USGS stations every 5 to 30
+    /// minutes depending on stage, everything else every
+    /// `poll_interval_minutes`) until shutdown is requested, then
+    /// finish the cycle in progress, flush pending webhook summaries, close
+    /// idle database connections, and return `Ok(())`.
     pub fn run(&mut self) -> Result<(), Box<dyn Error>> {
         println!("🚀 Starting daemon loop...");
-        println!("   Poll interval: {} minutes", self.config.poll_interval_minutes);
+        println!("   Poll interval: {} minutes (USGS: {} at/above action stage, {} well below)",
+                self.config.poll_interval_minutes,
+                PollPriority::Flood.poll_interval_minutes(self.config.poll_interval_minutes),
+                PollPriority::Quiet.poll_interval_minutes(self.config.poll_interval_minutes));
         println!("   Monitoring {} USGS stations + {} CWMS locations + {} ASOS stations", 
                 self.stations.len(), self.cwms_locations.len(), self.asos_locations.len());
+        if let Some(webhook) = &self.poll_webhook {
+            println!("   Poll summaries POSTed to {}", webhook.url());
+        }
+        if let Some(path) = &self.config.heartbeat_path {
+            println!("   Heartbeat file: {}", path.display());
+        }
+        if self.config.dry_run {
+            println!("   DRY RUN: nothing will be written to the database");
+        }
         
-        loop {
-            let start = Utc::now();
-            
+        let mut cycles = 0;
+        while !self.shutdown_requested() {
+            cycles += 1;
             match self.poll_all_stations() {
                 Ok(results) => {
                     let total: usize = results.values().sum();
@@ -1252,32 +2894,120 @@ impl Daemon {
                     let asos_count = results.iter().filter(|(k, _)| k.starts_with("ASOS:")).count();
                     println!("✓ Poll complete: {} new readings ({} USGS, {} CWMS, {} ASOS)",
                             total, usgs_count, cwms_count, asos_count);
+                    
+                    if let Some(webhook) = self.poll_webhook.as_mut() {
+                        webhook.push(PollSummary::from_results(&results, &self.poll_failures, self.clock.now()));
+                    }
+                    
+                    if let Some(path) = &self.config.heartbeat_path
+                        && let Err(e) = touch_heartbeat(path, self.clock.now()) {
+                        eprintln!("Warning: Could not update heartbeat {}: {}", path.display(), e);
+                    }
                 }
                 Err(e) => {
                     eprintln!("✗ Poll error: {}", e);
                 }
             }
 
+            // Page in deferred history a little at a time
+            if !self.config.dry_run
+                && let Err(e) = self.process_backfill_queue(BACKFILL_PAGES_PER_CYCLE) {
+                eprintln!("Warning: Backfill queue processing failed: {}", e);
+            }
+
+            // Basin-wide work runs once per full poll, not on every wake-up
+            // for a USGS site polled early at flood priority
+            if self.full_cycle {
+                // Snapshot the computed basin status for /status/history
+                if let Err(e) = self.record_basin_status() {
+                    eprintln!("Warning: Failed to record basin status: {}", e);
+                }
+
+                // Rapid backwater onset (basin-level, not tied to one gauge)
+                if let Err(e) = self.check_backwater_onset() {
+                    eprintln!("Warning: Backwater onset check failed: {}", e);
+                }
+
+                // Prolonged time near action stage (nuisance flooding)
+                if let Err(e) = self.check_sustained_high_water() {
+                    eprintln!("Warning: Sustained high water check failed: {}", e);
+                }
+
+                // Heads-up while a gauge rises toward flood stage
+                if let Err(e) = self.check_approaching_flood_stage() {
+                    eprintln!("Warning: Approaching flood stage check failed: {}", e);
+                }
+            }
+
+            // Release any non-critical alerts held over quiet hours
+            if let Some(notifier) = self.notifier.as_mut() {
+                notifier.flush_deferred();
+            }
+
             // Daily digest: send once per day at the configured UTC hour.
-            let hour_utc = Utc::now().hour();
-            if let Some(ref notifier) = self.notifier {
-                let digest_hour = notifier.config().daily_digest_hour_utc;
-                if digest_hour >= 0 && hour_utc == digest_hour as u32 {
-                    if let Err(e) = notifier.send_daily_digest(&[]) {
-                        eprintln!("Warning: Failed to send daily digest: {}", e);
-                    }
+            let now = self.clock.now();
+            if let Some(ref notifier) = self.notifier
+                && digest_due(notifier.config().daily_digest_hour_utc, self.last_digest, now)
+            {
+                // Attempted once a day; a failure isn't retried every wake-up
+                self.last_digest = Some(now.date_naive());
+                if let Err(e) = notifier.send_daily_digest(&[]) {
+                    eprintln!("Warning: Failed to send daily digest: {}", e);
                 }
             }
             
-            // Sleep until next poll interval
-            let elapsed = (Utc::now() - start).num_seconds();
-            let sleep_seconds = (self.config.poll_interval_minutes * 60) as i64 - elapsed;
-            
-            if sleep_seconds > 0 {
-                std::thread::sleep(std::time::Duration::from_secs(sleep_seconds as u64));
+            // Sleep until the next station or source is due. Whole seconds
+            // would wake just short of it, find nothing due, and rerun the
+            // cycle's alert checks and status snapshot until it arrived.
+            if let Ok(wait) = (self.next_due() - self.clock.now()).to_std() {
+                self.sleep_unless_shutdown(wait);
+            }
+        }
+        
+        self.shut_down(cycles);
+        Ok(())
+    }
+    
+    /// Sleep for `duration`, waking early if shutdown is requested.
+    fn sleep_unless_shutdown(&self, duration: std::time::Duration) {
+        let deadline = std::time::Instant::now() + duration;
+        while !self.shutdown_requested() {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            if remaining.is_zero() {
+                return;
             }
+            std::thread::sleep(remaining.min(SHUTDOWN_CHECK_INTERVAL));
         }
     }
+    
+    /// Release what `run` holds once the loop has stopped after `cycles`
+    /// poll cycles.
+    fn shut_down(&mut self, cycles: u64) {
+        if cycles == 0 {
+            println!("🛑 Shutdown requested before the first poll cycle — stopping");
+        } else {
+            println!("🛑 Shutdown requested — stopping after {} poll cycle(s)", cycles);
+        }
+        
+        if let Some(webhook) = self.poll_webhook.as_mut() {
+            webhook.close();
+        }
+        
+        // Let in-flight poll threads finish before closing connections
+        self.thread_pool.join();
+        
+        if let Some(pool) = self.db.take() {
+            pool.close_idle();
+        }
+        
+        println!("✓ Daemon stopped");
+    }
+}
+
+/// Rewrite the heartbeat file with `now`, bumping its mtime. Watchdogs only
+/// need the mtime; the timestamp inside is for whoever opens the file.
+pub fn touch_heartbeat(path: &std::path::Path, now: DateTime<Utc>) -> std::io::Result<()> {
+    std::fs::write(path, format!("{}\n", now.to_rfc3339()))
 }
 
 // ---------------------------------------------------------------------------
@@ -1294,6 +3024,7 @@ mod tests {
         assert_eq!(daemon.config.poll_interval_minutes, 15);
         assert_eq!(daemon.config.staleness_threshold_minutes, 60);
         assert_eq!(daemon.config.backfill_days, 120);
+        assert!(!daemon.config.apply_approved_revisions);
     }
     
     #[test]
@@ -1302,12 +3033,250 @@ mod tests {
             poll_interval_minutes: 5,
             staleness_threshold_minutes: 30,
             backfill_days: 30,
+            cwms_timeout_secs: 60,
+            ..DaemonConfig::default()
         };
         
         let daemon = Daemon::with_config(config);
         assert_eq!(daemon.config.poll_interval_minutes, 5);
         assert_eq!(daemon.config.staleness_threshold_minutes, 30);
         assert_eq!(daemon.config.backfill_days, 30);
+        assert_eq!(daemon.config.cwms_timeout_secs, 60);
+        assert_eq!(daemon.config.usgs_timeout_secs, 45);
+        assert_eq!(daemon.config.backfill_concurrency, 3);
+        assert_eq!(daemon.config.max_backfill_days, 365);
+        // Backfills keep their longer timeouts, independent of the poll ones
+        assert_eq!(daemon.config.usgs_backfill_timeout_secs, 30);
+        assert_eq!(daemon.config.cwms_backfill_timeout_secs, 30);
+        assert_eq!(daemon.config.iem_backfill_timeout_secs, 30);
+        assert_eq!(daemon.config.usgs_backfill_timeout_secs * QUEUED_GAP_TIMEOUT_MULTIPLIER, 60);
+    }
+    
+    #[test]
+    fn test_dry_run_counts_what_would_be_inserted_without_a_database() {
+        let now = Utc::now();
+        let reading = |minutes_ago: i64, parameter_code: &str| GaugeReading {
+            agency_code: "USGS".to_string(),
+            site_code: "05568500".to_string(),
+            site_name: "Illinois River at Kingston Mines, IL".to_string(),
+            parameter_code: parameter_code.to_string(),
+            unit: "ft".to_string(),
+            value: 14.2,
+            datetime: (now - Duration::minutes(minutes_ago)).to_rfc3339(),
+            qualifier: "P".to_string(),
+            source: crate::model::ReadingSource::UsgsIv,
+        };
+        // Two stage, one discharge, and one a day in the future (skipped)
+        let readings = vec![reading(30, "00065"), reading(15, "00065"), reading(15, "00060"), reading(-1440, "00065")];
+        
+        // Never initialized: any SQL would fail with "Daemon not initialized"
+        let mut dry = Daemon::with_config(DaemonConfig { dry_run: true, ..DaemonConfig::default() });
+        assert_eq!(dry.warehouse_readings(&readings).unwrap(), 3);
+        
+        let cwms = |quality_code: i32| cwms::CwmsTimeseries {
+            timeseries_id: "PEOR.Elev.Inst.15Minutes.0.Ccp-Rev".to_string(),
+            location_id: "PEOR".to_string(),
+            parameter_id: "Elev".to_string(),
+            timestamp: now - Duration::minutes(15),
+            value: 440.1,
+            unit: "ft".to_string(),
+            quality_code,
+        };
+        // A value screened and rejected (quality 17) wouldn't be inserted either
+        assert_eq!(dry.warehouse_cwms_timeseries(&[cwms(0), cwms(17)]).unwrap(), 1);
+        
+        // Would-be inserts are counted for every source alike
+        let text = metrics::lock(&dry.metrics).render(now);
+        assert!(text.contains("flomon_readings_inserted_total{source=\"usgs\"} 3\n"));
+        assert!(text.contains("flomon_readings_inserted_total{source=\"cwms\"} 1\n"));
+        assert!(dry.update_monitoring_state("05568500", Some(now)).is_ok());
+        assert!(dry.record_failure("05568500").is_ok());
+        
+        let mut live = Daemon::with_config(DaemonConfig::default());
+        assert!(live.warehouse_readings(&readings).is_err());
+    }
+    
+    #[test]
+    fn test_spawn_fetches_bounded_and_in_submission_order() {
+        use std::sync::atomic::AtomicUsize;
+        
+        let pool = threadpool::ThreadPool::new(2);
+        let running = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (running_in, peak_in) = (Arc::clone(&running), Arc::clone(&peak));
+        
+        // Later items finish first
+        let pending = spawn_fetches(&pool, (0..6u64).collect(), move |i| {
+            let now = running_in.fetch_add(1, Ordering::SeqCst) + 1;
+            peak_in.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(30 - 5 * i));
+            running_in.fetch_sub(1, Ordering::SeqCst);
+            format!("site{}", i)
+        });
+        
+        assert_eq!(pending.wait(), vec!["site0", "site1", "site2", "site3", "site4", "site5"]);
+        assert!(peak.load(Ordering::SeqCst) <= 2, "pool size bounds concurrency");
+    }
+    
+    #[test]
+    fn test_requested_shutdown_stops_run() {
+        // Requested before run starts: no cycle runs (so nothing touches
+        // the missing database) and run returns cleanly
+        let mut daemon = Daemon::with_config(DaemonConfig::default());
+        daemon.request_shutdown();
+        assert!(daemon.run().is_ok());
+    }
+    
+    #[test]
+    fn test_shutdown_interrupts_sleep_between_polls() {
+        let daemon = Daemon::with_config(DaemonConfig::default());
+        let flag = daemon.shutdown_flag();
+        let stopper = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            flag.store(true, Ordering::SeqCst);
+        });
+        
+        let started = std::time::Instant::now();
+        daemon.sleep_unless_shutdown(std::time::Duration::from_secs(900));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        stopper.join().unwrap();
+    }
+    
+    #[test]
+    fn test_backfill_pages_cover_range_newest_first() {
+        let end = Utc::now();
+        let start = end - Duration::days(1000);
+        let pages = backfill_pages(start, end, 365);
+        
+        assert_eq!(pages.len(), 3);
+        assert_eq!(pages[0], (end - Duration::days(365), end));
+        assert_eq!(pages[2], (start, end - Duration::days(730)));
+        for window in pages.windows(2) {
+            assert_eq!(window[0].0, window[1].1, "pages should be contiguous");
+        }
+        assert!(backfill_pages(end, end, 365).is_empty());
+    }
+    
+    #[test]
+    fn test_coverage_reports_gaps_and_percent() {
+        let start = Utc::now() - Duration::hours(10);
+        let end = start + Duration::hours(10);
+        let interval = Duration::minutes(15);
+        
+        // Full first 4 hours, nothing for 4 hours, then the last 2 hours
+        let timestamps: Vec<DateTime<Utc>> = (0..40)
+            .filter(|i| !(16..32).contains(i))
+            .map(|i| start + interval * i)
+            .collect();
+        
+        let report = compute_coverage("05568500", &timestamps, start, end, interval);
+        assert_eq!(report.expected_readings, 40);
+        assert_eq!(report.observed_readings, 24);
+        assert!((report.coverage_percent - 60.0).abs() < 1e-9);
+        assert_eq!(report.gaps, vec![(start + interval * 15, start + interval * 32)]);
+    }
+    
+    #[test]
+    fn test_coverage_of_empty_window_is_one_gap() {
+        let start = Utc::now() - Duration::days(1);
+        let end = Utc::now();
+        let report = compute_coverage("05568500", &[], start, end, Duration::minutes(15));
+        
+        assert_eq!(report.observed_readings, 0);
+        assert_eq!(report.coverage_percent, 0.0);
+        assert_eq!(report.gaps, vec![(start, end)]);
+    }
+    
+    #[test]
+    fn test_interior_gaps_finds_one_24h_hole() {
+        let start = Utc::now() - Duration::days(3);
+        let interval = Duration::minutes(15);
+        
+        // A day of readings, a day missing, then another day
+        let timestamps: Vec<DateTime<Utc>> = (0..=288)
+            .filter(|i| !(97..192).contains(i))
+            .map(|i| start + interval * i)
+            .collect();
+        
+        let gaps = interior_gaps(&timestamps, interval);
+        assert_eq!(gaps, vec![(start + interval * 96, start + interval * 192)]);
+        assert_eq!(gaps[0].1 - gaps[0].0, Duration::hours(24));
+        
+        // A lone reading has nothing to be between
+        assert!(interior_gaps(&timestamps[..1], interval).is_empty());
+    }
+    
+    #[test]
+    fn test_poll_priority_from_stage() {
+        let thresholds = FloodThresholds {
+            action_stage_ft: 16.0,
+            flood_stage_ft: 18.0,
+            moderate_flood_stage_ft: 20.0,
+            major_flood_stage_ft: 24.0,
+        };
+        let priority = |stage| PollPriority::from_stage(stage, Some(&thresholds));
+        
+        assert_eq!(priority(Some(19.2)), PollPriority::Flood);
+        assert_eq!(priority(Some(16.0)), PollPriority::Flood);
+        assert_eq!(priority(Some(14.5)), PollPriority::Normal);
+        assert_eq!(priority(Some(13.0)), PollPriority::Normal);
+        assert_eq!(priority(Some(9.8)), PollPriority::Quiet);
+        // Nothing to judge by
+        assert_eq!(priority(None), PollPriority::Normal);
+        assert_eq!(PollPriority::from_stage(Some(30.0), None), PollPriority::Normal);
+    }
+    
+    #[test]
+    fn test_poll_priority_intervals() {
+        assert_eq!(PollPriority::Flood.poll_interval_minutes(15), 5);
+        assert_eq!(PollPriority::Normal.poll_interval_minutes(15), 15);
+        assert_eq!(PollPriority::Quiet.poll_interval_minutes(15), 30);
+        // A configured interval outside 5..30 is never overridden the wrong way
+        assert_eq!(PollPriority::Flood.poll_interval_minutes(2), 2);
+        assert_eq!(PollPriority::Quiet.poll_interval_minutes(60), 60);
+    }
+    
+    #[test]
+    fn test_digest_due_once_per_day_in_its_hour() {
+        use chrono::TimeZone;
+        let at = |day: u32, hour: u32, minute: u32| Utc.with_ymd_and_hms(2024, 5, day, hour, minute, 0).unwrap();
+        
+        assert!(digest_due(12, None, at(1, 12, 0)));
+        assert!(!digest_due(12, None, at(1, 11, 55)));
+        assert!(!digest_due(-1, None, at(1, 12, 0)), "negative hour disables the digest");
+        
+        // Early wake-ups later in the same hour don't resend
+        let sent = Some(at(1, 12, 0).date_naive());
+        assert!(!digest_due(12, sent, at(1, 12, 5)));
+        assert!(digest_due(12, sent, at(2, 12, 0)));
+    }
+    
+    #[test]
+    fn test_next_due_follows_each_stations_priority() {
+        use chrono::TimeZone;
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let mut daemon = Daemon::new().with_clock(Arc::new(clock::MockClock::new(now)));
+        daemon.stations = stations::load_stations();
+        assert!(daemon.is_due("05568500", now), "unscheduled stations are due");
+        assert_eq!(daemon.next_due(), now);
+        
+        daemon.next_full_poll = Some(now + Duration::minutes(15));
+        let codes: Vec<String> = daemon.stations.iter().map(|s| s.site_code.clone()).collect();
+        for code in &codes {
+            daemon.schedule_next_poll(code, None, now);
+        }
+        assert_eq!(daemon.next_due(), now + Duration::minutes(15));
+        
+        // Kingston Mines in flood comes due first
+        let flood = daemon.stations.iter()
+            .find(|s| s.site_code == "05568500")
+            .and_then(|s| s.thresholds.as_ref())
+            .unwrap()
+            .flood_stage_ft;
+        daemon.schedule_next_poll("05568500", Some(flood), now);
+        assert!(!daemon.is_due("05568500", now + Duration::minutes(4)));
+        assert!(daemon.is_due("05568500", now + Duration::minutes(5)));
+        assert_eq!(daemon.next_due(), now + Duration::minutes(5));
     }
     
     #[test]
@@ -1319,6 +3288,24 @@ mod tests {
         assert!(result.is_err(), "Should fail before initialization");
     }
     
+    #[test]
+    fn test_touch_heartbeat_rewrites_file() {
+        use chrono::TimeZone;
+        let path = std::env::temp_dir().join(format!("flomon_heartbeat_{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        
+        let first = Utc.with_ymd_and_hms(2019, 5, 1, 12, 0, 0).unwrap();
+        touch_heartbeat(&path, first).unwrap();
+        let mtime = std::fs::metadata(&path).unwrap().modified().unwrap();
+        
+        std::thread::sleep(std::time::Duration::from_millis(20));
+        touch_heartbeat(&path, first + Duration::minutes(15)).unwrap();
+        
+        assert!(std::fs::metadata(&path).unwrap().modified().unwrap() > mtime);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().trim(), "2019-05-01T12:15:00+00:00");
+        std::fs::remove_file(&path).unwrap();
+    }
+    
     // Additional tests would require database connection
     // See tests/daemon_lifecycle.rs for integration tests
 }
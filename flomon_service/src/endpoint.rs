@@ -5,21 +5,124 @@
 ///
 /// ## NEW Zone-Based Endpoints:
 /// - GET /zones - List all zones with metadata
-/// - GET /zone/{zone_id} - Get all sensors in a zone with current readings
+/// - GET /zone/{zone_id}[?format=csv] - Get all sensors in a zone with current
+///   readings (`format=csv` gives one row per sensor)
+/// - GET /zone/{zone_id}/history?since=&until=[&format=ndjson][&resolution=hourly] -
+///   Stored readings for every sensor in a zone; `ndjson` streams one record
+///   per line, `hourly` returns per-hour min/mean/max/count
+/// - GET /group/{name} - A user-defined sensor set from groups.toml (any
+///   sensors, across zones), rendered and rolled up like a zone
 /// - GET /status - Overall basin flood status across all zones
+/// - GET /stations/status - Flat list of every registry station's current
+///   stage, flood stage, severity, and staleness (map pins)
 /// - GET /backwater - Backwater flood analysis (Zone 0 + Zone 1)
-/// - GET /forecast - Lead time forecast based on active zones
+/// - GET /score - 0-100 compound flood score blending Kingston Mines stage,
+///   upstream rate of rise, LaGrange backwater, and basin precipitation,
+///   with each component's contribution
+/// - GET /compare-event?event_id={id} | ?year={yyyy} - Each station's current
+///   stage and trend against a past flood's crest and rise rate, in words
+///   ("currently 3.0 ft below the 2013 peak, rising at half the 2013 rate")
+/// - GET /forecast - Property outlook: active upstream and backwater zones,
+///   the hours until the peak reaches the property (fastest source's lead
+///   time narrowed by the Kingston Mines rate of rise), and a confidence
+/// - GET /forecast?sensor={sensor_id} - Stage projection blending the
+///   official NWS forecast with our short-term trend extrapolation, with an
+///   uncertainty band and both components
 /// - GET /health - Service health check
+/// - GET /health/config - Whether each config file still parses and still
+///   matches what the service loaded (edited without a restart = drifted)
+/// - GET /health/stations[?status=degraded,offline] - Every monitored
+///   sensor's row from `station_health` (status, age, poll failures), with
+///   active/degraded/offline totals for alerting scripts
+/// - GET /metrics - Daemon counters in Prometheus text format (readings
+///   inserted per source, poll failures per site, station staleness, last
+///   poll time)
+/// - GET /leadtimes - Travel time from each upstream gauge to Peoria, with
+///   expected arrival windows for what those gauges read now
+/// - GET /compare?upstream={site}&downstream={site} - Upstream stage shifted by
+///   the two gauges' travel-time difference: correlation at that offset, the
+///   lag the record prefers, aligned peaks, and when the upstream crest should
+///   reach the downstream gauge
+/// - GET /outages - Operator triage: every stale, missing, flatlined, or
+///   poll-failing sensor and why, plus zone sensors of any source running
+///   later than their source's expected update interval and a discharge
+///   mass-balance check across the Peoria reach
+/// - GET /rate/{site_code}[?hours=6] - Stage rate of rise (ft/hr) over the
+///   last few hours: latest, fastest, and window-average rates and a trend
+/// - GET /rating/{site_code} - Stage-discharge rating drift: how far the last
+///   year's stage/discharge pairs sit from the historical rating, flagged
+///   when the same stage now passes a materially different flow
+/// - GET /trend/{site_code}[?param=00065&hours=168&points=500][&format=csv] -
+///   Recent instantaneous values thinned to at most `points` for charting
+/// - GET /shef/{site_code}[?param=00060] - Latest stage (or discharge) as a
+///   SHEF `.A` message, text/plain, for relaying to the NWS office
+/// - GET /readings/{site_code}?qualifier=P[&since=<rfc3339>] - Stored readings
+///   filtered by USGS qualifier (QA auditing)
+/// - GET /sensor/{sensor_id}/at?time=<rfc3339>[&parameter=00060] - Value at an
+///   exact instant, interpolated between bracketing readings
+/// - GET /sensor/{sensor_id}/gaps?since=&until=[&parameter=00060] - Stretches
+///   of the stored record missing readings beyond the expected cadence
 ///
 /// ## DEPRECATED Endpoints (still functional but use zone-based views instead):
 /// - GET /site/{site_code} - Returns single-site data (use /zone/{zone_id} instead)
+///
+/// Sensors can be hidden from every view (zones, status, outages, lead
+/// times, per-sensor and per-site queries) with `ENDPOINT_SENSOR_ALLOWLIST`
+/// / `ENDPOINT_SENSOR_DENYLIST`, e.g. for a public deployment; see
+/// `SensorFilter`.
+///
+/// `/zones`, `/status`, and `/stations/status` carry `ETag`/`Last-Modified`
+/// from the latest ingest, advanced to the current minute because their
+/// staleness ages move with the clock, and answer `If-None-Match`/
+/// `If-Modified-Since` with 304 when unchanged.
+///
+/// Every error body has the same shape, with any endpoint-specific context
+/// (valid values, examples, migration hints) under `details`:
+/// `{"error": {"code": "not_found", "message": "...", "details": {...}}}`
 
-use crate::analysis::groupings::group_by_zone;
+use crate::analysis::aggregate::HOURLY_STATS_SQL;
+use crate::analysis::downsample::lttb;
+use crate::analysis::event_analog::{self, analog_events, compare_to_event, EventComparison, EventSelector};
+use crate::analysis::forecast_blend::{self, blend_forecast, extrapolate_stage, BlendWeights, BlendedPoint, ForecastPoint};
+use crate::analysis::gaps::{self, detect_gaps};
+use crate::config_status;
+use crate::analysis::groupings::{group_by_site, group_by_zone, SensorWithData};
+use crate::analysis::interpolate::{interpolate_between, TimedValue};
+use crate::analysis::mass_balance::{mass_balance_check, MassBalanceReport, MASS_BALANCE_OUTFLOW_SITE};
+use crate::analysis::precip::{zone_precip_totals, PrecipTotals};
+use crate::analysis::qualifiers::readings_by_qualifier;
+use crate::analysis::rating::detect_rating_drift;
+use crate::analysis::return_period::{return_period, return_period_of_stage};
+use crate::analysis::scoring::{compute_flood_score, ScoreWeights};
+use crate::analysis::seasonal::stage_percentile;
+use crate::analysis::status::{
+    active_zone_statuses, analyze_backwater_risk, assess_zone, assess_zones, basin_status,
+    current_from_gauge, detect_upstream_flood_pulse, fetch_all_recent_readings, is_stale_age,
+    remaining_lead_time, upstream_arrival_hours, zone_active_since, zone_alert_level,
+    BasinStatusResponse, CompositeReadingResponse, ZoneAssessment,
+};
+use crate::analysis::travel_time::{self, arrival_window, calibrate_lag, compare_aligned, PEORIA_SITE_CODE};
+use crate::alert::level::{AlertLevel, DisplayConfig, LevelDisplay};
+use crate::alert::thresholds::check_flood_stage;
+use crate::metrics::{self, SharedMetrics};
+use crate::monitor::{self, StationHealthRow};
+use crate::stations;
+use crate::groups::{self, SensorGroup};
 use crate::zones::{self, ZoneMetadata, get_zone, get_all_zones};
-use crate::model::GaugeReading;
-use chrono::{DateTime, Utc};
-use postgres::Client;
+use crate::model::{shef, GaugeReading, ReadingSource, PARAM_DISCHARGE, PARAM_STAGE};
+use crate::db;
+use crate::event_export::csv_field;
+use crate::units::{self, UnitSystem};
+use chrono::{DateTime, Datelike, DurationRound, Utc};
+use postgres::fallible_iterator::FallibleIterator;
+use postgres::types::ToSql;
+use postgres::{Client, Row, RowIter};
 use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::Instant;
 
 // ============================================================================
 // Response Types
@@ -40,6 +143,10 @@ pub struct ZoneListItem {
     pub lead_time_hours_max: Option<i64>,
     pub primary_alert_condition: String,
     pub sensor_count: usize,
+    /// Sensors with any current reading
+    pub active_sensor_count: usize,
+    /// Sensors with no reading or one past their staleness threshold
+    pub stale_sensor_count: usize,
 }
 
 /// Zone detail with all sensor readings
@@ -51,6 +158,22 @@ pub struct ZoneDetailResponse {
     pub metadata: ZoneMetadataResponse,
     pub sensors: Vec<SensorDetailResponse>,
     pub zone_status: ZoneStatusResponse,
+    /// What is driving the zone's level and what to watch next, in words
+    pub watch_guidance: String,
+    /// Zone-wide rainfall across its ASOS sensors (None if the zone has none)
+    pub precipitation: Option<PrecipTotals>,
+    pub last_updated: DateTime<Utc>,
+}
+
+/// A groups.toml sensor set, rendered like a zone
+#[derive(Debug, Serialize)]
+pub struct GroupDetailResponse {
+    pub group_name: String,
+    pub description: String,
+    pub sensors: Vec<SensorDetailResponse>,
+    pub group_status: ZoneStatusResponse,
+    /// Group IDs that match no (visible) zones.toml sensor
+    pub unmatched_sensor_ids: Vec<String>,
     pub last_updated: DateTime<Utc>,
 }
 
@@ -75,15 +198,46 @@ pub struct SensorDetailResponse {
     pub current_unit: Option<String>,
     pub current_timestamp: Option<String>,
     pub staleness_minutes: Option<i64>,
+    /// Reading stamped implausibly far in the future (source clock skew);
+    /// the sensor is counted as stale
+    pub future_dated: bool,
+    /// The IV feed had nothing recent, so `current_value` is the latest
+    /// USGS daily mean instead of an instantaneous reading
+    pub daily_fallback: bool,
+    /// Expected update interval for this sensor's source (zones.toml
+    /// `[freshness_sla]`); `None` when the source has none
+    pub sla_minutes: Option<i64>,
+    pub freshness: Option<String>,  // "within_sla" or "late"
+    
+    /// "stage" or "discharge": which USGS reading `current_value` prefers
+    pub primary_parameter: String,
     
     // Thresholds (if applicable)
     pub flood_stage_ft: Option<f64>,
     pub action_stage_ft: Option<f64>,
+    pub flood_flow_cfs: Option<f64>,
+    pub action_flow_cfs: Option<f64>,
+
+    // Percentile of current stage vs. history for this time of year (USGS stage only)
+    pub seasonal_percentile: Option<f64>,
+
+    // Gage zero in ft above NGVD29 (USGS stations with a configured datum);
+    // stage + datum = elevation comparable with CWMS pools
+    pub gage_datum_ft_ngvd29: Option<f64>,
+
+    // Return period of current stage from USGS annual peaks (USGS stage
+    // only; none below the 2-year stage), e.g. 25.0 with "≈ 25-year event".
+    // Above the fitted curve: no years, "> 500-year event (above max fitted)"
+    pub return_period_years: Option<f64>,
+    pub return_period_label: Option<String>,
 
     // Precipitation accumulations (ASOS sensors only)
     pub precip_24h_in: Option<f64>,
     pub precip_48h_in: Option<f64>,
 
+    // Which feed supplied the reading (composite USGS/CWMS sensors only)
+    pub composite: Option<CompositeReadingResponse>,
+
     // Relevance explanation
     pub relevance: String,
 }
@@ -96,64 +250,377 @@ pub struct CoordinatesResponse {
 
 #[derive(Debug, Serialize)]
 pub struct ZoneStatusResponse {
-    pub alert_level: String,  // "NORMAL", "WATCH", "WARNING", "CRITICAL"
+    pub alert_level: AlertLevel,
+    pub display: LevelDisplay,
     pub active_sensors: usize,
     pub stale_sensors: usize,
     pub sensors_above_action: Vec<String>,
     pub sensors_above_flood: Vec<String>,
 }
 
-/// Overall basin status
+/// Travel times from upstream gauges to the property
+#[derive(Debug, Serialize)]
+pub struct LeadTimesResponse {
+    pub reference_site: String,
+    pub calibration_window_days: i32,
+    pub stations: Vec<StationLeadTimeResponse>,
+    pub last_updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StationLeadTimeResponse {
+    pub site_code: String,
+    pub name: String,
+    pub distance_direction: String,
+    pub distance_from_peoria_miles: f64,
+    pub travel_time_to_peoria_hours: f64,
+    pub observed_lag_hours: Option<f64>,
+    pub lag_correlation: Option<f64>,
+    pub current_stage_ft: Option<f64>,
+    pub current_timestamp: Option<DateTime<Utc>>,
+    /// NORMAL, ACTION, FLOOD, MODERATE, or MAJOR; `None` without a reading
+    /// or thresholds
+    pub flood_category: Option<AlertLevel>,
+    pub flood_category_display: Option<LevelDisplay>,
+    pub expected_arrival: Option<ArrivalWindowResponse>,
+}
+
+/// When the current upstream stage should reach Peoria
+#[derive(Debug, Serialize)]
+pub struct ArrivalWindowResponse {
+    pub earliest: DateTime<Utc>,
+    pub latest: DateTime<Utc>,
+}
+
+/// An upstream gauge lined up against a downstream one by travel time
 #[derive(Debug, Serialize)]
-pub struct BasinStatusResponse {
-    pub overall_status: String,  // "NORMAL", "ELEVATED", "FLOOD_WATCH", "FLOOD_WARNING"
-    pub active_zones: Vec<ActiveZoneStatus>,
-    pub backwater_risk: BackwaterRiskResponse,
-    pub upstream_flood_pulse: UpstreamFloodPulseResponse,
-    pub compound_event_risk: String,  // "LOW", "MODERATE", "HIGH"
+pub struct TravelComparisonResponse {
+    pub upstream: ComparedStationResponse,
+    pub downstream: ComparedStationResponse,
+    pub window_days: i32,
+    /// Difference of the two nominal travel times to Peoria
+    pub nominal_offset_hours: f64,
+    /// Correlation of hourly stage changes with upstream shifted by the
+    /// nominal offset
+    pub correlation: Option<f64>,
+    pub paired_hours: usize,
+    pub observed_lag_hours: Option<f64>,
+    pub observed_lag_correlation: Option<f64>,
+    /// Observed minus nominal lag; positive means the river is running
+    /// slower than the registry says
+    pub lag_drift_hours: Option<f64>,
+    /// When the upstream crest in the window should reach the downstream
+    /// gauge
+    pub expected_peak_arrival: Option<ArrivalWindowResponse>,
+    /// Whether that whole window is already in the past
+    pub peak_arrival_passed: Option<bool>,
+    pub explanation: String,
     pub last_updated: DateTime<Utc>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct ActiveZoneStatus {
+pub struct ComparedStationResponse {
+    pub site_code: String,
+    pub name: String,
+    pub travel_time_to_peoria_hours: f64,
+    pub peak_stage_ft: Option<f64>,
+    pub peak_at: Option<DateTime<Utc>>,
+}
+
+/// Current stage and flood category of one registry station, for map pins
+#[derive(Debug, Serialize)]
+pub struct StationStatusResponse {
+    pub site_code: String,
+    pub name: String,
+    pub lat: f64,
+    pub lon: f64,
+    pub stage_ft: Option<f64>,
+    pub flood_stage_ft: Option<f64>,
+    /// NORMAL, ACTION, FLOOD, MODERATE, or MAJOR; `None` without a reading
+    /// or thresholds, or when the only reading is a daily mean
+    pub severity: Option<AlertLevel>,
+    /// No stage reading, or the latest is older than the staleness threshold
+    pub stale: bool,
+}
+
+/// Every station's current conditions against one past flood
+#[derive(Debug, Serialize)]
+pub struct CompareEventResponse {
+    /// The requested event id, if compared by id
+    pub event_id: Option<i32>,
+    /// The requested year, if compared by year
+    pub year: Option<i32>,
+    pub generated_at: DateTime<Utc>,
+    pub stations: Vec<StationComparisonResponse>,
+}
+
+/// One station's comparison, with its registry name
+#[derive(Debug, Serialize)]
+pub struct StationComparisonResponse {
+    pub name: Option<String>,
+    #[serde(flatten)]
+    pub comparison: EventComparison,
+}
+
+/// Blended stage projection for one sensor, with both of its components
+#[derive(Debug, Serialize)]
+pub struct ForecastResponse {
+    pub sensor_id: String,
+    pub site_code: String,
+    pub issued: DateTime<Utc>,
+    pub flood_stage_ft: Option<f64>,
+    /// Official NWS AHPS forecast; empty until forecast ingestion exists
+    pub official: Vec<ForecastPoint>,
+    /// Least-squares trend of the last few hours projected forward
+    pub extrapolated: Vec<ForecastPoint>,
+    pub blend: Vec<BlendedPoint>,
+}
+
+/// Property-level outlook: which active zones are feeding a rise, when the
+/// peak should reach the property, and how far to trust that
+#[derive(Debug, Serialize)]
+pub struct PropertyForecastResponse {
+    pub active_threat: bool,
+    pub property_zone_status: AlertLevel,
+    /// Hours from now the peak should reach the property; `None` when no
+    /// upstream or backwater zone is active
+    pub estimated_peak_window_hours: Option<PeakWindowResponse>,
+    pub contributing_sources: Vec<ContributingSourceResponse>,
+    pub confidence: String,  // "LOW", "MODERATE", "HIGH"
+    /// Kingston Mines stage trend the window was narrowed by
+    pub kingston_mines_trend: &'static str,
+    pub explanation: String,
+    pub issued: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, PartialEq)]
+pub struct PeakWindowResponse {
+    pub earliest_hours: i64,
+    pub latest_hours: i64,
+}
+
+/// An active zone whose water is on its way to the property. Hours are
+/// from now, less the time the zone has already been active.
+#[derive(Debug, Serialize)]
+pub struct ContributingSourceResponse {
     pub zone_id: usize,
     pub zone_name: String,
-    pub status: String,
-    pub lead_time_hours: Option<i64>,
+    pub status: AlertLevel,
+    pub estimated_arrival_hours: Option<i64>,
+    pub lead_time_hours_min: Option<i64>,
+    pub lead_time_hours_max: Option<i64>,
     pub key_sensors_elevated: Vec<String>,
 }
 
+/// Stage rate of rise at one site over a recent window. Rates are `None`
+/// (and `trend` is "unknown") with fewer than two readings.
+#[derive(Debug, Serialize, PartialEq)]
+pub struct RateOfRiseResponse {
+    pub site_code: String,
+    pub window_hours: i64,
+    /// Between the two most recent readings
+    pub current_rate_ft_per_hr: Option<f64>,
+    /// Fastest rise between any two consecutive readings in the window
+    pub max_rate_ft_per_hr: Option<f64>,
+    /// First to last reading in the window
+    pub average_rate_ft_per_hr: Option<f64>,
+    /// "rising", "falling", "steady", or "unknown", from the average rate
+    pub trend: &'static str,
+    pub samples: usize,
+}
+
+/// A site's recent series, downsampled for a chart
 #[derive(Debug, Serialize)]
-pub struct BackwaterRiskResponse {
-    pub risk_level: String,  // "LOW", "MODERATE", "HIGH", "CRITICAL"
-    pub grafton_stage_ft: Option<f64>,
-    pub lagrange_pool_ft: Option<f64>,
-    pub lagrange_tailwater_ft: Option<f64>,
-    pub pool_tailwater_differential_ft: Option<f64>,
-    pub explanation: String,
+pub struct SiteSeriesResponse {
+    pub site_code: String,
+    pub parameter_code: String,
+    pub unit: Option<String>,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    /// Readings stored in the window, before downsampling
+    pub raw_count: usize,
+    pub points: Vec<SeriesPoint>,
 }
 
 #[derive(Debug, Serialize)]
-pub struct UpstreamFloodPulseResponse {
-    pub pulse_detected: bool,
-    pub estimated_arrival_hours: Option<i64>,
-    pub source_zones: Vec<usize>,
-    pub explanation: String,
+pub struct SeriesPoint {
+    pub t: DateTime<Utc>,
+    pub v: f64,
+}
+
+/// The `station_health` view for operators, optionally narrowed by status
+#[derive(Debug, Serialize)]
+pub struct StationHealthResponse {
+    /// Totals over every visible sensor, whatever the status filter
+    pub summary: StationHealthSummary,
+    /// One row per site and parameter, offline first
+    pub stations: Vec<StationHealthRow>,
+    pub last_updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct StationHealthSummary {
+    pub total: usize,
+    pub active: usize,
+    pub degraded: usize,
+    pub offline: usize,
+    /// Any other status (e.g. "unknown" before a first poll)
+    pub unknown: usize,
+}
+
+/// Every unhealthy sensor, for operators (not the public flood status)
+#[derive(Debug, Serialize)]
+pub struct OutagesResponse {
+    pub outage_count: usize,
+    pub sensors: Vec<SensorOutageResponse>,
+    /// Zone sensors (any source) whose latest reading is older than their
+    /// source's expected update interval
+    pub sla_breach_count: usize,
+    pub sla_breaches: Vec<SlaBreachResponse>,
+    /// Discharge cross-check across the Henry–Kingston Mines reach; a
+    /// flagged imbalance suggests one of its gauges is reading wrong
+    pub mass_balance: Option<MassBalanceReport>,
+    pub last_updated: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SlaBreachResponse {
+    pub sensor_id: String,
+    pub zone_id: usize,
+    pub source: String,
+    pub sla_minutes: i64,
+    pub latest_reading_time: Option<DateTime<Utc>>,
+    /// `None` when nothing recent is stored at all
+    pub age_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SensorOutageResponse {
+    pub site_code: String,
+    pub site_name: String,
+    pub parameter_code: String,
+    pub reason: String,  // "missing", "poll_failing", "stale", "flatlined"
+    pub status: String,
+    pub last_good_value: Option<f64>,
+    pub last_good_time: Option<DateTime<Utc>>,
+    pub age_minutes: Option<f64>,
+    pub staleness_threshold_minutes: i32,
+    pub consecutive_failures: i32,
+    pub last_poll_succeeded: Option<DateTime<Utc>>,
+}
+
+/// Holes in one sensor's stored record over a window
+#[derive(Debug, Serialize)]
+pub struct SensorGapsResponse {
+    pub sensor_id: String,
+    pub source: String,  // "USGS", "CWMS", or "ASOS"
+    pub since: DateTime<Utc>,
+    pub until: DateTime<Utc>,
+    pub expected_interval_minutes: i64,
+    pub gap_count: usize,
+    pub total_gap_minutes: i64,
+    pub gaps: Vec<GapResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GapResponse {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub duration_minutes: i64,
 }
 
 // ============================================================================
 // Main Endpoint Handlers
 // ============================================================================
 
+// ============================================================================
+// Sensor Visibility
+// ============================================================================
+
+/// Which sensors the API exposes, from `ENDPOINT_SENSOR_ALLOWLIST` and
+/// `ENDPOINT_SENSOR_DENYLIST`: comma-separated IDs matching any of a
+/// sensor's IDs (zones.toml `id`, USGS site, CWMS location, ASOS station).
+/// With an allowlist only listed sensors are shown; the denylist then hides
+/// more. Hidden sensors are still ingested, just left out of responses.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SensorFilter {
+    allow: Option<HashSet<String>>,
+    deny: HashSet<String>,
+}
+
+impl SensorFilter {
+    pub fn new(allowlist: Option<&str>, denylist: Option<&str>) -> Self {
+        let parse = |list: &str| -> HashSet<String> {
+            list.split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect()
+        };
+        Self {
+            allow: allowlist.map(parse).filter(|ids| !ids.is_empty()),
+            deny: denylist.map(parse).unwrap_or_default(),
+        }
+    }
+    
+    pub fn from_env() -> Self {
+        let allowlist = std::env::var("ENDPOINT_SENSOR_ALLOWLIST").ok();
+        let denylist = std::env::var("ENDPOINT_SENSOR_DENYLIST").ok();
+        Self::new(allowlist.as_deref(), denylist.as_deref())
+    }
+    
+    /// Whether a bare site/location/station ID may be shown
+    pub fn is_id_visible(&self, id: &str) -> bool {
+        self.allow.as_ref().is_none_or(|allow| allow.contains(id)) && !self.deny.contains(id)
+    }
+    
+    /// Whether a zones.toml sensor may be shown: allowed under any of its
+    /// IDs and denied under none
+    pub fn is_visible(&self, sensor: &zones::Sensor) -> bool {
+        self.allow.as_ref().is_none_or(|allow| sensor.ids().any(|id| allow.contains(id)))
+            && !sensor.ids().any(|id| self.deny.contains(id))
+    }
+    
+    pub fn is_filtering(&self) -> bool {
+        self.allow.is_some() || !self.deny.is_empty()
+    }
+}
+
+/// The process-wide filter, read from the environment on first use.
+fn sensor_filter() -> &'static SensorFilter {
+    static FILTER: OnceLock<SensorFilter> = OnceLock::new();
+    FILTER.get_or_init(SensorFilter::from_env)
+}
+
+/// zones.toml with hidden sensors removed. Every endpoint view goes
+/// through this rather than `zones::cached_zones`.
+fn load_visible_zones() -> Result<Arc<zones::ZonesConfig>, Box<dyn std::error::Error>> {
+    let config = zones::cached_zones()?;
+    let filter = sensor_filter();
+    if !filter.is_filtering() {
+        return Ok(config);
+    }
+    let mut visible = zones::ZonesConfig::clone(&config);
+    zones::retain_sensors(&mut visible, |sensor| filter.is_visible(sensor));
+    Ok(Arc::new(visible))
+}
+
+/// Trailing windows (hours) for zone-level precipitation rollups
+const ZONE_PRECIP_WINDOWS_HOURS: [i32; 3] = [6, 24, 48];
+
 /// Fetch all zones list
-pub fn fetch_zones_list(_client: &mut Client) -> Result<ZonesListResponse, String> {
-    let zones_config = zones::load_zones_default()
+pub fn fetch_zones_list(client: &mut Client) -> Result<ZonesListResponse, String> {
+    let zones_config = load_visible_zones()
         .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
     
+    let latest = fetch_latest_reading_times(client)?;
+    let now = Utc::now();
+    
     let mut zone_items = Vec::new();
     
     for (zone_id, zone) in get_all_zones(&zones_config) {
         let metadata = ZoneMetadata::for_zone(zone_id);
+        let (active_sensor_count, stale_sensor_count) = zone_sensor_health(zone, &latest, now);
         
         zone_items.push(ZoneListItem {
             zone_id,
@@ -162,6 +629,8 @@ pub fn fetch_zones_list(_client: &mut Client) -> Result<ZonesListResponse, Strin
             lead_time_hours_max: metadata.lead_time_hours_max,
             primary_alert_condition: metadata.primary_alert_condition,
             sensor_count: zone.sensors.len(),
+            active_sensor_count,
+            stale_sensor_count,
         });
     }
     
@@ -171,14 +640,40 @@ pub fn fetch_zones_list(_client: &mut Client) -> Result<ZonesListResponse, Strin
     })
 }
 
+/// (active, stale) sensor counts for a zone from a latest-readings snapshot,
+/// using the same rules as the zone detail view.
+fn zone_sensor_health(zone: &zones::Zone, latest: &LatestReadingTimes, now: DateTime<Utc>) -> (usize, usize) {
+    let mut active = 0;
+    let mut stale = 0;
+    
+    for sensor in &zone.sensors {
+        match latest.for_sensor(sensor) {
+            Some(ts) => {
+                active += 1;
+                if is_stale_age(Some((now - ts).num_minutes()), sensor.staleness_threshold_minutes()) {
+                    stale += 1;
+                }
+            }
+            None => stale += 1,
+        }
+    }
+    
+    (active, stale)
+}
+
 /// Fetch zone detail with all sensor readings
 pub fn fetch_zone_detail(client: &mut Client, zone_id: usize) -> Result<ZoneDetailResponse, String> {
-    let zones_config = zones::load_zones_default()
+    let zones_config = load_visible_zones()
         .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
     
     let zone = get_zone(&zones_config, zone_id)
         .ok_or_else(|| format!("Zone {} not found", zone_id))?;
     
+    // A zone with no sensors yet (valid during setup) has nothing to query
+    if zone.sensors.is_empty() {
+        return Ok(empty_zone_detail(zone_id, zone, &zones_config.display));
+    }
+    
     let metadata = ZoneMetadata::for_zone(zone_id);
     
     // Fetch all recent USGS readings
@@ -190,78 +685,119 @@ pub fn fetch_zone_detail(client: &mut Client, zone_id: usize) -> Result<ZoneDeta
         .find(|zr| zr.zone_id == zone_id)
         .ok_or_else(|| format!("Zone {} readings not found", zone_id))?;
     
-    // Build sensor details
+    let (sensors, assessment) = render_sensors(client, &this_zone_readings.sensors, &zones_config);
+    let alert_level = assessment.alert_level;
+    let watch_guidance = watch_guidance(&metadata, alert_level, &sensors, &assessment.above_action, &assessment.above_flood);
+    
+    let precipitation = zone_precip_totals(client, zone, &ZONE_PRECIP_WINDOWS_HOURS, Utc::now())
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to fetch precipitation for zone {}: {}", zone_id, e);
+            None
+        });
+    
+    Ok(ZoneDetailResponse {
+        zone_id,
+        zone_name: zone.name.clone(),
+        description: zone.description.clone(),
+        metadata: ZoneMetadataResponse {
+            lead_time_hours_min: metadata.lead_time_hours_min,
+            lead_time_hours_max: metadata.lead_time_hours_max,
+            primary_alert_condition: metadata.primary_alert_condition,
+        },
+        sensors,
+        zone_status: ZoneStatusResponse {
+            alert_level,
+            display: zones_config.display.display_for(alert_level),
+            active_sensors: assessment.active,
+            stale_sensors: assessment.stale,
+            sensors_above_action: assessment.above_action,
+            sensors_above_flood: assessment.above_flood,
+        },
+        watch_guidance,
+        precipitation,
+        last_updated: Utc::now(),
+    })
+}
+
+/// Current readings and aggregate status for a groups.toml group, with the
+/// same per-sensor rendering and alert roll-up as a zone.
+pub fn fetch_group_detail(client: &mut Client, group: &SensorGroup) -> Result<GroupDetailResponse, String> {
+    let zones_config = load_visible_zones()
+        .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
+    let (group_sensors, unmatched_sensor_ids) = group.resolve(&zones_config);
+    
+    let site_readings = group_by_site(fetch_all_recent_readings(client)?);
+    let sensor_data: Vec<SensorWithData> = group_sensors.into_iter()
+        .map(|sensor| SensorWithData {
+            readings: sensor.usgs_id.as_ref().and_then(|id| site_readings.get(id)).cloned(),
+            sensor,
+        })
+        .collect();
+    
+    let (sensors, assessment) = render_sensors(client, &sensor_data, &zones_config);
+    let alert_level = assessment.alert_level;
+    
+    Ok(GroupDetailResponse {
+        group_name: group.name.clone(),
+        description: group.description.clone(),
+        sensors,
+        group_status: ZoneStatusResponse {
+            alert_level,
+            display: zones_config.display.display_for(alert_level),
+            active_sensors: assessment.active,
+            stale_sensors: assessment.stale,
+            sensors_above_action: assessment.above_action,
+            sensors_above_flood: assessment.above_flood,
+        },
+        unmatched_sensor_ids,
+        last_updated: Utc::now(),
+    })
+}
+
+/// Sensor details for a zone or group view, alongside the assessment its
+/// status roll-up comes from. Adds the context that doesn't affect status:
+/// seasonal percentile, return period, gage datum, and precipitation totals.
+fn render_sensors(
+    client: &mut Client,
+    sensor_data: &[SensorWithData],
+    zones_config: &zones::ZonesConfig,
+) -> (Vec<SensorDetailResponse>, ZoneAssessment) {
+    let station_datums: HashMap<String, Option<f64>> = stations::load_stations()
+        .into_iter()
+        .map(|s| (s.site_code, s.gage_datum_ft_ngvd29))
+        .collect();
+    
+    let assessment = assess_zone(client, sensor_data, zones_config);
     let mut sensors = Vec::new();
-    let mut sensors_above_action = Vec::new();
-    let mut sensors_above_flood = Vec::new();
-    let mut active_count = 0;
-    let mut stale_count = 0;
     
-    for sensor_data in &this_zone_readings.sensors {
+    for (sensor_data, assessed) in sensor_data.iter().zip(&assessment.sensors) {
         let sensor = &sensor_data.sensor;
+        let (current_value, current_unit, current_timestamp, staleness) = assessed.current.clone();
         
-        // Extract current reading
-        let (current_value, current_unit, current_timestamp, staleness) = 
-            if let Some(ref readings) = sensor_data.readings {
-                // Prefer stage over discharge for thresholds
-                let stage_reading = readings.stage_ft.as_ref();
-                let discharge_reading = readings.discharge_cfs.as_ref();
-                
-                let reading_opt = stage_reading.or(discharge_reading);
-                
-                if let Some(reading) = reading_opt {
-                    let timestamp = chrono::DateTime::parse_from_rfc3339(&reading.datetime)
-                        .ok()
-                        .map(|dt| dt.with_timezone(&Utc));
-                    
-                    let staleness_min = timestamp.map(|ts| (Utc::now() - ts).num_minutes());
-                    
-                    active_count += 1;
-                    if staleness_min.unwrap_or(9999) > 120 {
-                        stale_count += 1;
-                    }
-                    
-                    (Some(reading.value), Some(reading.unit.clone()), 
-                     Some(reading.datetime.clone()), staleness_min)
-                } else {
-                    stale_count += 1;
-                    (None, None, None, None)
-                }
-            } else {
-                // For CWMS/ASOS sensors, fetch from appropriate tables
-                match fetch_sensor_reading(client, sensor) {
-                    Ok((val, unit, ts, stale)) => {
-                        if val.is_some() {
-                            active_count += 1;
-                            if stale.unwrap_or(9999) > 120 {
-                                stale_count += 1;
-                            }
-                        } else {
-                            stale_count += 1;
-                        }
-                        (val, unit, ts, stale)
-                    }
-                    Err(e) => {
-                        // Log error but continue processing other sensors
-                        eprintln!("Failed to fetch sensor {}: {}", sensor.primary_id(), e);
-                        stale_count += 1;
-                        (None, None, None, None)
-                    }
-                }
-            };
-        
-        // Check thresholds
-        if let (Some(value), Some(action)) = (current_value, sensor.action_stage_ft) {
-            if value >= action {
-                sensors_above_action.push(sensor.primary_id());
-            }
+        let mut seasonal_percentile = None;
+        let mut stage_frequency = None;
+        if let Some(reading) = assessed.usgs_reading.as_ref().filter(|r| r.parameter_code == PARAM_STAGE) {
+            // Central calendar day, matching the climatology's daily means
+            let day_of_year = DateTime::parse_from_rfc3339(&reading.datetime)
+                .map(|dt| dt.with_timezone(&Utc))
+                .unwrap_or_else(|_| Utc::now())
+                .with_timezone(&chrono_tz::America::Chicago)
+                .ordinal();
+            seasonal_percentile = stage_percentile(&reading.site_code, reading.value, day_of_year, client)
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to compute seasonal percentile for {}: {}", reading.site_code, e);
+                    None
+                });
+            stage_frequency = return_period(&reading.site_code, client)
+                .map(|curve| return_period_of_stage(reading.value, &curve))
+                .unwrap_or_else(|e| {
+                    eprintln!("Failed to compute return period for {}: {}", reading.site_code, e);
+                    None
+                });
         }
         
-        if let (Some(value), Some(flood)) = (current_value, sensor.flood_stage_ft) {
-            if value >= flood {
-                sensors_above_flood.push(sensor.primary_id());
-            }
-        }
+        let sla_minutes = zones_config.freshness_sla.minutes_for(sensor);
+        let freshness = sla_minutes.map(|sla| sla_status(staleness, sla).to_string());
         
         let (precip_24h_in, precip_48h_in) = if sensor.is_asos() {
             sensor.station_id.as_deref()
@@ -285,26 +821,105 @@ pub fn fetch_zone_detail(client: &mut Client, zone_id: usize) -> Result<ZoneDeta
             current_unit,
             current_timestamp,
             staleness_minutes: staleness,
+            future_dated: assessed.future_dated,
+            daily_fallback: assessed.daily_fallback,
+            sla_minutes,
+            freshness,
+            primary_parameter: sensor.primary_parameter().as_str().to_string(),
             flood_stage_ft: sensor.flood_stage_ft,
             action_stage_ft: sensor.action_stage_ft,
+            flood_flow_cfs: sensor.flood_flow_cfs,
+            action_flow_cfs: sensor.action_flow_cfs,
+            seasonal_percentile,
+            gage_datum_ft_ngvd29: sensor.usgs_id.as_ref()
+                .and_then(|id| station_datums.get(id))
+                .copied()
+                .flatten(),
+            return_period_years: stage_frequency.and_then(|f| f.years()),
+            return_period_label: stage_frequency.map(|f| f.describe()),
             precip_24h_in,
             precip_48h_in,
+            composite: assessed.composite.clone(),
             relevance: sensor.relevance.clone(),
         });
     }
     
-    // Determine zone alert level
-    let alert_level = if !sensors_above_flood.is_empty() {
-        "CRITICAL"
-    } else if !sensors_above_action.is_empty() {
-        "WARNING"
-    } else if stale_count > sensors.len() / 2 {
-        "DEGRADED"
+    (sensors, assessment)
+}
+
+/// "within_sla" or "late" for a reading of age `staleness` minutes against
+/// its source's expected update interval. Unknown and future-dated ages are
+/// late, as with staleness.
+fn sla_status(staleness: Option<i64>, sla_minutes: i64) -> &'static str {
+    if is_stale_age(staleness, sla_minutes) {
+        "late"
+    } else {
+        "within_sla"
+    }
+}
+
+/// "over the next 6-24h" from a zone's lead-time metadata.
+fn lead_window_text(metadata: &ZoneMetadata) -> String {
+    match (metadata.lead_time_hours_min, metadata.lead_time_hours_max) {
+        (Some(0) | None, Some(max)) => format!("over the next {}h", max),
+        (Some(min), Some(max)) => format!("over the next {}-{}h", min, max),
+        _ => "closely".to_string(),
+    }
+}
+
+/// Plain-language guidance for a zone: which sensors put it at its level
+/// and which downstream signal to follow over its lead time. Sensors are
+/// named by location, without the parenthetical detail.
+fn watch_guidance(
+    metadata: &ZoneMetadata,
+    level: AlertLevel,
+    sensors: &[SensorDetailResponse],
+    above_action: &[String],
+    above_flood: &[String],
+) -> String {
+    let names = |ids: &[String]| -> String {
+        ids.iter()
+            .map(|id| {
+                sensors.iter()
+                    .find(|s| &s.sensor_id == id)
+                    .map_or(id.as_str(), |s| s.location.split(" (").next().unwrap_or(&s.location))
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    
+    let drivers = if !above_flood.is_empty() {
+        Some(format!("{} above flood stage", names(above_flood)))
+    } else if !above_action.is_empty() {
+        Some(format!("{} at action stage", names(above_action)))
     } else {
-        "NORMAL"
+        None
     };
     
-    Ok(ZoneDetailResponse {
+    match (level, drivers) {
+        (AlertLevel::Unknown, _) => format!(
+            "No sensors reporting; conditions to watch for: {}",
+            metadata.primary_alert_condition
+        ),
+        (AlertLevel::Degraded, _) => format!(
+            "Most sensors are stale; confirm conditions from {} before relying on this zone",
+            metadata.watch_next
+        ),
+        (level, Some(drivers)) if level.is_elevated() => format!(
+            "{}; watch {} {}",
+            drivers, metadata.watch_next, lead_window_text(metadata)
+        ),
+        _ => format!("Conditions normal; watch for {}", metadata.primary_alert_condition),
+    }
+}
+
+/// Well-formed detail response for a zone configured with no sensors
+fn empty_zone_detail(zone_id: usize, zone: &zones::Zone, display: &DisplayConfig) -> ZoneDetailResponse {
+    let metadata = ZoneMetadata::for_zone(zone_id);
+    let alert_level = zone_alert_level(0, 0, 0.0);
+    let watch_guidance = watch_guidance(&metadata, alert_level, &[], &[], &[]);
+    
+    ZoneDetailResponse {
         zone_id,
         zone_name: zone.name.clone(),
         description: zone.description.clone(),
@@ -313,290 +928,547 @@ pub fn fetch_zone_detail(client: &mut Client, zone_id: usize) -> Result<ZoneDeta
             lead_time_hours_max: metadata.lead_time_hours_max,
             primary_alert_condition: metadata.primary_alert_condition,
         },
-        sensors,
+        sensors: Vec::new(),
         zone_status: ZoneStatusResponse {
-            alert_level: alert_level.to_string(),
-            active_sensors: active_count,
-            stale_sensors: stale_count,
-            sensors_above_action,
-            sensors_above_flood,
+            alert_level,
+            display: display.display_for(alert_level),
+            active_sensors: 0,
+            stale_sensors: 0,
+            sensors_above_action: Vec::new(),
+            sensors_above_flood: Vec::new(),
         },
+        watch_guidance,
+        precipitation: None,
         last_updated: Utc::now(),
-    })
+    }
 }
 
 /// Fetch overall basin status
 pub fn fetch_basin_status(client: &mut Client) -> Result<BasinStatusResponse, String> {
-    let _zones_config = zones::load_zones_default()
+    let zones_config = load_visible_zones()
         .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
     
-    let mut active_zones = Vec::new();
-    let mut overall_elevated = false;
-    let mut overall_watch = false;
-    let mut overall_warning = false;
+    basin_status(client, &zones_config)
+}
+
+/// Canonical form of an alert level stored before levels were unified
+/// ("FLOOD_WARNING" → "FLOOD"); unrecognized strings pass through.
+fn canonical_level(stored: &str) -> String {
+    AlertLevel::parse(stored).map_or_else(|| stored.to_string(), |level| level.as_str().to_string())
+}
+
+/// Basin status snapshots recorded at or after `since`, oldest first.
+/// Levels recorded in the old vocabularies are reported canonically.
+/// Snapshots are recorded from every configured sensor, so hidden sensors
+/// are dropped from each zone's `key_sensors_elevated` here.
+pub fn fetch_basin_status_history(
+    client: &mut Client,
+    since: DateTime<Utc>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let visible_ids: Option<HashSet<String>> = if sensor_filter().is_filtering() {
+        let zones_config = load_visible_zones()
+            .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
+        Some(get_all_zones(&zones_config)
+            .into_iter()
+            .flat_map(|(_, zone)| zone.sensors.iter().map(|s| s.primary_id()))
+            .collect())
+    } else {
+        None
+    };
+    
+    let rows = client.query(
+        "SELECT recorded_at, overall_status, compound_event_risk, active_zones, backwater_risk
+         FROM public.basin_status_history
+         WHERE recorded_at >= $1
+         ORDER BY recorded_at ASC",
+        &[&since]
+    ).map_err(|e| format!("Failed to fetch basin status history: {}", e))?;
     
-    // Check each zone for activity
-    for zone_id in 0..=6 {
-        let zone_detail = fetch_zone_detail(client, zone_id)?;
-        
-        let zone_active = match zone_detail.zone_status.alert_level.as_str() {
-            "CRITICAL" => {
-                overall_warning = true;
-                true
-            }
-            "WARNING" => {
-                overall_watch = true;
-                true
+    Ok(rows.iter()
+        .map(|row| {
+            let recorded_at: DateTime<Utc> = row.get(0);
+            let overall_status: String = row.get(1);
+            let compound_event_risk: String = row.get(2);
+            let mut active_zones: serde_json::Value = row.get(3);
+            
+            if let Some(zones) = active_zones.as_array_mut() {
+                for zone in zones {
+                    if let Some(status) = zone.get("status").and_then(|s| s.as_str()).map(canonical_level) {
+                        zone["status"] = serde_json::Value::from(status);
+                    }
+                    if let Some(visible) = &visible_ids
+                        && let Some(ids) = zone.get_mut("key_sensors_elevated").and_then(|ids| ids.as_array_mut())
+                    {
+                        ids.retain(|id| id.as_str().is_some_and(|id| visible.contains(id)));
+                    }
+                }
             }
-            "DEGRADED" | "NORMAL" => false,
-            _ => false,
-        };
-        
-        if zone_active || !zone_detail.zone_status.sensors_above_action.is_empty() {
-            overall_elevated = true;
-            let metadata = ZoneMetadata::for_zone(zone_id);
+            let backwater_risk: serde_json::Value = row.get(4);
             
-            active_zones.push(ActiveZoneStatus {
-                zone_id,
-                zone_name: zone_detail.zone_name.clone(),
-                status: zone_detail.zone_status.alert_level.clone(),
-                lead_time_hours: metadata.lead_time_hours_max,
-                key_sensors_elevated: zone_detail.zone_status.sensors_above_action,
-            });
-        }
-    }
+            serde_json::json!({
+                "recorded_at": recorded_at,
+                "overall_status": canonical_level(&overall_status),
+                "compound_event_risk": compound_event_risk,
+                "active_zones": active_zones,
+                "backwater_risk": backwater_risk,
+            })
+        })
+        .collect())
+}
+
+/// Flood category of a station's stage reading, or `None` without a
+/// reading or thresholds. A daily-mean fallback reading has no category
+/// (see `stage_exceedance`).
+fn flood_category(reading: Option<&GaugeReading>, thresholds: Option<&crate::model::FloodThresholds>) -> Option<AlertLevel> {
+    let reading = reading.filter(|r| r.source != ReadingSource::UsgsDv);
+    let (reading, thresholds) = (reading?, thresholds?);
+    Some(
+        check_flood_stage(reading, thresholds)
+            .map_or(AlertLevel::Normal, |alert| AlertLevel::from(&alert.severity))
+    )
+}
+
+/// Latest stage, flood stage, and flood category of every registry station,
+/// from a single latest-readings query.
+pub fn fetch_stations_status(client: &mut Client) -> Result<Vec<StationStatusResponse>, String> {
+    let latest_stage: HashMap<String, GaugeReading> = fetch_all_recent_readings(client)?
+        .into_iter()
+        .filter(|r| r.parameter_code == PARAM_STAGE)
+        .map(|r| (r.site_code.clone(), r))
+        .collect();
     
-    // Determine overall status
-    let overall_status = if overall_warning {
-        "FLOOD_WARNING"
-    } else if overall_watch {
-        "FLOOD_WATCH"
-    } else if overall_elevated {
-        "ELEVATED"
-    } else {
-        "NORMAL"
-    };
+    Ok(stations::load_stations()
+        .into_iter()
+        .filter(|s| sensor_filter().is_id_visible(&s.site_code))
+        .map(|station| {
+            let reading = latest_stage.get(&station.site_code);
+            let staleness = reading.and_then(|r| current_from_gauge(r).3);
+            
+            StationStatusResponse {
+                severity: flood_category(reading, station.thresholds.as_ref()),
+                flood_stage_ft: station.thresholds.as_ref().map(|t| t.flood_stage_ft),
+                stage_ft: reading.map(|r| r.value),
+                stale: is_stale_age(staleness, zones::DEFAULT_STALENESS_THRESHOLD_MINUTES),
+                site_code: station.site_code,
+                name: station.name,
+                lat: station.latitude,
+                lon: station.longitude,
+            }
+        })
+        .collect())
+}
+
+/// Nominal and empirical travel times for every upstream station, with the
+/// window in which each station's latest stage should arrive at Peoria.
+pub fn fetch_lead_times(client: &mut Client) -> Result<LeadTimesResponse, String> {
+    // A daily mean has no arrival instant to project forward
+    let latest_stage: HashMap<String, GaugeReading> = fetch_all_recent_readings(client)?
+        .into_iter()
+        .filter(|r| r.parameter_code == PARAM_STAGE && r.source != ReadingSource::UsgsDv)
+        .map(|r| (r.site_code.clone(), r))
+        .collect();
+    
+    let display = load_visible_zones()
+        .map(|config| config.display.clone())
+        .unwrap_or_else(|e| {
+            eprintln!("Failed to load zones.toml for display labels, using defaults: {}", e);
+            DisplayConfig::default()
+        });
     
-    // Backwater risk analysis
-    let backwater_risk = analyze_backwater_risk(client)?;
+    let now = Utc::now();
+    let peoria_hourly = travel_time::hourly_stage(client, PEORIA_SITE_CODE, travel_time::LAG_CALIBRATION_DAYS, now)?;
     
-    // Upstream flood pulse detection
-    let upstream_pulse = detect_upstream_flood_pulse(&active_zones);
+    let mut upstream: Vec<stations::Station> = stations::load_stations()
+        .into_iter()
+        .filter(|s| s.travel_time_to_peoria_hours > 0.0 && sensor_filter().is_id_visible(&s.site_code))
+        .collect();
+    upstream.sort_by(|a, b| a.travel_time_to_peoria_hours.total_cmp(&b.travel_time_to_peoria_hours));
     
-    // Compound event risk
-    let zone_0_active = active_zones.iter().any(|z| z.zone_id == 0);
-    let zone_4_plus_active = active_zones.iter().any(|z| z.zone_id >= 4);
+    let mut lead_times = Vec::new();
     
-    let compound_risk = if zone_0_active && zone_4_plus_active {
-        "HIGH"
-    } else if zone_0_active || zone_4_plus_active {
-        "MODERATE"
-    } else {
-        "LOW"
-    };
+    for station in upstream {
+        let lag = calibrate_lag(client, &station.site_code, &peoria_hourly, now)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to calibrate lag for {}: {}", station.site_code, e);
+                None
+            });
+        let observed_lag_hours = lag.map(|l| l.lag_hours as f64);
+        
+        let reading = latest_stage.get(&station.site_code);
+        let current_timestamp = reading
+            .and_then(|r| DateTime::parse_from_rfc3339(&r.datetime).ok())
+            .map(|dt| dt.with_timezone(&Utc));
+        
+        let flood_category = flood_category(reading, station.thresholds.as_ref());
+        let flood_category_display = flood_category.map(|level| display.display_for(level));
+        
+        let expected_arrival = current_timestamp.map(|ts| {
+            let (earliest, latest) = arrival_window(ts, station.travel_time_to_peoria_hours, observed_lag_hours);
+            ArrivalWindowResponse { earliest, latest }
+        });
+        
+        lead_times.push(StationLeadTimeResponse {
+            site_code: station.site_code,
+            name: station.name,
+            distance_direction: station.distance_direction,
+            distance_from_peoria_miles: station.distance_from_peoria_miles,
+            travel_time_to_peoria_hours: station.travel_time_to_peoria_hours,
+            observed_lag_hours,
+            lag_correlation: lag.map(|l| l.correlation),
+            current_stage_ft: reading.map(|r| r.value),
+            current_timestamp,
+            flood_category,
+            flood_category_display,
+            expected_arrival,
+        });
+    }
     
-    Ok(BasinStatusResponse {
-        overall_status: overall_status.to_string(),
-        active_zones,
-        backwater_risk,
-        upstream_flood_pulse: upstream_pulse,
-        compound_event_risk: compound_risk.to_string(),
+    Ok(LeadTimesResponse {
+        reference_site: PEORIA_SITE_CODE.to_string(),
+        calibration_window_days: travel_time::LAG_CALIBRATION_DAYS,
+        stations: lead_times,
         last_updated: Utc::now(),
     })
 }
 
-/// Analyze backwater flood risk
-fn analyze_backwater_risk(client: &mut Client) -> Result<BackwaterRiskResponse, String> {
-    // Fetch key sensors from Zone 0 (Mississippi) and Zone 1 (LaGrange)
-    let grafton_stage = fetch_cwms_stage(client, "Grafton", "GRFI2")?;
-    let lagrange_pool = fetch_cwms_stage(client, "LaGrange", "IL08P")?;
-    let lagrange_tailwater = fetch_cwms_stage(client, "LaGrange", "IL08TW")?;
-    
-    let differential = match (lagrange_pool, lagrange_tailwater) {
-        (Some(pool), Some(tw)) => Some(pool - tw),
-        _ => None,
+/// Recent stage at `upstream_site` and `downstream_site`, with upstream
+/// shifted forward by the difference of their travel times to Peoria, to
+/// see whether a rise upstream is propagating down on schedule.
+pub fn fetch_travel_aligned_comparison(
+    client: &mut Client,
+    upstream_site: &str,
+    downstream_site: &str,
+) -> Result<TravelComparisonResponse, String> {
+    let find = |site_code: &str| {
+        stations::find_station(site_code)
+            .filter(|s| sensor_filter().is_id_visible(&s.site_code))
+            .ok_or_else(|| format!("Site {} not found", site_code))
     };
+    let (upstream, downstream) = (find(upstream_site)?, find(downstream_site)?);
     
-    // Analyze risk level
-    let risk_level = match (grafton_stage, differential) {
-        (Some(grafton), Some(diff)) => {
-            if grafton > 25.0 && diff < 0.5 {
-                "CRITICAL"
-            } else if grafton > 20.0 && diff < 1.0 {
-                "HIGH"
-            } else if grafton > 18.0 || diff < 2.0 {
-                "MODERATE"
-            } else {
-                "LOW"
-            }
-        }
-        _ => "UNKNOWN",
-    };
+    let nominal_offset_hours = upstream.travel_time_to_peoria_hours - downstream.travel_time_to_peoria_hours;
+    if nominal_offset_hours <= 0.0 {
+        return Err(format!(
+            "{} ({}h to Peoria) is not upstream of {} ({}h to Peoria)",
+            upstream.site_code, upstream.travel_time_to_peoria_hours,
+            downstream.site_code, downstream.travel_time_to_peoria_hours
+        ));
+    }
     
-    let explanation = format!(
-        "Backwater risk is {} based on Grafton stage ({:.1} ft) and LaGrange pool-tailwater differential ({:.1} ft). \
-         When Grafton exceeds 20ft and LaGrange differential drops below 1ft, Mississippi backwater is dominating Illinois River drainage.",
-        risk_level,
-        grafton_stage.unwrap_or(0.0),
-        differential.unwrap_or(99.0)
-    );
+    let now = Utc::now();
+    let days = travel_time::COMPARE_WINDOW_DAYS;
+    let upstream_hourly = travel_time::hourly_stage(client, &upstream.site_code, days, now)?;
+    let downstream_hourly = travel_time::hourly_stage(client, &downstream.site_code, days, now)?;
+    let aligned = compare_aligned(&upstream_hourly, &downstream_hourly, nominal_offset_hours.round() as usize);
+    
+    // Same grid origin as hourly_stage
+    let grid_start = (now - chrono::Duration::days(days as i64))
+        .duration_trunc(chrono::Duration::hours(1))
+        .map_err(|e| format!("Failed to align hourly grid: {}", e))?;
+    let hour = |i: usize| grid_start + chrono::Duration::hours(i as i64);
+    
+    let observed_lag_hours = aligned.observed.map(|l| l.lag_hours as f64);
+    let expected_peak_arrival = aligned.upstream_peak.map(|(i, _)| {
+        let (earliest, latest) = arrival_window(hour(i), nominal_offset_hours, observed_lag_hours);
+        ArrivalWindowResponse { earliest, latest }
+    });
+    let peak_arrival_passed = expected_peak_arrival.as_ref().map(|w| w.latest < now);
+    
+    let explanation = match (aligned.correlation, &expected_peak_arrival, peak_arrival_passed) {
+        (None, _, _) => format!(
+            "Not enough varying stage at both gauges over the last {} days to line them up.", days
+        ),
+        (Some(r), Some(window), Some(false)) => format!(
+            "Upstream crest expected at {} between {} and {} (correlation {:.2} at the {:.0}h nominal offset).",
+            downstream.site_code, window.earliest.format("%Y-%m-%d %H:%M UTC"),
+            window.latest.format("%Y-%m-%d %H:%M UTC"), r, nominal_offset_hours
+        ),
+        (Some(r), _, _) => format!(
+            "Upstream crest in the window has already passed {} (correlation {:.2} at the {:.0}h nominal offset).",
+            downstream.site_code, r, nominal_offset_hours
+        ),
+    };
     
-    Ok(BackwaterRiskResponse {
-        risk_level: risk_level.to_string(),
-        grafton_stage_ft: grafton_stage,
-        lagrange_pool_ft: lagrange_pool,
-        lagrange_tailwater_ft: lagrange_tailwater,
-        pool_tailwater_differential_ft: differential,
+    let compared = |station: stations::Station, peak: Option<(usize, f64)>| ComparedStationResponse {
+        site_code: station.site_code,
+        name: station.name,
+        travel_time_to_peoria_hours: station.travel_time_to_peoria_hours,
+        peak_stage_ft: peak.map(|(_, stage)| stage),
+        peak_at: peak.map(|(i, _)| hour(i)),
+    };
+    
+    Ok(TravelComparisonResponse {
+        upstream: compared(upstream, aligned.upstream_peak),
+        downstream: compared(downstream, aligned.downstream_peak),
+        window_days: days,
+        nominal_offset_hours,
+        correlation: aligned.correlation,
+        paired_hours: aligned.paired_hours,
+        observed_lag_hours,
+        observed_lag_correlation: aligned.observed.map(|l| l.correlation),
+        lag_drift_hours: observed_lag_hours.map(|lag| lag - nominal_offset_hours),
+        expected_peak_arrival,
+        peak_arrival_passed,
         explanation,
+        last_updated: now,
     })
 }
 
-/// Detect upstream flood pulse
-fn detect_upstream_flood_pulse(active_zones: &[ActiveZoneStatus]) -> UpstreamFloodPulseResponse {
-    let upstream_active: Vec<usize> = active_zones.iter()
-        .filter(|z| z.zone_id >= 4)  // Zones 4, 5, 6
-        .map(|z| z.zone_id)
-        .collect();
-    
-    let pulse_detected = !upstream_active.is_empty();
+/// Why a monitored sensor is unhealthy, or `None` if it is fine.
+///
+/// One reason per sensor, most fundamental first: no data at all, then the
+/// poller failing to reach USGS, then data that arrives but is old, then
+/// data that is current but frozen.
+fn outage_reason(row: &StationHealthRow, flatlined: bool) -> Option<&'static str> {
+    let stale = row.is_stale.unwrap_or(false)
+        || row.age_minutes.is_some_and(|age| age > row.staleness_threshold_minutes as f64);
     
-    let estimated_arrival = if upstream_active.contains(&6) {
-        Some(72)  // 3 days from Chicago
-    } else if upstream_active.contains(&5) {
-        Some(48)  // 2 days from Dresden Island
-    } else if upstream_active.contains(&4) {
-        Some(24)  // 1 day from Starved Rock
+    if row.latest_reading_time.is_none() {
+        Some("missing")
+    } else if row.consecutive_failures > 0 {
+        Some("poll_failing")
+    } else if stale {
+        Some("stale")
+    } else if flatlined {
+        Some("flatlined")
     } else {
         None
+    }
+}
+
+/// Statuses `/health/stations?status=` accepts
+const STATION_HEALTH_STATUSES: &[&str] = &["active", "degraded", "offline", "unknown"];
+
+/// A `station_health` status as one of `STATION_HEALTH_STATUSES`
+fn health_status(row: &StationHealthRow) -> &'static str {
+    match row.status.as_str() {
+        "active" => "active",
+        "degraded" => "degraded",
+        "offline" => "offline",
+        _ => "unknown",
+    }
+}
+
+/// Status totals over `rows`
+fn summarize_station_health(rows: &[StationHealthRow]) -> StationHealthSummary {
+    let mut summary = StationHealthSummary { total: rows.len(), ..Default::default() };
+    for row in rows {
+        match health_status(row) {
+            "active" => summary.active += 1,
+            "degraded" => summary.degraded += 1,
+            "offline" => summary.offline += 1,
+            _ => summary.unknown += 1,
+        }
+    }
+    summary
+}
+
+/// Parse a comma-separated `?status=` filter, e.g. "degraded,offline"
+fn parse_status_filter(param: &str) -> Result<Vec<String>, String> {
+    param.split(',')
+        .map(|s| s.trim().to_lowercase())
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            if STATION_HEALTH_STATUSES.contains(&s.as_str()) {
+                Ok(s)
+            } else {
+                Err(format!("Invalid status '{}'", s))
+            }
+        })
+        .collect()
+}
+
+/// Every visible sensor's `station_health` row, keeping only `statuses`
+/// when given. The summary always counts all of them.
+pub fn fetch_station_health(client: &mut Client, statuses: Option<&[String]>) -> Result<StationHealthResponse, String> {
+    let rows: Vec<StationHealthRow> = monitor::get_station_health(client)
+        .map_err(|e| format!("Failed to fetch station health: {}", e))?
+        .into_iter()
+        .filter(|row| sensor_filter().is_id_visible(&row.site_code))
+        .collect();
+    
+    let summary = summarize_station_health(&rows);
+    let stations = match statuses {
+        Some(statuses) => rows.into_iter()
+            .filter(|row| statuses.iter().any(|s| s == health_status(row)))
+            .collect(),
+        None => rows,
     };
     
-    let explanation = if pulse_detected {
-        format!(
-            "Upstream flood pulse detected in zones: {}. Estimated arrival at property in {} hours.",
-            upstream_active.iter().map(|z| z.to_string()).collect::<Vec<_>>().join(", "),
-            estimated_arrival.unwrap_or(0)
-        )
-    } else {
-        "No upstream flood pulse detected in upper basin zones.".to_string()
+    Ok(StationHealthResponse { summary, stations, last_updated: Utc::now() })
+}
+
+/// All sensors in `station_health` that are missing, stale, poll-failing,
+/// or flatlined, worst status first.
+pub fn fetch_outages(client: &mut Client) -> Result<OutagesResponse, String> {
+    let health = monitor::get_station_health(client)
+        .map_err(|e| format!("Failed to fetch station health: {}", e))?;
+    
+    let mut sensors = Vec::new();
+    
+    for row in health.into_iter().filter(|row| sensor_filter().is_id_visible(&row.site_code)) {
+        // Only sensors that otherwise look healthy need the extra query
+        let reason = match outage_reason(&row, false) {
+            Some(reason) => reason,
+            None => {
+                let flatlined = monitor::detect_flatline(
+                    &row.site_code,
+                    &row.parameter_code,
+                    client,
+                    monitor::DEFAULT_FLATLINE_MIN_REPEATS,
+                );
+                match outage_reason(&row, flatlined) {
+                    Some(reason) => reason,
+                    None => continue,
+                }
+            }
+        };
+        
+        sensors.push(SensorOutageResponse {
+            site_code: row.site_code,
+            site_name: row.site_name,
+            parameter_code: row.parameter_code,
+            reason: reason.to_string(),
+            status: row.status,
+            last_good_value: row.latest_reading_value,
+            last_good_time: row.latest_reading_time,
+            age_minutes: row.age_minutes,
+            staleness_threshold_minutes: row.staleness_threshold_minutes,
+            consecutive_failures: row.consecutive_failures,
+            last_poll_succeeded: row.last_poll_succeeded,
+        });
+    }
+    
+    let sla_breaches = fetch_sla_breaches(client)?;
+    
+    let mass_balance = match mass_balance_check(Utc::now(), client) {
+        // Would reveal a hidden gauge's discharge
+        Ok(report) if std::iter::once(&report.outflow).chain(&report.inflows)
+            .any(|flow| !sensor_filter().is_id_visible(&flow.site_code)) => None,
+        Ok(report) => Some(report),
+        Err(e) => {
+            eprintln!("Failed to run mass balance check: {}", e);
+            None
+        }
     };
     
-    UpstreamFloodPulseResponse {
-        pulse_detected,
-        estimated_arrival_hours: estimated_arrival,
-        source_zones: upstream_active,
-        explanation,
+    Ok(OutagesResponse {
+        outage_count: sensors.len(),
+        sensors,
+        sla_breach_count: sla_breaches.len(),
+        sla_breaches,
+        mass_balance,
+        last_updated: Utc::now(),
+    })
+}
+
+/// Zone sensors whose latest reading is older than their source's
+/// `[freshness_sla]` interval. A sensor listed in several zones is reported
+/// once, under the first.
+fn fetch_sla_breaches(client: &mut Client) -> Result<Vec<SlaBreachResponse>, String> {
+    let zones_config = load_visible_zones()
+        .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
+    let latest = fetch_latest_reading_times(client)?;
+    let now = Utc::now();
+    
+    let mut seen = HashSet::new();
+    let mut breaches = Vec::new();
+    
+    for (zone_id, zone) in get_all_zones(&zones_config) {
+        for sensor in &zone.sensors {
+            let Some(sla_minutes) = zones_config.freshness_sla.minutes_for(sensor) else {
+                continue;
+            };
+            
+            let latest_reading_time = latest.for_sensor(sensor);
+            let age_minutes = latest_reading_time.map(|ts| (now - ts).num_minutes());
+            if sla_status(age_minutes, sla_minutes) != "late" || !seen.insert(sensor.primary_id()) {
+                continue;
+            }
+            
+            breaches.push(SlaBreachResponse {
+                sensor_id: sensor.primary_id(),
+                zone_id,
+                source: sensor.source.clone(),
+                sla_minutes,
+                latest_reading_time,
+                age_minutes,
+            });
+        }
     }
+    
+    Ok(breaches)
 }
 
 // ============================================================================
 // Helper Functions
 // ============================================================================
 
-/// Fetch all recent USGS readings (last 4 hours)
-fn fetch_all_recent_readings(client: &mut Client) -> Result<Vec<GaugeReading>, String> {
+/// Latest reading time per USGS site, CWMS location, and ASOS station
+#[derive(Debug, Default)]
+struct LatestReadingTimes {
+    usgs: HashMap<String, DateTime<Utc>>,
+    cwms: HashMap<String, DateTime<Utc>>,
+    asos: HashMap<String, DateTime<Utc>>,
+}
+
+impl LatestReadingTimes {
+    /// Latest reading time for a zone sensor, resolved the way the zone
+    /// detail view picks its feed. Composite sensors take the newer feed.
+    fn for_sensor(&self, sensor: &zones::Sensor) -> Option<DateTime<Utc>> {
+        let usgs = sensor.usgs_id.as_ref().and_then(|id| self.usgs.get(id)).copied();
+        let cwms = sensor.cwms_location.as_ref().and_then(|id| self.cwms.get(id)).copied();
+        
+        if sensor.composite_feeds().is_some() {
+            usgs.max(cwms)
+        } else if usgs.is_some() {
+            usgs
+        } else if sensor.is_cwms() {
+            cwms
+        } else if sensor.is_asos() {
+            sensor.station_id.as_ref().and_then(|id| self.asos.get(id)).copied()
+        } else {
+            None
+        }
+    }
+}
+
+/// Snapshot of the latest reading time for every source in one query.
+/// USGS uses the same 4-hour window as `fetch_all_recent_readings`.
+fn fetch_latest_reading_times(client: &mut Client) -> Result<LatestReadingTimes, String> {
     let rows = client.query(
-        "SELECT DISTINCT ON (site_code, parameter_code)
-            site_code,
-            parameter_code,
-            unit,
-            value,
-            reading_time,
-            qualifier
+        "SELECT 'usgs', site_code, MAX(reading_time)
          FROM usgs_raw.gauge_readings
          WHERE reading_time >= NOW() - INTERVAL '4 hours'
-         ORDER BY site_code, parameter_code, reading_time DESC",
-        &[]
-    ).map_err(|e| format!("Failed to fetch recent readings: {}", e))?;
+           AND parameter_code IN ($1, $2)
+         GROUP BY site_code
+         UNION ALL
+         SELECT 'cwms', location_id, MAX(timestamp)
+         FROM usace.cwms_timeseries
+         GROUP BY location_id
+         UNION ALL
+         SELECT 'asos', station_id, MAX(observation_time)
+         FROM public.asos_observations
+         WHERE precip_1hr_in IS NOT NULL
+         GROUP BY station_id",
+        &[&PARAM_STAGE, &PARAM_DISCHARGE]
+    ).map_err(|e| format!("Failed to fetch latest reading times: {}", e))?;
     
-    let mut readings = Vec::new();
+    let mut latest = LatestReadingTimes::default();
     
     for row in rows {
-        let site_code: String = row.get(0);
-        let parameter_code: String = row.get(1);
-        let unit: String = row.get(2);
-        let value: rust_decimal::Decimal = row.get(3);
-        let reading_time: DateTime<Utc> = row.get(4);
-        let qualifier: String = row.get(5);
-        
-        readings.push(GaugeReading {
-            site_code: site_code.clone(),
-            site_name: site_code.clone(),  // Will be enriched later
-            parameter_code,
-            unit,
-            value: value.to_string().parse().unwrap_or(0.0),
-            datetime: reading_time.to_rfc3339(),
-            qualifier,
-        });
-    }
-    
-    Ok(readings)
-}
-
-/// Fetch sensor reading (for CWMS/ASOS sensors)
-fn fetch_sensor_reading(
-    client: &mut Client,
-    sensor: &zones::Sensor
-) -> Result<(Option<f64>, Option<String>, Option<String>, Option<i64>), String> {
-    
-    if sensor.is_cwms() {
-        // Query CWMS timeseries table
-        if let Some(cwms_loc) = &sensor.cwms_location {
-            let rows = client.query(
-                "SELECT value, unit, timestamp
-                 FROM usace.cwms_timeseries
-                 WHERE location_id = $1
-                 ORDER BY timestamp DESC
-                 LIMIT 1",
-                &[cwms_loc]
-            ).map_err(|e| format!("CWMS query failed: {}", e))?;
-            
-            if let Some(row) = rows.first() {
-                let value: rust_decimal::Decimal = row.get(0);
-                let unit: String = row.get(1);
-                let timestamp: DateTime<Utc> = row.get(2);
-                let staleness = (Utc::now() - timestamp).num_minutes();
-                
-                return Ok((
-                    Some(value.to_string().parse().unwrap_or(0.0)),
-                    Some(unit),
-                    Some(timestamp.to_rfc3339()),
-                    Some(staleness)
-                ));
-            }
-        }
-    } else if sensor.is_asos() {
-        // Query ASOS observations table
-        if let Some(station_id) = &sensor.station_id {
-            let rows = client.query(
-                "SELECT precip_1hr_in, observation_time
-                 FROM public.asos_observations
-                 WHERE station_id = $1
-                 ORDER BY observation_time DESC
-                 LIMIT 1",
-                &[station_id]
-            ).map_err(|e| {
-                eprintln!("ASOS query error for station {}: {:?}", station_id, e);
-                format!("ASOS query failed: {}", e)
-            })?;
-            
-            if let Some(row) = rows.first() {
-                let value_opt: Option<f64> = row.get(0);
-                let timestamp: DateTime<Utc> = row.get(1);
-                let staleness = (Utc::now() - timestamp).num_minutes();
-                
-                if let Some(value) = value_opt {
-                    return Ok((
-                        Some(value),
-                        Some("in".to_string()),
-                        Some(timestamp.to_rfc3339()),
-                        Some(staleness)
-                    ));
-                }
-            }
-        }
+        let source: &str = row.get(0);
+        let id: String = row.get(1);
+        let ts: DateTime<Utc> = row.get(2);
+        
+        match source {
+            "usgs" => latest.usgs.insert(id, ts),
+            "cwms" => latest.cwms.insert(id, ts),
+            _ => latest.asos.insert(id, ts),
+        };
     }
     
-    Ok((None, None, None, None))
+    Ok(latest)
 }
 
 /// Fetch precipitation accumulations for an ASOS station.
@@ -624,174 +1496,2487 @@ fn fetch_precip_totals(client: &mut Client, station_id: &str) -> (Option<f64>, O
     (p24, p48)
 }
 
-/// Fetch CWMS stage for a specific location
-fn fetch_cwms_stage(client: &mut Client, location_name: &str, _shef_id: &str) -> Result<Option<f64>, String> {
-    let rows = client.query(
-        "SELECT value
-         FROM usace.cwms_timeseries
-         WHERE location_id LIKE $1
-         ORDER BY timestamp DESC
-         LIMIT 1",
-        &[&format!("%{}%", location_name)]
-    ).map_err(|e| format!("CWMS stage query failed: {}", e))?;
+/// Stored readings on either side of a requested instant
+struct BracketingReadings {
+    before: Option<TimedValue>,
+    after: Option<TimedValue>,
+    unit: Option<String>,
+}
+
+/// Fetch the stored readings immediately before and after `at` for a sensor.
+///
+/// USGS sensors read `usgs_raw.gauge_readings` for `parameter` (default
+/// stage); CWMS sensors read `usace.cwms_timeseries`, optionally filtered by
+/// CWMS parameter ("Stage", "Elev", ...), keeping both points on the same
+/// timeseries.
+fn fetch_bracketing_readings(
+    client: &mut Client,
+    sensor: &zones::Sensor,
+    at: DateTime<Utc>,
+    parameter: Option<&str>,
+) -> Result<BracketingReadings, String> {
+    let to_timed = |row: &postgres::Row| {
+        let timestamp: DateTime<Utc> = row.get(0);
+        let value: rust_decimal::Decimal = row.get(1);
+        TimedValue {
+            timestamp,
+            value: value.to_string().parse().unwrap_or(0.0),
+        }
+    };
     
-    if let Some(row) = rows.first() {
-        let value: rust_decimal::Decimal = row.get(0);
-        Ok(Some(value.to_string().parse().unwrap_or(0.0)))
-    } else {
-        Ok(None)
+    if let Some(site_code) = &sensor.usgs_id {
+        let parameter_code = parameter.unwrap_or(crate::model::PARAM_STAGE);
+        let before = client.query(
+            "SELECT reading_time, value, unit FROM usgs_raw.gauge_readings
+             WHERE site_code = $1 AND parameter_code = $2 AND reading_time <= $3
+             ORDER BY reading_time DESC LIMIT 1",
+            &[site_code, &parameter_code, &at]
+        ).map_err(|e| format!("Failed to fetch readings: {}", e))?;
+        let after = client.query(
+            "SELECT reading_time, value, unit FROM usgs_raw.gauge_readings
+             WHERE site_code = $1 AND parameter_code = $2 AND reading_time >= $3
+             ORDER BY reading_time ASC LIMIT 1",
+            &[site_code, &parameter_code, &at]
+        ).map_err(|e| format!("Failed to fetch readings: {}", e))?;
+        
+        let unit = before.first().or(after.first()).map(|row| row.get::<_, String>(2));
+        return Ok(BracketingReadings {
+            before: before.first().map(to_timed),
+            after: after.first().map(to_timed),
+            unit,
+        });
+    }
+    
+    if let Some(location_id) = &sensor.cwms_location {
+        let before = client.query(
+            "SELECT timestamp, value, unit, timeseries_id FROM usace.cwms_timeseries
+             WHERE location_id = $1 AND ($2::text IS NULL OR parameter_id = $2) AND timestamp <= $3
+             ORDER BY timestamp DESC LIMIT 1",
+            &[location_id, &parameter, &at]
+        ).map_err(|e| format!("Failed to fetch CWMS readings: {}", e))?;
+        let timeseries_id: Option<String> = before.first().map(|row| row.get(3));
+        let after = client.query(
+            "SELECT timestamp, value, unit, timeseries_id FROM usace.cwms_timeseries
+             WHERE location_id = $1 AND ($2::text IS NULL OR parameter_id = $2) AND timestamp >= $3
+               AND ($4::text IS NULL OR timeseries_id = $4)
+             ORDER BY timestamp ASC LIMIT 1",
+            &[location_id, &parameter, &at, &timeseries_id]
+        ).map_err(|e| format!("Failed to fetch CWMS readings: {}", e))?;
+        
+        let unit = before.first().or(after.first()).map(|row| row.get::<_, String>(2));
+        return Ok(BracketingReadings {
+            before: before.first().map(to_timed),
+            after: after.first().map(to_timed),
+            unit,
+        });
     }
+    
+    Err(format!("Sensor {} has no USGS or CWMS time series to interpolate", sensor.primary_id()))
 }
 
 // ============================================================================
-// HTTP Server
+// Zone History
 // ============================================================================
 
-/// Start HTTP endpoint server on the specified port
-pub fn start_endpoint_server(port: u16, mut client: Client) -> Result<(), String> {
-    let server = tiny_http::Server::http(format!("0.0.0.0:{}", port))
-        .map_err(|e| format!("Failed to start HTTP server: {}", e))?;
+/// Readings for a zone's USGS, CWMS, and ASOS sensors in one result, the
+/// source for both raw and hourly history. CWMS rows carry the full
+/// timeseries ID as their parameter: pool and tailwater share a location
+/// and the `Elev` parameter, and only the timeseries ID keeps them apart.
+const ZONE_HISTORY_UNION: &str = "
+        SELECT site_code AS sensor_id, 'USGS' AS source, parameter_code AS parameter,
+               value::float8 AS value, unit, reading_time AS observed_at
+        FROM usgs_raw.gauge_readings
+        WHERE site_code = ANY($1) AND reading_time >= $4 AND reading_time < $5
+        UNION ALL
+        SELECT location_id, 'CWMS', timeseries_id, value::float8, unit, timestamp
+        FROM usace.cwms_timeseries
+        WHERE location_id = ANY($2) AND timestamp >= $4 AND timestamp < $5
+        UNION ALL
+        SELECT station_id, 'ASOS', 'precip_1hr', precip_1hr_in, 'in', observation_time
+        FROM public.asos_observations
+        WHERE station_id = ANY($3) AND precip_1hr_in IS NOT NULL
+          AND observation_time >= $4 AND observation_time < $5";
+
+/// Default history window when `since` is omitted
+const DEFAULT_HISTORY_DAYS: i64 = 7;
+
+/// Granularity of zone history records (`resolution` query parameter)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryResolution {
+    /// Every stored reading
+    Raw,
+    /// Per-hour min/mean/max/count for each sensor and parameter
+    Hourly,
+}
+
+impl HistoryResolution {
+    pub fn from_query(param: Option<&str>) -> Result<Self, String> {
+        match param {
+            None | Some("raw") => Ok(HistoryResolution::Raw),
+            Some("hourly") => Ok(HistoryResolution::Hourly),
+            Some(other) => Err(format!("Invalid resolution '{}'. Use 'raw' or 'hourly'.", other)),
+        }
+    }
     
-    println!("📡 Zone-based HTTP endpoint listening on http://0.0.0.0:{}", port);
-    println!("   NEW ZONE-BASED ENDPOINTS:");
-    println!("   GET /zones - List all zones with metadata");
-    println!("   GET /zone/{{zone_id}} - Get zone detail (0-6)");
-    println!("   GET /status - Overall basin flood status");
-    println!("   GET /backwater - Backwater flood analysis");
-    println!("   GET /health - Service health check");
-    println!("   ");
-    println!("   DEPRECATED (but still functional):");
-    println!("   GET /site/{{site_code}} - Single-site query (use /zone instead)\n");
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HistoryResolution::Raw => "raw",
+            HistoryResolution::Hourly => "hourly",
+        }
+    }
     
-    for request in server.incoming_requests() {
-        let url = request.url();
-        
-        // Route requests
-        let response = if url == "/health" {
-            handle_health()
-        } else if url == "/zones" {
-            handle_zones_list(&mut client)
-        } else if url.starts_with("/zone/") {
-            let zone_id_str = url.trim_start_matches("/zone/");
-            handle_zone_detail(&mut client, zone_id_str)
-        } else if url == "/status" {
-            handle_basin_status(&mut client)
-        } else if url == "/backwater" {
-            handle_backwater_analysis(&mut client)
-        } else if url.starts_with("/site/") {
-            // DEPRECATED endpoint
-            handle_deprecated_site_query(&mut client, url)
-        } else {
-            create_response(
-                404,
-                serde_json::json!({
-                    "error": "Not found",
-                    "available_endpoints": {
-                        "zones": "/zones",
-                        "zone_detail": "/zone/{zone_id}",
-                        "basin_status": "/status",
-                        "backwater_analysis": "/backwater",
-                        "health": "/health",
-                        "deprecated_site_query": "/site/{site_code}"
-                    }
-                })
-            )
-        };
-        
-        if let Err(e) = request.respond(response) {
-            eprintln!("Failed to send response: {}", e);
+    /// Time-ordered query over `ZONE_HISTORY_UNION`, so rows can be streamed
+    /// straight off a cursor.
+    fn sql(&self) -> String {
+        match self {
+            HistoryResolution::Raw => format!(
+                "SELECT sensor_id, source, parameter, value, unit, observed_at
+                 FROM ({}) history
+                 ORDER BY observed_at, sensor_id, parameter",
+                ZONE_HISTORY_UNION
+            ),
+            HistoryResolution::Hourly => format!(
+                "SELECT sensor_id, source, parameter, unit, date_trunc('hour', observed_at) AS hour, {}
+                 FROM ({}) history
+                 GROUP BY sensor_id, source, parameter, unit, hour
+                 ORDER BY hour, sensor_id, parameter",
+                HOURLY_STATS_SQL, ZONE_HISTORY_UNION
+            ),
         }
     }
     
-    Ok(())
+    fn record(&self) -> fn(&Row) -> serde_json::Value {
+        match self {
+            HistoryResolution::Raw => history_record,
+            HistoryResolution::Hourly => hourly_history_record,
+        }
+    }
 }
 
-/// Handle /health endpoint
-fn handle_health() -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    create_response(
-        200,
-        serde_json::json!({
-            "status": "ok",
-            "service": "flomon_service",
-            "version": "0.2.0",
-            "endpoint_version": "zone-based"
-        })
-    )
+/// USGS, CWMS, and ASOS identifiers of a zone's sensors
+fn zone_history_ids(zone: &zones::Zone) -> (Vec<String>, Vec<String>, Vec<String>) {
+    let usgs = zone.sensors.iter().filter_map(|s| s.usgs_id.clone()).collect();
+    let cwms = zone.sensors.iter().filter_map(|s| s.cwms_location.clone()).collect();
+    let asos = zone.sensors.iter()
+        .filter(|s| s.is_asos())
+        .filter_map(|s| s.station_id.clone())
+        .collect();
+    (usgs, cwms, asos)
 }
 
-/// Handle /zones endpoint
-fn handle_zones_list(client: &mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    match fetch_zones_list(client) {
-        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
-        Err(e) => create_response(500, serde_json::json!({"error": e})),
+/// `since`/`until` query parameters (RFC 3339), defaulting to the last
+/// `DEFAULT_HISTORY_DAYS` up to now.
+fn parse_history_window(query: &HashMap<String, String>) -> Result<(DateTime<Utc>, DateTime<Utc>), String> {
+    let parse = |key: &str| -> Result<Option<DateTime<Utc>>, String> {
+        query.get(key)
+            .map(|s| DateTime::parse_from_rfc3339(s)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|_| format!("Invalid '{}' timestamp '{}'. Use RFC 3339.", key, s)))
+            .transpose()
+    };
+    
+    let until = parse("until")?.unwrap_or_else(Utc::now);
+    let since = parse("since")?.unwrap_or(until - chrono::Duration::days(DEFAULT_HISTORY_DAYS));
+    
+    if since >= until {
+        return Err("'since' must be before 'until'".to_string());
     }
+    Ok((since, until))
 }
 
-/// Handle /zone/{zone_id} endpoint
-fn handle_zone_detail(client: &mut Client, zone_id_str: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    let zone_id: usize = match zone_id_str.parse() {
-        Ok(id) if id <= 6 => id,
-        _ => return create_response(
-            400,
-            serde_json::json!({
-                "error": "Invalid zone_id. Must be 0-6.",
-                "valid_zones": [0, 1, 2, 3, 4, 5, 6]
-            })
-        ),
-    };
+fn history_record(row: &Row) -> serde_json::Value {
+    let value: Option<f64> = row.get(3);
+    let observed_at: DateTime<Utc> = row.get(5);
     
-    match fetch_zone_detail(client, zone_id) {
-        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
-        Err(e) => create_response(500, serde_json::json!({"error": e})),
-    }
+    serde_json::json!({
+        "sensor_id": row.get::<_, String>(0),
+        "source": row.get::<_, String>(1),
+        "parameter": row.get::<_, String>(2),
+        "value": value,
+        "unit": row.get::<_, String>(4),
+        "timestamp": observed_at,
+    })
 }
 
-/// Handle /status endpoint
-fn handle_basin_status(client: &mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    match fetch_basin_status(client) {
-        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
-        Err(e) => create_response(500, serde_json::json!({"error": e})),
+fn hourly_history_record(row: &Row) -> serde_json::Value {
+    let hour: DateTime<Utc> = row.get(4);
+    
+    serde_json::json!({
+        "sensor_id": row.get::<_, String>(0),
+        "source": row.get::<_, String>(1),
+        "parameter": row.get::<_, String>(2),
+        "unit": row.get::<_, String>(3),
+        "timestamp": hour,
+        "min": row.get::<_, Option<f64>>(5),
+        "mean": row.get::<_, Option<f64>>(6),
+        "max": row.get::<_, Option<f64>>(7),
+        "count": row.get::<_, i64>(8),
+    })
+}
+
+/// Run the zone history query, yielding rows from a cursor.
+fn query_zone_history<'a>(
+    client: &'a mut Client,
+    zone: &zones::Zone,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    resolution: HistoryResolution,
+) -> Result<RowIter<'a>, String> {
+    let (usgs, cwms, asos) = zone_history_ids(zone);
+    let params: [&(dyn ToSql + Sync); 5] = [&usgs, &cwms, &asos, &since, &until];
+    
+    client.query_raw(&resolution.sql(), params)
+        .map_err(|e| format!("Failed to query zone history: {}", e))
+}
+
+/// All readings (or hourly aggregates) for a zone between `since` and
+/// `until`, buffered.
+pub fn fetch_zone_history(
+    client: &mut Client,
+    zone_id: usize,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    resolution: HistoryResolution,
+) -> Result<Vec<serde_json::Value>, String> {
+    let zones_config = load_visible_zones()
+        .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
+    let zone = get_zone(&zones_config, zone_id)
+        .ok_or_else(|| format!("Zone {} not found", zone_id))?;
+    
+    let record = resolution.record();
+    query_zone_history(client, zone, since, until, resolution)?
+        .map(|row| Ok(record(&row)))
+        .collect()
+        .map_err(|e| format!("Failed to read zone history: {}", e))
+}
+
+/// Newline-delimited JSON body produced one row at a time from a cursor,
+/// so memory stays bounded however long the window. A read error mid-stream
+/// ends the body with an `error_body` line, since the status line has
+/// already been sent.
+struct NdjsonRows<'a> {
+    rows: RowIter<'a>,
+    record: fn(&Row) -> serde_json::Value,
+    units: UnitSystem,
+    line: Vec<u8>,
+    pos: usize,
+    done: bool,
+}
+
+impl<'a> NdjsonRows<'a> {
+    fn new(rows: RowIter<'a>, record: fn(&Row) -> serde_json::Value, units: UnitSystem) -> Self {
+        Self { rows, record, units, line: Vec::new(), pos: 0, done: false }
+    }
+    
+    /// Load the next line into the buffer; false once the stream is over
+    fn fill_line(&mut self) -> bool {
+        if self.done {
+            return false;
+        }
+        
+        let record = match self.rows.next() {
+            Ok(Some(row)) => {
+                let mut record = (self.record)(&row);
+                units::localize(&mut record, self.units);
+                units::round_for_display(&mut record);
+                record
+            }
+            Ok(None) => {
+                self.done = true;
+                return false;
+            }
+            Err(e) => {
+                self.done = true;
+                error_body(500, format!("Failed to read zone history: {}", e), serde_json::json!({}))
+            }
+        };
+        
+        self.line = serde_json::to_vec(&record).unwrap();
+        self.line.push(b'\n');
+        self.pos = 0;
+        true
     }
 }
 
-/// Handle /backwater endpoint
-fn handle_backwater_analysis(client: &mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    match analyze_backwater_risk(client) {
-        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
-        Err(e) => create_response(500, serde_json::json!({"error": e})),
+impl Read for NdjsonRows<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        if self.pos >= self.line.len() && !self.fill_line() {
+            return Ok(0);
+        }
+        
+        let n = buf.len().min(self.line.len() - self.pos);
+        buf[..n].copy_from_slice(&self.line[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
     }
 }
 
-/// Handle deprecated /site/{site_code} endpoint
-fn handle_deprecated_site_query(_client: &mut Client, url: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    create_response(
-        410,  // Gone
-        serde_json::json!({
-            "error": "This endpoint is deprecated",
-            "message": "The /site/{site_code} endpoint has been replaced by zone-based views",
-            "migration_guide": {
-                "instead_of": format!("GET {}", url),
-                "use": "GET /zones - to list all zones, then GET /zone/{zone_id} for zone detail",
-                "example": "GET /zone/2 - for your property zone (Upper Peoria Lake)"
-            },
-            "new_endpoints": {
-                "zones_list": "/zones",
-                "zone_detail": "/zone/{zone_id}",
-                "basin_status": "/status",
-                "backwater": "/backwater"
-            }
-        })
-    )
+/// Whether a request should be streamed as NDJSON rather than routed
+fn is_streaming_history_request(url: &str, query: &HashMap<String, String>) -> bool {
+    url.starts_with("/zone/")
+        && url.ends_with("/history")
+        && query.get("format").map(String::as_str) == Some("ndjson")
 }
 
-/// Create HTTP response with JSON body
-fn create_response(status_code: u16, json: serde_json::Value) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
-    let body = serde_json::to_string_pretty(&json).unwrap();
-    let bytes = body.into_bytes();
+/// Stream `/zone/{id}/history?format=ndjson`. The pooled connection is
+/// held for the life of the stream, so a slow consumer ties up one
+/// connection rather than every other request.
+fn stream_zone_history(
+    pool: &db::DbPool,
+    request: tiny_http::Request,
+    url: &str,
+    query: &HashMap<String, String>,
+    started: Instant,
+) {
+    let respond_error = |request: tiny_http::Request, status: u16, error: String| {
+        respond_logged(request, error_response(status, error), started);
+    };
     
-    tiny_http::Response::from_data(bytes)
-        .with_status_code(tiny_http::StatusCode::from(status_code))
-        .with_header(
-            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap()
-        )
+    let units = match UnitSystem::from_query(query.get("units").map(String::as_str)) {
+        Ok(units) => units,
+        Err(e) => return respond_error(request, 400, e),
+    };
+    let (since, until) = match parse_history_window(query) {
+        Ok(window) => window,
+        Err(e) => return respond_error(request, 400, e),
+    };
+    let resolution = match HistoryResolution::from_query(query.get("resolution").map(String::as_str)) {
+        Ok(resolution) => resolution,
+        Err(e) => return respond_error(request, 400, e),
+    };
+    let zone_id_str = url.trim_start_matches("/zone/").trim_end_matches("/history");
+    let zones_config = match load_visible_zones() {
+        Ok(config) => config,
+        Err(e) => return respond_error(request, 500, format!("Failed to load zones.toml: {}", e)),
+    };
+    let Some(zone) = zone_id_str.parse().ok().and_then(|id| get_zone(&zones_config, id)) else {
+        return respond_error(request, 400, "Invalid zone_id. Must be 0-6.".to_string());
+    };
+    
+    let mut client = match pool.get() {
+        Ok(client) => client,
+        Err(e) => return respond_error(request, 503, format!("Database connection unavailable: {}", e)),
+    };
+    let rows = match query_zone_history(&mut client, zone, since, until, resolution) {
+        Ok(rows) => rows,
+        Err(e) => return respond_error(request, 500, e),
+    };
+    
+    // No length: tiny_http sends the body with chunked encoding as it's read
+    let response = tiny_http::Response::new(
+        tiny_http::StatusCode(200),
+        vec![tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/x-ndjson"[..]).unwrap()],
+        NdjsonRows::new(rows, resolution.record(), units),
+        None,
+        None,
+    );
+    respond_logged(request, response, started);
+}
+
+// ============================================================================
+// Sensor Gaps
+// ============================================================================
+
+/// Distinct reading times for a sensor over `since..=until`, ascending, with
+/// the source they came from and its expected cadence in minutes.
+///
+/// Sources are tried in the same order as `fetch_bracketing_readings`:
+/// USGS (all parameters, or just `parameter`), then CWMS, then ASOS.
+fn fetch_reading_times(
+    client: &mut Client,
+    sensor: &zones::Sensor,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    parameter: Option<&str>,
+) -> Result<(&'static str, i64, Vec<DateTime<Utc>>), String> {
+    let (source, interval, sql, id) = if let Some(site_code) = &sensor.usgs_id {
+        let rows = client.query(
+            "SELECT DISTINCT reading_time FROM usgs_raw.gauge_readings
+             WHERE site_code = $1 AND ($2::text IS NULL OR parameter_code = $2)
+               AND reading_time BETWEEN $3 AND $4
+             ORDER BY reading_time",
+            &[site_code, &parameter, &since, &until]
+        ).map_err(|e| format!("Failed to fetch reading times: {}", e))?;
+        return Ok(("USGS", gaps::USGS_IV_INTERVAL_MINUTES, rows.iter().map(|row| row.get(0)).collect()));
+    } else if let Some(location_id) = &sensor.cwms_location {
+        ("CWMS", gaps::HOURLY_INTERVAL_MINUTES,
+         "SELECT DISTINCT timestamp FROM usace.cwms_timeseries
+          WHERE location_id = $1 AND split_part(parameter_id, '-', 1) IN ('Stage', 'Elev')
+            AND timestamp BETWEEN $2 AND $3
+          ORDER BY timestamp",
+         location_id)
+    } else if let Some(station_id) = sensor.station_id.as_ref().filter(|_| sensor.is_asos()) {
+        ("ASOS", gaps::HOURLY_INTERVAL_MINUTES,
+         "SELECT DISTINCT observation_time FROM public.asos_observations
+          WHERE station_id = $1 AND observation_time BETWEEN $2 AND $3
+          ORDER BY observation_time",
+         station_id)
+    } else {
+        return Err(format!("Sensor {} has no stored time series", sensor.primary_id()));
+    };
+    
+    let rows = client.query(sql, &[id, &since, &until])
+        .map_err(|e| format!("Failed to fetch reading times: {}", e))?;
+    Ok((source, interval, rows.iter().map(|row| row.get(0)).collect()))
+}
+
+fn gap_response(start: DateTime<Utc>, end: DateTime<Utc>) -> GapResponse {
+    GapResponse { start, end, duration_minutes: (end - start).num_minutes() }
+}
+
+/// Gaps in a sensor's record, detected exactly as the backfill coverage
+/// check does.
+pub fn fetch_sensor_gaps(
+    client: &mut Client,
+    sensor: &zones::Sensor,
+    since: DateTime<Utc>,
+    until: DateTime<Utc>,
+    parameter: Option<&str>,
+) -> Result<SensorGapsResponse, String> {
+    let (source, interval_minutes, timestamps) = fetch_reading_times(client, sensor, since, until, parameter)?;
+    
+    let gaps: Vec<GapResponse> = detect_gaps(&timestamps, since, until, chrono::Duration::minutes(interval_minutes))
+        .into_iter()
+        .map(|(start, end)| gap_response(start, end))
+        .collect();
+    
+    Ok(SensorGapsResponse {
+        sensor_id: sensor.primary_id(),
+        source: source.to_string(),
+        since,
+        until,
+        expected_interval_minutes: interval_minutes,
+        gap_count: gaps.len(),
+        total_gap_minutes: gaps.iter().map(|g| g.duration_minutes).sum(),
+        gaps,
+    })
+}
+
+// ============================================================================
+// Rate of Rise
+// ============================================================================
+
+/// Hours of stage `/rate/{site_code}` looks back over without `?hours=`
+pub const DEFAULT_RATE_WINDOW_HOURS: i64 = 6;
+
+/// Largest `?hours=` `/rate/{site_code}` accepts; a rate over more than a
+/// week says little about the current rise
+pub const MAX_RATE_WINDOW_HOURS: i64 = 168;
+
+/// Parse an `?hours=` window: `default` when absent, `None` unless it's a
+/// whole number from 1 to `max`. Bounding it also keeps `now - hours` from
+/// overflowing.
+fn parse_window_hours(raw: Option<&str>, default: i64, max: i64) -> Option<i64> {
+    match raw.map(str::parse::<i64>) {
+        None => Some(default),
+        Some(Ok(hours)) if (1..=max).contains(&hours) => Some(hours),
+        Some(_) => None,
+    }
+}
+
+/// Window-average rates within this of zero (either way) are "steady"
+pub const STEADY_RATE_FT_PER_HR: f64 = 0.02;
+
+/// Rate of rise over `stage` (oldest first), in ft/hr.
+pub fn rate_of_rise(site_code: &str, window_hours: i64, stage: &[TimedValue]) -> RateOfRiseResponse {
+    let hours_between = |a: &TimedValue, b: &TimedValue| {
+        (b.timestamp - a.timestamp).num_seconds() as f64 / 3600.0
+    };
+    let step_rates: Vec<f64> = stage.windows(2)
+        .filter(|pair| hours_between(&pair[0], &pair[1]) > 0.0)
+        .map(|pair| (pair[1].value - pair[0].value) / hours_between(&pair[0], &pair[1]))
+        .collect();
+    
+    let average_rate_ft_per_hr = match (stage.first(), stage.last()) {
+        (Some(first), Some(last)) if hours_between(first, last) > 0.0 => {
+            Some((last.value - first.value) / hours_between(first, last))
+        }
+        _ => None,
+    };
+    let trend = match average_rate_ft_per_hr {
+        None => "unknown",
+        Some(rate) if rate >= STEADY_RATE_FT_PER_HR => "rising",
+        Some(rate) if rate <= -STEADY_RATE_FT_PER_HR => "falling",
+        Some(_) => "steady",
+    };
+    
+    RateOfRiseResponse {
+        site_code: site_code.to_string(),
+        window_hours,
+        current_rate_ft_per_hr: step_rates.last().copied(),
+        max_rate_ft_per_hr: step_rates.iter().copied().reduce(f64::max),
+        average_rate_ft_per_hr,
+        trend,
+        samples: stage.len(),
+    }
+}
+
+/// Rate of rise of instantaneous stage (00065) at `site_code` over the last
+/// `window_hours`.
+pub fn fetch_rate_of_rise(client: &mut Client, site_code: &str, window_hours: i64) -> Result<RateOfRiseResponse, String> {
+    let now = Utc::now();
+    let since = now - chrono::Duration::hours(window_hours);
+    let rows = client.query(
+        "SELECT reading_time, value::float8 FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2
+           AND source IS DISTINCT FROM $3
+           AND reading_time > $4 AND reading_time <= $5
+         ORDER BY reading_time ASC",
+        &[&site_code, &PARAM_STAGE, &ReadingSource::UsgsDv.as_str(), &since, &now]
+    ).map_err(|e| format!("Failed to fetch stage for {}: {}", site_code, e))?;
+    
+    let stage: Vec<TimedValue> = rows.iter()
+        .map(|row| TimedValue { timestamp: row.get(0), value: row.get(1) })
+        .collect();
+    Ok(rate_of_rise(site_code, window_hours, &stage))
+}
+
+// ============================================================================
+// Trend Series
+// ============================================================================
+
+/// `/trend/{site_code}` defaults: a week of stage, at most 500 points
+pub const DEFAULT_TREND_HOURS: i64 = 168;
+pub const DEFAULT_TREND_POINTS: usize = 500;
+
+/// Largest `?points=` accepted; more than a chart can use
+pub const MAX_TREND_POINTS: usize = 5000;
+
+/// Largest `?hours=` accepted: a year of record
+pub const MAX_TREND_HOURS: i64 = 24 * 366;
+
+/// Parameter codes we ingest: discharge, stage, and any station's
+/// configured `expected_parameters`.
+fn known_parameters() -> Vec<String> {
+    let mut codes: Vec<String> = stations::DEFAULT_USGS_PARAMETERS.iter().map(|p| p.to_string()).collect();
+    for station in stations::load_stations() {
+        for code in station.expected_parameters {
+            if !codes.contains(&code) {
+                codes.push(code);
+            }
+        }
+    }
+    codes
+}
+
+/// Instantaneous `parameter_code` readings at `site_code` over
+/// `start..=end`, LTTB-downsampled to at most `max_points`.
+pub fn fetch_site_series(
+    client: &mut Client,
+    site_code: &str,
+    parameter_code: &str,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    max_points: usize,
+) -> Result<SiteSeriesResponse, String> {
+    let rows = client.query(
+        "SELECT reading_time, value::float8, unit FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2
+           AND source IS DISTINCT FROM $3
+           AND reading_time >= $4 AND reading_time <= $5
+         ORDER BY reading_time ASC",
+        &[&site_code, &parameter_code, &ReadingSource::UsgsDv.as_str(), &start, &end]
+    ).map_err(|e| format!("Failed to fetch {} series for {}: {}", parameter_code, site_code, e))?;
+    
+    let series: Vec<TimedValue> = rows.iter()
+        .map(|row| TimedValue { timestamp: row.get(0), value: row.get(1) })
+        .collect();
+    
+    Ok(SiteSeriesResponse {
+        site_code: site_code.to_string(),
+        parameter_code: parameter_code.to_string(),
+        unit: rows.last().map(|row| row.get(2)),
+        start,
+        end,
+        raw_count: series.len(),
+        points: lttb(&series, max_points).into_iter()
+            .map(|p| SeriesPoint { t: p.timestamp, v: p.value })
+            .collect(),
+    })
+}
+
+// ============================================================================
+// Forecast
+// ============================================================================
+
+/// Instantaneous stage at `site_code` over the `forecast_blend::TREND_WINDOW_HOURS`
+/// before `now`, oldest first.
+fn fetch_recent_stage(client: &mut Client, site_code: &str, now: DateTime<Utc>) -> Result<Vec<TimedValue>, String> {
+    let since = now - chrono::Duration::hours(forecast_blend::TREND_WINDOW_HOURS);
+    let rows = client.query(
+        "SELECT reading_time, value::float8 FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2
+           AND source IS DISTINCT FROM $3
+           AND reading_time > $4 AND reading_time <= $5
+         ORDER BY reading_time ASC",
+        &[&site_code, &PARAM_STAGE, &ReadingSource::UsgsDv.as_str(), &since, &now]
+    ).map_err(|e| format!("Failed to fetch recent stage for {}: {}", site_code, e))?;
+    
+    Ok(rows.iter()
+        .map(|row| TimedValue { timestamp: row.get(0), value: row.get(1) })
+        .collect())
+}
+
+/// Current stage and 24-hour trend of every visible station with an
+/// analog event for `selector`. `None` when no event matches.
+pub fn fetch_event_comparison(
+    client: &mut Client,
+    selector: EventSelector,
+) -> Result<Option<CompareEventResponse>, String> {
+    let events = analog_events(selector, client)?;
+    if events.is_empty() {
+        return Ok(None);
+    }
+    
+    // A days-old daily mean says nothing about where the crest is now
+    let latest_stage: HashMap<String, GaugeReading> = fetch_all_recent_readings(client)?
+        .into_iter()
+        .filter(|r| r.parameter_code == PARAM_STAGE && r.source != ReadingSource::UsgsDv)
+        .map(|r| (r.site_code.clone(), r))
+        .collect();
+    let names: HashMap<String, String> = stations::load_stations()
+        .into_iter()
+        .map(|s| (s.site_code, s.name))
+        .collect();
+    
+    let now = Utc::now();
+    let mut comparisons = Vec::new();
+    for event in events.iter().filter(|e| sensor_filter().is_id_visible(&e.site_code)) {
+        let Some(reading) = latest_stage.get(&event.site_code) else {
+            continue;
+        };
+        let rate = event_analog::current_rate_ft_per_day(&event.site_code, now, client)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to compute stage trend for {}: {}", event.site_code, e);
+                None
+            });
+        
+        comparisons.push(StationComparisonResponse {
+            name: names.get(&event.site_code).cloned(),
+            comparison: compare_to_event(reading.value, rate, event),
+        });
+    }
+    
+    let (event_id, year) = match selector {
+        EventSelector::Id(id) => (Some(id), None),
+        EventSelector::Year(year) => (None, Some(year)),
+    };
+    Ok(Some(CompareEventResponse { event_id, year, generated_at: now, stations: comparisons }))
+}
+
+/// Blend of the official forecast and our trend extrapolation for a
+/// USGS stage sensor.
+pub fn fetch_forecast(client: &mut Client, sensor: &zones::Sensor) -> Result<ForecastResponse, String> {
+    let site_code = sensor.usgs_id.clone()
+        .ok_or_else(|| format!("Sensor {} has no USGS stage record to project", sensor.primary_id()))?;
+    
+    let now = Utc::now();
+    let extrapolated = extrapolate_stage(&fetch_recent_stage(client, &site_code, now)?, now);
+    // NWS AHPS forecasts are not ingested yet, so the blend is
+    // extrapolation-only until they are
+    let official: Vec<ForecastPoint> = Vec::new();
+    let blend = blend_forecast(&official, &extrapolated, &BlendWeights::default(), now);
+    
+    Ok(ForecastResponse {
+        sensor_id: sensor.primary_id(),
+        site_code,
+        issued: now,
+        flood_stage_ft: sensor.flood_stage_ft,
+        official,
+        extrapolated,
+        blend,
+    })
+}
+
+/// Zone the property sits in (Upper Peoria Lake)
+const PROPERTY_ZONE_ID: usize = 2;
+
+/// Property outlook from the active zones' lead times, the upstream flood
+/// pulse, and the current rate of rise at Kingston Mines.
+pub fn fetch_property_forecast(client: &mut Client) -> Result<PropertyForecastResponse, String> {
+    let now = Utc::now();
+    let zones_config = load_visible_zones()
+        .map_err(|e| format!("Failed to load zones.toml: {}", e))?;
+    let zones = assess_zones(client, &zones_config)?;
+    let property_zone_status = zones.iter()
+        .find(|(zone_id, _)| *zone_id == PROPERTY_ZONE_ID)
+        .map(|(_, zone)| zone.alert_level)
+        .ok_or_else(|| format!("Zone {} not found", PROPERTY_ZONE_ID))?;
+    let active_zones = active_zone_statuses(&zones, &zones_config);
+    let pulse = detect_upstream_flood_pulse(&active_zones);
+    
+    let mut sources = Vec::new();
+    for zone in active_zones.into_iter().filter(|z| z.zone_id != PROPERTY_ZONE_ID) {
+        let metadata = ZoneMetadata::for_zone(zone.zone_id);
+        let hours_active = zone_active_since(client, zone.zone_id)
+            .unwrap_or_else(|e| {
+                eprintln!("Failed to determine how long zone {} has been active: {}", zone.zone_id, e);
+                None
+            })
+            .map_or(0, |since| (now - since).num_hours());
+        let remaining = |hours: Option<i64>| hours.map(|h| remaining_lead_time(h, hours_active));
+        
+        // Upstream zones arrive on the pulse's schedule; the rest at the
+        // near end of their lead time
+        let arrival = upstream_arrival_hours(zone.zone_id).or(metadata.lead_time_hours_min);
+        sources.push(ContributingSourceResponse {
+            zone_id: zone.zone_id,
+            zone_name: zone.zone_name,
+            status: zone.status,
+            estimated_arrival_hours: remaining(arrival),
+            lead_time_hours_min: remaining(metadata.lead_time_hours_min),
+            lead_time_hours_max: remaining(metadata.lead_time_hours_max),
+            key_sensors_elevated: zone.key_sensors_elevated,
+        });
+    }
+    
+    let rate = fetch_rate_of_rise(client, MASS_BALANCE_OUTFLOW_SITE, DEFAULT_RATE_WINDOW_HOURS)?;
+    let mut forecast = property_forecast(property_zone_status, sources, rate.trend, now);
+    if pulse.pulse_detected {
+        forecast.explanation = format!("{} {}", forecast.explanation, pulse.explanation);
+    }
+    Ok(forecast)
+}
+
+/// Assemble the outlook. The peak window is the fastest source's lead
+/// time, narrowed by the Kingston Mines trend: rising already means the
+/// near end (arrival or sooner), steady or falling the far end.
+fn property_forecast(
+    property_zone_status: AlertLevel,
+    mut sources: Vec<ContributingSourceResponse>,
+    kingston_mines_trend: &'static str,
+    now: DateTime<Utc>,
+) -> PropertyForecastResponse {
+    sources.sort_by_key(|s| s.estimated_arrival_hours.unwrap_or(i64::MAX));
+    let active_threat = property_zone_status.is_elevated() || !sources.is_empty();
+    
+    let estimated_peak_window_hours = sources.first().and_then(|fastest| {
+        let arrival = fastest.estimated_arrival_hours?;
+        let earliest = fastest.lead_time_hours_min.unwrap_or(arrival).min(arrival);
+        let latest = fastest.lead_time_hours_max.unwrap_or(arrival).max(arrival);
+        Some(match kingston_mines_trend {
+            "rising" => PeakWindowResponse { earliest_hours: earliest, latest_hours: arrival },
+            "steady" | "falling" => PeakWindowResponse { earliest_hours: arrival, latest_hours: latest },
+            _ => PeakWindowResponse { earliest_hours: earliest, latest_hours: latest },
+        })
+    });
+    
+    // Sources and the gauge agreeing is the strongest signal; no gauge
+    // trend at all the weakest
+    let confidence = match (kingston_mines_trend, sources.is_empty()) {
+        ("unknown", _) => "LOW",
+        ("rising", false) => "HIGH",
+        ("rising", true) => "MODERATE",
+        (_, true) => "HIGH",
+        (_, false) => "MODERATE",
+    };
+    
+    let explanation = match (&estimated_peak_window_hours, active_threat) {
+        (_, false) => "No active threat: no zone is elevated and the property zone is not above action stage.".to_string(),
+        (None, true) => format!(
+            "Property zone is at {} with no active upstream or backwater source; Kingston Mines is {}.",
+            property_zone_status.as_str(), kingston_mines_trend
+        ),
+        (Some(window), true) => format!(
+            "Peak expected at the property in {}-{} hours (zone {} is the fastest active source); Kingston Mines is {}.",
+            window.earliest_hours, window.latest_hours, sources[0].zone_id, kingston_mines_trend
+        ),
+    };
+    
+    PropertyForecastResponse {
+        active_threat,
+        property_zone_status,
+        estimated_peak_window_hours,
+        contributing_sources: sources,
+        confidence: confidence.to_string(),
+        kingston_mines_trend,
+        explanation,
+        issued: now,
+    }
+}
+
+// ============================================================================
+// HTTP Server
+// ============================================================================
+
+/// Default cap on concurrently in-flight (queued + executing) requests.
+/// Override with the `ENDPOINT_MAX_IN_FLIGHT` environment variable.
+const DEFAULT_MAX_IN_FLIGHT: usize = 16;
+
+/// Counts a request as in flight for as long as it is held.
+///
+/// Acquired on the accept thread before a request is handed to a worker and
+/// released (on drop) once the response has been sent.
+struct InFlightGuard {
+    counter: Arc<AtomicUsize>,
+}
+
+impl InFlightGuard {
+    /// Reserve a slot, or `None` if `max` requests are already in flight.
+    fn try_acquire(counter: &Arc<AtomicUsize>, max: usize) -> Option<Self> {
+        counter
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max).then_some(n + 1))
+            .ok()
+            .map(|_| Self { counter: Arc::clone(counter) })
+    }
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.counter.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// ============================================================================
+// Conditional GET
+// ============================================================================
+
+/// `If-None-Match` / `If-Modified-Since` sent by a polling client
+#[derive(Debug, Default, Clone)]
+struct ConditionalHeaders {
+    if_none_match: Option<String>,
+    if_modified_since: Option<String>,
+}
+
+impl ConditionalHeaders {
+    fn from_request(request: &tiny_http::Request) -> Self {
+        let header = |name: &'static str| request.headers().iter()
+            .find(|h| h.field.equiv(name))
+            .map(|h| h.value.as_str().to_string());
+        
+        Self {
+            if_none_match: header("If-None-Match"),
+            if_modified_since: header("If-Modified-Since"),
+        }
+    }
+}
+
+/// Most recent ingest activity across all sources: the last USGS poll, and
+/// the newest CWMS, ASOS, and NWS alert rows. Responses built from the
+/// database cannot change until this moves.
+fn latest_poll_time(client: &mut Client) -> Result<Option<DateTime<Utc>>, String> {
+    let row = client.query_one(
+        "SELECT GREATEST(
+             (SELECT MAX(last_poll_attempted) FROM usgs_raw.monitoring_state),
+             (SELECT MAX(timestamp) FROM usace.cwms_timeseries),
+             (SELECT MAX(observation_time) FROM public.asos_observations),
+             (SELECT MAX(last_seen) FROM nws.alerts),
+             (SELECT MAX(polled_at) FROM nws.alert_polls)
+         )",
+        &[]
+    ).map_err(|e| format!("Failed to fetch latest poll time: {}", e))?;
+    
+    Ok(row.get(0))
+}
+
+/// When a response built at `now` last changed: the latest poll, or the
+/// start of the current minute if later. Staleness ages and stale flags are
+/// whole minutes since a reading, so they move without any new ingest.
+fn validator_time(last_poll: DateTime<Utc>, now: DateTime<Utc>) -> DateTime<Utc> {
+    let minute = now.duration_trunc(chrono::Duration::minutes(1)).unwrap_or(now);
+    last_poll.max(minute)
+}
+
+/// Strong ETag for a response built as of `last_poll` in `units`
+fn etag_for(last_poll: DateTime<Utc>, units: UnitSystem) -> String {
+    format!("\"{}-{}\"", last_poll.timestamp_millis(), units.as_str())
+}
+
+/// HTTP-date (RFC 7231 IMF-fixdate), e.g. `Wed, 01 May 2024 12:00:00 GMT`
+fn http_date(ts: DateTime<Utc>) -> String {
+    ts.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Whether the client's cached copy is still current.
+///
+/// `If-None-Match` wins when present (RFC 7232 §6); `If-Modified-Since` is
+/// compared at whole-second precision, as `Last-Modified` carries no more.
+fn is_not_modified(conditional: &ConditionalHeaders, etag: &str, last_poll: DateTime<Utc>) -> bool {
+    if let Some(if_none_match) = &conditional.if_none_match {
+        return if_none_match.split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == "*" || tag == etag);
+    }
+    
+    conditional.if_modified_since.as_deref()
+        .and_then(|since| DateTime::parse_from_rfc2822(since).ok())
+        .is_some_and(|since| last_poll.timestamp() <= since.timestamp())
+}
+
+/// Serve `handler` with `ETag`/`Last-Modified` from the latest poll time
+/// (see `validator_time`), answering 304 without running it when the
+/// client is up to date.
+///
+/// Readings only change when the daemon ingests and ages only once a
+/// minute, so polling dashboards mostly get an empty 304. If the poll time
+/// can't be read, the handler runs uncached.
+fn with_cache_validators(
+    client: &mut Client,
+    conditional: &ConditionalHeaders,
+    units: UnitSystem,
+    handler: impl FnOnce(&mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>>,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let last_poll = match latest_poll_time(client) {
+        Ok(Some(ts)) => validator_time(ts, Utc::now()),
+        Ok(None) => return handler(client),
+        Err(e) => {
+            eprintln!("{}", e);
+            return handler(client);
+        }
+    };
+    
+    let etag = etag_for(last_poll, units);
+    let last_modified = http_date(last_poll);
+    let validators = |response: tiny_http::Response<std::io::Cursor<Vec<u8>>>| response
+        .with_header(tiny_http::Header::from_bytes(&b"ETag"[..], etag.as_bytes()).unwrap())
+        .with_header(tiny_http::Header::from_bytes(&b"Last-Modified"[..], last_modified.as_bytes()).unwrap());
+    
+    if is_not_modified(conditional, &etag, last_poll) {
+        return validators(tiny_http::Response::from_data(Vec::new()).with_status_code(304));
+    }
+    
+    let response = handler(client);
+    if response.status_code().0 == 200 {
+        validators(response)
+    } else {
+        response
+    }
+}
+
+/// Start HTTP endpoint server on the specified port
+///
+/// `/health` and `/metrics` are answered directly on the accept thread so
+/// load-balancer checks and scrapes never queue behind expensive handlers
+/// like `/status`; `/metrics` reads `metrics`, the daemon's counters. All other
+/// routes run on a worker pool, each request checking a connection out of
+/// `pool` (the daemon's, shared with the poll loop); once
+/// `ENDPOINT_MAX_IN_FLIGHT` requests are outstanding, new ones get a 503
+/// instead of queueing without bound. Every response, including the fast
+/// paths and 503s, is timed and logged by `respond_logged`.
+pub fn start_endpoint_server(port: u16, pool: db::DbPool, metrics: SharedMetrics) -> Result<(), String> {
+    let server = tiny_http::Server::http(format!("0.0.0.0:{}", port))
+        .map_err(|e| format!("Failed to start HTTP server: {}", e))?;
+    
+    let max_in_flight = std::env::var("ENDPOINT_MAX_IN_FLIGHT")
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .filter(|&n: &usize| n > 0)
+        .unwrap_or(DEFAULT_MAX_IN_FLIGHT);
+    
+    println!("📡 Zone-based HTTP endpoint listening on http://0.0.0.0:{}", port);
+    println!("   NEW ZONE-BASED ENDPOINTS:");
+    println!("   GET /zones - List all zones with metadata");
+    println!("   GET /zone/{{zone_id}}[?format=csv] - Get zone detail (0-6)");
+    println!("   GET /zone/{{zone_id}}/history?since=<rfc3339>[&format=ndjson][&resolution=hourly] - Zone reading history");
+    println!("   GET /group/{{name}} - Custom sensor group from groups.toml");
+    println!("   GET /status - Overall basin flood status");
+    println!("   GET /backwater - Backwater flood analysis");
+    println!("   GET /score - Compound flood score (0-100) with component breakdown");
+    println!("   GET /compare-event?year=2013 - Current conditions vs. a past flood");
+    println!("   GET /forecast[?sensor={{sensor_id}}] - Property peak window, or one sensor's stage projection");
+    println!("   GET /leadtimes - Upstream travel times and expected arrival at Peoria");
+    println!("   GET /compare?upstream=05557000&downstream=05568500 - Travel-time aligned gauge comparison");
+    println!("   GET /outages - Unhealthy sensors with reasons (operator triage)");
+    println!("   GET /health - Service health check");
+    println!("   GET /metrics - Prometheus metrics");
+    println!("   GET /health/config - Config files drifted from or failing to parse since load");
+    println!("   GET /readings/{{site_code}}?qualifier=P - Readings by USGS qualifier");
+    println!("   GET /rating/{{site_code}} - Stage-discharge rating drift vs. history");
+    println!("   GET /shef/{{site_code}} - Latest reading as a SHEF .A message");
+    println!("   GET /sensor/{{sensor_id}}/at?time=<rfc3339> - Interpolated value at a timestamp");
+    println!("   GET /sensor/{{sensor_id}}/gaps?since=<rfc3339> - Missing stretches in a sensor's record");
+    println!("   ");
+    println!("   DEPRECATED (but still functional):");
+    println!("   GET /site/{{site_code}} - Single-site query (use /zone instead)");
+    println!("   Max in-flight requests: {} (sharing {} database connections)", max_in_flight, pool.size());
+    if sensor_filter().is_filtering() {
+        println!("   Sensor allowlist/denylist active (ENDPOINT_SENSOR_ALLOWLIST / ENDPOINT_SENSOR_DENYLIST)");
+    }
+    println!();
+    
+    serve_requests(&server, pool, metrics, max_in_flight);
+    Ok(())
+}
+
+/// Accept loop behind `start_endpoint_server`: answer `/health` and
+/// `/metrics` inline and hand everything else to up to `max_in_flight`
+/// workers, each with its own connection from `pool`.
+fn serve_requests(server: &tiny_http::Server, pool: db::DbPool, metrics: SharedMetrics, max_in_flight: usize) {
+    let in_flight = Arc::new(AtomicUsize::new(0));
+    let workers = threadpool::ThreadPool::new(max_in_flight);
+    
+    for request in server.incoming_requests() {
+        let started = Instant::now();
+        let (url, query) = split_query(request.url());
+        
+        // Fast path: never queued, never touches the database
+        if url == "/health" {
+            respond_logged(request, handle_health(), started);
+            continue;
+        }
+        if url == "/metrics" {
+            respond_logged(request, handle_metrics(&metrics), started);
+            continue;
+        }
+        
+        let guard = match InFlightGuard::try_acquire(&in_flight, max_in_flight) {
+            Some(guard) => guard,
+            None => {
+                let response = error_response_with_details(
+                    503,
+                    "Server busy",
+                    serde_json::json!({
+                        "reason": format!("{} requests already in flight; retry shortly", max_in_flight)
+                    })
+                );
+                respond_logged(request, response, started);
+                continue;
+            }
+        };
+        
+        let conditional = ConditionalHeaders::from_request(&request);
+        let pool = pool.clone();
+        workers.execute(move || {
+            if is_streaming_history_request(&url, &query) {
+                stream_zone_history(&pool, request, &url, &query, started);
+                drop(guard);
+                return;
+            }
+            
+            let response = match pool.get() {
+                Ok(mut client) => route_request(&mut client, &url, &query, &conditional),
+                Err(e) => error_response(503, format!("Database connection unavailable: {}", e)),
+            };
+            
+            respond_logged(request, response, started);
+            drop(guard);
+        });
+    }
+}
+
+/// Dispatch a request path to its handler
+fn route_request(
+    client: &mut Client,
+    url: &str,
+    query: &HashMap<String, String>,
+    conditional: &ConditionalHeaders,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let units = match UnitSystem::from_query(query.get("units").map(String::as_str)) {
+        Ok(units) => units,
+        Err(e) => return error_response(400, e),
+    };
+    
+    if url == "/health" {
+        handle_health()
+    } else if url == "/health/config" {
+        handle_health_config()
+    } else if url == "/health/stations" {
+        handle_station_health(client, query)
+    } else if url == "/zones" {
+        with_cache_validators(client, conditional, units, handle_zones_list)
+    } else if url.starts_with("/zone/") && url.ends_with("/history") {
+        let zone_id_str = url.trim_start_matches("/zone/").trim_end_matches("/history");
+        handle_zone_history(client, zone_id_str, query, units)
+    } else if url.starts_with("/zone/") {
+        let zone_id_str = url.trim_start_matches("/zone/");
+        handle_zone_detail(client, zone_id_str, query, units)
+    } else if url.starts_with("/group/") {
+        handle_group_detail(client, url.trim_start_matches("/group/"), units)
+    } else if url == "/status" {
+        with_cache_validators(client, conditional, units, |client| handle_basin_status(client, units))
+    } else if url == "/status/history" {
+        handle_basin_status_history(client, query, units)
+    } else if url == "/backwater" {
+        handle_backwater_analysis(client, units)
+    } else if url == "/score" {
+        handle_flood_score(client)
+    } else if url == "/leadtimes" {
+        handle_lead_times(client, units)
+    } else if url == "/compare" {
+        handle_compare(client, query, units)
+    } else if url == "/stations/status" {
+        with_cache_validators(client, conditional, units, |client| handle_stations_status(client, units))
+    } else if url == "/compare-event" {
+        handle_compare_event(client, query, units)
+    } else if url == "/forecast" {
+        handle_forecast(client, query, units)
+    } else if url == "/outages" {
+        handle_outages(client)
+    } else if url.starts_with("/trend/") {
+        handle_trend(client, url.trim_start_matches("/trend/"), query)
+    } else if url.starts_with("/rate/") {
+        handle_rate_of_rise(client, url.trim_start_matches("/rate/"), query)
+    } else if url.starts_with("/rating/") {
+        handle_rating_drift(client, url.trim_start_matches("/rating/"))
+    } else if url.starts_with("/shef/") {
+        handle_shef(client, url.trim_start_matches("/shef/"), query)
+    } else if url.starts_with("/readings/") {
+        let site_code = url.trim_start_matches("/readings/");
+        handle_readings_by_qualifier(client, site_code, query, units)
+    } else if url.starts_with("/sensor/") && url.ends_with("/at") {
+        let sensor_id = url.trim_start_matches("/sensor/").trim_end_matches("/at");
+        handle_sensor_value_at(client, sensor_id, query, units)
+    } else if url.starts_with("/sensor/") && url.ends_with("/gaps") {
+        let sensor_id = url.trim_start_matches("/sensor/").trim_end_matches("/gaps");
+        handle_sensor_gaps(client, sensor_id, query)
+    } else if url.starts_with("/site/") {
+        // DEPRECATED endpoint
+        handle_deprecated_site_query(client, url)
+    } else {
+        error_response_with_details(
+            404,
+            "Not found",
+            serde_json::json!({
+                "available_endpoints": {
+                    "zones": "/zones",
+                    "zone_detail": "/zone/{zone_id}[?format=csv]",
+                    "zone_history": "/zone/{zone_id}/history?since=<rfc3339>[&until=<rfc3339>][&format=ndjson][&resolution=hourly]",
+                    "group_detail": "/group/{name}",
+                    "basin_status": "/status",
+                    "basin_status_history": "/status/history?since=<rfc3339>",
+                    "backwater_analysis": "/backwater",
+                    "flood_score": "/score",
+                    "lead_times": "/leadtimes",
+                    "compare": "/compare?upstream={site_code}&downstream={site_code}",
+                    "compare_event": "/compare-event?event_id={id} or /compare-event?year={yyyy}",
+                    "forecast": "/forecast[?sensor={sensor_id}]",
+                    "stations_status": "/stations/status",
+                    "outages": "/outages",
+                    "rate_of_rise": "/rate/{site_code}[?hours=6]",
+                    "rating_drift": "/rating/{site_code}",
+                    "trend": "/trend/{site_code}[?param=00065&hours=168&points=500][&format=csv]",
+                    "health": "/health",
+                    "metrics": "/metrics",
+                    "health_config": "/health/config",
+                    "station_health": "/health/stations[?status=degraded,offline]",
+                    "readings_by_qualifier": "/readings/{site_code}?qualifier=P",
+                    "shef": "/shef/{site_code}[?param=00060]",
+                    "sensor_value_at": "/sensor/{sensor_id}/at?time=<rfc3339>",
+                    "sensor_gaps": "/sensor/{sensor_id}/gaps?since=<rfc3339>[&until=<rfc3339>][&parameter=00060]",
+                    "units": "append ?units=metric for SI output (default imperial)",
+                    "deprecated_site_query": "/site/{site_code}"
+                }
+            })
+        )
+    }
+}
+
+/// Handle /health endpoint
+fn handle_health() -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    create_response(
+        200,
+        serde_json::json!({
+            "status": "ok",
+            "service": "flomon_service",
+            "version": "0.2.0",
+            "endpoint_version": "zone-based"
+        })
+    )
+}
+
+/// Handle /metrics endpoint (Prometheus text exposition format)
+fn handle_metrics(metrics: &SharedMetrics) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let body = metrics::lock(metrics).render(Utc::now());
+    
+    tiny_http::Response::from_data(body.into_bytes())
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/plain; version=0.0.4"[..]).unwrap()
+        )
+}
+
+/// Handle /health/config endpoint: 503 if any config file fails to parse,
+/// otherwise 200 with `status` "drifted" when a loaded file has changed on
+/// disk since it was read.
+fn handle_health_config() -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let files = config_status::check_all();
+    let invalid = files.iter().any(|f| f.parses == Some(false));
+    let drifted = files.iter().any(|f| f.drifted);
+    
+    let status = if invalid { "invalid" } else if drifted { "drifted" } else { "ok" };
+    create_response(
+        if invalid { 503 } else { 200 },
+        serde_json::json!({
+            "status": status,
+            "files": files,
+        })
+    )
+}
+
+/// Handle /health/stations?status=X[,Y] endpoint
+fn handle_station_health(client: &mut Client, query: &HashMap<String, String>) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let statuses = match query.get("status").map(|s| parse_status_filter(s)) {
+        Some(Ok(statuses)) => Some(statuses),
+        Some(Err(e)) => return error_response_with_details(
+            400,
+            e,
+            serde_json::json!({
+                "valid_statuses": STATION_HEALTH_STATUSES,
+                "example": "/health/stations?status=degraded,offline",
+            })
+        ),
+        None => None,
+    };
+    
+    match fetch_station_health(client, statuses.as_deref()) {
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /zones endpoint
+fn handle_zones_list(client: &mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match fetch_zones_list(client) {
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /group/{name} endpoint
+fn handle_group_detail(client: &mut Client, name: &str, units: UnitSystem) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let groups_config = match groups::load_groups_default() {
+        Ok(config) => config,
+        Err(e) => return error_response(500, format!("Failed to load groups.toml: {}", e)),
+    };
+    let name = urlencoding::decode(name).map(|n| n.into_owned()).unwrap_or_else(|_| name.to_string());
+    let Some(group) = groups_config.find(&name) else {
+        return error_response_with_details(
+            404,
+            format!("No group named '{}'", name),
+            serde_json::json!({"available_groups": groups_config.names()})
+        );
+    };
+    
+    match fetch_group_detail(client, group) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /zone/{zone_id} endpoint
+fn handle_zone_detail(
+    client: &mut Client,
+    zone_id_str: &str,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let zone_id: usize = match zone_id_str.parse() {
+        Ok(id) if id <= 6 => id,
+        _ => return error_response_with_details(
+            400,
+            "Invalid zone_id. Must be 0-6.",
+            serde_json::json!({"valid_zones": [0, 1, 2, 3, 4, 5, 6]})
+        ),
+    };
+    
+    match fetch_zone_detail(client, zone_id) {
+        Ok(data) if wants_csv(query) => {
+            create_csv_response(200, zone_detail_csv(serde_json::to_value(&data).unwrap(), units))
+        }
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /zone/{zone_id}/history endpoint (buffered JSON; NDJSON requests
+/// are streamed before routing)
+fn handle_zone_history(
+    client: &mut Client,
+    zone_id_str: &str,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let zone_id: usize = match zone_id_str.parse() {
+        Ok(id) if id <= 6 => id,
+        _ => return error_response_with_details(
+            400,
+            "Invalid zone_id. Must be 0-6.",
+            serde_json::json!({"valid_zones": [0, 1, 2, 3, 4, 5, 6]})
+        ),
+    };
+    let (since, until) = match parse_history_window(query) {
+        Ok(window) => window,
+        Err(e) => return error_response(400, e),
+    };
+    let resolution = match HistoryResolution::from_query(query.get("resolution").map(String::as_str)) {
+        Ok(resolution) => resolution,
+        Err(e) => return error_response(400, e),
+    };
+    
+    match fetch_zone_history(client, zone_id, since, until, resolution) {
+        Ok(records) => create_localized_response(
+            200,
+            serde_json::json!({
+                "zone_id": zone_id,
+                "since": since,
+                "until": until,
+                "resolution": resolution.as_str(),
+                "count": records.len(),
+                "records": records,
+            }),
+            units,
+        ),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /status endpoint
+fn handle_basin_status(client: &mut Client, units: UnitSystem) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match fetch_basin_status(client) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /status/history?since=<rfc3339> endpoint
+///
+/// `since` defaults to 7 days ago when omitted.
+fn handle_basin_status_history(
+    client: &mut Client,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let since = match query.get("since") {
+        Some(s) => match DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => dt.with_timezone(&Utc),
+            Err(_) => return error_response(400, format!("Invalid 'since' timestamp '{}'. Use RFC 3339.", s)),
+        },
+        None => Utc::now() - chrono::Duration::days(7),
+    };
+    
+    match fetch_basin_status_history(client, since) {
+        Ok(snapshots) => create_localized_response(
+            200,
+            serde_json::json!({
+                "since": since,
+                "count": snapshots.len(),
+                "snapshots": snapshots,
+            }),
+            units,
+        ),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /backwater endpoint
+fn handle_backwater_analysis(client: &mut Client, units: UnitSystem) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match analyze_backwater_risk(client) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /score endpoint
+fn handle_flood_score(client: &mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match compute_flood_score(client, &ScoreWeights::default(), Utc::now()) {
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /leadtimes endpoint
+fn handle_lead_times(client: &mut Client, units: UnitSystem) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match fetch_lead_times(client) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /compare?upstream=X&downstream=Y endpoint
+fn handle_compare(
+    client: &mut Client,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let (Some(upstream), Some(downstream)) = (query.get("upstream"), query.get("downstream")) else {
+        return error_response_with_details(
+            400,
+            "Both 'upstream' and 'downstream' site codes are required",
+            serde_json::json!({"example": "/compare?upstream=05557000&downstream=05568500"})
+        );
+    };
+    let mut travel_hours = Vec::new();
+    for site_code in [upstream, downstream] {
+        match stations::find_station(site_code) {
+            Some(station) if sensor_filter().is_id_visible(site_code) => {
+                travel_hours.push(station.travel_time_to_peoria_hours);
+            }
+            _ => return error_response(404, format!("Site {} not found", site_code)),
+        }
+    }
+    if travel_hours[0] <= travel_hours[1] {
+        return error_response_with_details(
+            400,
+            format!("{} is not upstream of {}", upstream, downstream),
+            serde_json::json!({
+                "travel_time_to_peoria_hours": {upstream.as_str(): travel_hours[0], downstream.as_str(): travel_hours[1]}
+            })
+        );
+    }
+    
+    match fetch_travel_aligned_comparison(client, upstream, downstream) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /stations/status endpoint
+fn handle_stations_status(client: &mut Client, units: UnitSystem) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match fetch_stations_status(client) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /forecast[?sensor=<sensor_id>] endpoint
+fn handle_forecast(
+    client: &mut Client,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let Some(sensor_id) = query.get("sensor") else {
+        return match fetch_property_forecast(client) {
+            Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+            Err(e) => error_response(500, e),
+        };
+    };
+    let sensor = match find_sensor(sensor_id) {
+        Ok(sensor) => sensor,
+        Err(response) => return response,
+    };
+    
+    match fetch_forecast(client, &sensor) {
+        Ok(data) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Err(e) if e.contains("no USGS stage") => error_response(400, e),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /compare-event?event_id=N or ?year=YYYY endpoint
+fn handle_compare_event(
+    client: &mut Client,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let example = serde_json::json!({"examples": ["/compare-event?event_id=42", "/compare-event?year=2013"]});
+    let selector = match (query.get("event_id"), query.get("year")) {
+        (Some(id), _) => match id.parse() {
+            Ok(id) => EventSelector::Id(id),
+            Err(_) => return error_response_with_details(400, format!("Invalid event_id '{}'", id), example),
+        },
+        (None, Some(year)) => match year.parse() {
+            Ok(year) => EventSelector::Year(year),
+            Err(_) => return error_response_with_details(400, format!("Invalid year '{}'", year), example),
+        },
+        (None, None) => {
+            return error_response_with_details(400, "Missing query parameter 'event_id' or 'year'", example);
+        }
+    };
+    
+    match fetch_event_comparison(client, selector) {
+        Ok(Some(data)) => create_localized_response(200, serde_json::to_value(&data).unwrap(), units),
+        Ok(None) => error_response(404, "No recorded flood event matches"),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /outages endpoint
+fn handle_outages(client: &mut Client) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    match fetch_outages(client) {
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /trend/{site_code}?param=X&hours=N&points=M endpoint
+fn handle_trend(
+    client: &mut Client,
+    site_code: &str,
+    query: &HashMap<String, String>,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    if !sensor_filter().is_id_visible(site_code) {
+        return error_response(404, format!("Site {} not found", site_code));
+    }
+    let example = serde_json::json!({"example": format!("/trend/{}?param=00065&hours=168&points=500", site_code)});
+    
+    let parameter_code = query.get("param").map(String::as_str).unwrap_or(PARAM_STAGE);
+    let known = known_parameters();
+    if !known.iter().any(|p| p == parameter_code) {
+        return error_response_with_details(
+            400,
+            format!("Unknown parameter code '{}'", parameter_code),
+            serde_json::json!({"valid_parameters": known, "example": example["example"]})
+        );
+    }
+    let Some(hours) = parse_window_hours(query.get("hours").map(String::as_str), DEFAULT_TREND_HOURS, MAX_TREND_HOURS) else {
+        return error_response_with_details(
+            400,
+            format!("Invalid 'hours': use a whole number from 1 to {}", MAX_TREND_HOURS),
+            example
+        );
+    };
+    let points = match query.get("points").map(|p| p.parse::<usize>()) {
+        None => DEFAULT_TREND_POINTS,
+        Some(Ok(points)) if (2..=MAX_TREND_POINTS).contains(&points) => points,
+        Some(_) => return error_response_with_details(
+            400,
+            format!("Invalid 'points': use a whole number from 2 to {}", MAX_TREND_POINTS),
+            example
+        ),
+    };
+    
+    let end = Utc::now();
+    match fetch_site_series(client, site_code, parameter_code, end - chrono::Duration::hours(hours), end, points) {
+        Ok(data) if wants_csv(query) => create_csv_response(200, series_csv(&data)),
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /shef/{site_code}[?param=00060] endpoint: the latest
+/// instantaneous reading as a SHEF `.A` message in text/plain
+fn handle_shef(
+    client: &mut Client,
+    site_code: &str,
+    query: &HashMap<String, String>,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let station = match stations::find_station(site_code) {
+        Some(station) if sensor_filter().is_id_visible(site_code) => station,
+        _ => return error_response(404, format!("Site {} not found", site_code)),
+    };
+    let Some(shef_id) = station.shef_id else {
+        return error_response(404, format!("Site {} has no shef_id (or nws_id) in usgs_stations.toml", site_code));
+    };
+    let parameter_code = query.get("param").map(String::as_str).unwrap_or(PARAM_STAGE);
+    let Some(element) = shef::PhysicalElement::from_param_code(parameter_code) else {
+        return error_response_with_details(
+            400,
+            format!("No SHEF physical element for parameter '{}'", parameter_code),
+            serde_json::json!({"valid_parameters": [PARAM_STAGE, PARAM_DISCHARGE]})
+        );
+    };
+    
+    // Daily means have no observation instant to report
+    let latest = client.query(
+        "SELECT value::float8, reading_time
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2 AND source <> 'usgs_dv'
+         ORDER BY reading_time DESC
+         LIMIT 1",
+        &[&site_code, &parameter_code]
+    );
+    match latest {
+        Ok(rows) => match rows.first() {
+            Some(row) => {
+                let message = shef::encode_reading(&shef_id, element, row.get(0), row.get(1));
+                tiny_http::Response::from_data(format!("{}\n", message).into_bytes())
+                    .with_header(
+                        tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/plain; charset=utf-8"[..]).unwrap()
+                    )
+            }
+            None => error_response(404, format!("No {} readings stored for {}", parameter_code, site_code)),
+        },
+        Err(e) => error_response(500, format!("Failed to fetch latest reading for {}: {}", site_code, e)),
+    }
+}
+
+/// Handle /rating/{site_code} endpoint
+fn handle_rating_drift(client: &mut Client, site_code: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    if !sensor_filter().is_id_visible(site_code) {
+        return error_response(404, format!("Site {} not found", site_code));
+    }
+    
+    match detect_rating_drift(site_code, Utc::now(), client) {
+        Ok(Some(drift)) => create_response(200, serde_json::json!(drift)),
+        Ok(None) => error_response(
+            404,
+            format!("Not enough paired stage and discharge history at {} to assess its rating", site_code),
+        ),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /rate/{site_code}?hours=N endpoint
+fn handle_rate_of_rise(
+    client: &mut Client,
+    site_code: &str,
+    query: &HashMap<String, String>,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    if !sensor_filter().is_id_visible(site_code) {
+        return error_response(404, format!("Site {} not found", site_code));
+    }
+    
+    let raw_hours = query.get("hours").map(String::as_str);
+    let Some(window_hours) = parse_window_hours(raw_hours, DEFAULT_RATE_WINDOW_HOURS, MAX_RATE_WINDOW_HOURS) else {
+        return error_response_with_details(
+            400,
+            format!(
+                "Invalid 'hours' value '{}'. Use a whole number from 1 to {}.",
+                raw_hours.unwrap_or_default(), MAX_RATE_WINDOW_HOURS
+            ),
+            serde_json::json!({"example": format!("/rate/{}?hours=6", site_code)})
+        );
+    };
+    
+    match fetch_rate_of_rise(client, site_code, window_hours) {
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /readings/{site_code}?qualifier=X endpoint
+///
+/// `since` defaults to 7 days ago when omitted.
+fn handle_readings_by_qualifier(
+    client: &mut Client,
+    site_code: &str,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    if !sensor_filter().is_id_visible(site_code) {
+        return error_response(404, format!("Site {} not found", site_code));
+    }
+    
+    let qualifier = match query.get("qualifier") {
+        Some(q) => q,
+        None => return error_response_with_details(
+            400,
+            "Missing required query parameter 'qualifier'",
+            serde_json::json!({"example": format!("/readings/{}?qualifier=P", site_code)})
+        ),
+    };
+    
+    let since = match query.get("since") {
+        Some(s) => match DateTime::parse_from_rfc3339(s) {
+            Ok(dt) => dt.with_timezone(&Utc),
+            Err(_) => return error_response(400, format!("Invalid 'since' timestamp '{}'. Use RFC 3339.", s)),
+        },
+        None => Utc::now() - chrono::Duration::days(7),
+    };
+    
+    match readings_by_qualifier(site_code, qualifier, since, client) {
+        Ok(readings) => create_localized_response(
+            200,
+            serde_json::json!({
+                "site_code": site_code,
+                "qualifier": qualifier,
+                "since": since,
+                "count": readings.len(),
+                "readings": readings.iter().map(|r| serde_json::json!({
+                    "parameter_code": r.parameter_code,
+                    "value": r.value,
+                    "unit": r.unit,
+                    "timestamp": r.datetime,
+                    "qualifier": r.qualifier,
+                    "source": r.source.as_str(),
+                })).collect::<Vec<_>>(),
+            }),
+            units,
+        ),
+        Err(e) if e.starts_with("Invalid qualifier") => error_response(400, e),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Readings closer than this to the requested instant are returned as-is
+const INTERPOLATION_TOLERANCE_MINUTES: i64 = 5;
+
+/// Sensor configured in zones.toml under `sensor_id`, or the error response
+/// (500 if zones.toml can't be read, 404 if no sensor matches).
+fn find_sensor(sensor_id: &str) -> Result<zones::Sensor, tiny_http::Response<std::io::Cursor<Vec<u8>>>> {
+    let zones_config = load_visible_zones().map_err(|e| {
+        error_response(500, format!("Failed to load zones.toml: {}", e))
+    })?;
+    
+    get_all_zones(&zones_config).into_iter()
+        .flat_map(|(_, zone)| zone.sensors.iter())
+        .find(|s| s.primary_id() == sensor_id)
+        .cloned()
+        .ok_or_else(|| error_response(404, format!("Sensor {} not found", sensor_id)))
+}
+
+/// Handle /sensor/{sensor_id}/gaps?since=<rfc3339>[&until=<rfc3339>] endpoint
+fn handle_sensor_gaps(
+    client: &mut Client,
+    sensor_id: &str,
+    query: &HashMap<String, String>,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let (since, until) = match parse_history_window(query) {
+        Ok(window) => window,
+        Err(e) => return error_response(400, e),
+    };
+    let sensor = match find_sensor(sensor_id) {
+        Ok(sensor) => sensor,
+        Err(response) => return response,
+    };
+    
+    let parameter = query.get("parameter").map(String::as_str);
+    match fetch_sensor_gaps(client, &sensor, since, until, parameter) {
+        Ok(data) => create_response(200, serde_json::to_value(&data).unwrap()),
+        Err(e) if e.contains("no stored time series") => error_response(400, e),
+        Err(e) => error_response(500, e),
+    }
+}
+
+/// Handle /sensor/{sensor_id}/at?time=<rfc3339> endpoint
+fn handle_sensor_value_at(
+    client: &mut Client,
+    sensor_id: &str,
+    query: &HashMap<String, String>,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    let at = match query.get("time").map(|t| DateTime::parse_from_rfc3339(t)) {
+        Some(Ok(dt)) => dt.with_timezone(&Utc),
+        Some(Err(_)) | None => return error_response_with_details(
+            400,
+            "Missing or invalid 'time' query parameter. Use RFC 3339.",
+            serde_json::json!({"example": format!("/sensor/{}/at?time=2024-05-01T12:00:00Z", sensor_id)})
+        ),
+    };
+    
+    let sensor = match find_sensor(sensor_id) {
+        Ok(sensor) => sensor,
+        Err(response) => return response,
+    };
+    
+    let parameter = query.get("parameter").map(String::as_str);
+    let BracketingReadings { before, after, unit } = match fetch_bracketing_readings(client, &sensor, at, parameter) {
+        Ok(result) => result,
+        Err(e) if e.contains("no USGS or CWMS") => return error_response(400, e),
+        Err(e) => return error_response(500, e),
+    };
+    
+    let tolerance = chrono::Duration::minutes(INTERPOLATION_TOLERANCE_MINUTES);
+    match interpolate_between(before.as_ref(), after.as_ref(), at, tolerance) {
+        Some(result) => {
+            let point = |tv: &Option<TimedValue>| tv.as_ref().map(|tv| serde_json::json!({
+                "timestamp": tv.timestamp,
+                "value": tv.value,
+            }));
+            create_localized_response(
+                200,
+                serde_json::json!({
+                    "sensor_id": sensor_id,
+                    "time": at,
+                    "value": result.value,
+                    "unit": unit,
+                    "interpolated": result.interpolated,
+                    "before": point(&result.before),
+                    "after": point(&result.after),
+                }),
+                units,
+            )
+        }
+        None => error_response_with_details(
+            404,
+            "No data bracketing that time",
+            serde_json::json!({
+                "sensor_id": sensor_id,
+                "time": at,
+                "earliest_after": after.map(|tv| tv.timestamp),
+                "latest_before": before.map(|tv| tv.timestamp),
+            })
+        ),
+    }
+}
+
+/// Handle deprecated /site/{site_code} endpoint
+fn handle_deprecated_site_query(_client: &mut Client, url: &str) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    error_response_with_details(
+        410,  // Gone
+        "This endpoint is deprecated; /site/{site_code} has been replaced by zone-based views",
+        serde_json::json!({
+            "migration_guide": {
+                "instead_of": format!("GET {}", url),
+                "use": "GET /zones - to list all zones, then GET /zone/{zone_id} for zone detail",
+                "example": "GET /zone/2 - for your property zone (Upper Peoria Lake)"
+            },
+            "new_endpoints": {
+                "zones_list": "/zones",
+                "zone_detail": "/zone/{zone_id}",
+                "basin_status": "/status",
+                "backwater": "/backwater"
+            }
+        })
+    )
+}
+
+/// Split a request URL into its path and decoded query parameters.
+fn split_query(url: &str) -> (String, HashMap<String, String>) {
+    let (path, query_str) = match url.split_once('?') {
+        Some((path, q)) => (path, q),
+        None => (url, ""),
+    };
+    
+    let params = query_str
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let decode = |s: &str| urlencoding::decode(s)
+                .map(|d| d.into_owned())
+                .unwrap_or_else(|_| s.to_string());
+            (decode(key), decode(value))
+        })
+        .collect();
+    
+    (path.to_string(), params)
+}
+
+/// Create HTTP response with JSON body, numbers rounded to their units'
+/// display precision
+fn create_response(status_code: u16, mut json: serde_json::Value) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    units::round_for_display(&mut json);
+    let body = serde_json::to_string_pretty(&json).unwrap();
+    let bytes = body.into_bytes();
+    
+    tiny_http::Response::from_data(bytes)
+        .with_status_code(tiny_http::StatusCode::from(status_code))
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap()
+        )
+}
+
+/// Machine-readable error code for an HTTP status
+fn error_code(status_code: u16) -> &'static str {
+    match status_code {
+        400 => "bad_request",
+        404 => "not_found",
+        410 => "gone",
+        503 => "server_busy",
+        _ => "internal_error",
+    }
+}
+
+/// The error envelope every endpoint uses:
+/// `{"error": {"code": "...", "message": "...", "details": {...}}}`
+fn error_body(status_code: u16, message: impl Into<String>, details: serde_json::Value) -> serde_json::Value {
+    serde_json::json!({
+        "error": {
+            "code": error_code(status_code),
+            "message": message.into(),
+            "details": details,
+        }
+    })
+}
+
+/// Error response with empty `details`
+fn error_response(status_code: u16, message: impl Into<String>) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    error_response_with_details(status_code, message, serde_json::json!({}))
+}
+
+/// Error response carrying extra context (examples, valid values, links)
+fn error_response_with_details(
+    status_code: u16,
+    message: impl Into<String>,
+    details: serde_json::Value,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    create_response(status_code, error_body(status_code, message, details))
+}
+
+/// Send `response` with an `X-Response-Time-Ms` header (time since
+/// `started`, when the request was accepted), and log one line to stdout:
+/// `HTTP GET /zone/2?units=metric 200 14ms`. For a streamed body the time
+/// is to the start of the stream.
+fn respond_logged<R: Read>(request: tiny_http::Request, response: tiny_http::Response<R>, started: Instant) {
+    let elapsed_ms = started.elapsed().as_millis();
+    // respond() consumes both, so read what the log line needs first
+    let status = response.status_code().0;
+    println!("HTTP {} {} {} {}ms", request.method(), request.url(), status, elapsed_ms);
+    
+    let response = response.with_header(
+        tiny_http::Header::from_bytes(&b"X-Response-Time-Ms"[..], elapsed_ms.to_string().as_bytes()).unwrap()
+    );
+    if let Err(e) = request.respond(response) {
+        eprintln!("Failed to send response: {}", e);
+    }
+}
+
+/// JSON response converted to the requested unit system (see `units`)
+fn create_localized_response(
+    status_code: u16,
+    mut json: serde_json::Value,
+    units: UnitSystem,
+) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    units::localize(&mut json, units);
+    create_response(status_code, json)
+}
+
+/// Create HTTP response with a CSV body
+fn create_csv_response(status_code: u16, body: String) -> tiny_http::Response<std::io::Cursor<Vec<u8>>> {
+    tiny_http::Response::from_data(body.into_bytes())
+        .with_status_code(tiny_http::StatusCode::from(status_code))
+        .with_header(
+            tiny_http::Header::from_bytes(&b"Content-Type"[..], &b"text/csv; charset=utf-8"[..]).unwrap()
+        )
+}
+
+/// `?format=csv`; anything else (or no `format`) keeps the JSON default
+fn wants_csv(query: &HashMap<String, String>) -> bool {
+    query.get("format").is_some_and(|f| f.eq_ignore_ascii_case("csv"))
+}
+
+/// `/zone/{id}?format=csv` columns: CSV header, then the sensor field
+/// it's read from. Suffixed names follow `?units` like the JSON keys do.
+const ZONE_CSV_COLUMNS: &[(&str, &str)] = &[
+    ("sensor_id", "sensor_id"),
+    ("type", "sensor_type"),
+    ("current_value", "current_value"),
+    ("unit", "current_unit"),
+    ("timestamp", "current_timestamp"),
+    ("staleness_minutes", "staleness_minutes"),
+    ("flood_stage_ft", "flood_stage_ft"),
+    ("action_stage_ft", "action_stage_ft"),
+];
+
+/// One CSV line, fields quoted where needed
+fn csv_line<'a>(fields: impl IntoIterator<Item = &'a str>) -> String {
+    let quoted: Vec<String> = fields.into_iter().map(csv_field).collect();
+    quoted.join(",") + "\n"
+}
+
+/// A JSON scalar as a CSV cell; null and missing are empty
+fn csv_cell(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::Null => String::new(),
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Zone detail as CSV, one row per sensor, converted and rounded exactly
+/// as the JSON response would be
+fn zone_detail_csv(mut json: serde_json::Value, units: UnitSystem) -> String {
+    units::localize(&mut json, units);
+    units::round_for_display(&mut json);
+    let columns: Vec<(String, String)> = ZONE_CSV_COLUMNS.iter()
+        .map(|(header, field)| (units::localized_key(header, units), units::localized_key(field, units)))
+        .collect();
+    
+    let mut out = csv_line(columns.iter().map(|(header, _)| header.as_str()));
+    for sensor in json["sensors"].as_array().into_iter().flatten() {
+        let cells: Vec<String> = columns.iter().map(|(_, field)| csv_cell(&sensor[field])).collect();
+        out.push_str(&csv_line(cells.iter().map(String::as_str)));
+    }
+    out
+}
+
+/// `/trend` series as CSV: one row per (downsampled) point
+fn series_csv(series: &SiteSeriesResponse) -> String {
+    let unit = series.unit.as_deref().unwrap_or("");
+    let mut out = csv_line(["site_code", "parameter_code", "timestamp", "value", "unit"]);
+    for point in &series.points {
+        let (t, v) = (point.t.to_rfc3339(), point.v.to_string());
+        out.push_str(&csv_line([series.site_code.as_str(), series.parameter_code.as_str(), &t, &v, unit]));
+    }
+    out
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_query() {
+        let (path, query) = split_query("/readings/05568500?qualifier=e&since=2024-05-01T00%3A00%3A00Z");
+        assert_eq!(path, "/readings/05568500");
+        assert_eq!(query.get("qualifier").map(String::as_str), Some("e"));
+        assert_eq!(query.get("since").map(String::as_str), Some("2024-05-01T00:00:00Z"));
+
+        let (path, query) = split_query("/status");
+        assert_eq!(path, "/status");
+        assert!(query.is_empty());
+    }
+
+    #[test]
+    fn test_empty_zone_detail_is_well_formed() {
+        let zone = zones::Zone {
+            name: "Setup Zone".to_string(),
+            description: "Configured but no sensors yet".to_string(),
+            sensors: Vec::new(),
+        };
+
+        let detail = empty_zone_detail(3, &zone, &DisplayConfig::default());
+        let json = serde_json::to_value(&detail).unwrap();
+
+        assert_eq!(json["zone_id"], 3);
+        assert_eq!(json["sensors"], serde_json::json!([]));
+        assert_eq!(json["zone_status"]["active_sensors"], 0);
+        assert_eq!(json["zone_status"]["alert_level"], "UNKNOWN");
+        assert_eq!(json["zone_status"]["display"]["label"], "No data");
+    }
+
+    #[test]
+    fn test_zone_sensor_health_from_snapshot() {
+        let zone: zones::Zone = toml::from_str(r#"
+            name = "Test Zone"
+            description = "One of each source"
+
+            [[sensors]]
+            usgs_id = "05568500"
+            source = "USGS"
+            type = "stage"
+            role = "direct"
+            location = "Kingston Mines"
+            lat = 40.55
+            lon = -89.77
+            relevance = "fresh"
+
+            [[sensors]]
+            cwms_location = "IL08"
+            source = "USACE/MVR"
+            type = "pool_elevation"
+            role = "direct"
+            location = "LaGrange"
+            lat = 40.03
+            lon = -90.35
+            relevance = "stale"
+
+            [[sensors]]
+            station_id = "SPI"
+            source = "IEM/ASOS"
+            type = "precipitation"
+            role = "precip"
+            location = "Springfield"
+            lat = 39.84
+            lon = -89.68
+            staleness_threshold_minutes = 180
+            relevance = "fresh under its own threshold"
+
+            [[sensors]]
+            usgs_id = "05570000"
+            source = "USGS"
+            type = "stage"
+            role = "direct"
+            location = "Seville"
+            lat = 40.49
+            lon = -90.04
+            relevance = "no data"
+        "#).unwrap();
+
+        let now = Utc::now();
+        let mut latest = LatestReadingTimes::default();
+        latest.usgs.insert("05568500".to_string(), now - chrono::Duration::minutes(15));
+        latest.cwms.insert("IL08".to_string(), now - chrono::Duration::minutes(300));
+        latest.asos.insert("SPI".to_string(), now - chrono::Duration::minutes(150));
+
+        // Active: Kingston Mines, LaGrange, SPI. Stale: LaGrange, Seville.
+        assert_eq!(zone_sensor_health(&zone, &latest, now), (3, 2));
+    }
+
+    #[test]
+    fn test_sensor_filter_allow_then_deny() {
+        let config = zones::load_zones_default().expect("zones.toml should parse");
+        let sensors: Vec<&zones::Sensor> = get_all_zones(&config).into_iter()
+            .flat_map(|(_, zone)| zone.sensors.iter())
+            .collect();
+        let kingston = sensors.iter().find(|s| s.usgs_id.as_deref() == Some("05568500")).unwrap();
+        
+        let open = SensorFilter::new(None, Some(""));
+        assert!(!open.is_filtering());
+        assert!(open.is_visible(kingston) && open.is_id_visible("05568500"));
+        
+        let public = SensorFilter::new(Some("05568500, 05567500"), Some("05567500"));
+        assert!(public.is_visible(kingston));
+        assert!(!public.is_id_visible("05567500"), "denylist wins over allowlist");
+        assert!(!public.is_id_visible("05568580"), "not on the allowlist");
+        assert_eq!(sensors.iter().filter(|s| public.is_visible(s)).count(),
+                   sensors.iter().filter(|s| s.ids().any(|id| id == "05568500")).count());
+    }
+
+    #[test]
+    fn test_watch_guidance_follows_level_and_lead_time() {
+        let zone0 = ZoneMetadata::for_zone(0);
+        let grafton = vec!["05587450".to_string()];
+        
+        let flooding = watch_guidance(&zone0, AlertLevel::Flood, &[], &[], &grafton);
+        assert_eq!(flooding, "05587450 above flood stage; watch the LaGrange tailwater–pool differential over the next 12-120h");
+        
+        let zone2 = ZoneMetadata::for_zone(2);
+        let action = watch_guidance(&zone2, AlertLevel::Action, &[], &["05568500".to_string()], &[]);
+        assert!(action.ends_with("watch the Kingston Mines rate of rise over the next 6h"), "{}", action);
+        
+        assert!(watch_guidance(&zone2, AlertLevel::Normal, &[], &[], &[]).contains("Kingston Mines stage > 14 ft"));
+        assert!(watch_guidance(&zone2, AlertLevel::Degraded, &[], &[], &[]).starts_with("Most sensors are stale"));
+    }
+
+    #[test]
+    fn test_canonical_level_of_stored_history() {
+        assert_eq!(canonical_level("FLOOD_WARNING"), "FLOOD");
+        assert_eq!(canonical_level("ELEVATED"), "WATCH");
+        assert_eq!(canonical_level("ACTION"), "ACTION");
+        assert_eq!(canonical_level("???"), "???");
+    }
+
+    #[test]
+    fn test_history_window_defaults_and_validation() {
+        let (since, until) = parse_history_window(&HashMap::new()).unwrap();
+        assert_eq!(until - since, chrono::Duration::days(DEFAULT_HISTORY_DAYS));
+
+        let (_, query) = split_query("/zone/2/history?since=2019-05-01T00:00:00Z&until=2019-06-01T00:00:00Z");
+        let (since, until) = parse_history_window(&query).unwrap();
+        assert_eq!((until - since).num_days(), 31);
+
+        let (_, reversed) = split_query("/zone/2/history?since=2019-06-01T00:00:00Z&until=2019-05-01T00:00:00Z");
+        assert!(parse_history_window(&reversed).is_err());
+
+        let (_, hourly) = split_query("/zone/2/history?resolution=hourly");
+        assert_eq!(HistoryResolution::from_query(hourly.get("resolution").map(String::as_str)), Ok(HistoryResolution::Hourly));
+        assert_eq!(HistoryResolution::from_query(None), Ok(HistoryResolution::Raw));
+        assert!(HistoryResolution::from_query(Some("daily")).is_err());
+
+        let (url, ndjson) = split_query("/zone/2/history?format=ndjson");
+        assert!(is_streaming_history_request(&url, &ndjson));
+        assert!(!is_streaming_history_request("/zone/2", &ndjson));
+    }
+
+    #[test]
+    fn test_property_forecast_window_narrowed_by_trend() {
+        let now = Utc::now();
+        let source = |zone_id, arrival, min, max| ContributingSourceResponse {
+            zone_id,
+            zone_name: format!("Zone {}", zone_id),
+            status: AlertLevel::Action,
+            estimated_arrival_hours: Some(arrival),
+            lead_time_hours_min: Some(min),
+            lead_time_hours_max: Some(max),
+            key_sensors_elevated: Vec::new(),
+        };
+        let sources = || vec![source(5, 48, 36, 72), source(4, 24, 18, 48)];
+        
+        // Fastest source (zone 4) sets the window
+        let unknown = property_forecast(AlertLevel::Normal, sources(), "unknown", now);
+        assert!(unknown.active_threat);
+        assert_eq!(unknown.contributing_sources[0].zone_id, 4);
+        assert_eq!(unknown.estimated_peak_window_hours, Some(PeakWindowResponse { earliest_hours: 18, latest_hours: 48 }));
+        assert_eq!(unknown.confidence, "LOW");
+        
+        let rising = property_forecast(AlertLevel::Normal, sources(), "rising", now);
+        assert_eq!(rising.estimated_peak_window_hours, Some(PeakWindowResponse { earliest_hours: 18, latest_hours: 24 }));
+        assert_eq!(rising.confidence, "HIGH");
+        
+        let steady = property_forecast(AlertLevel::Normal, sources(), "steady", now);
+        assert_eq!(steady.estimated_peak_window_hours, Some(PeakWindowResponse { earliest_hours: 24, latest_hours: 48 }));
+    }
+    
+    #[test]
+    fn test_property_forecast_without_threat() {
+        let calm = property_forecast(AlertLevel::Normal, Vec::new(), "steady", Utc::now());
+        assert!(!calm.active_threat);
+        assert_eq!(calm.estimated_peak_window_hours, None);
+        assert!(calm.explanation.starts_with("No active threat"), "{}", calm.explanation);
+        
+        // Local flooding with nothing upstream is still a threat, without a window
+        let local = property_forecast(AlertLevel::Flood, Vec::new(), "rising", Utc::now());
+        assert!(local.active_threat);
+        assert_eq!(local.estimated_peak_window_hours, None);
+        assert!(local.explanation.starts_with("Property zone is at FLOOD "), "{}", local.explanation);
+    }
+    
+    #[test]
+    fn test_flood_category_needs_reading_and_thresholds() {
+        let thresholds = crate::model::FloodThresholds {
+            action_stage_ft: 14.0,
+            flood_stage_ft: 16.0,
+            moderate_flood_stage_ft: 20.0,
+            major_flood_stage_ft: 24.0,
+        };
+        let stage = |value: f64| GaugeReading {
+            agency_code: "USGS".to_string(),
+            site_code: "05568500".to_string(),
+            site_name: "Illinois River at Kingston Mines, IL".to_string(),
+            parameter_code: PARAM_STAGE.to_string(),
+            unit: "ft".to_string(),
+            value,
+            datetime: "2019-06-01T12:00:00Z".to_string(),
+            qualifier: "P".to_string(),
+            source: ReadingSource::UsgsIv,
+        };
+        
+        assert_eq!(flood_category(Some(&stage(12.0)), Some(&thresholds)), Some(AlertLevel::Normal));
+        assert_eq!(flood_category(Some(&stage(21.0)), Some(&thresholds)), Some(AlertLevel::Moderate));
+        assert_eq!(flood_category(None, Some(&thresholds)), None);
+        assert_eq!(flood_category(Some(&stage(21.0)), None), None);
+
+        // Daily-mean fallback: shown, but never categorised
+        let daily = GaugeReading { source: ReadingSource::UsgsDv, ..stage(21.0) };
+        assert_eq!(flood_category(Some(&daily), Some(&thresholds)), None);
+    }
+
+    #[test]
+    fn test_sla_status_is_per_source_interval() {
+        // A 20-minute-old reading is fine for hourly USGS, late for 5-minute ASOS
+        assert_eq!(sla_status(Some(20), 60), "within_sla");
+        assert_eq!(sla_status(Some(20), 5), "late");
+        assert_eq!(sla_status(None, 360), "late");
+    }
+
+    #[test]
+    fn test_outage_reason_precedence() {
+        let healthy = StationHealthRow {
+            site_code: "05568500".to_string(),
+            site_name: "Illinois River at Kingston Mines, IL".to_string(),
+            parameter_code: PARAM_STAGE.to_string(),
+            status: "active".to_string(),
+            status_since: None,
+            is_stale: Some(false),
+            stale_since: None,
+            latest_reading_time: Some(Utc::now()),
+            latest_reading_value: Some(14.27),
+            age_minutes: Some(10.0),
+            staleness_threshold_minutes: 60,
+            last_poll_attempted: None,
+            last_poll_succeeded: None,
+            consecutive_failures: 0,
+        };
+        assert_eq!(outage_reason(&healthy, false), None);
+        assert_eq!(outage_reason(&healthy, true), Some("flatlined"));
+
+        let old = StationHealthRow { age_minutes: Some(90.0), is_stale: None, ..healthy };
+        assert_eq!(outage_reason(&old, true), Some("stale"));
+
+        let failing = StationHealthRow { consecutive_failures: 3, ..old };
+        assert_eq!(outage_reason(&failing, false), Some("poll_failing"));
+
+        let missing = StationHealthRow { latest_reading_time: None, ..failing };
+        assert_eq!(outage_reason(&missing, false), Some("missing"));
+    }
+
+    #[test]
+    fn test_station_health_summary_and_status_filter() {
+        let row = |status: &str| StationHealthRow {
+            site_code: "05568500".to_string(),
+            site_name: "Illinois River at Kingston Mines, IL".to_string(),
+            parameter_code: PARAM_STAGE.to_string(),
+            status: status.to_string(),
+            status_since: None,
+            is_stale: None,
+            stale_since: None,
+            latest_reading_time: None,
+            latest_reading_value: None,
+            age_minutes: None,
+            staleness_threshold_minutes: 60,
+            last_poll_attempted: None,
+            last_poll_succeeded: None,
+            consecutive_failures: 0,
+        };
+        let rows = vec![row("offline"), row("degraded"), row("active"), row("active"), row("paused")];
+        assert_eq!(
+            summarize_station_health(&rows),
+            StationHealthSummary { total: 5, active: 2, degraded: 1, offline: 1, unknown: 1 }
+        );
+
+        assert_eq!(parse_status_filter("Degraded, offline").unwrap(), vec!["degraded", "offline"]);
+        assert!(parse_status_filter("down").is_err());
+    }
+
+    #[test]
+    fn test_conditional_get_validators() {
+        use chrono::TimeZone;
+
+        let last_poll = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap() + chrono::Duration::milliseconds(250);
+        let etag = etag_for(last_poll, UnitSystem::Imperial);
+        assert_eq!(etag, "\"1714564800250-imperial\"");
+        assert_ne!(etag, etag_for(last_poll, UnitSystem::Metric), "units change the body");
+        assert_eq!(http_date(last_poll), "Wed, 01 May 2024 12:00:00 GMT");
+
+        let none_match = |value: &str| ConditionalHeaders { if_none_match: Some(value.to_string()), ..Default::default() };
+        assert!(is_not_modified(&none_match(&etag), &etag, last_poll));
+        assert!(is_not_modified(&none_match(&format!("\"other\", W/{}", etag)), &etag, last_poll));
+        assert!(!is_not_modified(&none_match("\"1714564700000-imperial\""), &etag, last_poll));
+
+        let since = |value: &str| ConditionalHeaders { if_modified_since: Some(value.to_string()), ..Default::default() };
+        assert!(is_not_modified(&since("Wed, 01 May 2024 12:00:00 GMT"), &etag, last_poll));
+        assert!(!is_not_modified(&since("Wed, 01 May 2024 11:45:00 GMT"), &etag, last_poll));
+        assert!(!is_not_modified(&since("not a date"), &etag, last_poll));
+
+        // If-None-Match takes precedence over a matching date
+        let both = ConditionalHeaders {
+            if_none_match: Some("\"stale\"".to_string()),
+            if_modified_since: Some("Wed, 01 May 2024 12:00:00 GMT".to_string()),
+        };
+        assert!(!is_not_modified(&both, &etag, last_poll));
+        assert!(!is_not_modified(&ConditionalHeaders::default(), &etag, last_poll));
+    }
+
+    #[test]
+    fn test_validator_time_advances_with_staleness_minute() {
+        use chrono::TimeZone;
+
+        let last_poll = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 30).unwrap();
+        let at = |h, m, s| Utc.with_ymd_and_hms(2024, 5, 1, h, m, s).unwrap();
+
+        // Same minute as the poll: the poll time stands
+        assert_eq!(validator_time(last_poll, at(12, 0, 45)), last_poll);
+        // No new ingest, but ages have ticked over: the validator moves
+        assert_eq!(validator_time(last_poll, at(12, 7, 20)), at(12, 7, 0));
+        assert_ne!(
+            etag_for(validator_time(last_poll, at(12, 7, 20)), UnitSystem::Imperial),
+            etag_for(validator_time(last_poll, at(12, 8, 5)), UnitSystem::Imperial)
+        );
+    }
+
+    #[test]
+    fn test_in_flight_guard_caps_and_releases() {
+        let counter = Arc::new(AtomicUsize::new(0));
+
+        let first = InFlightGuard::try_acquire(&counter, 2);
+        let second = InFlightGuard::try_acquire(&counter, 2);
+        assert!(first.is_some() && second.is_some());
+        assert!(InFlightGuard::try_acquire(&counter, 2).is_none(), "cap should reject a third request");
+
+        drop(first);
+        assert_eq!(counter.load(Ordering::SeqCst), 1);
+        assert!(InFlightGuard::try_acquire(&counter, 2).is_some(), "slot should free up after drop");
+    }
+    #[test]
+    fn test_error_body_envelope() {
+        let body = error_body(404, "Sensor X not found", serde_json::json!({"sensor_id": "X"}));
+        assert_eq!(body["error"]["code"], "not_found");
+        assert_eq!(body["error"]["message"], "Sensor X not found");
+        assert_eq!(body["error"]["details"]["sensor_id"], "X");
+
+        assert_eq!(error_body(500, "boom", serde_json::json!({}))["error"]["code"], "internal_error");
+    }
+
+    #[test]
+    fn test_gap_response_duration() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T06:00:00Z").unwrap().with_timezone(&Utc);
+        let gap = gap_response(start, start + chrono::Duration::minutes(135));
+        assert_eq!(gap.duration_minutes, 135);
+        assert_eq!(gap.end - gap.start, chrono::Duration::minutes(135));
+    }
+
+    #[test]
+    fn test_rate_of_rise_from_consecutive_readings() {
+        let start = DateTime::parse_from_rfc3339("2024-05-01T06:00:00Z").unwrap().with_timezone(&Utc);
+        let at = |minutes: i64, value: f64| TimedValue { timestamp: start + chrono::Duration::minutes(minutes), value };
+        // Mackinaw-style rise: 0.1 ft, then 0.5 ft, then 0.25 ft per 15 minutes
+        let stage = vec![at(0, 10.0), at(15, 10.1), at(30, 10.6), at(45, 10.85)];
+
+        let rate = rate_of_rise("05568580", 6, &stage);
+        assert_eq!(rate.samples, 4);
+        assert!((rate.current_rate_ft_per_hr.unwrap() - 1.0).abs() < 1e-9);
+        assert!((rate.max_rate_ft_per_hr.unwrap() - 2.0).abs() < 1e-9);
+        assert!((rate.average_rate_ft_per_hr.unwrap() - 0.85 / 0.75).abs() < 1e-9);
+        assert_eq!(rate.trend, "rising");
+
+        let falling = rate_of_rise("05568580", 6, &[at(0, 10.0), at(60, 9.5)]);
+        assert_eq!(falling.trend, "falling");
+        assert_eq!(rate_of_rise("05568580", 6, &[at(0, 10.0), at(60, 10.01)]).trend, "steady");
+    }
+
+    #[test]
+    fn test_known_parameters_cover_registry() {
+        let known = known_parameters();
+        assert!(known.iter().any(|p| p == PARAM_STAGE));
+        assert!(known.iter().any(|p| p == PARAM_DISCHARGE));
+        assert!(!known.iter().any(|p| p == "99999"));
+    }
+
+    #[test]
+    fn test_parse_window_hours_is_bounded() {
+        assert_eq!(parse_window_hours(None, 6, 168), Some(6));
+        assert_eq!(parse_window_hours(Some("12"), 6, 168), Some(12));
+        assert_eq!(parse_window_hours(Some("168"), 6, 168), Some(168));
+        assert_eq!(parse_window_hours(Some("169"), 6, 168), None);
+        assert_eq!(parse_window_hours(Some("0"), 6, 168), None);
+        assert_eq!(parse_window_hours(Some("-3"), 6, 168), None);
+        assert_eq!(parse_window_hours(Some("9223372036854775807"), 6, 168), None, "would overflow now - hours");
+        assert_eq!(parse_window_hours(Some("six"), 6, 168), None);
+    }
+
+    #[test]
+    fn test_rate_of_rise_needs_two_readings() {
+        let only = TimedValue { timestamp: Utc::now(), value: 12.0 };
+        let rate = rate_of_rise("05568580", 6, &[only]);
+        assert_eq!(rate.current_rate_ft_per_hr, None);
+        assert_eq!(rate.max_rate_ft_per_hr, None);
+        assert_eq!(rate.trend, "unknown");
+        assert_eq!(rate.samples, 1);
+
+        let body = serde_json::to_value(rate_of_rise("05568580", 6, &[])).unwrap();
+        assert!(body["current_rate_ft_per_hr"].is_null());
+        assert_eq!(body["trend"], "unknown");
+    }
+
+    #[test]
+    fn test_zone_detail_csv_rows_and_units() {
+        let detail = serde_json::json!({
+            "zone_id": 2,
+            "sensors": [
+                {"sensor_id": "05568500", "sensor_type": "stage", "current_value": 18.456,
+                 "current_unit": "ft", "current_timestamp": "2024-05-01T12:00:00+00:00",
+                 "staleness_minutes": 15, "flood_stage_ft": 18.0, "action_stage_ft": 16.0},
+                {"sensor_id": "PIA", "sensor_type": "precip, 1h", "current_value": null,
+                 "current_unit": null, "current_timestamp": null,
+                 "staleness_minutes": null, "flood_stage_ft": null, "action_stage_ft": null},
+            ]
+        });
+        
+        let csv = zone_detail_csv(detail.clone(), UnitSystem::Imperial);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "sensor_id,type,current_value,unit,timestamp,staleness_minutes,flood_stage_ft,action_stage_ft");
+        assert_eq!(lines[1], "05568500,stage,18.46,ft,2024-05-01T12:00:00+00:00,15,18.0,16.0");
+        assert_eq!(lines[2], "PIA,\"precip, 1h\",,,,,,");
+        
+        let metric = zone_detail_csv(detail, UnitSystem::Metric);
+        assert!(metric.starts_with("sensor_id,type,current_value,unit,timestamp,staleness_minutes,flood_stage_m,action_stage_m\n"));
+        assert!(metric.lines().nth(1).unwrap().starts_with("05568500,stage,5.625,m,"));
+    }
+    
+    #[test]
+    fn test_wants_csv_defaults_to_json() {
+        assert!(wants_csv(&split_query("/trend/05568500?format=csv").1));
+        assert!(wants_csv(&split_query("/zone/2?format=CSV").1));
+        assert!(!wants_csv(&split_query("/zone/2").1));
+        assert!(!wants_csv(&split_query("/zone/2?format=xml").1));
+    }
+
+    #[test]
+    fn test_respond_logged_adds_response_time() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/zone/9", server.server_addr().to_ip().unwrap());
+        let handle = std::thread::spawn(move || {
+            let request = server.recv().unwrap();
+            let started = Instant::now() - std::time::Duration::from_millis(25);
+            respond_logged(request, error_response(400, "Invalid zone_id. Must be 0-6."), started);
+        });
+        
+        let response = reqwest::blocking::get(&url).unwrap();
+        assert_eq!(response.status().as_u16(), 400);
+        let elapsed: u128 = response.headers()["X-Response-Time-Ms"].to_str().unwrap().parse().unwrap();
+        assert!(elapsed >= 25);
+        assert_eq!(response.headers()["Content-Type"], "application/json");
+        handle.join().unwrap();
+    }
+    
+    #[test]
+    fn test_concurrent_health_checks_while_database_unavailable() {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", server.server_addr().to_ip().unwrap());
+        // Every checkout fails, as if Postgres were down
+        let pool: db::DbPool = db::ConnectionPool::new(
+            2, std::time::Duration::from_millis(50),
+            || Err(db::DbConfigError::MissingDatabaseUrl), Client::is_closed,
+        );
+        std::thread::spawn(move || serve_requests(&server, pool, crate::metrics::shared(), 2));
+        
+        let requests: Vec<_> = (0..8)
+            .map(|i| {
+                let url = format!("{}{}", base, if i % 2 == 0 { "/health" } else { "/status" });
+                std::thread::spawn(move || reqwest::blocking::get(&url).unwrap().status().as_u16())
+            })
+            .collect();
+        let statuses: Vec<u16> = requests.into_iter().map(|r| r.join().unwrap()).collect();
+        
+        for (i, status) in statuses.iter().enumerate() {
+            // Database routes fail fast (no connection, or server busy)
+            let expected = if i % 2 == 0 { 200 } else { 503 };
+            assert_eq!(*status, expected, "request {} got {}", i, status);
+        }
+    }
 }
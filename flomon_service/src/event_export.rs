@@ -0,0 +1,326 @@
+//! Per-event observation export for the external Python workflow.
+//!
+//! Model training wants each historical flood event as one tidy table: every
+//! reading over the event window, one row per (source, site, parameter,
+//! time), labelled with the event phase and hours before the crest. The
+//! event and its phase labels come from `flood_analysis.events` and
+//! `flood_analysis.event_observations`; the readings themselves from
+//! `usgs_raw.gauge_readings` and `usace.cwms_timeseries`.
+//!
+//! Phases follow `event_observations.phase`. A raw reading takes the phase
+//! of the latest labelled observation at or before it; readings before the
+//! first labelled observation (or events never labelled) fall back to the
+//! fixed window boundaries `floml.precursors.label_phases` uses in its
+//! `'window'` mode.
+
+use chrono::{DateTime, Duration, Utc};
+use postgres::Client;
+use std::io::Write;
+
+/// Days after the crest still labelled "falling", and the window end for
+/// events with no `event_end`. Matches floml's `post_peak_window_days`.
+pub const POST_PEAK_WINDOW_DAYS: i64 = 7;
+
+/// Half-width of the "peak" phase around the crest. Matches floml's
+/// `peak_tolerance_hours`.
+pub const PEAK_TOLERANCE_HOURS: i64 = 6;
+
+pub const CSV_HEADER: &str = "event_id,source,site_id,parameter,timestamp,value,unit,phase,hours_before_peak";
+
+/// A `flood_analysis.events` row, reduced to what the export needs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportEvent {
+    pub id: i32,
+    pub site_code: String,
+    pub event_start: DateTime<Utc>,
+    pub event_peak: DateTime<Utc>,
+    pub event_end: Option<DateTime<Utc>>,
+    pub precursor_window_start: Option<DateTime<Utc>>,
+}
+
+impl ExportEvent {
+    /// Start of the exported window: the precursor window if analyzed
+    pub fn window_start(&self) -> DateTime<Utc> {
+        self.precursor_window_start.unwrap_or(self.event_start).min(self.event_start)
+    }
+
+    /// End of the exported window: the recorded end, or a week past the crest
+    pub fn window_end(&self) -> DateTime<Utc> {
+        self.event_end.unwrap_or(self.event_peak + Duration::days(POST_PEAK_WINDOW_DAYS))
+    }
+
+    /// Phase from the window boundaries alone (no rate-based rising onset,
+    /// so nothing is labelled "rising")
+    pub fn window_phase(&self, t: DateTime<Utc>) -> Option<&'static str> {
+        if t < self.window_start() {
+            None
+        } else if (t - self.event_peak).abs() <= Duration::hours(PEAK_TOLERANCE_HOURS) {
+            Some("peak")
+        } else if t < self.event_peak {
+            Some("precursor")
+        } else if t <= self.event_peak + Duration::days(POST_PEAK_WINDOW_DAYS) {
+            Some("falling")
+        } else {
+            Some("post")
+        }
+    }
+
+    /// Hours from `t` to the crest; negative after it
+    pub fn hours_before_peak(&self, t: DateTime<Utc>) -> f64 {
+        (self.event_peak - t).num_seconds() as f64 / 3600.0
+    }
+}
+
+/// (time, phase) of labelled observations at the event's own gauge, ascending
+pub type PhaseLabels = Vec<(DateTime<Utc>, String)>;
+
+/// One output row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportRow {
+    /// "usgs", "cwms", or "event_observation"
+    pub source: &'static str,
+    pub site_id: String,
+    pub parameter: String,
+    pub timestamp: DateTime<Utc>,
+    pub value: f64,
+    pub unit: String,
+    pub phase: Option<String>,
+}
+
+// ---------------------------------------------------------------------------
+// Phase Labelling
+// ---------------------------------------------------------------------------
+
+/// Phase for a reading at `t`: the latest labelled observation at or before
+/// it (`labels` ascending), else the window boundaries.
+pub fn phase_at(event: &ExportEvent, labels: &[(DateTime<Utc>, String)], t: DateTime<Utc>) -> Option<String> {
+    let labelled = labels.partition_point(|(at, _)| *at <= t);
+    match labelled.checked_sub(1) {
+        Some(i) => Some(labels[i].1.clone()),
+        None => event.window_phase(t).map(str::to_string),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Queries
+// ---------------------------------------------------------------------------
+
+pub fn fetch_event(client: &mut Client, event_id: i32) -> Result<Option<ExportEvent>, String> {
+    let row = client.query_opt(
+        "SELECT id, site_code, event_start, event_peak, event_end, precursor_window_start
+         FROM flood_analysis.events
+         WHERE id = $1",
+        &[&event_id]
+    ).map_err(|e| format!("Failed to fetch event {}: {}", event_id, e))?;
+
+    Ok(row.map(|row| ExportEvent {
+        id: row.get(0),
+        site_code: row.get(1),
+        event_start: row.get(2),
+        event_peak: row.get(3),
+        event_end: row.get(4),
+        precursor_window_start: row.get(5),
+    }))
+}
+
+/// Stage and discharge rows stored in `event_observations`, and the
+/// (time, phase) labels of the event's own site, ascending.
+fn fetch_observations(
+    client: &mut Client,
+    event: &ExportEvent,
+) -> Result<(Vec<ExportRow>, PhaseLabels), String> {
+    let rows = client.query(
+        "SELECT site_code, timestamp, phase, stage_ft::float8, discharge_cfs::float8
+         FROM flood_analysis.event_observations
+         WHERE event_id = $1
+         ORDER BY timestamp ASC",
+        &[&event.id]
+    ).map_err(|e| format!("Failed to fetch observations for event {}: {}", event.id, e))?;
+
+    let mut observations = Vec::new();
+    let mut labels = Vec::new();
+
+    for row in rows {
+        let site_code: String = row.get(0);
+        let timestamp: DateTime<Utc> = row.get(1);
+        let phase: Option<String> = row.get(2);
+
+        if let Some(phase) = &phase
+            && site_code == event.site_code
+        {
+            labels.push((timestamp, phase.clone()));
+        }
+
+        let values = [
+            (crate::model::PARAM_STAGE, row.get::<_, Option<f64>>(3), "ft"),
+            (crate::model::PARAM_DISCHARGE, row.get::<_, Option<f64>>(4), "ft3/s"),
+        ];
+        for (parameter, value, unit) in values {
+            if let Some(value) = value {
+                observations.push(ExportRow {
+                    source: "event_observation",
+                    site_id: site_code.clone(),
+                    parameter: parameter.to_string(),
+                    timestamp,
+                    value,
+                    unit: unit.to_string(),
+                    phase: phase.clone(),
+                });
+            }
+        }
+    }
+
+    Ok((observations, labels))
+}
+
+/// Raw readings over the event window: every parameter stored for the
+/// event's gauge, and every CWMS timeseries.
+fn fetch_raw_readings(client: &mut Client, event: &ExportEvent) -> Result<Vec<ExportRow>, String> {
+    let (start, end) = (event.window_start(), event.window_end());
+
+    let rows = client.query(
+        "SELECT 'usgs', site_code, parameter_code, reading_time, value::float8, unit
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND reading_time BETWEEN $2 AND $3
+         UNION ALL
+         SELECT 'cwms', location_id, timeseries_id, timestamp, value::float8, unit
+         FROM usace.cwms_timeseries
+         WHERE timestamp BETWEEN $2 AND $3
+         ORDER BY 4, 1, 2, 3",
+        &[&event.site_code, &start, &end]
+    ).map_err(|e| format!("Failed to fetch readings for event {}: {}", event.id, e))?;
+
+    Ok(rows.iter()
+        .map(|row| ExportRow {
+            source: if row.get::<_, &str>(0) == "usgs" { "usgs" } else { "cwms" },
+            site_id: row.get(1),
+            parameter: row.get(2),
+            timestamp: row.get(3),
+            value: row.get(4),
+            unit: row.get(5),
+            phase: None,
+        })
+        .collect())
+}
+
+/// Every row for `event`, time-ordered, with phases filled in.
+pub fn event_rows(client: &mut Client, event: &ExportEvent) -> Result<Vec<ExportRow>, String> {
+    let (mut rows, labels) = fetch_observations(client, event)?;
+
+    for mut row in fetch_raw_readings(client, event)? {
+        row.phase = phase_at(event, &labels, row.timestamp);
+        rows.push(row);
+    }
+
+    rows.sort_by(|a, b| a.timestamp.cmp(&b.timestamp).then(a.source.cmp(b.source)));
+    Ok(rows)
+}
+
+// ---------------------------------------------------------------------------
+// CSV
+// ---------------------------------------------------------------------------
+
+/// Quote a field if it contains a delimiter, quote, or newline.
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+pub fn write_csv<W: Write>(out: &mut W, event: &ExportEvent, rows: &[ExportRow]) -> std::io::Result<()> {
+    writeln!(out, "{}", CSV_HEADER)?;
+    for row in rows {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{},{:.2}",
+            event.id,
+            row.source,
+            csv_field(&row.site_id),
+            csv_field(&row.parameter),
+            row.timestamp.to_rfc3339(),
+            row.value,
+            csv_field(&row.unit),
+            row.phase.as_deref().unwrap_or(""),
+            event.hours_before_peak(row.timestamp),
+        )?;
+    }
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn event() -> ExportEvent {
+        ExportEvent {
+            id: 42,
+            site_code: "05567500".to_string(),
+            event_start: Utc.with_ymd_and_hms(2019, 5, 1, 0, 0, 0).unwrap(),
+            event_peak: Utc.with_ymd_and_hms(2019, 5, 5, 12, 0, 0).unwrap(),
+            event_end: None,
+            precursor_window_start: Some(Utc.with_ymd_and_hms(2019, 4, 24, 0, 0, 0).unwrap()),
+        }
+    }
+
+    #[test]
+    fn test_window_phases_without_labels() {
+        let event = event();
+        let peak = event.event_peak;
+
+        assert_eq!(event.window_end(), peak + Duration::days(7));
+        assert_eq!(event.window_phase(event.window_start() - Duration::hours(1)), None);
+        assert_eq!(event.window_phase(peak - Duration::days(2)), Some("precursor"));
+        assert_eq!(event.window_phase(peak + Duration::hours(5)), Some("peak"));
+        assert_eq!(event.window_phase(peak + Duration::days(3)), Some("falling"));
+        assert_eq!(event.window_phase(peak + Duration::days(8)), Some("post"));
+    }
+
+    #[test]
+    fn test_raw_readings_inherit_latest_stored_label() {
+        let event = event();
+        let peak = event.event_peak;
+        let labels = vec![
+            (peak - Duration::days(3), "rising".to_string()),
+            (peak - Duration::hours(6), "peak".to_string()),
+        ];
+
+        // Between labelled observations
+        assert_eq!(phase_at(&event, &labels, peak - Duration::days(1)).as_deref(), Some("rising"));
+        assert_eq!(phase_at(&event, &labels, peak).as_deref(), Some("peak"));
+        // Before the first label: window fallback
+        assert_eq!(phase_at(&event, &labels, peak - Duration::days(4)).as_deref(), Some("precursor"));
+    }
+
+    #[test]
+    fn test_csv_rows_carry_phase_and_hours_before_peak() {
+        let event = event();
+        let rows = vec![ExportRow {
+            source: "cwms",
+            site_id: "LaGrange".to_string(),
+            parameter: "LaGrange-TW.Elev.Inst.~1Hour.0.CBT-RAW".to_string(),
+            timestamp: event.event_peak - Duration::minutes(90),
+            value: 432.5,
+            unit: "ft".to_string(),
+            phase: Some("rising".to_string()),
+        }];
+
+        let mut out = Vec::new();
+        write_csv(&mut out, &event, &rows).unwrap();
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "42,cwms,LaGrange,LaGrange-TW.Elev.Inst.~1Hour.0.CBT-RAW,2019-05-05T10:30:00+00:00,432.5,ft,rising,1.50"
+        );
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+    }
+}
@@ -0,0 +1,131 @@
+//! User-defined sensor groups from groups.toml.
+//!
+//! Zones are fixed by hydrology; a group is any named set of sensors,
+//! drawn from whichever zones they sit in ("my dock gauges", "upstream
+//! warning set"). A group lists sensors by any ID they carry in zones.toml
+//! and is rendered by `/group/{name}` the same way as a zone. groups.toml
+//! is optional: without it there are simply no groups.
+
+use serde::Deserialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::zones::{get_all_zones, Sensor, ZonesConfig};
+
+/// Root of groups.toml
+#[derive(Debug, Deserialize, Default)]
+pub struct GroupsConfig {
+    #[serde(rename = "group", default)]
+    pub groups: Vec<SensorGroup>,
+}
+
+/// One named set of sensors
+#[derive(Debug, Deserialize, Clone)]
+pub struct SensorGroup {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// zones.toml sensor IDs (`id`, `usgs_id`, `cwms_location`, or `station_id`)
+    pub sensors: Vec<String>,
+}
+
+/// Load groups from a TOML file; a missing file is an empty config.
+pub fn load_groups<P: AsRef<Path>>(path: P) -> Result<GroupsConfig, Box<dyn std::error::Error>> {
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(GroupsConfig::default()),
+        Err(e) => return Err(e.into()),
+    };
+    let config: GroupsConfig = toml::from_str(&content)?;
+    Ok(config)
+}
+
+/// Load groups from the default location (groups.toml)
+pub fn load_groups_default() -> Result<GroupsConfig, Box<dyn std::error::Error>> {
+    load_groups("groups.toml")
+}
+
+impl GroupsConfig {
+    pub fn find(&self, name: &str) -> Option<&SensorGroup> {
+        self.groups.iter().find(|g| g.name == name)
+    }
+
+    pub fn names(&self) -> Vec<&str> {
+        self.groups.iter().map(|g| g.name.as_str()).collect()
+    }
+}
+
+impl SensorGroup {
+    /// The group's sensors from `zones`, in group order, plus the IDs that
+    /// matched none. A zones.toml `id` match wins over the other IDs, since
+    /// a CWMS pool and tailwater sensor share one `cwms_location`; a sensor
+    /// listed twice appears once.
+    pub fn resolve(&self, zones: &ZonesConfig) -> (Vec<Sensor>, Vec<String>) {
+        let all: Vec<&Sensor> = get_all_zones(zones)
+            .into_iter()
+            .flat_map(|(_, zone)| &zone.sensors)
+            .collect();
+
+        let mut picked: Vec<usize> = Vec::new();
+        let mut unmatched = Vec::new();
+        for id in &self.sensors {
+            let found = all.iter()
+                .position(|s| s.sensor_id.as_deref() == Some(id.as_str()))
+                .or_else(|| all.iter().position(|s| s.ids().any(|sid| sid == id)));
+            match found {
+                Some(i) if !picked.contains(&i) => picked.push(i),
+                Some(_) => {}
+                None => unmatched.push(id.clone()),
+            }
+        }
+        let sensors = picked.into_iter().map(|i| all[i].clone()).collect();
+        (sensors, unmatched)
+    }
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_groups_toml_parses() {
+        let config = load_groups("groups.toml").expect("groups.toml should parse");
+        assert!(!config.groups.is_empty());
+        for group in &config.groups {
+            assert!(!group.sensors.is_empty(), "group {} has no sensors", group.name);
+        }
+    }
+
+    #[test]
+    fn test_missing_file_means_no_groups() {
+        let config = load_groups("/nonexistent/groups.toml").unwrap();
+        assert!(config.groups.is_empty());
+        assert!(config.find("dock").is_none());
+    }
+
+    #[test]
+    fn test_resolve_spans_zones_and_reports_unmatched() {
+        let zones = crate::zones::load_zones("zones.toml").unwrap();
+        let group = SensorGroup {
+            name: "mixed".to_string(),
+            description: String::new(),
+            sensors: vec![
+                "05567500".to_string(), // zone 2, by usgs_id
+                "IL07TW".to_string(),   // zone 2, by id (shares cwms_location IL07)
+                "ORD".to_string(),      // zone 6, by station_id
+                "05567500".to_string(), // duplicate
+                "NOPE".to_string(),
+            ],
+        };
+
+        let (sensors, unmatched) = group.resolve(&zones);
+        let ids: Vec<String> = sensors.iter().map(|s| s.sensor_id.clone().unwrap_or_else(|| s.primary_id())).collect();
+        assert_eq!(ids, vec!["05567500", "IL07TW", "ORD"]);
+        assert_eq!(unmatched, vec!["NOPE"]);
+    }
+}
@@ -9,6 +9,8 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserialize;
 
+use crate::ingest::http;
+
 const CWMS_API_BASE: &str = "https://cwms-data.usace.army.mil/cwms-data";
 
 // ============================================================================
@@ -40,6 +42,21 @@ pub struct CwmsCatalogResponse {
     pub entries: Option<Vec<CwmsCatalogEntry>>,
 }
 
+/// Location record from `/locations/{name}` (only the fields we use)
+#[derive(Debug, Deserialize)]
+pub struct CwmsLocationResponse {
+    pub name: String,
+    #[serde(rename = "office-id")]
+    pub office: String,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+    #[serde(rename = "vertical-datum")]
+    pub vertical_datum: Option<String>,
+    pub elevation: Option<f64>,
+    #[serde(rename = "elevation-units")]
+    pub elevation_units: Option<String>,
+}
+
 #[derive(Debug)]
 pub struct CwmsValue {
     pub date_time: i64,  // Unix timestamp in milliseconds
@@ -77,6 +94,39 @@ pub struct CwmsTimeseries {
     pub quality_code: i32,
 }
 
+/// Authoritative datum and elevation for a CWMS location
+#[derive(Debug, Clone, PartialEq)]
+pub struct CwmsLocationMetadata {
+    pub location_id: String,
+    pub office: String,
+    /// Vertical datum elevations at this location are reported in
+    /// (e.g. "NGVD29", "NAVD88")
+    pub vertical_datum: Option<String>,
+    /// Ground/gage-zero elevation, in feet
+    pub elevation_ft: Option<f64>,
+    pub latitude: Option<f64>,
+    pub longitude: Option<f64>,
+}
+
+impl From<CwmsLocationResponse> for CwmsLocationMetadata {
+    fn from(r: CwmsLocationResponse) -> Self {
+        // Requested in English units, but convert if the office ignores that
+        let elevation_ft = r.elevation.map(|e| match r.elevation_units.as_deref() {
+            Some(u) if u.eq_ignore_ascii_case("m") => e / 0.3048,
+            _ => e,
+        });
+        
+        CwmsLocationMetadata {
+            location_id: r.name,
+            office: r.office,
+            vertical_datum: r.vertical_datum.filter(|d| !d.trim().is_empty()),
+            elevation_ft,
+            latitude: r.latitude,
+            longitude: r.longitude,
+        }
+    }
+}
+
 // ============================================================================
 // API Client
 // ============================================================================
@@ -88,12 +138,14 @@ pub struct CwmsTimeseries {
 /// - `office_id`: CWMS office ID (e.g., "MVS")
 /// - `begin`: Start time (inclusive)
 /// - `end`: End time (inclusive)
+/// - `max_attempts`: Tries before a 5xx or network error is returned
 pub fn fetch_timeseries(
     client: &reqwest::blocking::Client,
     timeseries_id: &str,
     office_id: &str,
     begin: DateTime<Utc>,
     end: DateTime<Utc>,
+    max_attempts: u32,
 ) -> Result<Vec<CwmsTimeseries>, Box<dyn std::error::Error>> {
     
     let url = format!(
@@ -107,10 +159,10 @@ pub fn fetch_timeseries(
     
     println!("   Fetching: {}", url);
     
-    let response = client
-        .get(&url)
-        .header("Accept", "application/json")
-        .send()?;
+    let response = http::send_with_retry(
+        || client.get(&url).header("Accept", "application/json"),
+        max_attempts,
+    )?;
     
     if !response.status().is_success() {
         return Err(format!("CWMS API error: {}", response.status()).into());
@@ -152,12 +204,13 @@ pub fn fetch_recent(
     timeseries_id: &str,
     office_id: &str,
     hours: i64,
+    max_attempts: u32,
 ) -> Result<Vec<CwmsTimeseries>, Box<dyn std::error::Error>> {
     
     let end = Utc::now();
     let begin = end - chrono::Duration::hours(hours);
     
-    fetch_timeseries(client, timeseries_id, office_id, begin, end)
+    fetch_timeseries(client, timeseries_id, office_id, begin, end, max_attempts)
 }
 
 /// Fetch historical data for backfill (date range)
@@ -167,12 +220,44 @@ pub fn fetch_historical(
     office_id: &str,
     start_date: NaiveDateTime,
     end_date: NaiveDateTime,
+    max_attempts: u32,
 ) -> Result<Vec<CwmsTimeseries>, Box<dyn std::error::Error>> {
     
     let begin = DateTime::<Utc>::from_naive_utc_and_offset(start_date, Utc);
     let end = DateTime::<Utc>::from_naive_utc_and_offset(end_date, Utc);
     
-    fetch_timeseries(client, timeseries_id, office_id, begin, end)
+    fetch_timeseries(client, timeseries_id, office_id, begin, end, max_attempts)
+}
+
+/// Fetch a location's vertical datum and elevation from `/locations`
+///
+/// # Parameters
+/// - `location`: CWMS location ID (e.g., "IL07", "Grafton")
+/// - `office`: CWMS office ID (e.g., "MVR")
+pub fn fetch_location_metadata(
+    client: &reqwest::blocking::Client,
+    location: &str,
+    office: &str,
+) -> Result<CwmsLocationMetadata, Box<dyn std::error::Error>> {
+    
+    let url = format!(
+        "{}/locations/{}?office={}&unit=EN",
+        CWMS_API_BASE,
+        urlencoding::encode(location),
+        office
+    );
+    
+    let response = client
+        .get(&url)
+        .header("Accept", "application/json;version=2")
+        .send()?;
+    
+    if !response.status().is_success() {
+        return Err(format!("CWMS locations API error: {}", response.status()).into());
+    }
+    
+    let api_response: CwmsLocationResponse = response.json()?;
+    Ok(api_response.into())
 }
 
 // ============================================================================
@@ -231,27 +316,127 @@ pub fn discover_timeseries(
     Ok(timeseries_ids)
 }
 
+/// Version segment of a timeseries ID (the part after the last dot),
+/// e.g. "CBT-RAW" in `Peoria-Pool.Elev.Inst.~1Hour.0.CBT-RAW`
+pub fn timeseries_version(timeseries_id: &str) -> &str {
+    timeseries_id.rsplit('.').next().unwrap_or(timeseries_id)
+}
+
+/// Parameter type segment of a timeseries ID (the third), e.g. "Inst"
+/// or "Ave" in `IL07.Flow.Ave.~1Day.1Day.Ccp-Rev`
+pub fn timeseries_parameter_type(timeseries_id: &str) -> Option<&str> {
+    timeseries_id.split('.').nth(2).filter(|kind| !kind.is_empty())
+}
+
+/// Interval segment of a timeseries ID (the fourth), e.g. "~1Day" in
+/// `IL07.Flow.Ave.~1Day.1Day.Ccp-Rev`
+pub fn timeseries_interval(timeseries_id: &str) -> Option<&str> {
+    timeseries_id.split('.').nth(3).filter(|interval| !interval.is_empty())
+}
+
+/// Duration segment of a timeseries ID (the fifth), e.g. "1Day" in
+/// `IL07.Flow.Ave.~1Day.1Day.Ccp-Rev`; "0" for instantaneous values
+pub fn timeseries_duration(timeseries_id: &str) -> Option<&str> {
+    timeseries_id.split('.').nth(4).filter(|duration| !duration.is_empty())
+}
+
+/// Base parameter of a timeseries ID (the second segment, without any
+/// sub-parameter), e.g. "Elev" in `Peoria-Pool.Elev.Inst.~1Hour.0.CBT-RAW`
+/// or `IL07.Elev-Tailwater.Inst.1Hour.0.Ccp-Rev`
+pub fn timeseries_parameter(timeseries_id: &str) -> Option<&str> {
+    let parameter = timeseries_id.split('.').nth(1)?;
+    parameter.split('-').next().filter(|base| !base.is_empty())
+}
+
+/// Parameter segment of a timeseries ID with any sub-parameter kept, e.g.
+/// "Elev" in `IL08.Elev.Inst.~1Hour.0.CBT-RAW` but "Elev-Tailwater" in
+/// `IL08.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW`. This is what tells a lock's
+/// pool and tailwater apart when both live under the same location.
+pub fn timeseries_base_parameter(timeseries_id: &str) -> Option<&str> {
+    timeseries_id.split('.').nth(1).filter(|parameter| !parameter.is_empty())
+}
+
+/// First timeseries matching `matches`, preferring one whose version is
+/// `preferred_version` (case-insensitive) when several match.
+fn find_preferred<'a>(
+    all_timeseries: &'a [String],
+    preferred_version: Option<&str>,
+    matches: impl Fn(&str) -> bool,
+) -> Option<&'a String> {
+    let mut candidates = all_timeseries.iter().filter(|ts| matches(ts));
+    let first = candidates.next()?;
+    
+    let preferred = preferred_version.and_then(|version| {
+        std::iter::once(first)
+            .chain(candidates)
+            .find(|ts| timeseries_version(ts).eq_ignore_ascii_case(version))
+    });
+    
+    Some(preferred.unwrap_or(first))
+}
+
+/// Pick the pool elevation timeseries from a catalog listing
+///
+/// Prioritize: Pool.Elev.Inst > Pool.Elev.Ave > any with "Pool" and "Elev",
+/// then a plain `.Elev.` at the lock itself (`IL08.Elev.Inst...`), which MVR
+/// publishes pool elevation under. `preferred_version` only chooses among
+/// candidates of the same priority.
+pub fn select_pool_elevation(all_timeseries: &[String], preferred_version: Option<&str>) -> Option<String> {
+    find_preferred(all_timeseries, preferred_version, |ts| ts.contains("-Pool.") && ts.contains(".Elev.Inst"))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| ts.contains("-Pool.") && ts.contains(".Elev.")))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| ts.contains("Pool") && ts.contains("Elev")))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| {
+            timeseries_base_parameter(ts) == Some("Elev") && !ts.contains("TW") && !ts.contains("Tailwater")
+        }))
+        .cloned()
+}
+
+/// Pick the tailwater elevation timeseries from a catalog listing
+///
+/// Patterns: -TW.Elev, -Tailwater.Elev, TW-*.Elev, .Elev-Tailwater
+pub fn select_tailwater_elevation(all_timeseries: &[String], preferred_version: Option<&str>) -> Option<String> {
+    find_preferred(all_timeseries, preferred_version, |ts| ts.contains("-TW.") && ts.contains(".Elev.Inst"))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| {
+            (ts.contains("-TW.") || ts.contains("TW-") || ts.contains("Tailwater")) && ts.contains(".Elev")
+        }))
+        .cloned()
+}
+
+/// Pick the stage timeseries (for river gauges, not pools) from a catalog listing
+pub fn select_stage(all_timeseries: &[String], preferred_version: Option<&str>) -> Option<String> {
+    find_preferred(all_timeseries, preferred_version, |ts| ts.contains(".Stage.Inst"))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| ts.contains(".Stage.")))
+        .cloned()
+}
+
+/// Pick the discharge timeseries from a catalog listing
+///
+/// Prioritize: Flow.Inst > Flow-Out.Inst > any other Flow (e.g. daily
+/// Flow.Ave) > any other Flow-Out. A river gauge's own flow wins; at a lock
+/// and dam, which only publishes its outflow, that is the river's discharge.
+pub fn select_discharge(all_timeseries: &[String], preferred_version: Option<&str>) -> Option<String> {
+    find_preferred(all_timeseries, preferred_version, |ts| ts.contains(".Flow.Inst"))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| ts.contains(".Flow-Out.Inst")))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| ts.contains(".Flow.")))
+        .or_else(|| find_preferred(all_timeseries, preferred_version, |ts| ts.contains(".Flow-Out.")))
+        .cloned()
+}
+
 /// Discover pool elevation timeseries for a location
 ///
-/// Searches for timeseries containing "Pool" and "Elev" in the location pattern
+/// Searches for timeseries containing "Pool" and "Elev" in the location pattern,
+/// preferring `preferred_version` when the catalog offers several versions
 pub fn discover_pool_elevation(
     client: &reqwest::blocking::Client,
     office: &str,
     location_base: &str,
+    preferred_version: Option<&str>,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     
     let pattern = format!("{}.*", location_base);
     let all_timeseries = discover_timeseries(client, office, &pattern)?;
     
-    // Look for pool elevation timeseries
-    // Prioritize: Pool.Elev.Inst > Pool.Elev.Ave > any with "Pool" and "Elev"
-    let pool_elev = all_timeseries.iter()
-        .find(|ts| ts.contains("-Pool.") && ts.contains(".Elev.Inst"))
-        .or_else(|| all_timeseries.iter().find(|ts| ts.contains("-Pool.") && ts.contains(".Elev.")))
-        .or_else(|| all_timeseries.iter().find(|ts| ts.contains("Pool") && ts.contains("Elev")))
-        .cloned();
-    
-    Ok(pool_elev)
+    Ok(select_pool_elevation(&all_timeseries, preferred_version))
 }
 
 /// Discover tailwater elevation timeseries for a location
@@ -259,19 +444,13 @@ pub fn discover_tailwater_elevation(
     client: &reqwest::blocking::Client,
     office: &str,
     location_base: &str,
+    preferred_version: Option<&str>,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     
     let pattern = format!("{}.*", location_base);
     let all_timeseries = discover_timeseries(client, office, &pattern)?;
     
-    // Look for tailwater elevation timeseries
-    // Patterns: -TW.Elev, -Tailwater.Elev, TW-*.Elev
-    let tw_elev = all_timeseries.iter()
-        .find(|ts| ts.contains("-TW.") && ts.contains(".Elev.Inst"))
-        .or_else(|| all_timeseries.iter().find(|ts| (ts.contains("-TW.") || ts.contains("TW-") || ts.contains("Tailwater")) && ts.contains(".Elev.")))
-        .cloned();
-    
-    Ok(tw_elev)
+    Ok(select_tailwater_elevation(&all_timeseries, preferred_version))
 }
 
 /// Discover stage timeseries for a river gauge location (not a pool)
@@ -279,18 +458,92 @@ pub fn discover_stage(
     client: &reqwest::blocking::Client,
     office: &str,
     location_base: &str,
+    preferred_version: Option<&str>,
 ) -> Result<Option<String>, Box<dyn std::error::Error>> {
     
     let pattern = format!("{}.*", location_base);
     let all_timeseries = discover_timeseries(client, office, &pattern)?;
     
-    // Look for stage timeseries (for river gauges, not pools)
-    let stage = all_timeseries.iter()
-        .find(|ts| ts.contains(".Stage.Inst"))
-        .or_else(|| all_timeseries.iter().find(|ts| ts.contains(".Stage.")))
-        .cloned();
+    Ok(select_stage(&all_timeseries, preferred_version))
+}
+
+/// Discover discharge timeseries for a location
+pub fn discover_discharge(
+    client: &reqwest::blocking::Client,
+    office: &str,
+    location_base: &str,
+    preferred_version: Option<&str>,
+) -> Result<Option<String>, Box<dyn std::error::Error>> {
+    
+    let pattern = format!("{}.*", location_base);
+    let all_timeseries = discover_timeseries(client, office, &pattern)?;
     
-    Ok(stage)
+    Ok(select_discharge(&all_timeseries, preferred_version))
+}
+
+// ============================================================================
+// Quality Codes
+// ============================================================================
+
+/// Quality bits: screened, then the validity flags (at most one is set)
+const QUALITY_SCREENED: u32 = 1 << 0;
+const QUALITY_OKAY: u32 = 1 << 1;
+const QUALITY_MISSING: u32 = 1 << 2;
+const QUALITY_QUESTIONABLE: u32 = 1 << 3;
+const QUALITY_REJECTED: u32 = 1 << 4;
+
+/// The validity part of a CWMS quality code.
+///
+/// The code is a bitmask: bit 0 says whether the value has been screened,
+/// bits 1-4 give the screening verdict (okay, missing, questionable,
+/// rejected). The higher bits (range, revision, replacement, test failed,
+/// protection) don't change whether a value is usable and are ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QualityFlags {
+    pub screened: bool,
+    /// Screened and judged okay
+    pub validated: bool,
+    pub missing: bool,
+    pub questionable: bool,
+    pub rejected: bool,
+}
+
+impl QualityFlags {
+    /// Missing and rejected values must not be used; questionable ones are
+    /// kept (with their code) for the analysis to weigh.
+    pub fn is_unusable(&self) -> bool {
+        self.missing || self.rejected
+    }
+
+    /// "unscreened", "okay", "missing", "questionable", or "rejected"
+    pub fn label(&self) -> &'static str {
+        if self.rejected {
+            "rejected"
+        } else if self.missing {
+            "missing"
+        } else if self.questionable {
+            "questionable"
+        } else if self.validated {
+            "okay"
+        } else {
+            "unscreened"
+        }
+    }
+}
+
+/// Decode a CWMS quality code (e.g. 0 = unscreened, 3 = screened okay,
+/// 5 = missing, 17 = rejected). The protected bit makes some codes
+/// negative as an i32; only the bit pattern matters.
+pub fn decode_quality(code: i32) -> QualityFlags {
+    let bits = code as u32;
+    let screened = bits & QUALITY_SCREENED != 0;
+    QualityFlags {
+        screened,
+        validated: screened && bits & QUALITY_OKAY != 0,
+        missing: bits & QUALITY_MISSING != 0,
+        questionable: bits & QUALITY_QUESTIONABLE != 0,
+        rejected: bits & QUALITY_REJECTED != 0,
+    }
 }
 
 // ============================================================================
@@ -365,6 +618,125 @@ mod tests {
         assert!(!detect_backwater(431.0, 430.0, 2.0));
     }
     
+    #[test]
+    fn test_select_prefers_configured_version() {
+        let catalog: Vec<String> = [
+            "IL08-Pool.Elev.Inst.~1Hour.0.CBT-RAW",
+            "IL08-Pool.Elev.Inst.~1Hour.0.CBT-REV",
+            "IL08-Pool.Elev.Ave.~1Day.1Day.Ccp-Rev",
+            "IL08-TW.Elev.Inst.~1Hour.0.CBT-RAW",
+        ].iter().map(|s| s.to_string()).collect();
+        
+        // No preference: first match in catalog order
+        assert_eq!(select_pool_elevation(&catalog, None).as_deref(), Some("IL08-Pool.Elev.Inst.~1Hour.0.CBT-RAW"));
+        
+        // Preference is case-insensitive
+        assert_eq!(select_pool_elevation(&catalog, Some("cbt-rev")).as_deref(), Some("IL08-Pool.Elev.Inst.~1Hour.0.CBT-REV"));
+        
+        // Never trades an Inst series for a lower-priority Ave one
+        assert_eq!(select_pool_elevation(&catalog, Some("Ccp-Rev")).as_deref(), Some("IL08-Pool.Elev.Inst.~1Hour.0.CBT-RAW"));
+        
+        // Unavailable preference falls back to the usual pick
+        assert_eq!(select_tailwater_elevation(&catalog, Some("CBT-REV")).as_deref(), Some("IL08-TW.Elev.Inst.~1Hour.0.CBT-RAW"));
+        assert_eq!(select_stage(&catalog, Some("CBT-REV")), None);
+    }
+    
+    #[test]
+    fn test_select_lock_elevations_by_sub_parameter() {
+        let catalog: Vec<String> = [
+            "IL08.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW",
+            "IL08.Elev.Inst.~1Hour.0.CBT-RAW",
+            "IL08.Stage.Inst.~1Hour.0.CBT-RAW",
+        ].iter().map(|s| s.to_string()).collect();
+        
+        assert_eq!(select_pool_elevation(&catalog, None).as_deref(), Some("IL08.Elev.Inst.~1Hour.0.CBT-RAW"));
+        assert_eq!(select_tailwater_elevation(&catalog, None).as_deref(), Some("IL08.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW"));
+        assert_eq!(timeseries_base_parameter(&catalog[0]), Some("Elev-Tailwater"));
+        assert_eq!(timeseries_base_parameter(&catalog[1]), Some("Elev"));
+        assert_eq!(timeseries_base_parameter("IL08"), None);
+    }
+    
+    #[test]
+    fn test_select_discharge_prefers_instantaneous_flow() {
+        let catalog: Vec<String> = [
+            "IL07.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW",
+            "IL07.Flow-Out.Ave.~1Day.1Day.Ccp-Rev",
+            "IL07.Flow.Ave.~1Day.1Day.Ccp-Rev",
+            "IL07.Flow.Inst.~1Hour.0.CBT-RAW",
+            "IL07.Stage.Inst.15Minutes.0.Ccp-Rev",
+        ].iter().map(|s| s.to_string()).collect();
+        
+        assert_eq!(select_discharge(&catalog, None).as_deref(), Some("IL07.Flow.Inst.~1Hour.0.CBT-RAW"));
+        
+        // Without an Inst series, daily flow ahead of the dam's outflow
+        let daily: Vec<String> = catalog.iter().filter(|ts| !ts.contains(".Flow.Inst")).cloned().collect();
+        assert_eq!(select_discharge(&daily, None).as_deref(), Some("IL07.Flow.Ave.~1Day.1Day.Ccp-Rev"));
+        
+        // A lock publishing only outflow: instantaneous first
+        let lock: Vec<String> = [
+            "IL08.Flow-Out.Ave.~1Day.1Day.Ccp-Rev",
+            "IL08.Flow-Out.Inst.~1Hour.0.CBT-RAW",
+        ].iter().map(|s| s.to_string()).collect();
+        assert_eq!(select_discharge(&lock, None).as_deref(), Some("IL08.Flow-Out.Inst.~1Hour.0.CBT-RAW"));
+        assert_eq!(timeseries_parameter_type("IL07.Flow.Ave.~1Day.1Day.Ccp-Rev"), Some("Ave"));
+        assert_eq!(timeseries_interval("IL07.Flow.Ave.~1Day.1Day.Ccp-Rev"), Some("~1Day"));
+        assert_eq!(timeseries_duration("IL07.Flow.Ave.~1Day.1Day.Ccp-Rev"), Some("1Day"));
+        assert_eq!(timeseries_version("IL07.Flow.Ave.~1Day.1Day.Ccp-Rev"), "Ccp-Rev");
+        
+        // Stage-only gauges have no discharge
+        assert_eq!(select_discharge(&catalog[4..], None), None);
+    }
+    
+    #[test]
+    fn test_location_metadata_from_response() {
+        let json = r#"{
+            "office-id": "MVR", "name": "IL07", "latitude": 40.69, "longitude": -89.56,
+            "vertical-datum": "NGVD29", "elevation": 131.064, "elevation-units": "m",
+            "horizontal-datum": "NAD83", "location-kind": "PROJECT"
+        }"#;
+        let response: CwmsLocationResponse = serde_json::from_str(json).unwrap();
+        let metadata = CwmsLocationMetadata::from(response);
+        
+        assert_eq!(metadata.location_id, "IL07");
+        assert_eq!(metadata.vertical_datum.as_deref(), Some("NGVD29"));
+        assert!((metadata.elevation_ft.unwrap() - 430.0).abs() < 1e-6);
+        
+        // Feet pass through; a blank datum is no datum
+        let json = r#"{"office-id": "MVS", "name": "Grafton", "vertical-datum": " ", "elevation": 403.8, "elevation-units": "ft"}"#;
+        let metadata = CwmsLocationMetadata::from(serde_json::from_str::<CwmsLocationResponse>(json).unwrap());
+        assert_eq!(metadata.vertical_datum, None);
+        assert_eq!(metadata.elevation_ft, Some(403.8));
+    }
+    
+    #[test]
+    fn test_decode_canonical_quality_codes() {
+        let unscreened = decode_quality(0);
+        assert_eq!(unscreened, QualityFlags::default());
+        assert_eq!(unscreened.label(), "unscreened");
+        assert!(!unscreened.is_unusable());
+        
+        let okay = decode_quality(3);
+        assert!(okay.screened && okay.validated);
+        assert_eq!(okay.label(), "okay");
+        
+        assert_eq!(decode_quality(5).label(), "missing");
+        assert!(decode_quality(5).is_unusable());
+        assert_eq!(decode_quality(9).label(), "questionable");
+        assert!(!decode_quality(9).is_unusable());
+        assert_eq!(decode_quality(17).label(), "rejected");
+        assert!(decode_quality(17).is_unusable());
+    }
+    
+    #[test]
+    fn test_decode_quality_ignores_higher_bits() {
+        // Okay, but revised (changed bit 7) and protected (bit 31)
+        let flags = decode_quality(3 | 1 << 7 | 1 << 31);
+        assert!(flags.validated && !flags.is_unusable());
+        
+        // Rejected with a failed range test (bit 16)
+        assert!(decode_quality(17 | 1 << 16).rejected);
+    }
+    
     #[test]
     fn test_classify_backwater_severity() {
         assert_eq!(classify_backwater_severity(0.3), "none");
@@ -0,0 +1,126 @@
+//! Retrying GET shared by the ingest clients.
+//!
+//! USGS, CWMS, and IEM all have the occasional 503 or dropped connection;
+//! without a retry a single blip costs that station a whole poll cycle.
+//! `get_with_retry` repeats a request on network errors and 5xx responses
+//! with doubling backoff (1s, 2s, 4s, ...) plus a little jitter, so
+//! stations failing together don't retry in lockstep. Other statuses are
+//! returned as-is for the caller to judge.
+
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::blocking::{Client, RequestBuilder, Response};
+
+/// Default attempts per request, including the first.
+pub const DEFAULT_HTTP_MAX_ATTEMPTS: u32 = 3;
+
+/// Wait before the first retry; doubled for each one after.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// GET `url`, retrying network errors and 5xx responses up to
+/// `max_attempts` attempts in all. The last attempt's error (a 5xx is
+/// turned into one) is returned once they are used up.
+pub fn get_with_retry(client: &Client, url: &str, max_attempts: u32) -> Result<Response, reqwest::Error> {
+    send_with_retry(|| client.get(url), max_attempts)
+}
+
+/// `get_with_retry` for a request that needs more than a URL (headers,
+/// query parameters); `request` builds a fresh one for each attempt.
+pub fn send_with_retry<F>(request: F, max_attempts: u32) -> Result<Response, reqwest::Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    send_with_backoff(request, max_attempts, INITIAL_BACKOFF)
+}
+
+fn send_with_backoff<F>(request: F, max_attempts: u32, backoff: Duration) -> Result<Response, reqwest::Error>
+where
+    F: Fn() -> RequestBuilder,
+{
+    let max_attempts = max_attempts.max(1);
+    let mut wait = backoff;
+    let mut attempt = 1;
+    loop {
+        let result = request().send();
+        let retryable = match &result {
+            Ok(response) => response.status().is_server_error(),
+            Err(e) => !e.is_builder(),
+        };
+        if !retryable {
+            return result;
+        }
+        if attempt >= max_attempts {
+            return result.and_then(Response::error_for_status);
+        }
+
+        let reason = match &result {
+            Ok(response) => format!("{} from {}", response.status(), response.url()),
+            Err(e) => e.to_string(),
+        };
+        let delay = wait + jitter(wait);
+        eprintln!(
+            "Warning: HTTP attempt {}/{} failed ({}) — retrying in {}ms",
+            attempt, max_attempts, reason, delay.as_millis()
+        );
+        thread::sleep(delay);
+        wait *= 2;
+        attempt += 1;
+    }
+}
+
+/// Up to a quarter of `wait`, taken from the clock's sub-second digits.
+fn jitter(wait: Duration) -> Duration {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    wait.mul_f64(f64::from(nanos % 1000) / 4000.0)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Serve `statuses` in order, one per request, from a local server.
+    fn mock_server(statuses: Vec<u16>) -> (String, thread::JoinHandle<()>) {
+        let server = tiny_http::Server::http("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/iv", server.server_addr().to_ip().unwrap());
+        let handle = thread::spawn(move || {
+            for status in statuses {
+                let request = server.recv().unwrap();
+                request.respond(tiny_http::Response::from_string("ok").with_status_code(status)).unwrap();
+            }
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_retries_503_until_success() {
+        let (url, server) = mock_server(vec![503, 503, 200]);
+        let client = Client::new();
+
+        let response = send_with_backoff(|| client.get(&url), 3, Duration::from_millis(1)).unwrap();
+        assert_eq!(response.status().as_u16(), 200);
+        assert_eq!(response.text().unwrap(), "ok");
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn test_gives_up_after_max_attempts_and_skips_client_errors() {
+        let (url, server) = mock_server(vec![503, 502, 404]);
+        let client = Client::new();
+
+        let err = send_with_backoff(|| client.get(&url), 2, Duration::from_millis(1)).unwrap_err();
+        assert_eq!(err.status().map(|s| s.as_u16()), Some(502));
+
+        // A 404 won't improve on retry
+        let response = send_with_backoff(|| client.get(&url), 3, Duration::from_millis(1)).unwrap();
+        assert_eq!(response.status().as_u16(), 404);
+        server.join().unwrap();
+    }
+}
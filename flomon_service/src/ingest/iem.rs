@@ -10,6 +10,8 @@
 use chrono::{DateTime, NaiveDateTime, Utc};
 use serde::Deserialize;
 
+use crate::ingest::http;
+
 const IEM_BASE_URL: &str = "https://mesonet.agron.iastate.edu";
 
 // ============================================================================
@@ -85,6 +87,33 @@ pub struct AsosObservation {
     pub weather_codes: Option<String>,
 }
 
+/// A well-formed ASOS CSV response. Anything IEM sends that isn't one (a
+/// blank body, an error page) is an `Err` from `fetch_recent_precip`.
+#[derive(Debug, Clone)]
+pub enum AsosResponse {
+    /// Header plus at least one data row
+    Observations(Vec<AsosObservation>),
+    /// Header only: the station reported nothing in the window, which is
+    /// a successful poll, not missing data
+    Empty,
+}
+
+impl AsosResponse {
+    pub fn is_empty(&self) -> bool {
+        matches!(self, AsosResponse::Empty)
+    }
+    
+    pub fn into_observations(self) -> Vec<AsosObservation> {
+        match self {
+            AsosResponse::Observations(observations) => observations,
+            AsosResponse::Empty => Vec::new(),
+        }
+    }
+}
+
+/// First column of the header row IEM puts on every `onlycomma` response
+const ASOS_CSV_HEADER_PREFIX: &str = "station,";
+
 // ============================================================================
 // API Client Functions
 // ============================================================================
@@ -128,12 +157,15 @@ pub fn fetch_current(
 
 /// Fetch recent observations (last N hours)
 ///
-/// Uses the ASOS endpoint for comprehensive weather data
+/// Uses the ASOS endpoint for comprehensive weather data. A response with
+/// a header but no rows is `AsosResponse::Empty`; one without the CSV
+/// header, or whose rows all fail to parse, is an error.
 pub fn fetch_recent_precip(
     client: &reqwest::blocking::Client,
     station_id: &str,
     hours: i64,
-) -> Result<Vec<AsosObservation>, Box<dyn std::error::Error>> {
+    max_attempts: u32,
+) -> Result<AsosResponse, Box<dyn std::error::Error>> {
     
     let end = Utc::now();
     let begin = end - chrono::Duration::hours(hours);
@@ -152,9 +184,7 @@ pub fn fetch_recent_precip(
         end.format("%H")
     );
     
-    let response = client
-        .get(&url)
-        .send()?;
+    let response = http::get_with_retry(client, &url, max_attempts)?;
     
     if !response.status().is_success() {
         return Err(format!("IEM ASOS API error: {}", response.status()).into());
@@ -165,13 +195,25 @@ pub fn fetch_recent_precip(
 }
 
 /// Parse IEM ASOS CSV response
-fn parse_asos_csv(csv: &str, _station_id: &str) -> Result<Vec<AsosObservation>, Box<dyn std::error::Error>> {
-    let mut observations = Vec::new();
+fn parse_asos_csv(csv: &str, station_id: &str) -> Result<AsosResponse, Box<dyn std::error::Error>> {
+    let mut lines = csv.lines().filter(|line| !line.trim().is_empty());
     
-    for (i, line) in csv.lines().enumerate() {
-        if i == 0 || line.trim().is_empty() {
-            continue; // Skip header or empty lines
+    // No header means IEM didn't answer the query (blank body, error text),
+    // not that the station was quiet
+    match lines.next() {
+        Some(header) if header.starts_with(ASOS_CSV_HEADER_PREFIX) => {}
+        Some(other) => {
+            let snippet: String = other.chars().take(80).collect();
+            return Err(format!("IEM ASOS response for {} has no CSV header: {}", station_id, snippet).into());
         }
+        None => return Err(format!("IEM ASOS returned an empty body for {}", station_id).into()),
+    }
+    
+    let mut observations = Vec::new();
+    let mut data_rows = 0;
+    
+    for line in lines {
+        data_rows += 1;
         
         let fields: Vec<&str> = line.split(',').collect();
         if fields.len() < 21 {
@@ -247,7 +289,15 @@ fn parse_asos_csv(csv: &str, _station_id: &str) -> Result<Vec<AsosObservation>,
         });
     }
     
-    Ok(observations)
+    // Rows that all failed to parse mean the format changed, not that the
+    // station was quiet
+    if data_rows > 0 && observations.is_empty() {
+        Err(format!("IEM ASOS response for {} has {} rows, none parseable", station_id, data_rows).into())
+    } else if observations.is_empty() {
+        Ok(AsosResponse::Empty)
+    } else {
+        Ok(AsosResponse::Observations(observations))
+    }
 }
 
 /// Parse a single IEM observation into our format
@@ -345,6 +395,34 @@ mod tests {
         assert_eq!(calculate_cumulative_precip(&obs), 0.55);
     }
     
+    const ASOS_HEADER: &str = "station,valid,tmpf,dwpf,relh,drct,sknt,p01i,alti,mslp,vsby,gust,skyc1,skyc2,skyc3,skyc4,skyl1,skyl2,skyl3,skyl4,wxcodes,ice_accretion_1hr,ice_accretion_3hr,ice_accretion_6hr,peak_wind_gust,peak_wind_drct,peak_wind_time,feel,metar,snowdepth";
+    
+    #[test]
+    fn test_header_only_response_is_empty_not_error() {
+        let response = parse_asos_csv(&format!("{}\n", ASOS_HEADER), "KPIA").unwrap();
+        assert!(response.is_empty());
+        assert!(response.into_observations().is_empty());
+    }
+    
+    #[test]
+    fn test_missing_header_is_error() {
+        assert!(parse_asos_csv("", "KPIA").is_err());
+        assert!(parse_asos_csv("\n\n", "KPIA").is_err());
+        assert!(parse_asos_csv("ERROR: Invalid station provided", "KPIA").is_err());
+        
+        let row = "PIA,2019-05-01 12:54,65.0,55.0,70.0,180.0,10.0,0.25,29.92,1013.0,10.0,null,BKN,OVC,null,null,null,null,null,null,-RA";
+        let response = parse_asos_csv(&format!("{}\n{}\n", ASOS_HEADER, row), "KPIA").unwrap();
+        let observations = response.into_observations();
+        assert_eq!(observations.len(), 1);
+        assert_eq!(observations[0].precip_1hr_in, Some(0.25));
+    }
+    
+    #[test]
+    fn test_unparseable_rows_are_error_not_empty() {
+        let truncated = "PIA,2019-05-01 12:54,65.0,55.0";
+        assert!(parse_asos_csv(&format!("{}\n{}\n{}\n", ASOS_HEADER, truncated, truncated), "KPIA").is_err());
+    }
+    
     #[test]
     fn test_detect_rainfall_event() {
         let obs = vec![
@@ -1,5 +1,8 @@
 pub mod cwms;
 pub mod fixtures;
+pub mod http;
 pub mod iem;
+pub mod nws;
+pub mod nws_alerts;
 pub mod peak_flow;
 pub mod usgs;
@@ -0,0 +1,223 @@
+//! NWS River Forecast Client
+//!
+//! Retrieves official short-term stage forecasts that NWS river forecast
+//! centers issue for AHPS forecast points (e.g. KINI2, Kingston Mines).
+//! AHPS hydrographs are now served by the National Water Prediction
+//! Service, whose `stageflow/forecast` endpoint returns one issuance: a
+//! list of 6-hourly (stage, flow) points.
+//!
+//! Forecasts are stored as issued in `nws.stage_forecasts`, keyed by
+//! (nws_id, issued_time, valid_time), so re-polling an issuance is a no-op.
+//!
+//! API Documentation: https://api.water.noaa.gov/nwps/v1/docs/
+//! Forecast: https://api.water.noaa.gov/nwps/v1/gauges/KINI2/stageflow/forecast
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+use crate::model::NwisError;
+
+const NWPS_API_BASE_URL: &str = "https://api.water.noaa.gov/nwps/v1";
+
+/// NWPS marks a missing stage or flow with this value.
+const MISSING_VALUE: f64 = -999.0;
+
+// ============================================================================
+// API Response Structures
+// ============================================================================
+
+/// One forecast issuance from /gauges/{lid}/stageflow/forecast
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForecastResponse {
+    issued_time: Option<String>,
+    #[serde(default)]
+    primary_units: Option<String>,
+    #[serde(default)]
+    secondary_units: Option<String>,
+    #[serde(default)]
+    data: Vec<ForecastValue>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ForecastValue {
+    valid_time: String,
+    generated_time: Option<String>,
+    primary: Option<f64>,
+    secondary: Option<f64>,
+}
+
+/// One forecast point ready for database storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct StageForecast {
+    pub nws_id: String,
+    pub issued_time: DateTime<Utc>,
+    pub valid_time: DateTime<Utc>,
+    pub stage_ft: f64,
+    /// Forecast flow, when the point has a rating to report one
+    pub flow_cfs: Option<f64>,
+}
+
+// ============================================================================
+// URL Construction and Parsing
+// ============================================================================
+
+/// Build the stage/flow forecast URL for an NWS gauge (AHPS location ID)
+pub fn build_forecast_url(nws_id: &str) -> String {
+    format!("{}/gauges/{}/stageflow/forecast", NWPS_API_BASE_URL, nws_id.to_uppercase())
+}
+
+fn parse_time(s: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(s).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+/// A reported value, or `None` for null and the -999 sentinel
+fn present(value: Option<f64>) -> Option<f64> {
+    value.filter(|v| *v > MISSING_VALUE)
+}
+
+/// Multiplier from a reported flow unit to cfs
+fn flow_to_cfs(units: Option<&str>) -> Option<f64> {
+    match units.map(str::to_lowercase).as_deref() {
+        Some("kcfs") => Some(1000.0),
+        Some("cfs") | Some("ft3/s") => Some(1.0),
+        _ => None,
+    }
+}
+
+/// Parse a stageflow/forecast response for `nws_id`.
+///
+/// A gauge with no current forecast yields an empty list, as does a
+/// forecast whose points are all missing. Points with a missing stage are
+/// skipped; a missing flow (or one in units we don't recognize) is `None`.
+/// The issuance time falls back to the first point's `generatedTime`.
+pub fn parse_forecast_response(nws_id: &str, json: &str) -> Result<Vec<StageForecast>, NwisError> {
+    let response: ForecastResponse = serde_json::from_str(json)
+        .map_err(|e| NwisError::ParseError(format!("NWS forecast deserialization failed: {}", e)))?;
+
+    if let Some(units) = response.primary_units.as_deref()
+        && !units.is_empty()
+        && !units.eq_ignore_ascii_case("ft")
+    {
+        return Err(NwisError::ParseError(format!("Unexpected forecast stage units '{}' for {}", units, nws_id)));
+    }
+    let flow_factor = flow_to_cfs(response.secondary_units.as_deref());
+
+    let issued_time = response.issued_time.as_deref()
+        .and_then(parse_time)
+        .or_else(|| response.data.iter().find_map(|v| v.generated_time.as_deref().and_then(parse_time)));
+
+    let mut forecasts = Vec::new();
+    for value in &response.data {
+        let Some(stage_ft) = present(value.primary) else {
+            continue;
+        };
+        let valid_time = parse_time(&value.valid_time)
+            .ok_or_else(|| NwisError::ParseError(format!("Invalid forecast validTime '{}'", value.valid_time)))?;
+        let issued_time = issued_time
+            .ok_or_else(|| NwisError::ParseError(format!("Forecast for {} has no issuance time", nws_id)))?;
+
+        forecasts.push(StageForecast {
+            nws_id: nws_id.to_uppercase(),
+            issued_time,
+            valid_time,
+            stage_ft,
+            flow_cfs: present(value.secondary).zip(flow_factor).map(|(flow, factor)| flow * factor),
+        });
+    }
+
+    Ok(forecasts)
+}
+
+// ============================================================================
+// API Client Functions
+// ============================================================================
+
+/// Fetch the current official stage forecast for an NWS gauge
+pub fn fetch_ahps_forecast(
+    client: &reqwest::blocking::Client,
+    nws_id: &str,
+) -> Result<Vec<StageForecast>, NwisError> {
+    let url = build_forecast_url(nws_id);
+
+    let response = client
+        .get(&url)
+        .header("Accept", "application/json")
+        .send()
+        .map_err(|e| NwisError::RequestFailed(format!("NWS forecast request for {} failed: {}", nws_id, e)))?;
+
+    if !response.status().is_success() {
+        return Err(NwisError::HttpError(response.status().as_u16()));
+    }
+
+    let body = response.text()
+        .map_err(|e| NwisError::RequestFailed(format!("Failed to read NWS forecast for {}: {}", nws_id, e)))?;
+    parse_forecast_response(nws_id, &body)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FORECAST_JSON: &str = r#"{
+        "issuedTime": "2024-05-01T14:05:00Z",
+        "wfo": "ILX",
+        "primaryName": "Stage",
+        "primaryUnits": "ft",
+        "secondaryName": "Flow",
+        "secondaryUnits": "kcfs",
+        "data": [
+            {"validTime": "2024-05-01T18:00:00Z", "generatedTime": "2024-05-01T14:05:00Z", "primary": 17.21, "secondary": 38.5},
+            {"validTime": "2024-05-02T00:00:00Z", "generatedTime": "2024-05-01T14:05:00Z", "primary": 17.6, "secondary": -999},
+            {"validTime": "2024-05-02T06:00:00Z", "generatedTime": "2024-05-01T14:05:00Z", "primary": -999, "secondary": -999}
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_forecast_tolerates_missing_values() {
+        let forecasts = parse_forecast_response("kini2", FORECAST_JSON).unwrap();
+
+        assert_eq!(forecasts.len(), 2, "point with no stage is skipped");
+        assert_eq!(forecasts[0].nws_id, "KINI2");
+        assert_eq!(forecasts[0].issued_time.to_rfc3339(), "2024-05-01T14:05:00+00:00");
+        assert_eq!(forecasts[0].valid_time.to_rfc3339(), "2024-05-01T18:00:00+00:00");
+        assert_eq!(forecasts[0].stage_ft, 17.21);
+        assert_eq!(forecasts[0].flow_cfs, Some(38500.0));
+        assert_eq!(forecasts[1].flow_cfs, None);
+    }
+
+    #[test]
+    fn test_short_or_absent_forecast() {
+        // A single point, stage-only, issuance taken from generatedTime
+        let short = r#"{"primaryUnits": "ft", "data": [
+            {"validTime": "2024-05-01T18:00:00-05:00", "generatedTime": "2024-05-01T14:05:00Z", "primary": 12.0}
+        ]}"#;
+        let forecasts = parse_forecast_response("CHTI2", short).unwrap();
+        assert_eq!(forecasts.len(), 1);
+        assert_eq!(forecasts[0].valid_time.to_rfc3339(), "2024-05-01T23:00:00+00:00");
+        assert_eq!(forecasts[0].issued_time.to_rfc3339(), "2024-05-01T14:05:00+00:00");
+        assert_eq!(forecasts[0].flow_cfs, None);
+
+        let none = r#"{"issuedTime": null, "primaryUnits": "", "data": []}"#;
+        assert!(parse_forecast_response("HENI2", none).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_malformed_forecast_is_error() {
+        assert!(parse_forecast_response("KINI2", "{not json").is_err());
+        assert!(parse_forecast_response("KINI2", r#"{"primaryUnits": "kcfs", "data": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_build_forecast_url() {
+        assert_eq!(
+            build_forecast_url("kini2"),
+            "https://api.water.noaa.gov/nwps/v1/gauges/KINI2/stageflow/forecast"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! NWS Alerts API Client
+//!
+//! Retrieves official flood warning/watch/advisory products from the
+//! National Weather Service for the counties along the monitored reach.
+//! These carry the authoritative NWS determination, so they are stored
+//! alongside our computed status for cross-checking.
+//!
+//! API Documentation: https://www.weather.gov/documentation/services-web-api
+//! Active alerts: https://api.weather.gov/alerts/active?zone=ILC143,ILC179
+
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+
+const NWS_API_BASE_URL: &str = "https://api.weather.gov";
+
+/// api.weather.gov rejects requests without an identifying User-Agent.
+const USER_AGENT: &str = "flomon_service (Peoria Illinois River flood monitoring)";
+
+/// NWS county zone (UGC) codes along the monitored Illinois River reach.
+pub const DEFAULT_ALERT_ZONES: &[&str] = &[
+    "ILC143", // Peoria
+    "ILC179", // Tazewell
+    "ILC203", // Woodford
+    "ILC123", // Marshall
+    "ILC155", // Putnam
+    "ILC011", // Bureau
+    "ILC099", // LaSalle
+    "ILC057", // Fulton
+    "ILC125", // Mason
+];
+
+// ============================================================================
+// API Response Structures
+// ============================================================================
+
+/// GeoJSON FeatureCollection returned by /alerts/active
+#[derive(Debug, Deserialize)]
+struct AlertsResponse {
+    features: Vec<AlertFeature>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertFeature {
+    properties: AlertProperties,
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertProperties {
+    id: String,
+    event: String,
+    severity: String,
+    headline: Option<String>,
+    effective: Option<String>,
+    expires: Option<String>,
+    #[serde(rename = "areaDesc")]
+    area_desc: String,
+}
+
+/// Processed flood-related alert for database storage
+#[derive(Debug, Clone, PartialEq)]
+pub struct NwsAlert {
+    pub id: String,
+    pub event: String,
+    pub severity: String,
+    pub headline: Option<String>,
+    pub effective: Option<DateTime<Utc>>,
+    pub expires: Option<DateTime<Utc>>,
+    pub areas: Vec<String>,
+}
+
+// ============================================================================
+// URL Construction and Parsing
+// ============================================================================
+
+/// Build the active-alerts URL for a set of NWS zone codes
+pub fn build_alerts_url(zones: &[&str]) -> String {
+    format!("{}/alerts/active?zone={}", NWS_API_BASE_URL, zones.join(","))
+}
+
+/// True for flood-related products (Flood Warning, Flash Flood Watch,
+/// Flood Advisory, Flood Statement, ...)
+pub fn is_flood_event(event: &str) -> bool {
+    event.to_lowercase().contains("flood")
+}
+
+/// Parse an /alerts/active response, keeping only flood-related alerts
+pub fn parse_alerts_response(json: &str) -> Result<Vec<NwsAlert>, String> {
+    let response: AlertsResponse = serde_json::from_str(json)
+        .map_err(|e| format!("Failed to parse NWS alerts response: {}", e))?;
+
+    let parse_time = |s: &Option<String>| {
+        s.as_deref()
+            .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+    };
+
+    Ok(response.features.into_iter()
+        .map(|f| f.properties)
+        .filter(|p| is_flood_event(&p.event))
+        .map(|p| NwsAlert {
+            effective: parse_time(&p.effective),
+            expires: parse_time(&p.expires),
+            areas: p.area_desc
+                .split(';')
+                .map(|a| a.trim().to_string())
+                .filter(|a| !a.is_empty())
+                .collect(),
+            id: p.id,
+            event: p.event,
+            severity: p.severity,
+            headline: p.headline,
+        })
+        .collect())
+}
+
+// ============================================================================
+// API Client Functions
+// ============================================================================
+
+/// Fetch active flood-related alerts for the given NWS zones
+pub fn fetch_active_flood_alerts(
+    client: &reqwest::blocking::Client,
+    zones: &[&str],
+) -> Result<Vec<NwsAlert>, Box<dyn std::error::Error>> {
+    let url = build_alerts_url(zones);
+
+    let response = client
+        .get(&url)
+        .header("User-Agent", USER_AGENT)
+        .header("Accept", "application/geo+json")
+        .send()?;
+
+    if !response.status().is_success() {
+        return Err(format!("NWS alerts API error: {}", response.status()).into());
+    }
+
+    let body = response.text()?;
+    Ok(parse_alerts_response(&body)?)
+}
+
+// ============================================================================
+// Tests
+// ============================================================================
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALERTS_JSON: &str = r#"{
+        "type": "FeatureCollection",
+        "features": [
+            {
+                "properties": {
+                    "id": "urn:oid:2.49.0.1.840.0.flood-warning-1",
+                    "event": "Flood Warning",
+                    "severity": "Severe",
+                    "headline": "Flood Warning issued for the Illinois River at Peoria",
+                    "effective": "2024-05-01T10:15:00-05:00",
+                    "expires": "2024-05-03T10:15:00-05:00",
+                    "areaDesc": "Peoria, IL; Tazewell, IL; Woodford, IL"
+                }
+            },
+            {
+                "properties": {
+                    "id": "urn:oid:2.49.0.1.840.0.wind-advisory-1",
+                    "event": "Wind Advisory",
+                    "severity": "Moderate",
+                    "headline": null,
+                    "effective": "2024-05-01T10:15:00-05:00",
+                    "expires": null,
+                    "areaDesc": "Peoria, IL"
+                }
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_parse_keeps_only_flood_alerts() {
+        let alerts = parse_alerts_response(ALERTS_JSON).unwrap();
+
+        assert_eq!(alerts.len(), 1);
+        let alert = &alerts[0];
+        assert_eq!(alert.event, "Flood Warning");
+        assert_eq!(alert.severity, "Severe");
+        assert_eq!(alert.areas, vec!["Peoria, IL", "Tazewell, IL", "Woodford, IL"]);
+        assert_eq!(
+            alert.effective.unwrap().to_rfc3339(),
+            "2024-05-01T15:15:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_flood_event_matching() {
+        assert!(is_flood_event("Flood Warning"));
+        assert!(is_flood_event("Flash Flood Watch"));
+        assert!(is_flood_event("Flood Advisory"));
+        assert!(!is_flood_event("Severe Thunderstorm Warning"));
+    }
+
+    #[test]
+    fn test_build_alerts_url() {
+        let url = build_alerts_url(&["ILC143", "ILC179"]);
+        assert_eq!(url, "https://api.weather.gov/alerts/active?zone=ILC143,ILC179");
+    }
+
+    #[test]
+    fn test_malformed_response_is_error() {
+        assert!(parse_alerts_response("{not json").is_err());
+    }
+}
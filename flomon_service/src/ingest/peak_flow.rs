@@ -9,9 +9,13 @@
 /// the highest water level and flow rate recorded during a water year (Oct 1 - Sep 30).
 ///
 /// Used to populate nws.flood_events table with ground-truth historical flood events
-/// for training predictive models and validating alert systems.
+/// for training predictive models and validating alert systems, and
+/// usgs_raw.annual_peaks with every peak stage for flood-frequency curves.
 
-use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use postgres::GenericClient;
+
+use crate::model::central_to_utc;
 use std::collections::HashMap;
 
 /// Parsed peak flow record from USGS RDB format
@@ -28,6 +32,49 @@ pub struct PeakFlowRecord {
     pub alternate_gage_height_ft: Option<f64>,
 }
 
+impl PeakFlowRecord {
+    /// Highest stage recorded for the peak: the gage height at peak flow,
+    /// or the alternate (annual maximum) gage height where that is higher.
+    pub fn peak_stage_ft(&self) -> Option<f64> {
+        match (self.gage_height_ft, self.alternate_gage_height_ft) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            (a, b) => a.or(b),
+        }
+    }
+}
+
+/// Peak Streamflow RDB URL for `site_code`
+pub fn peak_flow_url(site_code: &str) -> String {
+    format!("https://nwis.waterdata.usgs.gov/nwis/peak?site_no={}&agency_cd=USGS&format=rdb", site_code)
+}
+
+/// Store every record with a stage in usgs_raw.annual_peaks, replacing the
+/// stored values for a peak USGS has since revised. Returns the number of
+/// rows written.
+pub fn upsert_annual_peaks<C: GenericClient>(
+    client: &mut C,
+    records: &[PeakFlowRecord],
+) -> Result<usize, postgres::Error> {
+    let mut written = 0;
+    for record in records {
+        let Some(stage) = record.peak_stage_ft() else {
+            continue;
+        };
+        let stage = rust_decimal::Decimal::from_f64_retain(stage).unwrap_or_default();
+        let discharge = record.peak_discharge_cfs.and_then(rust_decimal::Decimal::from_f64_retain);
+        written += client.execute(
+            "INSERT INTO usgs_raw.annual_peaks (site_code, peak_date, peak_stage_ft, peak_discharge_cfs)
+             VALUES ($1, $2, $3, $4)
+             ON CONFLICT (site_code, peak_date) DO UPDATE SET
+                 peak_stage_ft = EXCLUDED.peak_stage_ft,
+                 peak_discharge_cfs = EXCLUDED.peak_discharge_cfs,
+                 fetched_at = NOW()",
+            &[&record.site_code, &record.peak_date, &stage, &discharge]
+        )? as usize;
+    }
+    Ok(written)
+}
+
 /// Flood event derived from peak flow record + threshold comparison
 #[derive(Debug, Clone)]
 pub struct FloodEvent {
@@ -37,6 +84,55 @@ pub struct FloodEvent {
     pub severity: FloodSeverity,
 }
 
+impl FloodEvent {
+    /// Crest time in UTC. Peak-flow records give Central local time.
+    pub fn crest_time_utc(&self) -> DateTime<Utc> {
+        central_to_utc(self.crest_time)
+    }
+}
+
+/// Peak-flow records carry only the crest, so stored events start this
+/// long before it.
+pub const PEAK_EVENT_LEAD_HOURS: i64 = 24;
+
+/// Whether an upsert created a new event or revised an existing one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpsertOutcome {
+    Inserted,
+    Updated,
+}
+
+/// Insert `event` into nws.flood_events, or revise the stored peak stage
+/// and severity if an event with the same site and crest already exists.
+///
+/// USGS revises historical peaks, so re-running the ingest absorbs the
+/// revision instead of skipping or duplicating the event. Relies on the
+/// `unique_flood_event_crest` constraint (sql/013).
+pub fn upsert_flood_event<C: GenericClient>(
+    client: &mut C,
+    event: &FloodEvent,
+    data_source: &str,
+) -> Result<UpsertOutcome, postgres::Error> {
+    let crest = event.crest_time_utc();
+    let event_start = crest - Duration::hours(PEAK_EVENT_LEAD_HOURS);
+    let peak = rust_decimal::Decimal::from_f64_retain(event.peak_stage_ft).unwrap_or_default();
+
+    // xmax is 0 only on a freshly inserted row version
+    let row = client.query_one(
+        "INSERT INTO nws.flood_events
+         (site_code, event_start, crest_time, peak_stage_ft, severity, data_source)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (site_code, crest_time) DO UPDATE SET
+             peak_stage_ft = EXCLUDED.peak_stage_ft,
+             severity = EXCLUDED.severity,
+             data_source = EXCLUDED.data_source
+         RETURNING (xmax = 0)",
+        &[&event.site_code, &event_start, &crest, &peak, &event.severity.as_str(), &data_source]
+    )?;
+
+    Ok(if row.get::<_, bool>(0) { UpsertOutcome::Inserted } else { UpsertOutcome::Updated })
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FloodSeverity {
     Flood,      // Minor flooding (stage >= flood_stage_ft)
@@ -318,6 +414,9 @@ USGS	05567500	2015-12-29		31400		19.09
         assert_eq!(events.len(), 1);
         assert_eq!(events[0].peak_stage_ft, 18.79);
         assert_eq!(events[0].severity, FloodSeverity::Flood);
+
+        // No peak time recorded → local noon, CDT in April (17:00 UTC)
+        assert_eq!(events[0].crest_time_utc().to_rfc3339(), "2013-04-18T17:00:00+00:00");
         
         // Second record: 17.65 ft < 18.0 ft (below flood stage - not included)
     }
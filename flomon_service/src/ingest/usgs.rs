@@ -9,7 +9,9 @@
 /// The IV service returns WaterML rendered as JSON. See `fixtures.rs` for
 /// annotated examples of the response structure.
 
-use crate::model::{GaugeReading, NwisError};
+use crate::model::{GaugeReading, NwisError, ReadingSource, DEFAULT_AGENCY_CODE};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::America::Chicago;
 use serde::Deserialize;
 
 // ---------------------------------------------------------------------------
@@ -46,6 +48,13 @@ struct SourceInfo {
 #[derive(Deserialize)]
 struct SiteCode {
     value: String,
+    /// Operating agency; absent from some responses, which are all USGS
+    #[serde(rename = "agencyCode", default = "default_agency_code")]
+    agency_code: String,
+}
+
+fn default_agency_code() -> String {
+    DEFAULT_AGENCY_CODE.to_string()
 }
 
 #[derive(Deserialize)]
@@ -88,6 +97,9 @@ struct ValueEntry {
 const IV_BASE_URL: &str = "https://waterservices.usgs.gov/nwis/iv/";
 const DV_BASE_URL: &str = "https://waterservices.usgs.gov/nwis/dv/";
 
+/// Days of history the IV service will return; anything older comes from DV.
+pub const IV_MAX_HISTORY_DAYS: u64 = 120;
+
 /// Builds a USGS IV API URL for the given site codes, parameter codes,
 /// and ISO 8601 period (e.g. `"PT1H"` for the past hour, `"PT3H"` for
 /// the past three hours).
@@ -124,6 +136,28 @@ pub fn build_iv_url(sites: &[&str], param_codes: &[&str], period: &str) -> Strin
     )
 }
 
+/// Builds a USGS Instantaneous Values (IV) API URL for an explicit
+/// `start`..`end` window rather than a period ending now, for refetching
+/// a known gap. IV only serves the last `IV_MAX_HISTORY_DAYS`.
+pub fn build_iv_range_url(
+    sites: &[&str],
+    param_codes: &[&str],
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+) -> String {
+    let sites_param = sites.join(",");
+    let params_param = param_codes.join(",");
+    
+    format!(
+        "{}?sites={}&parameterCd={}&startDT={}&endDT={}&format=json&siteStatus=active",
+        IV_BASE_URL,
+        sites_param,
+        params_param,
+        start.format("%Y-%m-%dT%H:%MZ"),
+        end.format("%Y-%m-%dT%H:%MZ")
+    )
+}
+
 /// Builds a USGS Daily Values (DV) API URL for the given site codes,
 /// parameter codes, and date range.
 ///
@@ -162,6 +196,28 @@ pub fn build_dv_url(
     )
 }
 
+/// Inclusive DV `startDT`/`endDT` dates for the half-open window
+/// `[start, end)`, or `None` if it holds no whole local day.
+///
+/// DV dates are Central-time days and `endDT` is inclusive, so requesting
+/// `end`'s own date would fetch a day that whatever covers `end` onward
+/// also covers. The seam policy is that the day `end` falls on belongs to
+/// the later fetch:
+///
+/// - At the DV/IV seam (`end` = the IV window's start, e.g. 120 days back)
+///   DV stops on day 121 and IV supplies day 120 from its start time on.
+///   That one partial day is never given a daily mean beside its
+///   instantaneous readings.
+/// - Contiguous DV pages (`[a, b)`, `[b, c)`) share no date.
+///
+/// The days a window covers are those whose local date is on or after
+/// `start`'s and before `end`'s.
+pub fn dv_date_range(start: DateTime<Utc>, end: DateTime<Utc>) -> Option<(NaiveDate, NaiveDate)> {
+    let first = start.with_timezone(&Chicago).date_naive();
+    let last = end.with_timezone(&Chicago).date_naive().pred_opt()?;
+    (last >= first).then_some((first, last))
+}
+
 // ---------------------------------------------------------------------------
 // Response parsing
 // ---------------------------------------------------------------------------
@@ -190,13 +246,12 @@ pub fn parse_iv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
     // Process each timeSeries entry
     for series in response.value.time_series {
         // Extract metadata
-        let site_code = series
+        let site = series
             .source_info
             .site_code
             .first()
-            .ok_or_else(|| NwisError::ParseError("Missing siteCode".to_string()))?
-            .value
-            .clone();
+            .ok_or_else(|| NwisError::ParseError("Missing siteCode".to_string()))?;
+        let (site_code, agency_code) = (site.value.clone(), site.agency_code.clone());
 
         let site_name = series.source_info.site_name.clone();
 
@@ -249,6 +304,7 @@ pub fn parse_iv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
 
         // Create the GaugeReading
         readings.push(GaugeReading {
+            agency_code,
             site_code,
             site_name,
             parameter_code,
@@ -256,6 +312,7 @@ pub fn parse_iv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
             value,
             datetime: latest.date_time.clone(),
             qualifier,
+            source: ReadingSource::UsgsIv,
         });
     }
 
@@ -285,13 +342,12 @@ pub fn parse_iv_response_all(json: &str) -> Result<Vec<GaugeReading>, NwisError>
     let mut all_readings = Vec::new();
 
     for series in response.value.time_series {
-        let site_code = series
+        let site = series
             .source_info
             .site_code
             .first()
-            .ok_or_else(|| NwisError::ParseError("Missing siteCode".to_string()))?
-            .value
-            .clone();
+            .ok_or_else(|| NwisError::ParseError("Missing siteCode".to_string()))?;
+        let (site_code, agency_code) = (site.value.clone(), site.agency_code.clone());
 
         let site_name = series.source_info.site_name.clone();
 
@@ -335,6 +391,7 @@ pub fn parse_iv_response_all(json: &str) -> Result<Vec<GaugeReading>, NwisError>
                 .to_string();
 
             all_readings.push(GaugeReading {
+                agency_code: agency_code.clone(),
                 site_code: site_code.clone(),
                 site_name: site_name.clone(),
                 parameter_code: parameter_code.clone(),
@@ -342,6 +399,7 @@ pub fn parse_iv_response_all(json: &str) -> Result<Vec<GaugeReading>, NwisError>
                 value,
                 datetime: entry.date_time.clone(),
                 qualifier: qualifier.clone(),
+                source: ReadingSource::UsgsIv,
             });
         }
     }
@@ -355,17 +413,70 @@ pub fn parse_iv_response_all(json: &str) -> Result<Vec<GaugeReading>, NwisError>
     Ok(all_readings)
 }
 
+// ---------------------------------------------------------------------------
+// DV parsing
+// ---------------------------------------------------------------------------
+
+/// Share of DV values that may fail to parse before the whole response is
+/// treated as malformed (default for `parse_dv_response`).
+pub const DEFAULT_MAX_DV_PARSE_FAILURE_FRACTION: f64 = 0.01;
+
+/// Unparseable values quoted in the summary warning.
+const PARSE_FAILURE_SAMPLES: usize = 3;
+
+/// Values that failed to parse during one DV response, summarized instead
+/// of logged one by one (a multi-decade fetch is tens of thousands of
+/// values).
+#[derive(Debug, Default)]
+struct ParseFailures {
+    count: usize,
+    samples: Vec<String>,
+}
+
+impl ParseFailures {
+    fn record(&mut self, raw: &str) {
+        self.count += 1;
+        if self.samples.len() < PARSE_FAILURE_SAMPLES {
+            self.samples.push(format!("'{}'", raw));
+        }
+    }
+
+    fn summary(&self, total: usize) -> String {
+        format!(
+            "{} of {} values failed to parse (e.g. {})",
+            self.count, total, self.samples.join(", ")
+        )
+    }
+}
+
 /// Parses a USGS Daily Values (DV) API JSON response into a flat list
 /// of `GaugeReading`s, returning ALL daily values in the time range.
 ///
 /// Unlike `parse_iv_response` which returns only the most recent value
 /// per timeSeries, this returns one reading per day for historical analysis.
 ///
+/// Equivalent to `parse_dv_response_with_limit` with
+/// `DEFAULT_MAX_DV_PARSE_FAILURE_FRACTION`.
+pub fn parse_dv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
+    parse_dv_response_with_limit(json, DEFAULT_MAX_DV_PARSE_FAILURE_FRACTION)
+}
+
+/// `parse_dv_response` with an explicit tolerance for unparseable values.
+///
+/// Values that don't parse as numbers are skipped and reported in a single
+/// summary warning (count plus a few samples). If more than
+/// `max_failure_fraction` of all values fail, the response is rejected
+/// rather than returned as a sparse record.
+///
 /// # Errors
-/// - `NwisError::ParseError` — malformed or unexpected JSON structure.
+/// - `NwisError::ParseError` — malformed or unexpected JSON structure, or
+///   too many unparseable values.
 /// - `NwisError::NoDataAvailable` — all `timeSeries` entries had either
 ///   an empty `value` array or only USGS sentinel values (`-999999`).
-pub fn parse_dv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
+pub fn parse_dv_response_with_limit(
+    json: &str,
+    max_failure_fraction: f64,
+) -> Result<Vec<GaugeReading>, NwisError> {
     // Parse the JSON into our serde structs (same format as IV)
     let response: IvResponse = serde_json::from_str(json)
         .map_err(|e| NwisError::ParseError(format!("JSON deserialization failed: {}", e)))?;
@@ -378,17 +489,18 @@ pub fn parse_dv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
     }
 
     let mut all_readings = Vec::new();
+    let mut total_values = 0;
+    let mut failures = ParseFailures::default();
 
     // Process each timeSeries entry
     for series in response.value.time_series {
         // Extract metadata
-        let site_code = series
+        let site = series
             .source_info
             .site_code
             .first()
-            .ok_or_else(|| NwisError::ParseError("Missing siteCode".to_string()))?
-            .value
-            .clone();
+            .ok_or_else(|| NwisError::ParseError("Missing siteCode".to_string()))?;
+        let (site_code, agency_code) = (site.value.clone(), site.agency_code.clone());
 
         let site_name = series.source_info.site_name.clone();
 
@@ -415,13 +527,13 @@ pub fn parse_dv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
         }
 
         // Process ALL values (not just the most recent like IV does)
+        total_values += values_wrapper.value.len();
         for entry in &values_wrapper.value {
-            // Parse the value string to f64
+            // Parse the value string to f64; failures are tallied, not fatal
             let value: f64 = match entry.value.parse() {
                 Ok(v) => v,
-                Err(e) => {
-                    // Log but don't fail - skip bad values
-                    eprintln!("Warning: Failed to parse value '{}': {}", entry.value, e);
+                Err(_) => {
+                    failures.record(&entry.value);
                     continue;
                 }
             };
@@ -441,6 +553,7 @@ pub fn parse_dv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
 
             // Create a GaugeReading for each daily value
             all_readings.push(GaugeReading {
+                agency_code: agency_code.clone(),
                 site_code: site_code.clone(),
                 site_name: site_name.clone(),
                 parameter_code: parameter_code.clone(),
@@ -448,10 +561,22 @@ pub fn parse_dv_response(json: &str) -> Result<Vec<GaugeReading>, NwisError> {
                 value,
                 datetime: entry.date_time.clone(),
                 qualifier: qualifier.clone(),
+                source: ReadingSource::UsgsDv,
             });
         }
     }
 
+    if failures.count > 0 {
+        if failures.count as f64 > total_values as f64 * max_failure_fraction {
+            return Err(NwisError::ParseError(format!(
+                "{}; exceeds the {:.1}% tolerance",
+                failures.summary(total_values),
+                max_failure_fraction * 100.0
+            )));
+        }
+        eprintln!("Warning: DV response: {}", failures.summary(total_values));
+    }
+
     // If we didn't collect any valid readings, return NoDataAvailable
     if all_readings.is_empty() {
         return Err(NwisError::NoDataAvailable(
@@ -518,6 +643,18 @@ mod tests {
 
     // --- DV URL construction ------------------------------------------------
 
+    #[test]
+    fn test_build_iv_range_url_uses_utc_bounds() {
+        use chrono::TimeZone;
+        let start = Utc.with_ymd_and_hms(2024, 5, 1, 6, 15, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 5, 2, 6, 0, 0).unwrap();
+        let url = build_iv_range_url(&["05568500"], &[PARAM_STAGE], start, end);
+        assert!(url.contains("waterservices.usgs.gov/nwis/iv/"), "must target the IV endpoint, got: {}", url);
+        assert!(url.contains("startDT=2024-05-01T06:15Z"), "got: {}", url);
+        assert!(url.contains("endDT=2024-05-02T06:00Z"), "got: {}", url);
+        assert!(!url.contains("period="), "a range request must not also send a period");
+    }
+
     #[test]
     fn test_build_dv_url_targets_dv_endpoint() {
         let url = build_dv_url(
@@ -560,6 +697,44 @@ mod tests {
         assert!(url.contains("1940-09-30"), "must support full year range");
     }
 
+    #[test]
+    fn test_dv_range_stops_before_iv_seam() {
+        use chrono::{Duration, TimeZone};
+
+        // 15:00 UTC = 10:00 CDT; IV covers the last 120 days from 10:00
+        let now = Utc.with_ymd_and_hms(2024, 6, 1, 15, 0, 0).unwrap();
+        let iv_start = now - Duration::days(IV_MAX_HISTORY_DAYS as i64);
+        let seam_day = NaiveDate::from_ymd_opt(2024, 2, 2).unwrap();
+        assert_eq!(iv_start.with_timezone(&Chicago).date_naive(), seam_day);
+
+        // DV ends on day 121, the day before IV's first (partial) day
+        let (first, last) = dv_date_range(now - Duration::days(365), iv_start).unwrap();
+        assert_eq!(first, NaiveDate::from_ymd_opt(2023, 6, 2).unwrap());
+        assert_eq!(last, seam_day.pred_opt().unwrap());
+
+        // Contiguous pages neither overlap nor leave a day out
+        let boundary = iv_start - Duration::days(30);
+        let (_, older_last) = dv_date_range(now - Duration::days(365), boundary).unwrap();
+        let (newer_first, _) = dv_date_range(boundary, iv_start).unwrap();
+        assert_eq!(older_last.succ_opt().unwrap(), newer_first);
+    }
+
+    #[test]
+    fn test_dv_range_uses_central_dates() {
+        use chrono::TimeZone;
+
+        // 03:00 UTC on the 2nd is still the 1st in Chicago
+        let start = Utc.with_ymd_and_hms(2024, 3, 2, 3, 0, 0).unwrap();
+        let end = Utc.with_ymd_and_hms(2024, 3, 4, 3, 0, 0).unwrap();
+        assert_eq!(
+            dv_date_range(start, end),
+            Some((NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 3, 2).unwrap()))
+        );
+
+        // A window inside one local day holds no whole DV day
+        assert_eq!(dv_date_range(start, start + chrono::Duration::hours(2)), None);
+    }
+
     // --- Parsing: happy path ------------------------------------------------
 
     #[test]
@@ -660,6 +835,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_captures_agency_code() {
+        let readings = parse_iv_response(fixture_kingston_mines_json()).expect("should parse");
+        assert!(readings.iter().all(|r| r.agency_code == "USGS"));
+
+        // Another agency's site with the same number stays distinct
+        let json = dv_json_with_values(&["512.0"]).replace(r#""value": "99999999""#, r#""value": "99999999", "agencyCode": "USACE""#);
+        let usace = parse_dv_response(&json).expect("should parse");
+        assert_eq!(usace[0].agency_code, "USACE");
+        assert_eq!(usace[0].site_key(), "USACE:99999999");
+    }
+
+    #[test]
+    fn test_parse_tags_readings_with_source() {
+        let iv = parse_iv_response(fixture_kingston_mines_json()).expect("should parse");
+        assert!(iv.iter().all(|r| r.source == ReadingSource::UsgsIv));
+
+        // DV responses share the IV JSON shape
+        let dv = parse_dv_response(fixture_kingston_mines_json()).expect("should parse");
+        assert!(dv.iter().all(|r| r.source == ReadingSource::UsgsDv));
+    }
+
     // --- Parsing: error and edge cases --------------------------------------
 
     #[test]
@@ -743,4 +940,48 @@ mod tests {
             result
         );
     }
+
+    /// DV response for one series with the given raw value strings.
+    fn dv_json_with_values(values: &[&str]) -> String {
+        let entries: Vec<String> = values.iter().enumerate()
+            .map(|(i, v)| format!(
+                r#"{{ "value": "{}", "qualifiers": ["A"], "dateTime": "2020-01-{:02}T00:00:00.000" }}"#,
+                v, i % 28 + 1
+            ))
+            .collect();
+        format!(
+            r#"{{ "value": {{ "timeSeries": [{{
+                "sourceInfo": {{ "siteName": "Test Site", "siteCode": [{{ "value": "99999999" }}] }},
+                "variable": {{
+                  "variableCode": [{{ "value": "00060" }}],
+                  "unit": {{ "unitCode": "ft3/s" }},
+                  "noDataValue": -999999.0
+                }},
+                "values": [{{ "value": [{}] }}]
+            }}] }} }}"#,
+            entries.join(",")
+        )
+    }
+
+    #[test]
+    fn test_parse_dv_skips_unparseable_values_within_tolerance() {
+        let mut values = vec!["1200"; 99];
+        values.push("Ice");
+        let readings = parse_dv_response_with_limit(&dv_json_with_values(&values), 0.05)
+            .expect("one bad value in 100 is within a 5% tolerance");
+        assert_eq!(readings.len(), 99);
+    }
+
+    #[test]
+    fn test_parse_dv_rejects_mostly_unparseable_response() {
+        let values = ["1200", "Eqp", "Ssn", "***"];
+        let result = parse_dv_response_with_limit(&dv_json_with_values(&values), 0.05);
+        match result {
+            Err(NwisError::ParseError(msg)) => {
+                assert!(msg.starts_with("3 of 4 values"), "got {}", msg);
+                assert!(msg.contains("'Eqp'"), "summary should quote samples: {}", msg);
+            }
+            other => panic!("expected ParseError, got {:?}", other),
+        }
+    }
 }
@@ -5,39 +5,77 @@
 /// ```text
 /// flomon_service
 /// +-- model       - shared data types (GaugeReading, FloodThresholds, NwisError, ...)
+/// |   +-- shef    - SHEF .A messages for relaying a reading to NWS
 /// +-- config      - station registry configuration loader (usgs_stations.toml)
+/// +-- config_status - loaded vs. on-disk config files (drift, parse errors)
+/// +-- clock       - pluggable source of "now" (system clock, MockClock for tests)
 /// +-- stations    - USGS site code registry with NWS flood stage thresholds
 /// +-- zones       - Hydrological zone-based sensor grouping (zones.toml)
+/// +-- groups      - user-defined sensor sets spanning zones (groups.toml)
 /// +-- usace_locations - USACE/CWMS location registry (usace_stations.toml)
 /// +-- asos_locations - ASOS station registry (iem_asos.toml)
 /// +-- daemon      - main daemon loop (startup, backfill, polling, warehousing)
 /// +-- endpoint    - Zone-based HTTP API for flood monitoring
+/// +-- metrics     - operational counters in Prometheus text format (/metrics)
+/// +-- event_export - one flood event's readings as a phase-labelled CSV (bin/export_event)
+/// +-- units       - imperial/metric conversion of API output (?units=metric)
+/// +-- webhook     - optional POST of each poll cycle's summary (batched, retried, dead-lettered)
+/// +-- write_buffer - on-disk queue of readings the database couldn't take (replayed next cycle)
 /// +-- ingest
 /// |   +-- usgs    - USGS NWIS IV API: URL construction + JSON parsing
 /// |   +-- cwms    - USACE CWMS API: timeseries data retrieval
 /// |   +-- iem     - IEM/ASOS weather data API client
+/// |   +-- nws_alerts - NWS official flood warnings/watches (api.weather.gov)
+/// |   +-- nws     - NWS AHPS stage forecasts (NWPS stageflow/forecast)
+/// |   +-- http    - GET with exponential backoff on 5xx and network errors
 /// |   +-- fixtures (test only) - representative API response payloads
 /// +-- monitor     - real-time staleness tracking (hybrid DB + in-memory)
+/// |   +-- flood_events - live flood events, gated on duration and crest height
 /// +-- alert
+/// |   +-- level      - canonical alert level with display labels/colors
 /// |   +-- thresholds - flood stage severity evaluation
 /// |   +-- staleness  - gauge reading freshness checking
 /// +-- analysis
+///     +-- aggregate  - hourly min/mean/max/count of 15-minute readings
+///     +-- align      - pairs two series' readings within a time tolerance
+///     +-- backwater  - onset rate of LaGrange backwater (tailwater vs pool)
+///     +-- downsample - LTTB thinning of long series for charts
+///     +-- event_analog - current stage and trend vs. a past flood ("3 ft below 2013")
+///     +-- forecast_blend - official forecast + trend extrapolation as one projection
+///     +-- gaps       - missing stretches in a sensor's record (backfill + API)
 ///     +-- grouping   - organizes flat readings into per-site or per-zone structs
+///     +-- interpolate - value at an arbitrary timestamp from bracketing readings
+///     +-- mass_balance - inflow vs outflow discharge cross-check (bad gauge detection)
+///     +-- precip     - ASOS precipitation rolled up per basin and per zone
+///     +-- qualifiers - stored readings filtered by USGS qualifier (QA audits)
+///     +-- rating     - recent stage-discharge pairs vs the historical rating (shifts)
+///     +-- return_period - return period ("100-year flood") of annual peak stages
+///     +-- scoring    - 0-100 compound flood score (stage, upstream rise, backwater, rain)
+///     +-- seasonal   - historical percentile of current stage for the time of year
+///     +-- sustained  - prolonged stage above a threshold (nuisance flooding)
 /// ```
 
 /// Public modules
 pub mod alert;
 pub mod analysis;
 pub mod asos_locations;
+pub mod clock;
 pub mod config;
+pub mod config_status;
 pub mod daemon;
 pub mod db;
 pub mod endpoint;
+pub mod event_export;
+pub mod groups;
 pub mod ingest;
 pub mod logging;
+pub mod metrics;
 pub mod model;
 pub mod monitor;
 pub mod stations;
+pub mod units;
 pub mod usace_locations;
 pub mod verify;
+pub mod webhook;
+pub mod write_buffer;
 pub mod zones;
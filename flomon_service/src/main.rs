@@ -12,12 +12,19 @@
 //!
 //! Usage:
 //!   cargo run --release -- verify          # Verify data source configuration
+//!   cargo run --release -- --verify-stations # Check the USGS station registry against the live API
 //!   cargo run --release -- --endpoint 8080 # Start daemon with HTTP endpoint on port 8080
+//!   cargo run --release -- --migrate       # Apply pending SQL migrations, then start daemon
+//!   cargo run --release -- --dry-run       # Fetch and parse everything, but write nothing
+//!   cargo run --release -- --strict-config # Refuse to start on config cross-reference problems
 //!
 //! Environment:
 //!   DATABASE_URL - PostgreSQL connection string
+//!   DATABASE_URL_FILE - File containing the connection string (overrides DATABASE_URL)
 
-use flomon_service::daemon::Daemon;
+use chrono::{Duration, Utc};
+use flomon_service::daemon::{Daemon, DaemonConfig, MIN_BACKFILL_COVERAGE_PERCENT, USGS_IV_INTERVAL_MINUTES};
+use flomon_service::db;
 use flomon_service::endpoint;
 use flomon_service::logging::{self, LogLevel};
 use std::env;
@@ -51,6 +58,31 @@ fn main() {
         }
     }
     
+    // Check the station registry against the live USGS API, with retries
+    if args.len() > 1 && args[1] == "--verify-stations" {
+        println!("🔍 Verifying USGS station registry against the live API...\n");
+        
+        let stations = flomon_service::stations::load_stations();
+        let site_codes: Vec<String> = stations.iter().map(|s| s.site_code.clone()).collect();
+        let results = flomon_service::stations::verify_live(&site_codes, flomon_service::stations::DEFAULT_VERIFY_RETRIES);
+        
+        for (station, result) in stations.iter().zip(&results) {
+            match &result.error {
+                None => println!("   ✓ {} ({}) - discharge={}, stage={}",
+                                 station.name, station.site_code, result.has_discharge, result.has_stage),
+                Some(e) => println!("   ✗ {} ({}) - {} [{} attempt(s)]",
+                                    station.name, station.site_code, e, result.attempts),
+            }
+            for warning in result.parameter_warnings(&station.expected_parameters) {
+                println!("     ⚠️  {}", warning);
+            }
+        }
+        
+        let ok = results.iter().filter(|r| r.is_ok()).count();
+        println!("\n{} of {} stations verified", ok, results.len());
+        std::process::exit(if ok == results.len() { 0 } else { 1 });
+    }
+    
     // Initialize logging system
     // Log to both console and file in the current directory
     let log_file = "./flomon_service.log";
@@ -62,6 +94,9 @@ fn main() {
     
     // Parse remaining command-line arguments
     let mut endpoint_port: Option<u16> = None;
+    let mut migrate = false;
+    let mut dry_run = false;
+    let mut strict_config = false;
     
     let mut i = 1;
     while i < args.len() {
@@ -75,18 +110,63 @@ fn main() {
                     std::process::exit(1);
                 }
             }
+            "--migrate" => {
+                migrate = true;
+                i += 1;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            "--strict-config" => {
+                strict_config = true;
+                i += 1;
+            }
             _ => {
                 eprintln!("Unknown argument: {}", args[i]);
                 eprintln!("Usage:");
                 eprintln!("  {} verify           - Verify data source configuration", args[0]);
+                eprintln!("  {} --verify-stations - Check station registry against the live USGS API", args[0]);
                 eprintln!("  {} --endpoint PORT  - Start monitoring daemon", args[0]);
+                eprintln!("  {} --migrate        - Apply pending SQL migrations before starting", args[0]);
+                eprintln!("  {} --dry-run        - Log what would be written instead of writing it", args[0]);
+                eprintln!("  {} --strict-config  - Fail startup on zones.toml references to unconfigured stations", args[0]);
                 std::process::exit(1);
             }
         }
     }
     
     // Create daemon with default configuration
-    let mut daemon = Daemon::new();
+    let mut config = DaemonConfig::from_env();
+    if dry_run {
+        println!("🧪 Dry run: fetching and parsing only, nothing will be written\n");
+        config.dry_run = true;
+    }
+    config.strict_config |= strict_config;
+    
+    // Apply bundled migrations before the daemon validates the schema.
+    // Dry runs (--dry-run or DRY_RUN) write nothing, schema included.
+    if migrate && config.dry_run {
+        println!("🧪 Dry run: skipping --migrate, no migrations applied\n");
+    } else if migrate {
+        println!("🗄️  Applying database migrations...");
+        let applied = db::connect_simple().and_then(|mut client| db::apply_migrations(&mut client));
+        match applied {
+            Ok(applied) if applied.is_empty() => println!("✓ Schema is up to date\n"),
+            Ok(applied) => {
+                for name in &applied {
+                    println!("   ✓ {}", name);
+                }
+                println!("✓ Applied {} migration(s)\n", applied.len());
+            }
+            Err(e) => {
+                eprintln!("\n❌ Migration failed: {}\n", e);
+                std::process::exit(1);
+            }
+        }
+    }
+    
+    let mut daemon = Daemon::with_config(config);
     
     // Initialize: validate database and load stations
     println!("📊 Initializing daemon...");
@@ -130,14 +210,48 @@ fn main() {
     
     // Run backfill for stations that need it
     if !backfill_needed.is_empty() {
-        println!("\n📥 Backfilling {} USGS stations...", backfill_needed.len());
-        for site_code in &backfill_needed {
-            match daemon.backfill_station(site_code) {
-                Ok(count) => println!("   ✓ {} - Inserted {} readings", site_code, count),
-                Err(e) => eprintln!("   ✗ {} - Backfill failed: {}", site_code, e),
+        let total = backfill_needed.len();
+        println!("\n📥 Backfilling {} USGS stations ({} at a time)...",
+                total, daemon.get_config().backfill_concurrency);
+        
+        let mut completed = 0;
+        let mut total_inserted = 0;
+        let mut backfilled = Vec::new();
+        daemon.backfill_stations_concurrently(&backfill_needed, |site_code, result| {
+            completed += 1;
+            match result {
+                Ok(count) => {
+                    total_inserted += count;
+                    backfilled.push(site_code.to_string());
+                    println!("   ✓ [{}/{}] {} - Inserted {} readings", completed, total, site_code, count);
+                }
+                Err(e) => eprintln!("   ✗ [{}/{}] {} - Backfill failed: {}", completed, total, site_code, e),
+            }
+        });
+        println!("   USGS backfill complete: {} readings across {} stations\n", total_inserted, total);
+        
+        // Confirm the high-resolution window actually landed contiguously
+        let config = daemon.get_config();
+        let window_days = config.backfill_days.min(config.max_backfill_days).min(120) as i64;
+        let end = Utc::now();
+        let start = end - Duration::days(window_days);
+        for site_code in &backfilled {
+            match daemon.verify_backfill(site_code, start, end, Duration::minutes(USGS_IV_INTERVAL_MINUTES)) {
+                Ok(report) if report.coverage_percent < MIN_BACKFILL_COVERAGE_PERCENT => {
+                    eprintln!("   ⚠️  {} - Only {:.1}% coverage over the last {} days ({} gaps)",
+                             site_code, report.coverage_percent, window_days, report.gaps.len());
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("   {} - Error verifying backfill coverage: {}", site_code, e),
             }
         }
-        println!();
+    }
+    
+    // Annual peaks for return-period curves
+    println!("📈 Loading USGS annual peaks...");
+    match daemon.load_annual_peaks() {
+        Ok(count) => println!("   {} annual peaks across {} stations\n", count, station_codes.len()),
+        Err(e) => eprintln!("   Failed to load annual peaks: {}\n", e),
     }
     
     // Check CWMS locations for stale data
@@ -234,19 +348,20 @@ fn main() {
     if let Some(port) = endpoint_port {
         println!("🚀 Starting HTTP endpoint server...");
         
-        // Get a new database connection for the endpoint
-        match flomon_service::db::connect_with_validation() {
-            Ok(client) => {
+        // The endpoint borrows connections from the daemon's pool
+        match daemon.db_pool() {
+            Some(pool) => {
                 // Spawn endpoint server in background thread
+                let metrics = daemon.metrics();
                 std::thread::spawn(move || {
-                    if let Err(e) = endpoint::start_endpoint_server(port, client) {
+                    if let Err(e) = endpoint::start_endpoint_server(port, pool, metrics) {
                         eprintln!("❌ Endpoint server error: {}", e);
                     }
                 });
                 println!("   Endpoint running on http://0.0.0.0:{}\n", port);
             }
-            Err(e) => {
-                eprintln!("❌ Failed to connect to database for endpoint: {}", e);
+            None => {
+                eprintln!("❌ Daemon has no database pool for the endpoint");
                 eprintln!("   Continuing without HTTP endpoint\n");
             }
         }
@@ -257,7 +372,19 @@ fn main() {
     println!("   Poll interval: 15 minutes");
     println!("   Monitoring {} USGS stations + {} CWMS locations", 
             daemon.get_stations().len(), daemon.get_cwms_locations().len());
-    println!("   Press Ctrl+C to stop\n");
+    println!("   Press Ctrl+C to stop (twice to force)\n");
+    
+    // SIGINT/SIGTERM: finish the current cycle, then exit cleanly
+    let shutdown = daemon.shutdown_flag();
+    if let Err(e) = ctrlc::set_handler(move || {
+        if shutdown.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            eprintln!("\nForced exit");
+            std::process::exit(130);
+        }
+        eprintln!("\nShutting down after the current poll cycle...");
+    }) {
+        eprintln!("Warning: Could not install signal handler ({}) — Ctrl+C will stop immediately", e);
+    }
     
     if let Err(e) = daemon.run() {
         eprintln!("\n❌ Daemon error: {}", e);
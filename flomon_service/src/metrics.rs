@@ -0,0 +1,158 @@
+//! Operational counters for Prometheus.
+//!
+//! The daemon updates a `SharedMetrics` as it polls and warehouses; the
+//! endpoint server holds the same handle and serves `render` at
+//! `/metrics`. Nothing here touches the database, so a scrape still works
+//! while Postgres is down — which is when it matters most.
+//!
+//! Station staleness is kept as each site's newest reading time and
+//! turned into minutes at scrape time, so it keeps growing between polls
+//! and through an outage instead of freezing at the last poll's value.
+
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+/// Metrics shared between the daemon, its backfill workers, and the
+/// endpoint server.
+pub type SharedMetrics = Arc<Mutex<Metrics>>;
+
+/// A fresh, empty `SharedMetrics`.
+pub fn shared() -> SharedMetrics {
+    Arc::new(Mutex::new(Metrics::default()))
+}
+
+/// Lock `metrics`, carrying on past a panic in another holder: counters
+/// are only ever incremented, so a poisoned value is still usable.
+pub fn lock(metrics: &SharedMetrics) -> MutexGuard<'_, Metrics> {
+    metrics.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Counters and gauges since the process started.
+#[derive(Debug, Default, Clone)]
+pub struct Metrics {
+    /// Readings newly inserted, by source ("usgs", "cwms", "asos")
+    readings_inserted: BTreeMap<String, u64>,
+    /// Failed polls, by (source, site)
+    poll_failures: BTreeMap<(String, String), u64>,
+    /// Newest stored reading per USGS site
+    latest_reading: BTreeMap<String, DateTime<Utc>>,
+    /// When the last poll cycle finished
+    last_poll: Option<DateTime<Utc>>,
+}
+
+impl Metrics {
+    /// Count `count` readings newly warehoused from `source`.
+    pub fn record_inserted(&mut self, source: &str, count: usize) {
+        *self.readings_inserted.entry(source.to_string()).or_default() += count as u64;
+    }
+
+    /// Count one failed poll of `site` from `source`.
+    pub fn record_poll_failure(&mut self, source: &str, site: &str) {
+        *self.poll_failures.entry((source.to_string(), site.to_string())).or_default() += 1;
+    }
+
+    /// Note `site`'s newest reading, for the staleness gauge.
+    pub fn record_latest_reading(&mut self, site: &str, at: DateTime<Utc>) {
+        let latest = self.latest_reading.entry(site.to_string()).or_insert(at);
+        *latest = (*latest).max(at);
+    }
+
+    /// Note that a poll cycle finished at `at`.
+    pub fn record_poll(&mut self, at: DateTime<Utc>) {
+        self.last_poll = Some(at);
+    }
+
+    /// Prometheus text exposition format (version 0.0.4), with staleness
+    /// measured to `now`.
+    pub fn render(&self, now: DateTime<Utc>) -> String {
+        let mut out = String::new();
+
+        family(&mut out, "flomon_readings_inserted_total", "counter",
+               "Readings newly inserted into the warehouse, by source.");
+        for (source, count) in &self.readings_inserted {
+            let _ = writeln!(out, "flomon_readings_inserted_total{{source=\"{}\"}} {}", escape(source), count);
+        }
+
+        family(&mut out, "flomon_poll_failures_total", "counter",
+               "Failed polls, by source and site.");
+        for ((source, site), count) in &self.poll_failures {
+            let _ = writeln!(out, "flomon_poll_failures_total{{source=\"{}\",site=\"{}\"}} {}",
+                             escape(source), escape(site), count);
+        }
+
+        family(&mut out, "flomon_station_staleness_minutes", "gauge",
+               "Minutes since the newest stored reading, by USGS site.");
+        for (site, latest) in &self.latest_reading {
+            let minutes = (now - *latest).num_seconds().max(0) as f64 / 60.0;
+            let _ = writeln!(out, "flomon_station_staleness_minutes{{site=\"{}\"}} {:.1}", escape(site), minutes);
+        }
+
+        family(&mut out, "flomon_last_poll_timestamp_seconds", "gauge",
+               "Unix time the last poll cycle finished.");
+        if let Some(last_poll) = self.last_poll {
+            let _ = writeln!(out, "flomon_last_poll_timestamp_seconds {}", last_poll.timestamp());
+        }
+
+        out
+    }
+}
+
+/// `# HELP` and `# TYPE` lines opening a metric family.
+fn family(out: &mut String, name: &str, kind: &str, help: &str) {
+    let _ = writeln!(out, "# HELP {} {}", name, help);
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+/// Escape a label value: backslash, double quote, and newline.
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    #[test]
+    fn test_render_counters_and_staleness() {
+        let now = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let mut metrics = Metrics::default();
+        metrics.record_inserted("usgs", 40);
+        metrics.record_inserted("usgs", 2);
+        metrics.record_inserted("asos", 3);
+        metrics.record_poll_failure("usgs", "05568500");
+        metrics.record_poll_failure("usgs", "05568500");
+        metrics.record_latest_reading("05567500", now - Duration::minutes(90));
+        metrics.record_latest_reading("05567500", now - Duration::minutes(30));
+        metrics.record_poll(now);
+
+        let text = metrics.render(now);
+        assert!(text.contains("# TYPE flomon_readings_inserted_total counter\n"));
+        assert!(text.contains("flomon_readings_inserted_total{source=\"usgs\"} 42\n"));
+        assert!(text.contains("flomon_readings_inserted_total{source=\"asos\"} 3\n"));
+        assert!(text.contains("flomon_poll_failures_total{source=\"usgs\",site=\"05568500\"} 2\n"));
+        assert!(text.contains("flomon_station_staleness_minutes{site=\"05567500\"} 30.0\n"));
+        assert!(text.contains(&format!("flomon_last_poll_timestamp_seconds {}\n", now.timestamp())));
+
+        // Staleness keeps growing between polls
+        let later = metrics.render(now + Duration::hours(1));
+        assert!(later.contains("flomon_station_staleness_minutes{site=\"05567500\"} 90.0\n"));
+    }
+
+    #[test]
+    fn test_label_values_are_escaped() {
+        let mut metrics = Metrics::default();
+        metrics.record_poll_failure("cwms", "Peoria \"L&D\"\\Pool");
+
+        let text = metrics.render(Utc::now());
+        assert!(text.contains("site=\"Peoria \\\"L&D\\\"\\\\Pool\""), "got:\n{}", text);
+        // Families are declared even before anything has been recorded
+        assert!(text.contains("# TYPE flomon_last_poll_timestamp_seconds gauge\n"));
+    }
+}
@@ -6,7 +6,13 @@
 /// Core data types for the Peoria flood monitoring service.
 ///
 /// This module defines the shared domain model imported by all other modules.
-/// It contains no logic, no I/O, and no external dependencies — only types.
+/// It contains no I/O and no business logic — only types, plus the
+/// Central-time conversion every source's local timestamps rely on.
+
+use chrono::{DateTime, Duration, LocalResult, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::America::Chicago;
+
+pub mod shef;
 
 // ---------------------------------------------------------------------------
 // Parameter codes
@@ -18,6 +24,36 @@ pub const PARAM_DISCHARGE: &str = "00060";
 /// USGS parameter code for gage height (stage), in feet.
 pub const PARAM_STAGE: &str = "00065";
 
+/// USGS qualifier for provisional data, which may still be revised.
+pub const QUALIFIER_PROVISIONAL: &str = "P";
+
+/// USGS qualifier for approved (final) data.
+pub const QUALIFIER_APPROVED: &str = "A";
+
+// ---------------------------------------------------------------------------
+// Station-local time
+// ---------------------------------------------------------------------------
+
+/// Standard-time (CST) offset from UTC, in hours.
+const CST_OFFSET_HOURS: i64 = -6;
+
+/// Convert a naive US Central wall-clock time to UTC, applying CST or CDT
+/// as in effect on that date.
+///
+/// Every monitored station is in Central time, and several sources (USGS
+/// daily values, peak-flow records) report local dates/times without an
+/// offset. DST edge cases:
+/// - Fall back (01:00–02:00 occurs twice): the first, CDT, occurrence is used.
+/// - Spring forward (02:00–03:00 doesn't exist): the time is read as CST,
+///   landing one hour later on the wall clock.
+pub fn central_to_utc(naive: NaiveDateTime) -> DateTime<Utc> {
+    match Chicago.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => dt.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+        LocalResult::None => Utc.from_utc_datetime(&(naive - Duration::hours(CST_OFFSET_HOURS))),
+    }
+}
+
 // ---------------------------------------------------------------------------
 // Reading types
 // ---------------------------------------------------------------------------
@@ -29,6 +65,9 @@ pub const PARAM_STAGE: &str = "00065";
 /// enclosing `timeSeries` object.
 #[derive(Debug, Clone, PartialEq)]
 pub struct GaugeReading {
+    /// Operating agency (NWIS `agencyCode`); `DEFAULT_AGENCY_CODE` unless
+    /// the response says otherwise
+    pub agency_code: String,
     pub site_code: String,
     pub site_name: String,
     pub parameter_code: String,
@@ -36,6 +75,67 @@ pub struct GaugeReading {
     pub value: f64,
     pub datetime: String,   // ISO 8601, e.g. "2024-05-01T12:00:00.000-05:00"
     pub qualifier: String,  // "P" = provisional, "A" = approved
+    pub source: ReadingSource,
+}
+
+impl GaugeReading {
+    /// Key that identifies the site across agencies: the bare site code
+    /// for USGS sites (so existing lookups by site code keep working),
+    /// `AGENCY:site_code` otherwise.
+    pub fn site_key(&self) -> String {
+        if self.agency_code == DEFAULT_AGENCY_CODE {
+            self.site_code.clone()
+        } else {
+            format!("{}:{}", self.agency_code, self.site_code)
+        }
+    }
+}
+
+/// Agency assumed when a source doesn't name one.
+pub const DEFAULT_AGENCY_CODE: &str = "USGS";
+
+/// Which upstream feed a reading came from.
+///
+/// Stored in the `source` column of `usgs_raw.gauge_readings` so that
+/// high-resolution IV data can be preferred over DV means and provenance
+/// can be audited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadingSource {
+    /// USGS Instantaneous Values (15-minute)
+    UsgsIv,
+    /// USGS Daily Values (daily mean)
+    UsgsDv,
+    /// USACE Corps Water Management System
+    Cwms,
+    /// IEM/ASOS weather station
+    Asos,
+    /// Stored before provenance was tracked
+    Unknown,
+}
+
+impl ReadingSource {
+    /// Database representation (`gauge_readings.source`).
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReadingSource::UsgsIv => "usgs_iv",
+            ReadingSource::UsgsDv => "usgs_dv",
+            ReadingSource::Cwms => "cwms",
+            ReadingSource::Asos => "asos",
+            ReadingSource::Unknown => "unknown",
+        }
+    }
+
+    /// Parse the database representation. Unrecognized values map to
+    /// `Unknown` rather than failing the whole query.
+    pub fn from_db_str(s: &str) -> Self {
+        match s {
+            "usgs_iv" => ReadingSource::UsgsIv,
+            "usgs_dv" => ReadingSource::UsgsDv,
+            "cwms" => ReadingSource::Cwms,
+            "asos" => ReadingSource::Asos,
+            _ => ReadingSource::Unknown,
+        }
+    }
 }
 
 /// Both available readings for a single site, grouped for convenient access.
@@ -87,6 +187,8 @@ pub enum NwisError {
     NoDataAvailable(String),
     /// A reading exists but is older than the configured freshness threshold.
     StaleData { site: String, age_minutes: u64 },
+    /// The request never got a response (connection failure, timeout).
+    RequestFailed(String),
 }
 
 impl std::fmt::Display for NwisError {
@@ -99,8 +201,53 @@ impl std::fmt::Display for NwisError {
             NwisError::StaleData { site, age_minutes } => {
                 write!(f, "Stale data for site {}: {} minutes old", site, age_minutes)
             }
+            NwisError::RequestFailed(msg) => write!(f, "Request failed: {}", msg),
         }
     }
 }
 
 impl std::error::Error for NwisError {}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+
+    fn naive(y: i32, m: u32, d: u32, h: u32, min: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap().and_hms_opt(h, min, 0).unwrap()
+    }
+
+    fn utc(y: i32, m: u32, d: u32, h: u32, min: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, min, 0).unwrap()
+    }
+
+    #[test]
+    fn test_central_to_utc_standard_and_daylight() {
+        // January: CST (UTC-6); July: CDT (UTC-5)
+        assert_eq!(central_to_utc(naive(2024, 1, 15, 0, 0)), utc(2024, 1, 15, 6, 0));
+        assert_eq!(central_to_utc(naive(2024, 7, 15, 0, 0)), utc(2024, 7, 15, 5, 0));
+    }
+
+    #[test]
+    fn test_central_to_utc_across_spring_forward() {
+        // 2024-03-10: clocks jump from 02:00 CST to 03:00 CDT
+        assert_eq!(central_to_utc(naive(2024, 3, 10, 1, 30)), utc(2024, 3, 10, 7, 30));
+        assert_eq!(central_to_utc(naive(2024, 3, 10, 3, 30)), utc(2024, 3, 10, 8, 30));
+        // 02:30 doesn't exist; read as CST
+        assert_eq!(central_to_utc(naive(2024, 3, 10, 2, 30)), utc(2024, 3, 10, 8, 30));
+    }
+
+    #[test]
+    fn test_central_to_utc_across_fall_back() {
+        // 2024-11-03: 01:00–02:00 occurs twice; first (CDT) occurrence wins
+        assert_eq!(central_to_utc(naive(2024, 11, 3, 1, 30)), utc(2024, 11, 3, 6, 30));
+        assert_eq!(central_to_utc(naive(2024, 11, 3, 2, 30)), utc(2024, 11, 3, 8, 30));
+        // Midnight the day before and after straddle the change
+        assert_eq!(central_to_utc(naive(2024, 11, 3, 0, 0)), utc(2024, 11, 3, 5, 0));
+        assert_eq!(central_to_utc(naive(2024, 11, 4, 0, 0)), utc(2024, 11, 4, 6, 0));
+    }
+}
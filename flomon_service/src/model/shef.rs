@@ -0,0 +1,100 @@
+//! SHEF `.A` messages for hand-relaying a reading to the NWS office.
+//!
+//! SHEF (Standard Hydrometeorologic Exchange Format) is the text format
+//! NWS offices ingest gauge reports in. A `.A` message carries one
+//! location, a date, and one or more `PE value` pairs:
+//!
+//! ```text
+//! .A KINI2 20240501 Z DH1200/HG 18.42
+//! ```
+//!
+//! Times are always sent in UTC (`Z`), and values in SHEF's English units:
+//! stage in feet, discharge in thousands of cfs.
+
+use chrono::{DateTime, Utc};
+
+use super::{PARAM_DISCHARGE, PARAM_STAGE};
+
+/// SHEF physical element codes for the parameters we store.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PhysicalElement {
+    /// `HG` — river stage, ft
+    Stage,
+    /// `QR` — river discharge, kcfs
+    Discharge,
+}
+
+impl PhysicalElement {
+    /// The SHEF element for a USGS parameter code, if it has one.
+    pub fn from_param_code(param_code: &str) -> Option<Self> {
+        match param_code {
+            PARAM_STAGE => Some(PhysicalElement::Stage),
+            PARAM_DISCHARGE => Some(PhysicalElement::Discharge),
+            _ => None,
+        }
+    }
+
+    /// Two-letter PE code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            PhysicalElement::Stage => "HG",
+            PhysicalElement::Discharge => "QR",
+        }
+    }
+
+    /// `value` (stage ft, discharge cfs) in SHEF units and precision.
+    fn format_value(&self, value: f64) -> String {
+        match self {
+            PhysicalElement::Stage => format!("{:.2}", value),
+            PhysicalElement::Discharge => format!("{:.3}", value / 1000.0),
+        }
+    }
+}
+
+/// A one-value `.A` message for `site_shef_id` observed at `datetime`.
+/// `value` is in our stored units (stage ft, discharge cfs).
+pub fn encode_reading(site_shef_id: &str, element: PhysicalElement, value: f64, datetime: DateTime<Utc>) -> String {
+    format!(
+        ".A {} {} Z DH{}/{} {}",
+        site_shef_id,
+        datetime.format("%Y%m%d"),
+        datetime.format("%H%M"),
+        element.code(),
+        element.format_value(value)
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_encode_stage() {
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap();
+        let stage = PhysicalElement::from_param_code("00065").unwrap();
+        assert_eq!(encode_reading("KINI2", stage, 18.42, at), ".A KINI2 20240501 Z DH1200/HG 18.42");
+        assert_eq!(encode_reading("KINI2", stage, 7.0, at), ".A KINI2 20240501 Z DH1200/HG 7.00");
+    }
+
+    #[test]
+    fn test_encode_discharge_in_kcfs() {
+        let at = Utc.with_ymd_and_hms(2024, 5, 1, 18, 45, 0).unwrap();
+        let discharge = PhysicalElement::from_param_code("00060").unwrap();
+        assert_eq!(encode_reading("KINI2", discharge, 45_230.0, at), ".A KINI2 20240501 Z DH1845/QR 45.230");
+        assert_eq!(PhysicalElement::from_param_code("00045"), None);
+    }
+
+    #[test]
+    fn test_date_and_time_are_zero_padded() {
+        let at = Utc.with_ymd_and_hms(2024, 1, 9, 3, 5, 0).unwrap();
+        assert_eq!(
+            encode_reading("HENI2", PhysicalElement::Stage, 12.3, at),
+            ".A HENI2 20240109 Z DH0305/HG 12.30"
+        );
+    }
+}
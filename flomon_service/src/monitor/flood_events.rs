@@ -0,0 +1,311 @@
+//! Live flood-event detection.
+//!
+//! Each stage reading at or above flood stage opens (or extends) a
+//! *candidate* event for its site. A candidate is only promoted to a row in
+//! `nws.flood_events` once it passes the `FloodEventGate`: it has stayed
+//! above flood stage for a minimum duration and crested a minimum height
+//! above it. A gauge that pokes over flood stage for one reading (noise, a
+//! seiche) and drops back is discarded without ever being written, the same
+//! way `significant_rise_threshold_ft` keeps small rises out of the
+//! historical analysis.
+//!
+//! Once persisted, new crests update the row and the first reading back
+//! below flood stage closes it.
+
+use chrono::{DateTime, Utc};
+use postgres::Client;
+use std::collections::HashMap;
+
+use crate::model::FloodThresholds;
+
+/// `data_source` recorded on events opened by the live detector, so they
+/// can be told apart from imported historical events.
+pub const LIVE_EVENT_DATA_SOURCE: &str = "Live USGS gauge readings";
+
+/// Default minimum time above flood stage before an event is recorded.
+pub const DEFAULT_MIN_EVENT_DURATION_MINUTES: i64 = 60;
+
+/// Default minimum crest above flood stage before an event is recorded.
+pub const DEFAULT_MIN_EVENT_PEAK_ABOVE_FLOOD_FT: f64 = 0.0;
+
+// ---------------------------------------------------------------------------
+// Gate
+// ---------------------------------------------------------------------------
+
+/// Conditions a threshold crossing must meet before it becomes a persisted
+/// flood event. Both apply; set either to zero to disable it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FloodEventGate {
+    /// Time from the first to the latest reading above flood stage
+    pub min_duration_minutes: i64,
+    /// Crest minus flood stage, in feet
+    pub min_peak_above_flood_ft: f64,
+}
+
+impl Default for FloodEventGate {
+    fn default() -> Self {
+        Self {
+            min_duration_minutes: DEFAULT_MIN_EVENT_DURATION_MINUTES,
+            min_peak_above_flood_ft: DEFAULT_MIN_EVENT_PEAK_ABOVE_FLOOD_FT,
+        }
+    }
+}
+
+impl FloodEventGate {
+    pub fn is_met(&self, event: &LiveFloodEvent) -> bool {
+        (event.last_above - event.start).num_minutes() >= self.min_duration_minutes
+            && event.peak_ft - event.flood_stage_ft >= self.min_peak_above_flood_ft
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tracker
+// ---------------------------------------------------------------------------
+
+/// An open flood event (candidate or persisted) at one site.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiveFloodEvent {
+    pub site_code: String,
+    pub flood_stage_ft: f64,
+    /// First reading at or above flood stage
+    pub start: DateTime<Utc>,
+    /// Latest reading at or above flood stage
+    pub last_above: DateTime<Utc>,
+    pub crest_time: DateTime<Utc>,
+    pub peak_ft: f64,
+    /// `nws.flood_events.id` once promoted
+    pub persisted_id: Option<i32>,
+}
+
+/// What a new reading means for the site's flood-event record.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FloodEventUpdate {
+    /// Nothing to write
+    None,
+    /// A candidate just passed the gate; insert it
+    Promote(LiveFloodEvent),
+    /// A persisted event reached a new crest
+    NewCrest(LiveFloodEvent),
+    /// A persisted event dropped below flood stage at the given time
+    End(LiveFloodEvent, DateTime<Utc>),
+}
+
+/// Open flood events per site, fed one stage reading at a time.
+#[derive(Debug, Default)]
+pub struct FloodEventTracker {
+    open: HashMap<String, LiveFloodEvent>,
+}
+
+impl FloodEventTracker {
+    /// Fold a stage reading into the site's open event. Readings no newer
+    /// than the last one seen above flood stage are ignored, so re-polling
+    /// the same value is harmless.
+    pub fn observe(
+        &mut self,
+        site_code: &str,
+        stage_ft: f64,
+        time: DateTime<Utc>,
+        flood_stage_ft: f64,
+        gate: &FloodEventGate,
+    ) -> FloodEventUpdate {
+        let above = stage_ft >= flood_stage_ft;
+
+        let Some(event) = self.open.get_mut(site_code) else {
+            if !above {
+                return FloodEventUpdate::None;
+            }
+            let event = LiveFloodEvent {
+                site_code: site_code.to_string(),
+                flood_stage_ft,
+                start: time,
+                last_above: time,
+                crest_time: time,
+                peak_ft: stage_ft,
+                persisted_id: None,
+            };
+            let update = if gate.is_met(&event) {
+                FloodEventUpdate::Promote(event.clone())
+            } else {
+                FloodEventUpdate::None
+            };
+            self.open.insert(site_code.to_string(), event);
+            return update;
+        };
+
+        if time <= event.last_above {
+            return FloodEventUpdate::None;
+        }
+
+        if !above {
+            // Receded: close a persisted event, drop a blip silently
+            let event = self.open.remove(site_code).expect("event is open");
+            return match event.persisted_id {
+                Some(_) => FloodEventUpdate::End(event, time),
+                None => FloodEventUpdate::None,
+            };
+        }
+
+        event.last_above = time;
+        let new_crest = stage_ft > event.peak_ft;
+        if new_crest {
+            event.peak_ft = stage_ft;
+            event.crest_time = time;
+        }
+
+        match event.persisted_id {
+            None if gate.is_met(event) => FloodEventUpdate::Promote(event.clone()),
+            Some(_) if new_crest => FloodEventUpdate::NewCrest(event.clone()),
+            _ => FloodEventUpdate::None,
+        }
+    }
+
+    /// Record the row id assigned to a promoted event.
+    pub fn mark_persisted(&mut self, site_code: &str, id: i32) {
+        if let Some(event) = self.open.get_mut(site_code) {
+            event.persisted_id = Some(id);
+        }
+    }
+
+    /// Re-open a persisted event (e.g. one left open across a restart).
+    pub fn resume(&mut self, event: LiveFloodEvent) {
+        self.open.insert(event.site_code.clone(), event);
+    }
+}
+
+/// NWS category for an event's crest, as stored in `nws.flood_events.severity`.
+pub fn event_severity(peak_ft: f64, thresholds: &FloodThresholds) -> &'static str {
+    if peak_ft >= thresholds.major_flood_stage_ft {
+        "major"
+    } else if peak_ft >= thresholds.moderate_flood_stage_ft {
+        "moderate"
+    } else {
+        "flood"
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Persistence
+// ---------------------------------------------------------------------------
+
+/// Insert a promoted event as ongoing (`event_end` NULL); returns its id.
+pub fn insert_event(client: &mut Client, event: &LiveFloodEvent, severity: &str) -> Result<i32, postgres::Error> {
+    let peak = rust_decimal::Decimal::from_f64_retain(event.peak_ft).unwrap_or_default();
+    let row = client.query_one(
+        "INSERT INTO nws.flood_events
+         (site_code, event_start, crest_time, peak_stage_ft, severity, data_source)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         RETURNING id",
+        &[&event.site_code, &event.start, &event.crest_time, &peak, &severity, &LIVE_EVENT_DATA_SOURCE]
+    )?;
+    Ok(row.get(0))
+}
+
+/// Write a new crest (and the severity it implies) to a persisted event.
+pub fn update_crest(client: &mut Client, event: &LiveFloodEvent, severity: &str) -> Result<(), postgres::Error> {
+    let peak = rust_decimal::Decimal::from_f64_retain(event.peak_ft).unwrap_or_default();
+    client.execute(
+        "UPDATE nws.flood_events
+         SET crest_time = $2, peak_stage_ft = $3, severity = $4
+         WHERE id = $1",
+        &[&event.persisted_id, &event.crest_time, &peak, &severity]
+    )?;
+    Ok(())
+}
+
+/// Close a persisted event.
+pub fn close_event(client: &mut Client, event: &LiveFloodEvent, end: DateTime<Utc>) -> Result<(), postgres::Error> {
+    client.execute(
+        "UPDATE nws.flood_events SET event_end = $2 WHERE id = $1",
+        &[&event.persisted_id, &end]
+    )?;
+    Ok(())
+}
+
+/// Live events still open in the database, to `resume` after a restart.
+/// `flood_stage_ft` comes from the current thresholds.
+pub fn load_open_events(
+    client: &mut Client,
+    thresholds: &HashMap<String, FloodThresholds>,
+) -> Result<Vec<LiveFloodEvent>, postgres::Error> {
+    let rows = client.query(
+        "SELECT id, site_code, event_start, COALESCE(crest_time, event_start), peak_stage_ft::float8
+         FROM nws.flood_events
+         WHERE event_end IS NULL AND data_source = $1",
+        &[&LIVE_EVENT_DATA_SOURCE]
+    )?;
+
+    Ok(rows.iter()
+        .filter_map(|row| {
+            let site_code: String = row.get(1);
+            let flood_stage_ft = thresholds.get(&site_code)?.flood_stage_ft;
+            let crest_time: DateTime<Utc> = row.get(3);
+            Some(LiveFloodEvent {
+                flood_stage_ft,
+                start: row.get(2),
+                last_above: crest_time,
+                crest_time,
+                peak_ft: row.get(4),
+                persisted_id: Some(row.get(0)),
+                site_code,
+            })
+        })
+        .collect())
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, TimeZone};
+
+    const FLOOD_FT: f64 = 18.0;
+
+    fn t(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2019, 6, 1, 0, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_single_reading_blip_is_never_persisted() {
+        let gate = FloodEventGate { min_duration_minutes: 60, min_peak_above_flood_ft: 0.0 };
+        let mut tracker = FloodEventTracker::default();
+
+        assert_eq!(tracker.observe("05567500", 18.3, t(0), FLOOD_FT, &gate), FloodEventUpdate::None);
+        assert_eq!(tracker.observe("05567500", 17.6, t(15), FLOOD_FT, &gate), FloodEventUpdate::None);
+        // Next crossing starts a fresh candidate rather than resuming the blip
+        assert_eq!(tracker.observe("05567500", 18.1, t(30), FLOOD_FT, &gate), FloodEventUpdate::None);
+        assert_eq!(tracker.open["05567500"].start, t(30));
+    }
+
+    #[test]
+    fn test_sustained_crossing_is_promoted_then_crests_and_ends() {
+        let gate = FloodEventGate { min_duration_minutes: 30, min_peak_above_flood_ft: 0.5 };
+        let mut tracker = FloodEventTracker::default();
+
+        tracker.observe("05567500", 18.2, t(0), FLOOD_FT, &gate);
+        // Long enough, but not high enough yet
+        assert_eq!(tracker.observe("05567500", 18.4, t(30), FLOOD_FT, &gate), FloodEventUpdate::None);
+
+        let promoted = match tracker.observe("05567500", 18.6, t(45), FLOOD_FT, &gate) {
+            FloodEventUpdate::Promote(event) => event,
+            other => panic!("expected promotion, got {:?}", other),
+        };
+        assert_eq!(promoted.start, t(0));
+        assert_eq!(promoted.peak_ft, 18.6);
+        tracker.mark_persisted("05567500", 7);
+
+        assert!(matches!(tracker.observe("05567500", 19.1, t(60), FLOOD_FT, &gate), FloodEventUpdate::NewCrest(_)));
+        assert_eq!(tracker.observe("05567500", 18.9, t(75), FLOOD_FT, &gate), FloodEventUpdate::None);
+
+        match tracker.observe("05567500", 17.8, t(90), FLOOD_FT, &gate) {
+            FloodEventUpdate::End(event, end) => {
+                assert_eq!(event.persisted_id, Some(7));
+                assert_eq!(event.crest_time, t(60));
+                assert_eq!(end, t(90));
+            }
+            other => panic!("expected end, got {:?}", other),
+        }
+    }
+}
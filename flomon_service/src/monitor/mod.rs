@@ -26,10 +26,17 @@
 /// - Performance (in-memory for hot path)
 /// - Auditability (DB tracks state changes over time)
 /// - Simplicity (no dual state files to keep in sync)
+///
+/// Submodules:
+/// - `flood_events` — promotes sustained flood-stage crossings to `nws.flood_events`.
+
+pub mod flood_events;
 
+use crate::clock::{self, SharedClock};
 use crate::model::GaugeReading;
 use chrono::{DateTime, Utc};
 use postgres::Client;
+use serde::Serialize;
 use std::collections::HashMap;
 
 // ---------------------------------------------------------------------------
@@ -66,6 +73,15 @@ impl StationStatus {
             _ => StationStatus::Unknown,
         }
     }
+
+    fn as_str(&self) -> &'static str {
+        match self {
+            StationStatus::Active => "active",
+            StationStatus::Degraded => "degraded",
+            StationStatus::Offline => "offline",
+            StationStatus::Unknown => "unknown",
+        }
+    }
 }
 
 /// In-memory cache of station states.
@@ -73,13 +89,20 @@ impl StationStatus {
 pub struct MonitoringCache {
     cache: HashMap<(String, String), StationCache>,
     last_refresh: DateTime<Utc>,
+    clock: SharedClock,
 }
 
 impl MonitoringCache {
     pub fn new() -> Self {
+        Self::with_clock(clock::system_clock())
+    }
+
+    /// Cache whose staleness checks read time from `clock`.
+    pub fn with_clock(clock: SharedClock) -> Self {
         Self {
             cache: HashMap::new(),
-            last_refresh: Utc::now(),
+            last_refresh: clock.now(),
+            clock,
         }
     }
 
@@ -117,7 +140,7 @@ impl MonitoringCache {
             self.cache.insert((site_code, parameter_code), cache_entry);
         }
 
-        self.last_refresh = Utc::now();
+        self.last_refresh = self.clock.now();
         Ok(())
     }
 
@@ -127,11 +150,16 @@ impl MonitoringCache {
     }
 
     /// Check if data is stale using cached threshold.
-    pub fn is_stale(&self, site_code: &str, parameter_code: &str, now: DateTime<Utc>) -> bool {
+    ///
+    /// A reading timestamped beyond `MAX_FUTURE_SKEW_MINUTES` ahead of now
+    /// is stale too: its source clock is wrong, so it can't vouch for the
+    /// station being alive.
+    pub fn is_stale(&self, site_code: &str, parameter_code: &str) -> bool {
         if let Some(cached) = self.get(site_code, parameter_code) {
             if let Some(reading_time) = cached.latest_reading_time {
-                let age_minutes = (now - reading_time).num_minutes();
-                return age_minutes > cached.staleness_threshold_minutes as i64;
+                let age_minutes = (self.clock.now() - reading_time).num_minutes();
+                return age_minutes > cached.staleness_threshold_minutes as i64
+                    || is_future_dated_age(age_minutes);
             }
         }
         true // Unknown stations are stale by default
@@ -219,7 +247,136 @@ pub fn get_station_health(
     Ok(results)
 }
 
-#[derive(Debug)]
+/// Set a station's status in `monitoring_state`, stamping `status_since`
+/// only when the status actually changes.
+pub fn update_station_status(
+    client: &mut Client,
+    site_code: &str,
+    status: &StationStatus,
+) -> Result<(), Box<dyn std::error::Error>> {
+    client.execute(
+        "UPDATE usgs_raw.monitoring_state
+         SET status = $2,
+             status_since = CASE WHEN status IS DISTINCT FROM $2 THEN NOW() ELSE status_since END,
+             updated_at = NOW()
+         WHERE site_code = $1",
+        &[&site_code, &status.as_str()],
+    )?;
+
+    Ok(())
+}
+
+// ---------------------------------------------------------------------------
+// Clock Skew
+// ---------------------------------------------------------------------------
+
+/// How far ahead of system time a reading may be stamped before we blame
+/// the source's clock. Covers ordinary NTP drift and rounding of
+/// observation times; anything beyond is a bad station clock.
+pub const MAX_FUTURE_SKEW_MINUTES: i64 = 5;
+
+/// True when an age (now minus reading time, in minutes) is negative
+/// beyond `MAX_FUTURE_SKEW_MINUTES`.
+pub fn is_future_dated_age(age_minutes: i64) -> bool {
+    age_minutes < -MAX_FUTURE_SKEW_MINUTES
+}
+
+/// True when `reading_time` is implausibly far ahead of `now`.
+pub fn is_future_dated(reading_time: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+    is_future_dated_age((now - reading_time).num_minutes())
+}
+
+/// Tally of future-dated records rejected from one batch, so the batch
+/// produces a single data-quality warning rather than one per record.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FutureDatedTally {
+    pub count: usize,
+    pub max_ahead_minutes: i64,
+}
+
+impl FutureDatedTally {
+    /// Record `reading_time` if it is future-dated; returns whether it was.
+    pub fn check(&mut self, reading_time: DateTime<Utc>, now: DateTime<Utc>) -> bool {
+        if !is_future_dated(reading_time, now) {
+            return false;
+        }
+        self.count += 1;
+        self.max_ahead_minutes = self.max_ahead_minutes.max((reading_time - now).num_minutes());
+        true
+    }
+
+    /// Warning text for the batch, or `None` if nothing was rejected.
+    pub fn warning(&self) -> Option<String> {
+        (self.count > 0).then(|| format!(
+            "Skipped {} reading(s) timestamped up to {} min in the future (source clock skew?)",
+            self.count, self.max_ahead_minutes
+        ))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Flatline Detection
+// ---------------------------------------------------------------------------
+
+/// Default number of identical consecutive readings tolerated before a
+/// sensor is considered stuck: 12 × 15-minute IV readings = 3 hours.
+pub const DEFAULT_FLATLINE_MIN_REPEATS: usize = 12;
+
+/// Returns true if the leading run of identical values in `values`
+/// (newest first) is longer than `min_repeats`.
+pub fn is_flatlined(values: &[f64], min_repeats: usize) -> bool {
+    let Some(&latest) = values.first() else {
+        return false;
+    };
+
+    let run = values.iter().take_while(|&&v| v == latest).count();
+    run > min_repeats
+}
+
+/// Detect a frozen sensor: more than `min_repeats` consecutive readings of
+/// exactly the same value.
+///
+/// Real rivers are never perfectly still at 0.01 ft resolution for hours;
+/// an unchanging value usually means an iced-over float or stuck encoder.
+/// Query failures are logged and treated as "not flatlined" — the reading
+/// itself has already been accepted, so we don't degrade on a hunch.
+pub fn detect_flatline(
+    site_code: &str,
+    parameter_code: &str,
+    client: &mut Client,
+    min_repeats: usize,
+) -> bool {
+    match query_flatline(site_code, parameter_code, client, min_repeats) {
+        Ok(flatlined) => flatlined,
+        Err(e) => {
+            eprintln!("Warning: flatline check failed for {} ({}): {}", site_code, parameter_code, e);
+            false
+        }
+    }
+}
+
+/// `detect_flatline` without the fallback: a query failure is returned, so
+/// callers that track flatline state can leave it unchanged.
+pub fn query_flatline(
+    site_code: &str,
+    parameter_code: &str,
+    client: &mut Client,
+    min_repeats: usize,
+) -> Result<bool, postgres::Error> {
+    let rows = client.query(
+        "SELECT value::float8
+         FROM usgs_raw.gauge_readings
+         WHERE site_code = $1 AND parameter_code = $2
+         ORDER BY reading_time DESC
+         LIMIT $3",
+        &[&site_code, &parameter_code, &(min_repeats as i64 + 1)],
+    )?;
+
+    let values: Vec<f64> = rows.iter().map(|row| row.get(0)).collect();
+    Ok(is_flatlined(&values, min_repeats))
+}
+
+#[derive(Debug, Serialize)]
 pub struct StationHealthRow {
     pub site_code: String,
     pub site_name: String,
@@ -275,50 +432,104 @@ pub struct StationHealthRow {
 mod tests {
     use super::*;
 
-    #[test]
-    fn test_cache_staleness_check() {
-        let mut cache = MonitoringCache::new();
+    use crate::clock::MockClock;
+    use chrono::{Duration, TimeZone};
+    use std::sync::Arc;
+
+    /// Cache holding one discharge reading taken at `reading_time`, with a
+    /// 60-minute staleness threshold.
+    fn cache_with_reading(clock: Arc<MockClock>, reading_time: DateTime<Utc>) -> MonitoringCache {
+        let mut cache = MonitoringCache::with_clock(clock);
         
-        // Simulate cached station with 60-minute threshold
         let station = StationCache {
             site_code: "05568500".to_string(),
             parameter_code: "00060".to_string(),
-            latest_reading_time: Some(Utc::now() - chrono::Duration::minutes(90)),
+            latest_reading_time: Some(reading_time),
             latest_reading_value: Some(42000.0),
             staleness_threshold_minutes: 60,
             status: StationStatus::Active,
-            last_poll_attempted: Some(Utc::now()),
+            last_poll_attempted: Some(reading_time),
         };
         
         cache.cache.insert(
             ("05568500".to_string(), "00060".to_string()),
             station,
         );
+        cache
+    }
+
+    #[test]
+    fn test_cache_staleness_check() {
+        let reading_time = Utc.with_ymd_and_hms(2019, 6, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(reading_time + Duration::minutes(90)));
+        let cache = cache_with_reading(clock, reading_time);
 
         // Should be stale (90 min > 60 min threshold)
-        assert!(cache.is_stale("05568500", "00060", Utc::now()));
+        assert!(cache.is_stale("05568500", "00060"));
     }
 
     #[test]
     fn test_cache_fresh_data() {
-        let mut cache = MonitoringCache::new();
-        
-        let station = StationCache {
-            site_code: "05568500".to_string(),
-            parameter_code: "00060".to_string(),
-            latest_reading_time: Some(Utc::now() - chrono::Duration::minutes(10)),
-            latest_reading_value: Some(42000.0),
-            staleness_threshold_minutes: 60,
-            status: StationStatus::Active,
-            last_poll_attempted: Some(Utc::now()),
-        };
-        
-        cache.cache.insert(
-            ("05568500".to_string(), "00060".to_string()),
-            station,
-        );
+        let reading_time = Utc.with_ymd_and_hms(2019, 6, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(reading_time + Duration::minutes(10)));
+        let cache = cache_with_reading(clock, reading_time);
 
         // Should NOT be stale (10 min < 60 min threshold)
-        assert!(!cache.is_stale("05568500", "00060", Utc::now()));
+        assert!(!cache.is_stale("05568500", "00060"));
+    }
+
+    #[test]
+    fn test_cache_goes_stale_as_clock_advances() {
+        let reading_time = Utc.with_ymd_and_hms(2019, 6, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(reading_time));
+        let cache = cache_with_reading(clock.clone(), reading_time);
+
+        clock.advance(Duration::minutes(60));
+        assert!(!cache.is_stale("05568500", "00060"), "exactly at threshold is still fresh");
+
+        clock.advance(Duration::minutes(1));
+        assert!(cache.is_stale("05568500", "00060"));
+    }
+
+    #[test]
+    fn test_future_dated_reading_is_stale_not_fresh() {
+        let now = Utc.with_ymd_and_hms(2019, 6, 1, 12, 0, 0).unwrap();
+        let clock = Arc::new(MockClock::new(now));
+
+        let slightly_ahead = cache_with_reading(clock.clone(), now + Duration::minutes(MAX_FUTURE_SKEW_MINUTES));
+        assert!(!slightly_ahead.is_stale("05568500", "00060"), "drift within tolerance is fresh");
+
+        let skewed = cache_with_reading(clock, now + Duration::hours(3));
+        assert!(skewed.is_stale("05568500", "00060"));
+    }
+
+    #[test]
+    fn test_future_dated_tally() {
+        let now = Utc.with_ymd_and_hms(2019, 6, 1, 12, 0, 0).unwrap();
+        let mut tally = FutureDatedTally::default();
+
+        assert!(!tally.check(now - Duration::minutes(15), now));
+        assert!(!tally.check(now + Duration::minutes(2), now));
+        assert_eq!(tally.warning(), None);
+
+        assert!(tally.check(now + Duration::minutes(30), now));
+        assert!(tally.check(now + Duration::minutes(90), now));
+        assert_eq!(tally, FutureDatedTally { count: 2, max_ahead_minutes: 90 });
+        assert!(tally.warning().unwrap().contains("90 min"));
+    }
+
+    #[test]
+    fn test_flatline_requires_more_than_min_repeats() {
+        // Newest first: four identical readings
+        let values = [14.27, 14.27, 14.27, 14.27, 14.25];
+
+        assert!(is_flatlined(&values, 3));
+        assert!(!is_flatlined(&values, 4));
+    }
+
+    #[test]
+    fn test_flatline_ignores_changing_and_empty_series() {
+        assert!(!is_flatlined(&[14.27, 14.28, 14.27, 14.27], 2));
+        assert!(!is_flatlined(&[], 0));
     }
 }
@@ -0,0 +1,450 @@
+//! Unit systems for API output.
+//!
+//! Everything is stored and computed in the native units of its source
+//! (feet, ft³/s, inches, °F). When a client asks for `?units=metric`, the
+//! endpoint converts the serialized JSON just before it is written:
+//!
+//! - keys carrying a unit suffix are converted and relabeled
+//!   (`flood_stage_ft` → `flood_stage_m`, `precip_24h_in` → `precip_24h_mm`)
+//! - `value`/`current_value` fields are converted according to their sibling
+//!   `unit`/`current_unit` string, which is rewritten to the metric label.
+//!   A `unit` also applies to `value` fields in nested objects (e.g. the
+//!   `before`/`after` points of an interpolated reading).
+//! - `min`/`mean`/`max` statistics (hourly aggregates) are converted by
+//!   their sibling `unit` just like `value`.
+//!
+//! `round_for_display` then trims every unit-bearing number found the same
+//! way to the precision its unit is meaningful to (`UNIT_PRECISION`), so
+//! Decimal→f64 noise like `18.420000000000002` never reaches a client.
+
+use serde_json::{Map, Value};
+
+/// Unit system requested by the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitSystem {
+    Imperial,
+    Metric,
+}
+
+impl UnitSystem {
+    /// Parse the `units` query parameter. Missing means imperial.
+    pub fn from_query(param: Option<&str>) -> Result<Self, String> {
+        match param {
+            None | Some("imperial") => Ok(UnitSystem::Imperial),
+            Some("metric") => Ok(UnitSystem::Metric),
+            Some(other) => Err(format!(
+                "Invalid units '{}'. Use 'imperial' or 'metric'.",
+                other
+            )),
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UnitSystem::Imperial => "imperial",
+            UnitSystem::Metric => "metric",
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Conversions
+// ---------------------------------------------------------------------------
+
+/// A unit conversion function (native value → metric value).
+pub type Conversion = fn(f64) -> f64;
+
+pub fn feet_to_meters(ft: f64) -> f64 {
+    ft * 0.3048
+}
+
+/// Cubic feet per second to cubic meters per second.
+pub fn cfs_to_cms(cfs: f64) -> f64 {
+    cfs * 0.028_316_846_592
+}
+
+pub fn inches_to_mm(inches: f64) -> f64 {
+    inches * 25.4
+}
+
+pub fn fahrenheit_to_celsius(f: f64) -> f64 {
+    (f - 32.0) * 5.0 / 9.0
+}
+
+/// Metric conversion for a native unit label, returning the metric label.
+///
+/// Labels are the ones our sources emit: USGS (`ft`, `ft3/s`), CWMS
+/// (`ft`, `cfs`, `kcfs`), and ASOS (`in`, `F`), plus the stage rates we
+/// derive (`ft/hr`, `ft/day`). Unknown labels are left unconverted.
+pub fn metric_conversion(unit: &str) -> Option<(Conversion, &'static str)> {
+    match unit {
+        "ft" => Some((feet_to_meters, "m")),
+        "ft/hr" => Some((feet_to_meters, "m/hr")),
+        "ft/day" => Some((feet_to_meters, "m/day")),
+        "ft3/s" | "cfs" => Some((cfs_to_cms, "m3/s")),
+        "kcfs" => Some((|v| cfs_to_cms(v * 1000.0), "m3/s")),
+        "in" => Some((inches_to_mm, "mm")),
+        "F" | "degF" => Some((fahrenheit_to_celsius, "C")),
+        _ => None,
+    }
+}
+
+/// Fields whose number is in the unit named by a sibling `unit` field.
+const UNIT_VALUE_KEYS: &[&str] = &["value", "min", "mean", "max"];
+
+/// Field-name suffixes that encode a unit, with their metric replacement.
+/// Rates and datum-referenced elevations keep their qualifier
+/// (`rate_ft_per_hr` → `rate_m_per_hr`, `gage_datum_ft_ngvd29` →
+/// `gage_datum_m_ngvd29`).
+const SUFFIX_CONVERSIONS: &[(&str, &str, Conversion)] = &[
+    ("_ft_per_hr", "_m_per_hr", feet_to_meters),
+    ("_ft_per_hour", "_m_per_hour", feet_to_meters),
+    ("_ft_per_day", "_m_per_day", feet_to_meters),
+    ("_ft_ngvd29", "_m_ngvd29", feet_to_meters),
+    ("_ft", "_m", feet_to_meters),
+    ("_cfs", "_cms", cfs_to_cms),
+    ("_in", "_mm", inches_to_mm),
+    ("_f", "_c", fahrenheit_to_celsius),
+];
+
+// ---------------------------------------------------------------------------
+// Display precision
+// ---------------------------------------------------------------------------
+
+/// Decimal places each unit is reported to, in either system. Stage to
+/// 0.01 ft (about 3 mm), discharge to whole cfs, precipitation to 0.01 in.
+/// Units not listed are left as computed.
+pub const UNIT_PRECISION: &[(&str, u32)] = &[
+    ("ft", 2),
+    ("m", 3),
+    ("ft/hr", 3),
+    ("m/hr", 4),
+    ("ft/day", 2),
+    ("m/day", 3),
+    ("ft3/s", 0),
+    ("cfs", 0),
+    ("kcfs", 2),
+    ("m3/s", 2),
+    ("in", 2),
+    ("mm", 1),
+    ("F", 1),
+    ("degF", 1),
+    ("C", 1),
+];
+
+/// Field-name suffixes and the unit label they stand for, in both systems.
+const SUFFIX_UNITS: &[(&str, &str)] = &[
+    ("_ft_per_hr", "ft/hr"),
+    ("_ft_per_hour", "ft/hr"),
+    ("_ft_per_day", "ft/day"),
+    ("_m_per_hr", "m/hr"),
+    ("_m_per_hour", "m/hr"),
+    ("_m_per_day", "m/day"),
+    ("_ft_ngvd29", "ft"),
+    ("_m_ngvd29", "m"),
+    ("_ft", "ft"),
+    ("_m", "m"),
+    ("_cfs", "cfs"),
+    ("_cms", "m3/s"),
+    ("_in", "in"),
+    ("_mm", "mm"),
+    ("_f", "F"),
+    ("_c", "C"),
+];
+
+/// Decimal places `unit` is reported to, if it has a display precision.
+pub fn display_precision(unit: &str) -> Option<u32> {
+    UNIT_PRECISION.iter()
+        .find(|(label, _)| *label == unit)
+        .map(|&(_, places)| places)
+}
+
+/// `value` rounded to `places` decimals; whole numbers when `places` is 0,
+/// so discharge serializes as `42300` rather than `42300.0`.
+pub fn round_to(value: f64, places: u32) -> Value {
+    if places == 0 {
+        return Value::from(value.round() as i64);
+    }
+    let scale = 10f64.powi(places as i32);
+    Value::from((value * scale).round() / scale)
+}
+
+/// Round every unit-bearing number in `json` (located the same way as for
+/// metric conversion: sibling `unit`/`current_unit` labels and unit-suffixed
+/// keys) to its unit's display precision. Call after `localize`.
+pub fn round_for_display(json: &mut Value) {
+    round_values(json, None);
+}
+
+fn round_values(json: &mut Value, inherited_unit: Option<&str>) {
+    match json {
+        Value::Object(map) => {
+            let unit = map.get("unit")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| inherited_unit.map(str::to_string));
+
+            for key in UNIT_VALUE_KEYS {
+                round_field(map, key, unit.as_deref());
+            }
+            let current_unit = map.get("current_unit").and_then(Value::as_str).map(str::to_string);
+            round_field(map, "current_value", current_unit.as_deref());
+
+            let suffixed: Vec<(String, &str)> = map.keys()
+                .filter_map(|key| {
+                    SUFFIX_UNITS.iter()
+                        .find(|(suffix, _)| key.ends_with(suffix))
+                        .map(|&(_, label)| (key.clone(), label))
+                })
+                .collect();
+            for (key, label) in suffixed {
+                round_field(map, &key, Some(label));
+            }
+
+            for (key, child) in map.iter_mut() {
+                if key != "value" {
+                    round_values(child, unit.as_deref());
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                round_values(item, inherited_unit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Round `map[key]` to `unit`'s display precision, if both are known.
+fn round_field(map: &mut Map<String, Value>, key: &str, unit: Option<&str>) {
+    let Some(places) = unit.and_then(display_precision) else {
+        return;
+    };
+    if let Some(v) = map.get(key).and_then(Value::as_f64) {
+        map.insert(key.to_string(), round_to(v, places));
+    }
+}
+
+// ---------------------------------------------------------------------------
+// JSON Localization
+// ---------------------------------------------------------------------------
+
+/// Convert a serialized response to `system` and record the system used in
+/// a top-level `unit_system` field.
+pub fn localize(json: &mut Value, system: UnitSystem) {
+    if system == UnitSystem::Metric {
+        convert_to_metric(json, None);
+    }
+
+    if let Value::Object(map) = json {
+        map.insert("unit_system".to_string(), Value::from(system.as_str()));
+    }
+}
+
+/// Recursively convert `json` in place. `inherited_unit` is the nearest
+/// enclosing `unit` label, applied to bare `value` fields.
+fn convert_to_metric(json: &mut Value, inherited_unit: Option<&str>) {
+    match json {
+        Value::Object(map) => {
+            let unit = map.get("unit")
+                .and_then(Value::as_str)
+                .map(str::to_string)
+                .or_else(|| inherited_unit.map(str::to_string));
+
+            for key in UNIT_VALUE_KEYS {
+                convert_unit_pair(map, key, "unit", unit.as_deref());
+            }
+            let current_unit = map.get("current_unit").and_then(Value::as_str).map(str::to_string);
+            convert_unit_pair(map, "current_value", "current_unit", current_unit.as_deref());
+            relabel_suffixed_keys(map);
+
+            for (key, child) in map.iter_mut() {
+                if key != "value" {
+                    convert_to_metric(child, unit.as_deref());
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                convert_to_metric(item, inherited_unit);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Convert `map[value_key]` from `unit`, rewriting `map[unit_key]` if present.
+fn convert_unit_pair(map: &mut Map<String, Value>, value_key: &str, unit_key: &str, unit: Option<&str>) {
+    let Some((convert, metric_label)) = unit.and_then(metric_conversion) else {
+        return;
+    };
+
+    if let Some(v) = map.get(value_key).and_then(Value::as_f64) {
+        map.insert(value_key.to_string(), Value::from(convert(v)));
+    }
+    if map.get(unit_key).is_some_and(Value::is_string) {
+        map.insert(unit_key.to_string(), Value::from(metric_label));
+    }
+}
+
+/// `key` as `localize` leaves it: `flood_stage_ft` reads `flood_stage_m`
+/// under metric; unsuffixed keys and imperial keys are unchanged.
+pub fn localized_key(key: &str, system: UnitSystem) -> String {
+    if system == UnitSystem::Metric {
+        for (suffix, metric_suffix, _) in SUFFIX_CONVERSIONS {
+            if let Some(stem) = key.strip_suffix(suffix) {
+                return format!("{}{}", stem, metric_suffix);
+            }
+        }
+    }
+    key.to_string()
+}
+
+/// Convert and rename keys such as `flood_stage_ft` → `flood_stage_m`.
+fn relabel_suffixed_keys(map: &mut Map<String, Value>) {
+    let keys: Vec<String> = map.keys().cloned().collect();
+
+    for key in keys {
+        for (suffix, metric_suffix, convert) in SUFFIX_CONVERSIONS {
+            if let Some(stem) = key.strip_suffix(suffix) {
+                let value = map.remove(&key).unwrap_or(Value::Null);
+                let converted = match value.as_f64() {
+                    Some(v) => Value::from(convert(v)),
+                    None => value,
+                };
+                map.insert(format!("{}{}", stem, metric_suffix), converted);
+                break;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn approx(a: &Value, b: f64) -> bool {
+        (a.as_f64().unwrap() - b).abs() < 1e-6
+    }
+
+    #[test]
+    fn test_pure_conversions() {
+        assert!((feet_to_meters(10.0) - 3.048).abs() < 1e-9);
+        assert!((cfs_to_cms(1000.0) - 28.316846592).abs() < 1e-9);
+        assert!((inches_to_mm(1.0) - 25.4).abs() < 1e-9);
+        assert!((fahrenheit_to_celsius(212.0) - 100.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_units_param_parsing() {
+        assert_eq!(UnitSystem::from_query(None), Ok(UnitSystem::Imperial));
+        assert_eq!(UnitSystem::from_query(Some("metric")), Ok(UnitSystem::Metric));
+        assert!(UnitSystem::from_query(Some("si")).is_err());
+    }
+
+    #[test]
+    fn test_imperial_only_labels_system() {
+        let mut body = json!({"flood_stage_ft": 16.0});
+        localize(&mut body, UnitSystem::Imperial);
+
+        assert_eq!(body, json!({"flood_stage_ft": 16.0, "unit_system": "imperial"}));
+    }
+
+    #[test]
+    fn test_metric_relabels_suffixed_fields() {
+        let mut body = json!({
+            "sensors": [{"flood_stage_ft": 10.0, "action_stage_ft": null, "precip_24h_in": 2.0}]
+        });
+        localize(&mut body, UnitSystem::Metric);
+
+        let sensor = &body["sensors"][0];
+        assert!(approx(&sensor["flood_stage_m"], 3.048));
+        assert!(sensor["action_stage_m"].is_null());
+        assert!(approx(&sensor["precip_24h_mm"], 50.8));
+        assert!(sensor.get("flood_stage_ft").is_none());
+        assert_eq!(body["unit_system"], "metric");
+    }
+
+    #[test]
+    fn test_metric_converts_value_by_unit() {
+        let mut body = json!({
+            "value": 1000.0,
+            "unit": "ft3/s",
+            "before": {"value": 2000.0},
+            "readings": [{"current_value": 10.0, "current_unit": "ft"}]
+        });
+        localize(&mut body, UnitSystem::Metric);
+
+        assert!(approx(&body["value"], 28.316846592));
+        assert_eq!(body["unit"], "m3/s");
+        assert!(approx(&body["before"]["value"], 56.633693184));
+        assert!(approx(&body["readings"][0]["current_value"], 3.048));
+        assert_eq!(body["readings"][0]["current_unit"], "m");
+    }
+
+    #[test]
+    fn test_metric_converts_hourly_stats_by_unit() {
+        let mut body = json!({
+            "records": [{"min": 10.0, "mean": 11.0, "max": 12.0, "count": 4, "unit": "ft"}]
+        });
+        localize(&mut body, UnitSystem::Metric);
+
+        let record = &body["records"][0];
+        assert!(approx(&record["min"], 3.048));
+        assert!(approx(&record["max"], 3.6576));
+        assert_eq!(record["count"], 4, "counts are not a unit quantity");
+        assert_eq!(record["unit"], "m");
+    }
+
+    #[test]
+    fn test_metric_converts_rates_and_datum_elevations() {
+        let mut body = json!({
+            "current_rate_ft_per_hr": 0.5,
+            "uncertainty_growth_ft_per_hour": 0.1,
+            "average_rise_rate_ft_per_day": 2.0,
+            "peoria_elevation_ft_ngvd29": 450.0,
+            "components": [{"value": 0.25, "unit": "ft/hr"}]
+        });
+        localize(&mut body, UnitSystem::Metric);
+
+        assert!(approx(&body["current_rate_m_per_hr"], 0.1524));
+        assert!(approx(&body["uncertainty_growth_m_per_hour"], 0.03048));
+        assert!(approx(&body["average_rise_rate_m_per_day"], 0.6096));
+        assert!(approx(&body["peoria_elevation_m_ngvd29"], 137.16));
+        assert!(body.get("current_rate_ft_per_hr").is_none() && body.get("peoria_elevation_ft_ngvd29").is_none());
+        assert!(approx(&body["components"][0]["value"], 0.0762));
+        assert_eq!(body["components"][0]["unit"], "m/hr");
+        assert_eq!(localized_key("max_rate_ft_per_hr", UnitSystem::Metric), "max_rate_m_per_hr");
+    }
+
+    #[test]
+    fn test_round_for_display_by_unit() {
+        let mut body = json!({
+            "current_stage_ft": 18.42 + 1e-12,
+            "discharge_cfs": 42300.4,
+            "precip_24h_in": 0.123,
+            "temp_f": 71.06,
+            "rate_ft_per_hour": 0.123456,
+            "readings": [{"value": 42299.7, "unit": "ft3/s"}, {"value": 1.23456, "unit": "ppm"}],
+            "records": [{"min": 3.04812, "max": 3.6576, "count": 4, "unit": "m"}]
+        });
+        round_for_display(&mut body);
+
+        assert_eq!(body["current_stage_ft"], json!(18.42));
+        assert_eq!(body["discharge_cfs"], json!(42300));
+        assert_eq!(body["precip_24h_in"], json!(0.12));
+        assert_eq!(body["temp_f"], json!(71.1));
+        assert_eq!(body["rate_ft_per_hour"], json!(0.123));
+        assert_eq!(body["readings"][0]["value"], json!(42300));
+        assert_eq!(body["readings"][1]["value"], json!(1.23456), "unknown unit, left alone");
+        assert_eq!(body["records"][0]["min"], json!(3.048));
+        assert_eq!(body["records"][0]["count"], json!(4));
+        assert_eq!(serde_json::to_string(&body["current_stage_ft"]).unwrap(), "18.42");
+    }
+}
@@ -9,6 +9,7 @@
 /// Location metadata is loaded from `usace_stations.toml`, allowing updates to timeseries
 /// IDs, relevance notes, and monitoring priorities without recompilation.
 
+use crate::ingest::cwms::CwmsLocationMetadata;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::fs;
@@ -19,7 +20,7 @@ use std::fs;
 
 /// Root configuration from usace_stations.toml
 #[derive(Debug, Deserialize)]
-struct UsaceConfig {
+pub(crate) struct UsaceConfig {
     #[serde(default)]
     usace_stations: Vec<UsaceStationConfig>,
 }
@@ -39,6 +40,7 @@ struct UsaceStationConfig {
     data_types: Vec<String>,
     relevance: String,
     flood_note: Option<String>,
+    preferred_version: Option<String>,
 }
 
 // ---------------------------------------------------------------------------
@@ -63,7 +65,9 @@ pub struct UsaceLocation {
     /// River mile (Illinois River or Mississippi River)
     pub river_mile: Option<f64>,
     
-    /// Pool elevation target (NGVD29 datum, for lock/dam pools)
+    /// Pool elevation target (NGVD29 datum, for lock/dam pools). CWMS
+    /// doesn't publish pool targets, so this stays configured; its datum is
+    /// checked against the CWMS one (`pool_target_datum_mismatch`).
     pub pool_target_ft: Option<f64>,
     
     /// Data types available (pool_elevation, tailwater_elevation, stage, discharge, etc.)
@@ -78,8 +82,32 @@ pub struct UsaceLocation {
     /// Monitoring priority (derived from relevance)
     pub priority: MonitoringPriority,
     
+    /// CWMS version suffix to prefer when discovery finds several
+    /// (e.g. "CBT-REV" over "CBT-RAW")
+    pub preferred_version: Option<String>,
+    
     /// Discovered timeseries IDs (populated at runtime from CWMS catalog)
     pub discovered_timeseries: Option<DiscoveredTimeseries>,
+    
+    /// Datum and elevation from CWMS `/locations` (populated at runtime)
+    pub metadata: Option<CwmsLocationMetadata>,
+}
+
+/// Datum `pool_target_ft` (from `pool_elevation_target_ft_ngvd29`) is in
+pub const POOL_TARGET_DATUM: &str = "NGVD29";
+
+impl UsaceLocation {
+    /// Vertical datum of this location's elevations as reported by CWMS
+    pub fn vertical_datum(&self) -> Option<&str> {
+        self.metadata.as_ref()?.vertical_datum.as_deref()
+    }
+    
+    /// True when CWMS reports a datum other than the one `pool_target_ft`
+    /// is configured in, so comparing the two would be off by the datum shift
+    pub fn pool_target_datum_mismatch(&self) -> bool {
+        self.pool_target_ft.is_some()
+            && self.vertical_datum().is_some_and(|d| !d.eq_ignore_ascii_case(POOL_TARGET_DATUM))
+    }
 }
 
 /// Timeseries IDs discovered from CWMS catalog at runtime
@@ -91,6 +119,58 @@ pub struct DiscoveredTimeseries {
     pub discharge: Option<String>,
 }
 
+impl DiscoveredTimeseries {
+    /// (data type, timeseries ID) for each discovered timeseries
+    fn entries(&self) -> impl Iterator<Item = (&'static str, &str)> {
+        [
+            ("pool_elevation", &self.pool_elevation),
+            ("tailwater_elevation", &self.tailwater_elevation),
+            ("stage", &self.stage),
+            ("discharge", &self.discharge),
+        ]
+        .into_iter()
+        .filter_map(|(data_type, ts_id)| ts_id.as_deref().map(|id| (data_type, id)))
+    }
+    
+    /// Check every discovered ID against the data type it was discovered for
+    pub fn validate(&self) -> Result<(), String> {
+        self.entries().try_for_each(|(data_type, ts_id)| validate_timeseries_parameter(data_type, ts_id))
+    }
+}
+
+/// CWMS base parameter a data type must be stored from
+pub fn expected_parameter(data_type: &str) -> Option<&'static str> {
+    match data_type {
+        "pool_elevation" | "tailwater_elevation" => Some("Elev"),
+        "stage" => Some("Stage"),
+        "discharge" => Some("Flow"),
+        _ => None,
+    }
+}
+
+/// Confirm `timeseries_id`'s parameter segment is the one `data_type`
+/// calls for. Readings are stored under that segment as `parameter_id`,
+/// so a `.Stage.` series picked for a pool elevation would otherwise be
+/// monitored as if it were an elevation.
+pub fn validate_timeseries_parameter(data_type: &str, timeseries_id: &str) -> Result<(), String> {
+    use crate::ingest::cwms::timeseries_parameter;
+    
+    let Some(expected) = expected_parameter(data_type) else {
+        return Ok(());
+    };
+    match timeseries_parameter(timeseries_id) {
+        Some(found) if found.eq_ignore_ascii_case(expected) => Ok(()),
+        Some(found) => Err(format!(
+            "Discovered {} timeseries {} has parameter '{}', expected '{}'",
+            data_type, timeseries_id, found, expected
+        )),
+        None => Err(format!(
+            "Discovered {} timeseries {} has no parameter segment, expected '{}'",
+            data_type, timeseries_id, expected
+        )),
+    }
+}
+
 /// Monitoring priority for polling frequency
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MonitoringPriority {
@@ -141,7 +221,9 @@ pub fn load_locations() -> Result<Vec<UsaceLocation>, String> {
                 relevance: station.relevance,
                 flood_notes: station.flood_note,
                 priority,
+                preferred_version: station.preferred_version,
                 discovered_timeseries: None, // Will be populated by discover_timeseries_ids()
+                metadata: None, // Will be populated by update_with_location_metadata()
             }
         })
         .collect();
@@ -207,7 +289,8 @@ pub fn locations_by_priority(priority: MonitoringPriority) -> Result<Vec<UsaceLo
 /// The TOML file contains provisional timeseries IDs based on documented patterns,
 /// but the exact version suffix (CBT-RAW vs lrgs-rev vs Ccp-Rev) varies by office
 /// and data stream. This function queries the CWMS catalog endpoint to discover
-/// what timeseries are actually available.
+/// what timeseries are actually available. When several versions match, the
+/// location's `preferred_version` wins if present.
 ///
 /// # Example
 /// ```no_run
@@ -237,7 +320,8 @@ pub fn discover_timeseries_ids(
         discovered.pool_elevation = cwms::discover_pool_elevation(
             client,
             &location.office,
-            &location.cwms_location
+            &location.cwms_location,
+            location.preferred_version.as_deref()
         ).map_err(|e| format!("Failed to discover pool elevation: {}", e))?;
         
         if let Some(ref ts_id) = discovered.pool_elevation {
@@ -250,7 +334,8 @@ pub fn discover_timeseries_ids(
         discovered.tailwater_elevation = cwms::discover_tailwater_elevation(
             client,
             &location.office,
-            &location.cwms_location
+            &location.cwms_location,
+            location.preferred_version.as_deref()
         ).map_err(|e| format!("Failed to discover tailwater elevation: {}", e))?;
         
         if let Some(ref ts_id) = discovered.tailwater_elevation {
@@ -263,7 +348,8 @@ pub fn discover_timeseries_ids(
         discovered.stage = cwms::discover_stage(
             client,
             &location.office,
-            &location.cwms_location
+            &location.cwms_location,
+            location.preferred_version.as_deref()
         ).map_err(|e| format!("Failed to discover stage: {}", e))?;
         
         if let Some(ref ts_id) = discovered.stage {
@@ -271,6 +357,20 @@ pub fn discover_timeseries_ids(
         }
     }
     
+    // Discover discharge if needed
+    if data_types.contains(&"discharge".to_string()) {
+        discovered.discharge = cwms::discover_discharge(
+            client,
+            &location.office,
+            &location.cwms_location,
+            location.preferred_version.as_deref()
+        ).map_err(|e| format!("Failed to discover discharge: {}", e))?;
+        
+        if let Some(ref ts_id) = discovered.discharge {
+            println!("      Discovered discharge: {}", ts_id);
+        }
+    }
+    
     Ok(discovered)
 }
 
@@ -289,10 +389,26 @@ pub fn update_with_discovered_timeseries(
         return Err(format!("No timeseries found for location: {}", location.name));
     }
     
+    discovered.validate()
+        .map_err(|e| format!("{} ({})", e, location.name))?;
+    
     location.discovered_timeseries = Some(discovered);
     Ok(())
 }
 
+/// Fetch a location's datum and elevation from CWMS and attach it
+pub fn update_with_location_metadata(
+    location: &mut UsaceLocation,
+    client: &reqwest::blocking::Client,
+) -> Result<(), String> {
+    use crate::ingest::cwms;
+    
+    let metadata = cwms::fetch_location_metadata(client, &location.cwms_location, &location.office)
+        .map_err(|e| format!("Failed to fetch location metadata: {}", e))?;
+    location.metadata = Some(metadata);
+    Ok(())
+}
+
 // ---------------------------------------------------------------------------
 // CWMS Timeseries ID Construction
 // ---------------------------------------------------------------------------
@@ -375,6 +491,52 @@ mod tests {
         assert_eq!(determine_priority("EXTENDED lead time"), MonitoringPriority::Medium);
     }
     
+    #[test]
+    fn test_cwms_datum_checks_pool_target() {
+        let mut location = load_locations().expect("Failed to load locations")
+            .into_iter()
+            .find(|loc| loc.pool_target_ft.is_some())
+            .expect("No pool location configured");
+        assert!(!location.pool_target_datum_mismatch());
+        
+        location.metadata = Some(CwmsLocationMetadata {
+            location_id: location.cwms_location.clone(),
+            office: location.office.clone(),
+            vertical_datum: Some("NAVD88".to_string()),
+            elevation_ft: Some(430.0),
+            latitude: None,
+            longitude: None,
+        });
+        assert_eq!(location.vertical_datum(), Some("NAVD88"));
+        assert!(location.pool_target_datum_mismatch());
+    }
+    
+    #[test]
+    fn test_discovered_parameter_matches_data_type() {
+        let discovered = DiscoveredTimeseries {
+            pool_elevation: Some("IL07.Elev.Inst.~1Hour.0.CBT-RAW".to_string()),
+            tailwater_elevation: Some("IL07.Elev-Tailwater.Inst.~1Hour.0.CBT-RAW".to_string()),
+            stage: Some("Grafton.Stage.Inst.15Minutes.0.Ccp-Rev".to_string()),
+            discharge: None,
+        };
+        assert!(discovered.validate().is_ok());
+    }
+    
+    #[test]
+    fn test_discovered_parameter_mismatch_rejected() {
+        let discovered = DiscoveredTimeseries {
+            pool_elevation: Some("Peoria-Pool.Stage.Inst.~1Hour.0.CBT-RAW".to_string()),
+            tailwater_elevation: None,
+            stage: None,
+            discharge: None,
+        };
+        let err = discovered.validate().unwrap_err();
+        assert!(err.contains("pool_elevation"), "{}", err);
+        assert!(err.contains("'Stage', expected 'Elev'"), "{}", err);
+        
+        assert!(validate_timeseries_parameter("stage", "Grafton").is_err());
+    }
+    
     #[test]
     fn test_timeseries_id_construction() {
         let id = build_pool_elev_id("Peoria-Pool");
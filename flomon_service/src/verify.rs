@@ -296,8 +296,9 @@ pub fn verify_asos_station(
     };
 
     // Test: Fetch last 4 hours of data
-    match crate::ingest::iem::fetch_recent_precip(client, station_id, 4) {
-        Ok(observations) => {
+    match crate::ingest::iem::fetch_recent_precip(client, station_id, 4, crate::ingest::http::DEFAULT_HTTP_MAX_ATTEMPTS) {
+        Ok(response) => {
+            let observations = response.into_observations();
             result.api_responsive = true;
             result.sample_data_count = observations.len();
 
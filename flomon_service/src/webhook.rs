@@ -0,0 +1,350 @@
+//! Optional push of each poll cycle's summary to an integrator's URL.
+//!
+//! Alerts only go out when something crosses a threshold; some downstream
+//! systems want every cycle (what was polled, what failed, how much was
+//! new) without scraping `/metrics`. With `POLL_WEBHOOK_URL` set, the
+//! daemon hands each cycle's `PollSummary` to a `PollWebhook`, which
+//! collects `batch_size` of them and POSTs the batch as a JSON array.
+//!
+//! Batches are POSTed from a background thread, so a slow or dead endpoint
+//! never holds up the poll loop. A batch that still fails after
+//! `max_retries` retries (with doubling backoff) is appended to a JSON-lines
+//! dead-letter file rather than retried forever, so it can't grow memory
+//! without bound either.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+
+/// Default number of cycle summaries sent per POST.
+pub const DEFAULT_POLL_WEBHOOK_BATCH_SIZE: usize = 1;
+
+/// Default retries after a failed POST before the batch is dead-lettered.
+pub const DEFAULT_POLL_WEBHOOK_MAX_RETRIES: u32 = 3;
+
+/// Default dead-letter file for batches that could not be delivered.
+pub const DEFAULT_POLL_WEBHOOK_DEAD_LETTER_PATH: &str = "poll_webhook_dead_letter.jsonl";
+
+/// HTTP timeout for each webhook POST.
+const WEBHOOK_TIMEOUT_SECS: u64 = 10;
+
+/// Wait before the first retry; doubled for each one after.
+const WEBHOOK_INITIAL_BACKOFF: std::time::Duration = std::time::Duration::from_secs(2);
+
+/// Stations polled, failed, and new readings for one source in a cycle.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct SourceSummary {
+    pub polled: usize,
+    pub failed: usize,
+    pub inserted: usize,
+}
+
+/// Outcome of one `poll_all_stations` cycle.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PollSummary {
+    pub timestamp: DateTime<Utc>,
+    /// Keyed by source ("USGS", "CWMS", "ASOS")
+    pub sources: BTreeMap<String, SourceSummary>,
+    pub total_inserted: usize,
+    /// Result keys (e.g. "USGS:05567500") whose poll failed, sorted
+    pub failures: Vec<String>,
+}
+
+impl PollSummary {
+    /// Summarise `poll_all_stations` results (keyed "SOURCE:id") given the
+    /// keys that failed.
+    pub fn from_results(
+        results: &HashMap<String, usize>,
+        failures: &[String],
+        timestamp: DateTime<Utc>,
+    ) -> Self {
+        let mut sources: BTreeMap<String, SourceSummary> = BTreeMap::new();
+        for (key, &inserted) in results {
+            let source = key.split(':').next().unwrap_or(key).to_string();
+            let summary = sources.entry(source).or_default();
+            summary.polled += 1;
+            summary.inserted += inserted;
+            if failures.contains(key) {
+                summary.failed += 1;
+            }
+        }
+
+        let mut failures = failures.to_vec();
+        failures.sort();
+
+        PollSummary {
+            timestamp,
+            total_inserted: results.values().sum(),
+            sources,
+            failures,
+        }
+    }
+}
+
+/// POST `payload` with `send`, retrying up to `max_retries` times and
+/// doubling `backoff` between attempts. Returns the last error if every
+/// attempt fails.
+pub fn deliver_with_retry<F>(
+    payload: &str,
+    max_retries: u32,
+    backoff: std::time::Duration,
+    mut send: F,
+) -> Result<(), String>
+where
+    F: FnMut(&str) -> Result<(), String>,
+{
+    let mut wait = backoff;
+    let mut attempt = 0;
+    loop {
+        match send(payload) {
+            Ok(()) => return Ok(()),
+            Err(e) if attempt >= max_retries => return Err(e),
+            Err(e) => {
+                eprintln!("Warning: Poll webhook attempt {} failed ({}) — retrying", attempt + 1, e);
+                std::thread::sleep(wait);
+                wait *= 2;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// Append an undeliverable batch, with the reason, to the dead-letter file.
+pub fn write_dead_letter(path: &Path, payload: &str, error: &str, at: DateTime<Utc>) -> io::Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+
+    let entry = serde_json::json!({
+        "failed_at": at.to_rfc3339(),
+        "error": error,
+        "batch": serde_json::from_str::<serde_json::Value>(payload).unwrap_or_else(|_| payload.into()),
+    });
+    let mut file = fs::OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", entry)
+}
+
+/// Batches cycle summaries and hands them to a background thread that POSTs
+/// them to the configured URL.
+pub struct PollWebhook {
+    url: String,
+    batch_size: usize,
+    pending: Vec<PollSummary>,
+    dead_letter_path: PathBuf,
+    /// Serialized batches (with their summary count) waiting to be sent;
+    /// `None` once `close` has run
+    sender: Option<mpsc::Sender<(usize, String)>>,
+    worker: Option<JoinHandle<()>>,
+}
+
+impl PollWebhook {
+    pub fn new(url: &str, batch_size: usize, max_retries: u32, dead_letter_path: &Path) -> Result<Self, String> {
+        let http = reqwest::blocking::Client::builder()
+            .timeout(std::time::Duration::from_secs(WEBHOOK_TIMEOUT_SECS))
+            .build()
+            .map_err(|e| format!("Failed to build HTTP client for poll webhook: {}", e))?;
+
+        let (sender, receiver) = mpsc::channel::<(usize, String)>();
+        let worker = {
+            let url = url.to_string();
+            let dead_letter_path = dead_letter_path.to_path_buf();
+            std::thread::Builder::new()
+                .name("poll-webhook".to_string())
+                .spawn(move || {
+                    for (count, payload) in receiver {
+                        send_batch(&http, &url, max_retries, &dead_letter_path, count, &payload);
+                    }
+                })
+                .map_err(|e| format!("Failed to start poll webhook thread: {}", e))?
+        };
+
+        Ok(PollWebhook {
+            url: url.to_string(),
+            batch_size: batch_size.max(1),
+            pending: Vec::new(),
+            dead_letter_path: dead_letter_path.to_path_buf(),
+            sender: Some(sender),
+            worker: Some(worker),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Queue a cycle's summary, sending the batch once it is full.
+    pub fn push(&mut self, summary: PollSummary) {
+        self.pending.push(summary);
+        if self.pending.len() >= self.batch_size {
+            self.flush();
+        }
+    }
+
+    /// Hand whatever is queued to the sender thread without waiting for it
+    /// to be delivered.
+    pub fn flush(&mut self) {
+        if self.pending.is_empty() {
+            return;
+        }
+
+        let batch = std::mem::take(&mut self.pending);
+        let payload = match serde_json::to_string(&batch) {
+            Ok(payload) => payload,
+            Err(e) => {
+                eprintln!("Warning: Could not serialize poll webhook batch: {}", e);
+                return;
+            }
+        };
+
+        let queued = match &self.sender {
+            Some(sender) => sender.send((batch.len(), payload)).map_err(|e| e.0.1),
+            None => Err(payload),
+        };
+        if let Err(payload) = queued {
+            dead_letter(&self.dead_letter_path, batch.len(), &payload, "poll webhook sender is not running");
+        }
+    }
+
+    /// Flush, then wait for the sender thread to deliver or dead-letter
+    /// every batch. A dead endpoint can hold this up for the full retry
+    /// schedule of each outstanding batch.
+    pub fn close(&mut self) {
+        self.flush();
+        self.sender = None;
+        if let Some(worker) = self.worker.take()
+            && worker.join().is_err()
+        {
+            eprintln!("Warning: Poll webhook thread panicked; undelivered batches were lost");
+        }
+    }
+}
+
+/// POST one batch, dead-lettering it if every attempt fails. Runs on the
+/// webhook's sender thread.
+fn send_batch(
+    http: &reqwest::blocking::Client,
+    url: &str,
+    max_retries: u32,
+    dead_letter_path: &Path,
+    count: usize,
+    payload: &str,
+) {
+    let result = deliver_with_retry(payload, max_retries, WEBHOOK_INITIAL_BACKOFF, |body| {
+        let resp = http
+            .post(url)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .map_err(|e| format!("request failed: {}", e))?;
+        if resp.status().is_success() {
+            Ok(())
+        } else {
+            Err(format!("HTTP {}", resp.status()))
+        }
+    });
+
+    if let Err(e) = result {
+        dead_letter(dead_letter_path, count, payload, &e);
+    }
+}
+
+/// Record an undeliverable batch, falling back to stderr if the dead-letter
+/// file can't be written.
+fn dead_letter(path: &Path, count: usize, payload: &str, error: &str) {
+    eprintln!("Warning: Poll webhook gave up on {} summaries: {}", count, error);
+    if let Err(io_err) = write_dead_letter(path, payload, error, Utc::now()) {
+        eprintln!(
+            "Warning: Could not write {}: {} — dropped batch: {}",
+            path.display(), io_err, payload
+        );
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_summary_counts_per_source() {
+        let results: HashMap<String, usize> = [
+            ("USGS:05567500", 4),
+            ("USGS:05568500", 0),
+            ("CWMS:Peoria Pool", 2),
+            ("ASOS:KPIA", 1),
+        ].into_iter().map(|(k, v)| (k.to_string(), v)).collect();
+        let failures = vec!["USGS:05568500".to_string()];
+        let at = Utc.with_ymd_and_hms(2019, 5, 1, 12, 0, 0).unwrap();
+
+        let summary = PollSummary::from_results(&results, &failures, at);
+
+        assert_eq!(summary.total_inserted, 7);
+        assert_eq!(summary.sources["USGS"], SourceSummary { polled: 2, failed: 1, inserted: 4 });
+        assert_eq!(summary.sources["CWMS"], SourceSummary { polled: 1, failed: 0, inserted: 2 });
+        assert_eq!(summary.failures, failures);
+    }
+
+    #[test]
+    fn test_delivery_retries_then_gives_up() {
+        let mut attempts = 0;
+        let result = deliver_with_retry("[]", 2, std::time::Duration::ZERO, |_| {
+            attempts += 1;
+            if attempts < 3 { Err("HTTP 503".to_string()) } else { Ok(()) }
+        });
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+
+        let mut attempts = 0;
+        let result = deliver_with_retry("[]", 2, std::time::Duration::ZERO, |_| {
+            attempts += 1;
+            Err(format!("HTTP 50{}", attempts))
+        });
+        assert_eq!(result, Err("HTTP 503".to_string()));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn test_dead_letter_appends_batches() {
+        let path = std::env::temp_dir().join(format!("flomon_poll_webhook_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let at = Utc.with_ymd_and_hms(2019, 5, 1, 12, 0, 0).unwrap();
+
+        write_dead_letter(&path, r#"[{"total_inserted":3}]"#, "HTTP 500", at).unwrap();
+        write_dead_letter(&path, r#"[{"total_inserted":5}]"#, "timed out", at).unwrap();
+
+        let lines: Vec<serde_json::Value> = fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["error"], "HTTP 500");
+        assert_eq!(lines[1]["batch"][0]["total_inserted"], 5);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_undeliverable_batch_is_dead_lettered_by_sender_thread() {
+        // A port nothing listens on refuses the POST immediately
+        let port = std::net::TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let path = std::env::temp_dir().join(format!("flomon_poll_webhook_closed_{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let at = Utc.with_ymd_and_hms(2019, 5, 1, 12, 0, 0).unwrap();
+
+        let mut webhook = PollWebhook::new(&format!("http://127.0.0.1:{}/", port), 1, 0, &path).unwrap();
+        webhook.push(PollSummary::from_results(&HashMap::new(), &[], at));
+        webhook.close();
+
+        let contents = fs::read_to_string(&path).unwrap();
+        assert_eq!(contents.lines().count(), 1);
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,197 @@
+//! On-disk buffer for readings the database could not take.
+//!
+//! A poll that fetches readings and then fails to warehouse them (Postgres
+//! restarting, a dropped connection) would otherwise throw the data away
+//! and leave it for a later backfill to re-fetch. With a buffer configured,
+//! those readings are appended to a JSON-lines file instead and replayed at
+//! the start of the next poll cycle. Replay goes through the same
+//! `ON CONFLICT DO NOTHING` insert as a live poll, so a reading that did
+//! land (or is replayed twice) is simply skipped.
+//!
+//! The file is bounded at `max_readings`; past that the oldest readings are
+//! dropped, since a backfill will recover them and the newest are the ones
+//! the live views need.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+use crate::model::{GaugeReading, ReadingSource};
+
+/// Default cap on buffered readings: about a day of 15-minute stage and
+/// discharge for every registry station.
+pub const DEFAULT_WRITE_BUFFER_MAX_READINGS: usize = 50_000;
+
+/// One line of the buffer file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct BufferedReading {
+    agency_code: String,
+    site_code: String,
+    site_name: String,
+    parameter_code: String,
+    unit: String,
+    value: f64,
+    datetime: String,
+    qualifier: String,
+    source: String,
+}
+
+impl From<&GaugeReading> for BufferedReading {
+    fn from(r: &GaugeReading) -> Self {
+        BufferedReading {
+            agency_code: r.agency_code.clone(),
+            site_code: r.site_code.clone(),
+            site_name: r.site_name.clone(),
+            parameter_code: r.parameter_code.clone(),
+            unit: r.unit.clone(),
+            value: r.value,
+            datetime: r.datetime.clone(),
+            qualifier: r.qualifier.clone(),
+            source: r.source.as_str().to_string(),
+        }
+    }
+}
+
+impl From<BufferedReading> for GaugeReading {
+    fn from(r: BufferedReading) -> Self {
+        GaugeReading {
+            agency_code: r.agency_code,
+            site_code: r.site_code,
+            site_name: r.site_name,
+            parameter_code: r.parameter_code,
+            unit: r.unit,
+            value: r.value,
+            datetime: r.datetime,
+            qualifier: r.qualifier,
+            source: ReadingSource::from_db_str(&r.source),
+        }
+    }
+}
+
+/// JSON-lines queue of unwarehoused readings at `path`.
+#[derive(Debug, Clone)]
+pub struct WriteBuffer {
+    path: PathBuf,
+    max_readings: usize,
+}
+
+impl WriteBuffer {
+    pub fn new<P: AsRef<Path>>(path: P, max_readings: usize) -> Self {
+        WriteBuffer { path: path.as_ref().to_path_buf(), max_readings }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Every buffered reading, oldest first. A missing file is an empty
+    /// buffer; unparseable lines (a write cut off mid-line) are skipped.
+    pub fn load(&self) -> io::Result<Vec<GaugeReading>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut readings = Vec::new();
+        for line in BufReader::new(file).lines() {
+            if let Ok(reading) = serde_json::from_str::<BufferedReading>(&line?) {
+                readings.push(reading.into());
+            }
+        }
+        Ok(readings)
+    }
+
+    /// Add `readings` to the buffer, dropping the oldest if it would grow
+    /// past `max_readings`. Returns how many readings were dropped.
+    pub fn append(&self, readings: &[GaugeReading]) -> io::Result<usize> {
+        let mut buffered = self.load()?;
+        buffered.extend(readings.iter().cloned());
+
+        let dropped = buffered.len().saturating_sub(self.max_readings);
+        self.write_all(&buffered[dropped..])?;
+        Ok(dropped)
+    }
+
+    /// Empty the buffer after a successful replay.
+    pub fn clear(&self) -> io::Result<()> {
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    /// Replace the buffer contents, via a temp file and rename so a crash
+    /// mid-write leaves the previous buffer intact.
+    fn write_all(&self, readings: &[GaugeReading]) -> io::Result<()> {
+        if let Some(dir) = self.path.parent().filter(|d| !d.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+
+        let tmp = self.path.with_extension("tmp");
+        let mut out = io::BufWriter::new(fs::File::create(&tmp)?);
+        for reading in readings {
+            serde_json::to_writer(&mut out, &BufferedReading::from(reading))?;
+            out.write_all(b"\n")?;
+        }
+        out.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+
+        fs::rename(&tmp, &self.path)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tests
+// ---------------------------------------------------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_buffer(name: &str, max_readings: usize) -> WriteBuffer {
+        let path = std::env::temp_dir().join(format!("flomon_write_buffer_{}_{}.jsonl", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        WriteBuffer::new(path, max_readings)
+    }
+
+    fn reading(minute: u32, value: f64) -> GaugeReading {
+        GaugeReading {
+            agency_code: "USGS".to_string(),
+            site_code: "05567500".to_string(),
+            site_name: "Illinois River at Peoria, IL".to_string(),
+            parameter_code: "00065".to_string(),
+            unit: "ft".to_string(),
+            value,
+            datetime: format!("2019-05-01T12:{:02}:00.000-05:00", minute),
+            qualifier: "P".to_string(),
+            source: ReadingSource::UsgsIv,
+        }
+    }
+
+    #[test]
+    fn test_buffered_readings_round_trip() {
+        let buffer = temp_buffer("round_trip", 100);
+        assert!(buffer.load().unwrap().is_empty());
+
+        let readings = vec![reading(0, 18.2), reading(15, 18.3)];
+        assert_eq!(buffer.append(&readings).unwrap(), 0);
+        assert_eq!(buffer.load().unwrap(), readings);
+
+        buffer.clear().unwrap();
+        assert!(buffer.load().unwrap().is_empty());
+        buffer.clear().unwrap();
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest_past_bound() {
+        let buffer = temp_buffer("bounded", 3);
+        buffer.append(&[reading(0, 1.0), reading(15, 2.0)]).unwrap();
+        let dropped = buffer.append(&[reading(30, 3.0), reading(45, 4.0)]).unwrap();
+
+        assert_eq!(dropped, 1);
+        let values: Vec<f64> = buffer.load().unwrap().iter().map(|r| r.value).collect();
+        assert_eq!(values, vec![2.0, 3.0, 4.0]);
+        buffer.clear().unwrap();
+    }
+}
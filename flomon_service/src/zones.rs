@@ -3,22 +3,111 @@
 /// Organizes sensors into hydrologically meaningful geographic zones
 /// with lead times and flood forecasting context.
 
+use crate::alert::level::DisplayConfig;
 use serde::Deserialize;
 use std::fs;
 use std::path::Path;
+use std::sync::{Arc, RwLock};
 
 // ============================================================================
 // TOML Configuration Structures
 // ============================================================================
 
 /// Root zones configuration
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct ZonesConfig {
     pub zones: ZoneCollection,
+    #[serde(default)]
+    pub role_weights: RoleWeights,
+    /// Label/color overrides for alert levels in API responses
+    #[serde(default)]
+    pub display: DisplayConfig,
+    /// Expected update interval per data source
+    #[serde(default)]
+    pub freshness_sla: FreshnessSla,
+}
+
+/// How much a threshold exceedance counts toward the zone alert level,
+/// by sensor role. A weight of 1.0 lets an exceedance raise the full alert
+/// (action → ACTION, flood → FLOOD); lower weights damp it (a proxy
+/// at action stage yields WATCH). A weight of 0.0 ignores the role.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct RoleWeights {
+    pub direct: f64,
+    pub boundary: f64,
+    pub proxy: f64,
+    pub precip: f64,
+}
+
+impl Default for RoleWeights {
+    fn default() -> Self {
+        Self {
+            direct: 1.0,
+            boundary: 0.75,
+            proxy: 0.5,
+            precip: 0.5,
+        }
+    }
+}
+
+impl RoleWeights {
+    /// Weight for a sensor role; unrecognized roles get the proxy weight
+    pub fn weight_for(&self, role: &str) -> f64 {
+        match role {
+            "direct" => self.direct,
+            "boundary" => self.boundary,
+            "precip" => self.precip,
+            _ => self.proxy,
+        }
+    }
+}
+
+/// How often each data source is expected to deliver a new reading, in
+/// minutes. A sensor whose latest reading is older than its source's
+/// interval is "late". Unlike `staleness_threshold_minutes`, which decides
+/// when a reading is too old to count toward the zone alert level, this is
+/// an operator-facing service level per feed.
+#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct FreshnessSla {
+    pub usgs: i64,
+    pub cwms: i64,
+    pub asos: i64,
+    pub nws_forecast: i64,
+}
+
+impl Default for FreshnessSla {
+    fn default() -> Self {
+        Self {
+            usgs: 60,
+            cwms: 60,
+            asos: 75,
+            nws_forecast: 360,
+        }
+    }
+}
+
+impl FreshnessSla {
+    /// Expected update interval for a sensor's source, or `None` for
+    /// sources without one (gridded precip, custom feeds)
+    pub fn minutes_for(&self, sensor: &Sensor) -> Option<i64> {
+        if sensor.usgs_id.is_some() {
+            Some(self.usgs)
+        } else if sensor.is_cwms() {
+            Some(self.cwms)
+        } else if sensor.is_asos() {
+            Some(self.asos)
+        } else if sensor.source.contains("NWS") {
+            Some(self.nws_forecast)
+        } else {
+            None
+        }
+    }
 }
 
 /// Collection of all zones
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Clone)]
 pub struct ZoneCollection {
     pub zone_0: Zone,
     pub zone_1: Zone,
@@ -62,8 +151,62 @@ pub struct Sensor {
     pub moderate_flood_ft: Option<f64>,
     pub major_flood_ft: Option<f64>,
     pub datum_note: Option<String>,
+    pub staleness_threshold_minutes: Option<i64>,  // Override for slow-reporting sources
+    pub composite_primary: Option<FeedSource>,     // Reconcile usgs_id + cwms_location, preferring this feed
+    pub primary_parameter: Option<PrimaryParameter>, // USGS reading that drives current value (default stage)
+    pub action_flow_cfs: Option<f64>,              // Discharge thresholds, used when primary is discharge
+    pub flood_flow_cfs: Option<f64>,
+}
+
+/// Which USGS reading is a gauge's headline value. Stage suits most gauges,
+/// but where backwater distorts stage, discharge is the cleaner flood signal.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum PrimaryParameter {
+    #[default]
+    Stage,
+    Discharge,
 }
 
+impl PrimaryParameter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PrimaryParameter::Stage => "stage",
+            PrimaryParameter::Discharge => "discharge",
+        }
+    }
+}
+
+/// Data feed behind one half of a composite sensor
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedSource {
+    Usgs,
+    Cwms,
+}
+
+impl FeedSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeedSource::Usgs => "usgs",
+            FeedSource::Cwms => "cwms",
+        }
+    }
+
+    /// The other feed of a USGS/CWMS pair
+    pub fn other(&self) -> FeedSource {
+        match self {
+            FeedSource::Usgs => FeedSource::Cwms,
+            FeedSource::Cwms => FeedSource::Usgs,
+        }
+    }
+}
+
+/// Age (minutes) past which a sensor's latest reading is treated as stale,
+/// unless the sensor sets `staleness_threshold_minutes`. Suits 15-minute
+/// USGS gauges; hourly ASOS and daily pool readings need longer.
+pub const DEFAULT_STALENESS_THRESHOLD_MINUTES: i64 = 120;
+
 // ============================================================================
 // Zone Metadata
 // ============================================================================
@@ -76,6 +219,8 @@ pub struct ZoneMetadata {
     pub lead_time_hours_min: Option<i64>,
     pub lead_time_hours_max: Option<i64>,
     pub primary_alert_condition: String,
+    /// Downstream signal to follow once this zone is elevated
+    pub watch_next: String,
 }
 
 impl ZoneMetadata {
@@ -88,6 +233,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(12),
                 lead_time_hours_max: Some(120), // 5 days
                 primary_alert_condition: "Grafton stage > 20 ft".to_string(),
+                watch_next: "the LaGrange tailwater–pool differential".to_string(),
             },
             1 => ZoneMetadata {
                 zone_id: 1,
@@ -95,6 +241,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(6),
                 lead_time_hours_max: Some(24),
                 primary_alert_condition: "LaGrange TW → pool diff < 1 ft".to_string(),
+                watch_next: "Peoria pool and Kingston Mines stage".to_string(),
             },
             2 => ZoneMetadata {
                 zone_id: 2,
@@ -102,6 +249,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(0),
                 lead_time_hours_max: Some(6),
                 primary_alert_condition: "Peoria pool > 447.5 ft / Kingston Mines stage > 14 ft".to_string(),
+                watch_next: "the Kingston Mines rate of rise".to_string(),
             },
             3 => ZoneMetadata {
                 zone_id: 3,
@@ -109,6 +257,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(6),
                 lead_time_hours_max: Some(18),
                 primary_alert_condition: "Mackinaw rate-of-rise > 1 ft/hr".to_string(),
+                watch_next: "Kingston Mines stage as tributary flow arrives".to_string(),
             },
             4 => ZoneMetadata {
                 zone_id: 4,
@@ -116,6 +265,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(18),
                 lead_time_hours_max: Some(48),
                 primary_alert_condition: "Henry stage > 15 ft".to_string(),
+                watch_next: "Peoria pool as the crest moves past Henry".to_string(),
             },
             5 => ZoneMetadata {
                 zone_id: 5,
@@ -123,6 +273,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(36),
                 lead_time_hours_max: Some(72),
                 primary_alert_condition: "Dresden pool elevated + Kankakee rising".to_string(),
+                watch_next: "Henry and Marseilles stage".to_string(),
             },
             6 => ZoneMetadata {
                 zone_id: 6,
@@ -130,6 +281,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: Some(72),
                 lead_time_hours_max: Some(120), // 3-5 days
                 primary_alert_condition: "O'Hare 6hr precip > 1.5 in + CSSC discharge spike".to_string(),
+                watch_next: "Dresden Island pool and Kankakee flow".to_string(),
             },
             _ => ZoneMetadata {
                 zone_id,
@@ -137,6 +289,7 @@ impl ZoneMetadata {
                 lead_time_hours_min: None,
                 lead_time_hours_max: None,
                 primary_alert_condition: "N/A".to_string(),
+                watch_next: "neighboring zones".to_string(),
             },
         }
     }
@@ -148,7 +301,7 @@ impl ZoneMetadata {
 
 /// Load zones configuration from TOML file
 pub fn load_zones<P: AsRef<Path>>(path: P) -> Result<ZonesConfig, Box<dyn std::error::Error>> {
-    let content = fs::read_to_string(path)?;
+    let content = fs::read_to_string(&path)?;
     let config: ZonesConfig = toml::from_str(&content)?;
     Ok(config)
 }
@@ -158,6 +311,28 @@ pub fn load_zones_default() -> Result<ZonesConfig, Box<dyn std::error::Error>> {
     load_zones("zones.toml")
 }
 
+/// zones.toml as last loaded by `cached_zones` or `reload_zones`
+static CACHED_ZONES: RwLock<Option<Arc<ZonesConfig>>> = RwLock::new(None);
+
+/// zones.toml, read from disk on first use and shared after that.
+/// Edits on disk are not seen until `reload_zones`.
+pub fn cached_zones() -> Result<Arc<ZonesConfig>, Box<dyn std::error::Error>> {
+    let cached = CACHED_ZONES.read().unwrap_or_else(|poisoned| poisoned.into_inner()).clone();
+    match cached {
+        Some(config) => Ok(config),
+        None => reload_zones(),
+    }
+}
+
+/// Re-read zones.toml and replace the cached copy. On error the previous
+/// copy (if any) stays in use.
+pub fn reload_zones() -> Result<Arc<ZonesConfig>, Box<dyn std::error::Error>> {
+    let config = Arc::new(load_zones_default()?);
+    crate::config_status::record_loaded("zones.toml");
+    *CACHED_ZONES.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(Arc::clone(&config));
+    Ok(config)
+}
+
 /// Get all zones as a vector (in order 0-6)
 pub fn get_all_zones(config: &ZonesConfig) -> Vec<(usize, &Zone)> {
     vec![
@@ -171,6 +346,17 @@ pub fn get_all_zones(config: &ZonesConfig) -> Vec<(usize, &Zone)> {
     ]
 }
 
+/// Drop every sensor for which `keep` is false, in all zones
+pub fn retain_sensors<F: Fn(&Sensor) -> bool>(config: &mut ZonesConfig, keep: F) {
+    let zones = &mut config.zones;
+    for zone in [
+        &mut zones.zone_0, &mut zones.zone_1, &mut zones.zone_2, &mut zones.zone_3,
+        &mut zones.zone_4, &mut zones.zone_5, &mut zones.zone_6,
+    ] {
+        zone.sensors.retain(|sensor| keep(sensor));
+    }
+}
+
 /// Get a specific zone by ID
 pub fn get_zone<'a>(config: &'a ZonesConfig, zone_id: usize) -> Option<&'a Zone> {
     match zone_id {
@@ -199,6 +385,13 @@ impl Sensor {
             .unwrap_or_else(|| "UNKNOWN".to_string())
     }
     
+    /// Every identifier configured for this sensor
+    pub fn ids(&self) -> impl Iterator<Item = &str> {
+        [&self.sensor_id, &self.usgs_id, &self.cwms_location, &self.station_id, &self.shef_id]
+            .into_iter()
+            .filter_map(|id| id.as_deref())
+    }
+    
     /// Check if this sensor is from USGS
     pub fn is_usgs(&self) -> bool {
         self.usgs_id.is_some()
@@ -214,6 +407,35 @@ impl Sensor {
         self.station_id.is_some() && self.source.contains("ASOS")
     }
     
+    /// (primary, backup) feeds if this is a composite sensor: one physical
+    /// location with both a USGS site and a CWMS location, reconciled into
+    /// a single reading. Requires `composite_primary` and both IDs.
+    pub fn composite_feeds(&self) -> Option<(FeedSource, FeedSource)> {
+        let primary = self.composite_primary?;
+        (self.usgs_id.is_some() && self.cwms_location.is_some())
+            .then(|| (primary, primary.other()))
+    }
+    
+    /// Reading that drives `current_value` and threshold checks
+    pub fn primary_parameter(&self) -> PrimaryParameter {
+        self.primary_parameter.unwrap_or_default()
+    }
+    
+    /// (action, flood) thresholds in the units of a reading of
+    /// `parameter_code`: cfs for discharge, feet for anything else
+    pub fn thresholds_for(&self, parameter_code: &str) -> (Option<f64>, Option<f64>) {
+        if parameter_code == crate::model::PARAM_DISCHARGE {
+            (self.action_flow_cfs, self.flood_flow_cfs)
+        } else {
+            (self.action_stage_ft, self.flood_stage_ft)
+        }
+    }
+    
+    /// Minutes after which this sensor's latest reading counts as stale
+    pub fn staleness_threshold_minutes(&self) -> i64 {
+        self.staleness_threshold_minutes.unwrap_or(DEFAULT_STALENESS_THRESHOLD_MINUTES)
+    }
+    
     /// Get sensor role priority (for sorting)
     pub fn role_priority(&self) -> u8 {
         match self.role.as_str() {
@@ -293,10 +515,83 @@ mod tests {
             moderate_flood_ft: None,
             major_flood_ft: None,
             datum_note: None,
+            staleness_threshold_minutes: None,
+            composite_primary: None,
+            primary_parameter: None,
+            action_flow_cfs: None,
+            flood_flow_cfs: None,
         };
         
         assert_eq!(sensor.primary_id(), "05568500");
         assert!(sensor.is_usgs());
         assert!(!sensor.is_cwms());
+        assert_eq!(sensor.staleness_threshold_minutes(), DEFAULT_STALENESS_THRESHOLD_MINUTES);
+        assert_eq!(sensor.composite_feeds(), None);
+        assert_eq!(sensor.primary_parameter(), PrimaryParameter::Stage);
+        
+        // Composite needs both feeds configured, not just a preference
+        let half_composite = Sensor { composite_primary: Some(FeedSource::Cwms), ..sensor };
+        assert_eq!(half_composite.composite_feeds(), None);
+        
+        let composite = Sensor {
+            cwms_location: Some("Grafton-Mississippi".to_string()),
+            ..half_composite
+        };
+        assert_eq!(composite.composite_feeds(), Some((FeedSource::Cwms, FeedSource::Usgs)));
+        
+        let flow_gauge = Sensor {
+            primary_parameter: Some(PrimaryParameter::Discharge),
+            flood_stage_ft: Some(18.0),
+            flood_flow_cfs: Some(45000.0),
+            ..composite
+        };
+        assert_eq!(flow_gauge.thresholds_for(crate::model::PARAM_DISCHARGE), (None, Some(45000.0)));
+        assert_eq!(flow_gauge.thresholds_for(crate::model::PARAM_STAGE), (None, Some(18.0)));
+    }
+    
+    #[test]
+    fn test_cached_zones_match_fresh_load() {
+        let cached = cached_zones().expect("zones.toml should parse");
+        let fresh = load_zones_default().expect("zones.toml should parse");
+        assert_eq!(format!("{:?}", cached), format!("{:?}", fresh));
+        assert!(Arc::ptr_eq(&cached, &cached_zones().unwrap()), "second call should not re-read");
+
+        let reloaded = reload_zones().unwrap();
+        assert_eq!(format!("{:?}", reloaded), format!("{:?}", fresh));
+        assert!(Arc::ptr_eq(&reloaded, &cached_zones().unwrap()));
+    }
+    
+    #[test]
+    fn test_freshness_sla_by_source() {
+        let sla: FreshnessSla = toml::from_str("asos = 60").unwrap();
+        assert_eq!(sla.asos, 60);
+        assert_eq!(sla.usgs, 60);
+        // ASOS reports hourly; anything under an hour flags every station
+        assert!(FreshnessSla::default().asos > 60);
+
+        let config = load_zones_default().expect("zones.toml should parse");
+        for (_, zone) in get_all_zones(&config) {
+            for sensor in &zone.sensors {
+                let expected = if sensor.usgs_id.is_some() {
+                    Some(sla.usgs)
+                } else if sensor.source.starts_with("USACE") {
+                    Some(sla.cwms)
+                } else if sensor.source == "IEM/ASOS" {
+                    Some(sla.asos)
+                } else {
+                    None
+                };
+                assert_eq!(sla.minutes_for(sensor), expected, "{}", sensor.primary_id());
+            }
+        }
+    }
+
+    #[test]
+    fn test_role_weights_parse_with_defaults() {
+        let weights: RoleWeights = toml::from_str("proxy = 0.25").unwrap();
+        assert_eq!(weights.proxy, 0.25);
+        assert_eq!(weights.direct, 1.0);
+        assert_eq!(weights.weight_for("proxy"), 0.25);
+        assert_eq!(weights.weight_for("unlisted"), 0.25);
     }
 }
@@ -14,7 +14,7 @@
 /// Run with: cargo test --test asos_integration -- --test-threads=1
 
 use flomon_service::asos_locations;
-use flomon_service::ingest::iem;
+use flomon_service::ingest::{http, iem};
 use postgres::{Client, NoTls};
 use chrono::Utc;
 use std::env;
@@ -118,7 +118,7 @@ fn test_asos_api_fetches_current_observations() {
         .build()
         .unwrap();
     
-    let result = iem::fetch_recent_precip(&client, "KPIA", 4);
+    let result = iem::fetch_recent_precip(&client, "KPIA", 4, http::DEFAULT_HTTP_MAX_ATTEMPTS);
     
     assert!(
         result.is_ok(),
@@ -126,7 +126,7 @@ fn test_asos_api_fetches_current_observations() {
         result.err()
     );
     
-    let observations = result.unwrap();
+    let observations = result.unwrap().into_observations();
     assert!(
         observations.len() > 0,
         "Should receive at least some observations from KPIA"
@@ -146,7 +146,7 @@ fn test_asos_observations_parse_correctly() {
         .build()
         .unwrap();
     
-    let observations = iem::fetch_recent_precip(&client, "KPIA", 4).unwrap();
+    let observations = iem::fetch_recent_precip(&client, "KPIA", 4, http::DEFAULT_HTTP_MAX_ATTEMPTS).unwrap().into_observations();
     
     let mut has_temp = false;
     let mut has_precip = false;
@@ -221,7 +221,7 @@ fn test_asos_observations_can_be_stored() {
         .build()
         .unwrap();
     
-    let observations = iem::fetch_recent_precip(&http_client, "KPIA", 1).unwrap();
+    let observations = iem::fetch_recent_precip(&http_client, "KPIA", 1, http::DEFAULT_HTTP_MAX_ATTEMPTS).unwrap().into_observations();
     assert!(observations.len() > 0, "Should have observations to test with");
     
     println!("Fetched {} observations from IEM", observations.len());
@@ -294,7 +294,7 @@ fn test_asos_duplicate_observations_handled() {
         .build()
         .unwrap();
     
-    let observations = iem::fetch_recent_precip(&http_client, "KPIA", 1).unwrap();
+    let observations = iem::fetch_recent_precip(&http_client, "KPIA", 1, http::DEFAULT_HTTP_MAX_ATTEMPTS).unwrap().into_observations();
     let obs = &observations[0];
     
     // Insert same observation twice
@@ -343,6 +343,11 @@ fn test_asos_appears_in_zone_sensors() {
         moderate_flood_ft: None,
         major_flood_ft: None,
         datum_note: None,
+        staleness_threshold_minutes: None,
+        composite_primary: None,
+        primary_parameter: None,
+        action_flow_cfs: None,
+        flood_flow_cfs: None,
     };
     
     assert!(asos_sensor.is_asos(), "Weather sensor should be identified as ASOS");
@@ -16,6 +16,7 @@
 ///
 /// Run with: cargo test --test daemon_lifecycle -- --test-threads=1
 
+use flomon_service::daemon::Daemon;
 use flomon_service::db;
 use flomon_service::stations;
 use postgres::{Client, NoTls};
@@ -197,19 +198,28 @@ fn test_daemon_backfills_from_empty_database() {
 }
 
 #[test]
-#[ignore] // Requires implementation of gap detection
+#[ignore] // Requires database and network access
 fn test_daemon_detects_and_fills_data_gaps() {
     // Daemon should detect gaps in time series and backfill them
     
     let mut client = setup_test_db();
     cleanup_test_data(&mut client);
     
-    // Insert readings with a 24-hour gap
+    // Readings on either side of a 24-hour hole
     let now = Utc::now();
-    let yesterday = now - Duration::days(1);
-    let three_days_ago = now - Duration::days(3);
+    let gap_start = now - Duration::days(2);
+    let gap_end = now - Duration::days(1);
+    
+    let mut reading_times: Vec<DateTime<Utc>> = Vec::new();
+    let mut t = now - Duration::days(3);
+    while t <= now {
+        if t <= gap_start || t >= gap_end {
+            reading_times.push(t);
+        }
+        t += Duration::minutes(15);
+    }
     
-    for reading_time in &[three_days_ago, yesterday, now] {
+    for reading_time in &reading_times {
         client.execute(
             "INSERT INTO usgs_raw.gauge_readings 
              (site_code, parameter_code, unit, value, reading_time, qualifier)
@@ -225,12 +235,17 @@ fn test_daemon_detects_and_fills_data_gaps() {
         ).expect("Insert should succeed");
     }
     
-    // TODO: Implement gap detection
-    // let gaps = daemon.detect_gaps("TEST0001", 15.minutes())?;
-    // assert_eq!(gaps.len(), 1, "Should detect one 24-hour gap");
+    let mut daemon = Daemon::new();
+    daemon.initialize().expect("Daemon should initialize");
+    
+    let gaps = daemon.detect_gaps("TEST0001", "00060", 15).expect("Gap detection should succeed");
+    assert_eq!(gaps.len(), 1, "Should detect one 24-hour gap");
+    assert!(gaps[0].1 - gaps[0].0 >= Duration::hours(24));
     
-    // TODO: Implement gap filling
-    // daemon.fill_gaps("TEST0001", &gaps).await?;
+    // TEST0001 isn't a real site, so nothing comes back, but a failed gap
+    // is skipped rather than aborting the fill
+    let inserted = daemon.fill_gaps("TEST0001", &gaps).expect("Gap filling should not fail");
+    assert_eq!(inserted, 0);
     
     cleanup_test_data(&mut client);
 }
@@ -23,8 +23,8 @@
 use flomon_service::db;
 use flomon_service::usace_locations;
 use flomon_service::asos_locations;
-use flomon_service::ingest::{usgs, cwms, iem};
-use flomon_service::model::GaugeReading;
+use flomon_service::ingest::{usgs, cwms, http, iem};
+use flomon_service::model::{GaugeReading, ReadingSource};
 
 use chrono::{DateTime, Utc};
 use postgres::Client;
@@ -268,7 +268,7 @@ fn test_cwms_api_returns_data_for_lagrange() {
                 
                 // Try to fetch recent data
                 if let Some(ref ts_id) = discovered.pool_elevation {
-                    let data_result = cwms::fetch_recent(&http_client, ts_id, &location.office, 4);
+                    let data_result = cwms::fetch_recent(&http_client, ts_id, &location.office, 4, http::DEFAULT_HTTP_MAX_ATTEMPTS);
                     
                     match data_result {
                         Ok(timeseries) => {
@@ -362,10 +362,11 @@ fn test_asos_api_returns_precipitation_data() {
     }
     
     // Try to fetch recent precipitation data (last 1 hour)
-    let archive_result = iem::fetch_recent_precip(&http_client, &kpia.station_id, 1);
+    let archive_result = iem::fetch_recent_precip(&http_client, &kpia.station_id, 1, http::DEFAULT_HTTP_MAX_ATTEMPTS);
     
     match archive_result {
-        Ok(observations) => {
+        Ok(response) => {
+            let observations = response.into_observations();
             println!("✓ ASOS/IEM recent precip returned {} observations", observations.len());
             
             if !observations.is_empty() {
@@ -393,6 +394,7 @@ fn test_usgs_data_can_be_inserted_into_database() {
     
     // Create a test USGS reading
     let reading = GaugeReading {
+        agency_code: "USGS".to_string(),
         site_code: "TEST001".to_string(),
         site_name: "Test Station".to_string(),
         parameter_code: "00060".to_string(),
@@ -400,6 +402,7 @@ fn test_usgs_data_can_be_inserted_into_database() {
         value: 1234.56,
         datetime: Utc::now().to_rfc3339(),
         qualifier: "P".to_string(),
+        source: ReadingSource::UsgsIv,
     };
     
     // Parse the datetime
@@ -416,7 +419,7 @@ fn test_usgs_data_can_be_inserted_into_database() {
         "INSERT INTO usgs_raw.gauge_readings 
          (site_code, parameter_code, unit, value, reading_time, qualifier)
          VALUES ($1, $2, $3, $4, $5, $6)
-         ON CONFLICT (site_code, parameter_code, reading_time) DO NOTHING",
+         ON CONFLICT (agency_code, site_code, parameter_code, reading_time) DO NOTHING",
         &[
             &reading.site_code,
             &reading.parameter_code,
@@ -664,7 +667,7 @@ fn test_usgs_full_pipeline_api_to_database() {
             "INSERT INTO usgs_raw.gauge_readings 
              (site_code, parameter_code, unit, value, reading_time, qualifier)
              VALUES ($1, $2, $3, $4, $5, $6)
-             ON CONFLICT (site_code, parameter_code, reading_time) DO NOTHING",
+             ON CONFLICT (agency_code, site_code, parameter_code, reading_time) DO NOTHING",
             &[
                 &reading.site_code,
                 &reading.parameter_code,
@@ -709,7 +712,7 @@ fn test_duplicate_prevention_idempotent_insert() {
             "INSERT INTO usgs_raw.gauge_readings 
              (site_code, parameter_code, unit, value, reading_time, qualifier)
              VALUES ($1, $2, $3, $4, $5, $6)
-             ON CONFLICT (site_code, parameter_code, reading_time) DO NOTHING",
+             ON CONFLICT (agency_code, site_code, parameter_code, reading_time) DO NOTHING",
             &[
                 &site_code,
                 &"00060",
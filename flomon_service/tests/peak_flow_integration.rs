@@ -9,13 +9,15 @@
 /// Prerequisites:
 /// - PostgreSQL running with flopro_db database
 /// - DATABASE_URL set in .env
-/// - sql/003_flood_metadata.sql migration applied
+/// - sql/003_flood_metadata.sql and sql/013_flood_event_crest_key.sql
+///   migrations applied
 ///
 /// Run with: cargo test --test peak_flow_integration -- --test-threads=1
 
 use flomon_service::config::load_config;
 use flomon_service::ingest::peak_flow::{
-    parse_rdb, identify_flood_events, FloodThresholds, FloodSeverity,
+    parse_rdb, identify_flood_events, upsert_flood_event, FloodEvent, FloodThresholds, FloodSeverity,
+    UpsertOutcome,
 };
 
 use chrono::{Duration, NaiveDate, NaiveTime, TimeZone, Utc};
@@ -250,8 +252,8 @@ fn test_full_pipeline_rdb_to_database() {
         .expect("Failed to start transaction");
     
     for event in &events {
-        // Convert NaiveDateTime to DateTime<Utc>
-        let crest_utc = Utc.from_utc_datetime(&event.crest_time);
+        // Convert Central local crest time to DateTime<Utc>
+        let crest_utc = event.crest_time_utc();
         let event_start = crest_utc - Duration::hours(24);
         
         tx.execute(
@@ -397,43 +399,37 @@ fn test_duplicate_prevention() {
     let mut client = get_test_client();
     clean_test_data(&mut client);
     
-    let crest_time = Utc.with_ymd_and_hms(2013, 4, 18, 7, 45, 0).unwrap();
-    let event_start = crest_time - Duration::hours(24);
-    
-    // Insert first time
-    client.execute(
-        "INSERT INTO nws.flood_events 
-         (site_code, event_start, crest_time, peak_stage_ft, severity, data_source)
-         VALUES ($1, $2, $3, $4, $5, $6)",
-        &[
-            &"05567500",
-            &event_start,
-            &crest_time,
-            &Decimal::from_f64_retain(18.79).unwrap(),
-            &"flood",
-            &"TEST - Duplicate Check",
-        ]
-    ).expect("Failed to insert first event");
+    let crest_time = NaiveDate::from_ymd_opt(2013, 4, 18).unwrap()
+        .and_time(NaiveTime::from_hms_opt(7, 45, 0).unwrap());
+    let mut event = FloodEvent {
+        site_code: "05567500".to_string(),
+        crest_time,
+        peak_stage_ft: 18.79,
+        severity: FloodSeverity::Flood,
+    };
     
-    // Check for duplicate (simulating what ingest_peak_flows.rs does)
-    let exists: i64 = client.query_one(
-        "SELECT COUNT(*) FROM nws.flood_events 
-         WHERE site_code = $1 AND crest_time = $2",
-        &[&"05567500", &crest_time],
-    ).expect("Failed to check duplicate").get(0);
+    let first = upsert_flood_event(&mut client, &event, "TEST - Duplicate Check")
+        .expect("Failed to insert first event");
+    assert_eq!(first, UpsertOutcome::Inserted);
     
-    assert_eq!(exists, 1, "Should find the existing event");
+    // Re-running the ingest with a revised peak updates the same row
+    event.peak_stage_ft = 20.12;
+    event.severity = FloodSeverity::Moderate;
+    let second = upsert_flood_event(&mut client, &event, "TEST - Duplicate Check")
+        .expect("Failed to upsert revised event");
+    assert_eq!(second, UpsertOutcome::Updated);
     
-    // Should skip insert if exists > 0 (this is what the binary does)
-    // Let's verify we CAN query by both site_code and crest_time
-    let result = client.query_one(
-        "SELECT peak_stage_ft FROM nws.flood_events 
+    let rows = client.query(
+        "SELECT peak_stage_ft, severity FROM nws.flood_events 
          WHERE site_code = $1 AND crest_time = $2",
-        &[&"05567500", &crest_time],
+        &[&"05567500", &event.crest_time_utc()],
     ).expect("Failed to query by site_code and crest_time");
     
-    let stage: Decimal = result.get(0);
-    assert!((stage.to_f64().unwrap() - 18.79).abs() < 0.01, "Expected ~18.79, got {}", stage);
+    assert_eq!(rows.len(), 1, "Upsert must not duplicate the event");
+    let stage: Decimal = rows[0].get(0);
+    let severity: String = rows[0].get(1);
+    assert!((stage.to_f64().unwrap() - 20.12).abs() < 0.01, "Expected ~20.12, got {}", stage);
+    assert_eq!(severity, "moderate");
     
     // Clean up
     clean_test_data(&mut client);